@@ -0,0 +1,255 @@
+//! Keyframed animation of the transformations of the objects and of the
+//! camera, and the render of the frames of an animation to numbered image
+//! files, for turntables and flythroughs. [`render_turntable`] renders
+//! the most common of them directly.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    features::{
+        camera::Camera, canvas::ppm_canvas::to_ppm, progress::RenderProgress,
+        shapes::bounds::BoundingBox, world::World,
+    },
+    Float, Matrix4, Point3, Transform, Vector3,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The pace of the blend between two keyframes.
+pub enum Easing {
+    /// At a constant speed.
+    #[default]
+    Linear,
+    /// Starting slowly.
+    EaseIn,
+    /// Stopping slowly.
+    EaseOut,
+    /// Starting and stopping slowly.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Returns the fraction of the blend done at the fraction `t` of the
+    /// time between two keyframes, both in `[0, 1]`.
+    pub fn apply<T: Float>(&self, t: T) -> T {
+        let one = T::one();
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => one - (one - t) * (one - t),
+            Easing::EaseInOut => t * t * (T::from_f64(3.0) - T::two() * t),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A transformation reached at a point in time.
+pub struct Keyframe<T: Float> {
+    pub time: T,
+    pub transform: Matrix4<T>,
+    /// The pace of the blend from this keyframe to the next one.
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// The keyframes of a transformation, sorted by time. Between two
+/// keyframes the transformation is blended as by
+/// [`Shape::set_motion`](crate::Shape::set_motion); it holds the first
+/// one before them and the last one after them.
+pub struct Track<T: Float> {
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T: Float> Track<T> {
+    pub fn new() -> Self {
+        Self {
+            keyframes: Vec::new(),
+        }
+    }
+
+    /// Returns this track with the keyframe of `transform` at `time`,
+    /// replacing the one at the same time, if any.
+    pub fn with_key(mut self, time: T, transform: Matrix4<T>, easing: Easing) -> Self {
+        let keyframe = Keyframe {
+            time,
+            transform,
+            easing,
+        };
+        match self.keyframes.iter().position(|k| k.time >= time) {
+            Some(i) if self.keyframes[i].time == time => self.keyframes[i] = keyframe,
+            Some(i) => self.keyframes.insert(i, keyframe),
+            None => self.keyframes.push(keyframe),
+        }
+        self
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// Returns the transformation at `time`, `None` without keyframes.
+    pub fn transform_at(&self, time: T) -> Option<Matrix4<T>> {
+        let next = self.keyframes.iter().position(|k| k.time > time);
+        match next {
+            Some(0) => Some(self.keyframes[0].transform),
+            Some(i) => {
+                let (a, b) = (&self.keyframes[i - 1], &self.keyframes[i]);
+                let t = a.easing.apply((time - a.time) / (b.time - a.time));
+                Some(Matrix4::interpolate(&a.transform, &b.transform, t))
+            }
+            None => self.keyframes.last().map(|k| k.transform),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The tracks of the objects of a world, designated by their index in
+/// [`World::objects`], and of the camera, over `duration`.
+pub struct Animation<T: Float> {
+    duration: T,
+    objects: Vec<(usize, Track<T>)>,
+    camera: Option<Track<T>>,
+}
+
+impl<T: Float> Animation<T> {
+    pub fn new(duration: T) -> Self {
+        Self {
+            duration,
+            objects: Vec::new(),
+            camera: None,
+        }
+    }
+
+    pub fn duration(&self) -> T {
+        self.duration
+    }
+
+    /// Returns this animation moving the object of index `index` along
+    /// `track`.
+    pub fn with_object(mut self, index: usize, track: Track<T>) -> Self {
+        self.objects.push((index, track));
+        self
+    }
+
+    /// Returns this animation moving the camera along `track`.
+    pub fn with_camera(mut self, track: Track<T>) -> Self {
+        self.camera = Some(track);
+        self
+    }
+
+    /// Moves the animated objects of `world` and the `camera` where they
+    /// are at `time`. The spatial structure over the objects of `world`
+    /// is dropped, see [`World::objects_mut`].
+    /// # Panics
+    ///
+    /// This function panics if an animated object is not in `world`.
+    pub fn apply(&self, world: &mut World<T>, camera: &mut Camera<T>, time: T) {
+        let objects = world.objects_mut();
+        for (index, track) in &self.objects {
+            if let Some(transform) = track.transform_at(time) {
+                objects[*index].set_transform(transform);
+            }
+        }
+        if let Some(transform) = self.camera.as_ref().and_then(|t| t.transform_at(time)) {
+            camera.set_transform(transform);
+        }
+    }
+
+    /// Returns the times of `frames` frames spread evenly over the
+    /// animation, the first at its start and the last at its end.
+    pub fn frame_times(&self, frames: usize) -> Vec<T> {
+        let last = T::from_f64(frames.saturating_sub(1).max(1) as f64);
+        (0..frames)
+            .map(|i| self.duration * T::from_f64(i as f64) / last)
+            .collect()
+    }
+}
+
+/// Renders `frames` frames of `animation`, see [`Animation::frame_times`],
+/// and writes them to `dir` as the plain PPM files `frame_0000.ppm`,
+/// `frame_0001.ppm`, ... The frames are rendered by `camera` with the
+/// Whitted integrator of its settings; `world` and `camera` are left as
+/// they are at the last frame.
+/// # Errors
+///
+/// This function will return an error if a frame cannot be written.
+pub fn render_sequence(
+    world: &mut World<f64>,
+    camera: &mut Camera<f64>,
+    animation: &Animation<f64>,
+    frames: usize,
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let mut paths = Vec::with_capacity(frames);
+    for (i, time) in animation.frame_times(frames).into_iter().enumerate() {
+        animation.apply(world, camera, time);
+        paths.push(write_frame(world, camera, dir, i)?);
+    }
+    Ok(paths)
+}
+
+/// Renders `frames` frames of `camera` orbiting once around the center of
+/// the objects of `world`, about `axis`, and writes them to `dir` like
+/// [`render_sequence`]. The frames are at equal angles, the last one a step
+/// before the first, so that the sequence loops. The center is the one of
+/// the bounds of the objects, leaving out the ones of infinite extent such
+/// as the planes, or the origin if none is finite.
+/// # Errors
+///
+/// This function will return an error if a frame cannot be written.
+pub fn render_turntable(
+    world: &World<f64>,
+    camera: &Camera<f64>,
+    frames: usize,
+    axis: Vector3<f64>,
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let c = turntable_center(world);
+    let mut paths = Vec::with_capacity(frames);
+    for i in 0..frames {
+        let angle = std::f64::consts::TAU * i as f64 / frames as f64;
+        // Turning the camera around the center is turning the world the
+        // other way in front of it.
+        let orbit = Transform::translation(c.x, c.y, c.z)
+            * Transform::rotation_around_axis(axis, -angle)
+            * Transform::translation(-c.x, -c.y, -c.z);
+        let frame = camera.clone().with_transform(camera.transform() * orbit);
+        paths.push(write_frame(world, &frame, dir, i)?);
+    }
+    Ok(paths)
+}
+
+/// Returns the center of the finite bounds of the objects of `world`.
+fn turntable_center(world: &World<f64>) -> Point3<f64> {
+    let mut bounds = BoundingBox::default();
+    for object in world.objects() {
+        let object_bounds = object.parent_space_bounds();
+        if object_bounds.is_finite() {
+            bounds.merge(&object_bounds);
+        }
+    }
+    match bounds.is_empty() {
+        true => Point3::new(0.0, 0.0, 0.0),
+        false => bounds.centroid(),
+    }
+}
+
+/// Renders the frame of index `index` with the Whitted integrator of the
+/// settings of `camera`, and writes it to `dir`.
+fn write_frame(
+    world: &World<f64>,
+    camera: &Camera<f64>,
+    dir: &Path,
+    index: usize,
+) -> io::Result<PathBuf> {
+    let integrator = camera.settings().whitted();
+    let pixels = camera.render_pixels(world, &integrator, &mut |_: &RenderProgress| {});
+    let path = dir.join(format!("frame_{index:04}.ppm"));
+    fs::write(&path, to_ppm(camera.hsize(), camera.vsize(), &pixels))?;
+    Ok(path)
+}
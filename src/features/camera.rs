@@ -0,0 +1,1025 @@
+//! Implementation of `Camera`, which maps the pixels of a canvas to the
+//! rays cast into the world and renders it.
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    features::{
+        canvas::{denoise::Denoiser, RawCanvas},
+        checkpoint::{fingerprint, Checkpoint, CheckpointError},
+        colors::Color,
+        integrators::{clamp_radiance, Integrator},
+        math::sampling::{random_in_unit_disk, seed_thread_rng, square_to_unit_disk},
+        progress::{count_primary_ray, rays_cast, ProgressObserver, RayCounts, RenderProgress},
+        rays::Ray,
+        samplers::Sampler,
+        settings::RenderSettings,
+        tiles::{tiles, TileOrder},
+        world::World,
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+/// Spreads the seeds of the settings apart before they are combined with
+/// the indices of the pixels (the golden ratio in 64 bits).
+const SEED_MULTIPLIER: u64 = 0x9e37_79b9_7f4a_7c15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How a [`Camera`] maps the positions on its canvas to the directions of
+/// the rays.
+pub enum Projection {
+    /// The pinhole camera of the book: straight lines stay straight. The
+    /// field of view must be below `π`.
+    #[default]
+    Perspective,
+    /// An equidistant fisheye lens: the angle to the view direction grows
+    /// linearly with the distance to the center of the canvas. The field
+    /// of view, up to `2π`, spans the diameter of the image circle
+    /// inscribed in the canvas; the pixels outside of it see nothing.
+    Fisheye,
+    /// An equirectangular panorama of all the directions: the columns span
+    /// 360° of longitude and the rows 180° of latitude, `+y` at the top.
+    /// The field of view is ignored. The layout is the one of
+    /// [`EnvironmentMap`](crate::EnvironmentMap), so the render of a camera
+    /// merely translated is the environment map of the scene seen from
+    /// there; the center of the canvas looks towards `+z`.
+    Equirectangular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A rectangle of pixels of the canvas, from `(min_x, min_y)` included to
+/// `(max_x, max_y)` excluded.
+pub struct PixelBounds {
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+impl PixelBounds {
+    pub fn new(min_x: usize, min_y: usize, max_x: usize, max_y: usize) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.max_x.saturating_sub(self.min_x)
+    }
+
+    pub fn height(&self) -> usize {
+        self.max_y.saturating_sub(self.min_y)
+    }
+
+    /// Returns the number of pixels within these bounds.
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// Returns the pixels within these bounds, row by row.
+    pub fn pixels(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (xs, ys) = (self.min_x..self.max_x, self.min_y..self.max_y);
+        ys.flat_map(move |y| xs.clone().map(move |x| (x, y)))
+    }
+
+    /// Returns `true` if the pixel `(x, y)` lies within these bounds.
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.min_x..self.max_x).contains(&x) && (self.min_y..self.max_y).contains(&y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The adaptive anti-aliasing of a [`Camera`]: once every pixel is
+/// rendered, the pixels in a neighborhood of high contrast, typically on
+/// the edges of the objects, receive extra samples spread over their area.
+pub struct AdaptiveSampling<T: Float> {
+    /// The variance of the luminance over the 3x3 pixels around a pixel
+    /// above which it is refined.
+    pub threshold: T,
+    /// The number of samples added to each refined pixel.
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The canvases of a render in several layers, or arbitrary output
+/// variables, for compositing or denoising. Besides the `beauty`, which is
+/// the ordinary render, the layers describe the surface seen through the
+/// center of each pixel when the shutter opens; they are zero where
+/// nothing is hit.
+pub struct RenderLayers<const W: usize, const H: usize, T: Float> {
+    pub beauty: RawCanvas<W, H, T>,
+    /// The color of the surface before any lighting, its pattern included.
+    pub albedo: RawCanvas<W, H, T>,
+    /// The normal facing the camera in world space, its components from
+    /// `-1` to `1` as red, green and blue.
+    pub normal: RawCanvas<W, H, T>,
+    /// The distance from the camera to the surface, in every channel.
+    pub depth: RawCanvas<W, H, T>,
+    /// The index of the object of the world hit plus one, in every channel.
+    pub object_id: RawCanvas<W, H, T>,
+}
+
+impl<const W: usize, const H: usize, T: Float> RenderLayers<W, H, T> {
+    /// Denoises the beauty in place, guided by the normal and albedo layers.
+    pub fn denoise(&mut self, denoiser: &Denoiser<T>) {
+        self.beauty.denoise(&self.normal, &self.albedo, denoiser);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A camera looking towards `-z` in its own space, rendering a canvas of
+/// `hsize` x `vsize` pixels. With the default [`Projection::Perspective`],
+/// it is the pinhole camera of the book, one unit in front of the canvas
+/// spanning `field_of_view` radians along its longest side.
+/// The transformation orients the world relative to the camera, usually a
+/// [`Matrix4::view_transform`].
+///
+/// With an `aperture`, the rays start from random points of a thin lens
+/// in the xy plane of the camera and converge at `focal_distance`: only
+/// the objects at that distance are sharp. The blur is resolved by the
+/// samples per pixel of the integrator.
+///
+/// With a `shutter` interval, the rays are cast at random times within it,
+/// and the moving shapes (see [`Shape::set_motion`](crate::Shape::set_motion))
+/// blur along their motion.
+///
+/// A render region restricts the rendering to a part of the canvas, to
+/// iterate quickly on a detail of the frame.
+///
+/// The pixels are rendered tile by tile, in the [`TileOrder`] of the
+/// camera, on the threads of its [`RenderSettings`].
+///
+/// A pixel of a single sample is seen through its center, as in the book.
+/// With several samples per pixel, their rays pass through random points of
+/// its area, smoothing the edges; [`AdaptiveSampling`] adds more of them to
+/// the pixels of high contrast.
+pub struct Camera<T: Float> {
+    hsize: usize,
+    vsize: usize,
+    field_of_view: T,
+    projection: Projection,
+    /// The diameter of the lens, zero for a pinhole.
+    aperture: T,
+    focal_distance: T,
+    /// The times at which the shutter opens and closes.
+    shutter: (T, T),
+    /// The pixels rendered, the whole canvas if `None`.
+    region: Option<PixelBounds>,
+    adaptive: Option<AdaptiveSampling<T>>,
+    tile_size: usize,
+    tile_order: TileOrder,
+    settings: RenderSettings,
+    transform: Matrix4<T>,
+    /// Cached inverse of `transform`, applied to every ray.
+    inverse: Matrix4<T>,
+    half_width: T,
+    half_height: T,
+    pixel_size: T,
+}
+
+impl<T: Float> Camera<T> {
+    pub fn new(hsize: usize, vsize: usize, field_of_view: T) -> Self {
+        let half_view = (field_of_view / T::two()).tan();
+        let aspect = T::from_f64(hsize as f64) / T::from_f64(vsize as f64);
+        let (half_width, half_height) = if aspect >= T::one() {
+            (half_view, half_view / aspect)
+        } else {
+            (half_view * aspect, half_view)
+        };
+        Self {
+            hsize,
+            vsize,
+            field_of_view,
+            projection: Projection::default(),
+            aperture: T::zero(),
+            focal_distance: T::one(),
+            shutter: (T::zero(), T::zero()),
+            region: None,
+            adaptive: None,
+            tile_size: 32,
+            tile_order: TileOrder::default(),
+            settings: RenderSettings::default(),
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            half_width,
+            half_height,
+            pixel_size: half_width * T::two() / T::from_f64(hsize as f64),
+        }
+    }
+
+    /// Sets the transformation orienting the world relative to the camera.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    pub fn with_transform(mut self, transform: Matrix4<T>) -> Self {
+        self.set_transform(transform);
+        self
+    }
+
+    /// Sets the projection of this camera, [`Projection::Perspective`] by
+    /// default.
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Sets the diameter of the lens, zero (a pinhole with everything in
+    /// focus) by default.
+    pub fn with_aperture(mut self, aperture: T) -> Self {
+        self.aperture = aperture;
+        self
+    }
+
+    /// Sets the distance of the sharp objects, along the view direction
+    /// for the perspective projection and in every direction otherwise.
+    /// One by default.
+    pub fn with_focal_distance(mut self, focal_distance: T) -> Self {
+        self.focal_distance = focal_distance;
+        self
+    }
+
+    /// Sets the times at which the shutter opens and closes, both `0` (an
+    /// instant) by default. The moving shapes are at their start at time
+    /// `0` and at their end at time `1`.
+    pub fn with_shutter(mut self, open: T, close: T) -> Self {
+        self.shutter = (open, close);
+        self
+    }
+
+    /// Renders only the pixels within `region`, clipped to the canvas; the
+    /// other pixels stay black.
+    pub fn with_render_region(mut self, region: PixelBounds) -> Self {
+        let clip = |v: usize, size: usize| v.min(size);
+        self.region = Some(PixelBounds::new(
+            clip(region.min_x, self.hsize),
+            clip(region.min_y, self.vsize),
+            clip(region.max_x, self.hsize),
+            clip(region.max_y, self.vsize),
+        ));
+        self
+    }
+
+    /// Renders only the part of the canvas from `(x_min, y_min)` to
+    /// `(x_max, y_max)` in fractions of its size, `(0, 0)` being the
+    /// top-left corner: every pixel overlapping that window is rendered.
+    pub fn with_crop_window(self, x_min: T, y_min: T, x_max: T, y_max: T) -> Self {
+        let pixel = |c: T, size: usize, round: fn(T) -> T| {
+            round(c.max(T::zero()).min(T::one()) * T::from_f64(size as f64))
+                .to_usize()
+                .unwrap_or(0)
+        };
+        let region = PixelBounds::new(
+            pixel(x_min, self.hsize, T::floor),
+            pixel(y_min, self.vsize, T::floor),
+            pixel(x_max, self.hsize, T::ceil),
+            pixel(y_max, self.vsize, T::ceil),
+        );
+        self.with_render_region(region)
+    }
+
+    /// Refines the pixels of high contrast with `samples` more samples each,
+    /// see [`AdaptiveSampling`].
+    pub fn with_adaptive_sampling(mut self, threshold: T, samples: usize) -> Self {
+        self.adaptive = Some(AdaptiveSampling { threshold, samples });
+        self
+    }
+
+    /// Sets the size of the square tiles rendered, 32 pixels by default.
+    /// # Panics
+    ///
+    /// This function panics if `tile_size` is zero.
+    pub fn with_tile_size(mut self, tile_size: usize) -> Self {
+        assert!(tile_size > 0, "The tiles cannot be empty.");
+        self.tile_size = tile_size;
+        self
+    }
+
+    /// Sets the order of the tiles, [`TileOrder::Scanline`] by default.
+    pub fn with_tile_order(mut self, tile_order: TileOrder) -> Self {
+        self.tile_order = tile_order;
+        self
+    }
+
+    /// Sets the settings of the renders, [`RenderSettings::default`] by
+    /// default: the integrator of [`Camera::render`], the threads, the
+    /// clamp of the samples and the seed of the random numbers.
+    pub fn with_settings(mut self, settings: RenderSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    pub fn field_of_view(&self) -> T {
+        self.field_of_view
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn aperture(&self) -> T {
+        self.aperture
+    }
+
+    pub fn focal_distance(&self) -> T {
+        self.focal_distance
+    }
+
+    pub fn shutter(&self) -> (T, T) {
+        self.shutter
+    }
+
+    pub fn adaptive_sampling(&self) -> Option<AdaptiveSampling<T>> {
+        self.adaptive
+    }
+
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    pub fn tile_order(&self) -> TileOrder {
+        self.tile_order
+    }
+
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Returns the tiles of the render region, in the order they are
+    /// rendered.
+    pub fn tiles(&self) -> Vec<PixelBounds> {
+        tiles(&self.render_region(), self.tile_size, self.tile_order)
+    }
+
+    /// Returns the pixels rendered, the whole canvas by default.
+    pub fn render_region(&self) -> PixelBounds {
+        self.region
+            .unwrap_or(PixelBounds::new(0, 0, self.hsize, self.vsize))
+    }
+
+    /// Returns the size of a pixel on the canvas one unit in front of the
+    /// camera.
+    pub fn pixel_size(&self) -> T {
+        self.pixel_size
+    }
+
+    pub fn transform(&self) -> Matrix4<T> {
+        self.transform
+    }
+
+    /// Sets the transformation orienting the world relative to the camera.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.inverse = transform
+            .inverse()
+            .expect("The transformation of a camera must be invertible.");
+        self.transform = transform;
+    }
+
+    /// Returns the ray from the camera through the center of the pixel
+    /// `(px, py)`, `None` if the projection maps nothing there.
+    pub fn ray_for_pixel(&self, px: usize, py: usize) -> Option<Ray<T>> {
+        let half = T::one() / T::two();
+        self.ray_for_position(T::from_f64(px as f64) + half, T::from_f64(py as f64) + half)
+    }
+
+    /// Returns the ray from the camera through the position `(x, y)` of the
+    /// canvas, in pixels from its top-left corner; `None` if the projection
+    /// maps nothing there. The ray is cast when the shutter opens.
+    pub fn ray_for_position(&self, x: T, y: T) -> Option<Ray<T>> {
+        let direction = self.local_direction(x, y)?;
+        let origin = self.inverse * Point3::new(T::zero(), T::zero(), T::zero());
+        Some(Ray::new(origin, (self.inverse * direction).normalized()).with_time(self.shutter.0))
+    }
+
+    /// Returns a ray through the position `(x, y)` of the canvas like
+    /// [`Camera::ray_for_position`], starting from a random point of the
+    /// lens drawn from `rng` when the camera has an aperture, and cast at a
+    /// random time while the shutter is open.
+    pub fn sample_ray(&self, x: T, y: T, rng: &mut SmallRng) -> Option<Ray<T>> {
+        let (open, close) = self.shutter;
+        let time = if close > open {
+            self.shutter_time(rng.gen())
+        } else {
+            open
+        };
+        let lens = if self.aperture == T::zero() {
+            (T::zero(), T::zero())
+        } else {
+            random_in_unit_disk(rng)
+        };
+        self.lens_ray(x, y, lens, time)
+    }
+
+    /// Returns a ray through the position `(x, y)` of the canvas like
+    /// [`Camera::sample_ray`], the point of the lens and the time being
+    /// drawn from the next dimensions of `sampler`.
+    pub fn sampler_ray(&self, x: T, y: T, sampler: &mut dyn Sampler) -> Option<Ray<T>> {
+        let (u, v) = sampler.next_2d();
+        let time = self.shutter_time(sampler.next_1d());
+        self.lens_ray(x, y, square_to_unit_disk(u, v), time)
+    }
+
+    /// Returns the time at the fraction `u` of the shutter interval.
+    fn shutter_time(&self, u: f64) -> T {
+        let (open, close) = self.shutter;
+        if close > open {
+            open + (close - open) * T::from_f64(u)
+        } else {
+            open
+        }
+    }
+
+    /// Returns the ray through the position `(x, y)` of the canvas from
+    /// the point `lens` of the unit disk, scaled to the aperture, at `time`.
+    fn lens_ray(&self, x: T, y: T, lens: (T, T), time: T) -> Option<Ray<T>> {
+        if self.aperture == T::zero() {
+            return Some(self.ray_for_position(x, y)?.with_time(time));
+        }
+        let direction = self.local_direction(x, y)?;
+        // The perspective directions end on the plane z = -1, the others
+        // are unit vectors: the focus lies on a plane or on a sphere.
+        let direction = match self.projection {
+            Projection::Perspective => direction,
+            _ => direction.normalized(),
+        };
+        let focus = Point3::new(T::zero(), T::zero(), T::zero()) + direction * self.focal_distance;
+        let (lx, ly) = lens;
+        let radius = self.aperture / T::two();
+        let lens = Point3::new(lx * radius, ly * radius, T::zero());
+        let origin = self.inverse * lens;
+        Some(Ray::new(origin, (self.inverse * (focus - lens)).normalized()).with_time(time))
+    }
+
+    /// Returns the direction in camera space seen at the position `(x, y)`
+    /// of the canvas.
+    fn local_direction(&self, x: T, y: T) -> Option<Vector3<T>> {
+        match self.projection {
+            Projection::Perspective => {
+                // The camera looks towards -z, so +x is to the left.
+                let world_x = self.half_width - x * self.pixel_size;
+                let world_y = self.half_height - y * self.pixel_size;
+                Some(Vector3::new(world_x, world_y, -T::one()))
+            }
+            Projection::Fisheye => {
+                let (hsize, vsize) = (self.hsize as f64, self.vsize as f64);
+                let radius = T::from_f64(hsize.min(vsize) / 2.0);
+                let dx = (x - T::from_f64(hsize / 2.0)) / radius;
+                let dy = (y - T::from_f64(vsize / 2.0)) / radius;
+                let r = (dx * dx + dy * dy).sqrt();
+                if r > T::one() {
+                    return None;
+                }
+                if r == T::zero() {
+                    return Some(Vector3::new(T::zero(), T::zero(), -T::one()));
+                }
+                let (sin, cos) = (r * self.field_of_view / T::two()).sin_cos();
+                Some(Vector3::new(-dx / r * sin, -dy / r * sin, -cos))
+            }
+            Projection::Equirectangular => {
+                let u = x / T::from_f64(self.hsize as f64);
+                let v = y / T::from_f64(self.vsize as f64);
+                // The inverse of the spherical mapping of the textures.
+                let theta = v * T::PI();
+                let phi = T::PI() - T::two() * T::PI() * u;
+                let (sin_theta, cos_theta) = theta.sin_cos();
+                let (sin_phi, cos_phi) = phi.sin_cos();
+                Some(Vector3::new(
+                    sin_theta * sin_phi,
+                    cos_theta,
+                    sin_theta * cos_phi,
+                ))
+            }
+        }
+    }
+
+    /// Renders `world` with the [`Whitted`](crate::Whitted) ray tracing of the book, with
+    /// the bounces and samples of the settings of this camera.
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render<const W: usize, const H: usize>(&self, world: &World<T>) -> RawCanvas<W, H, T> {
+        self.render_with(world, &self.settings.whitted())
+    }
+
+    /// Renders `world` with `integrator`, averaging the estimates of its
+    /// samples per pixel along as many [`Camera::sample_ray`]. The random
+    /// numbers of each pixel are seeded by its position and the seed of the
+    /// settings, so renders are reproducible whatever the threads. The
+    /// pixels the projection maps nothing to, or outside of the render
+    /// region, are black.
+    ///
+    /// With [`AdaptiveSampling`], a second pass refines the pixels of high
+    /// contrast in the first one. The overlay of the settings, if any, is
+    /// stamped last.
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render_with<const W: usize, const H: usize>(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+    ) -> RawCanvas<W, H, T> {
+        self.render_with_progress(world, integrator, &mut |_: &RenderProgress| {})
+    }
+
+    /// Renders `world` with `integrator` like [`Camera::render_with`],
+    /// reporting to `observer` after each tile. The refinement of the
+    /// adaptive sampling follows the last report.
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render_with_progress<const W: usize, const H: usize>(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        observer: &mut dyn ProgressObserver,
+    ) -> RawCanvas<W, H, T> {
+        self.assert_canvas_size::<W, H>();
+        RawCanvas::from_pixels(self.render_pixels(world, integrator, observer))
+    }
+
+    /// Renders `world` with `integrator` like
+    /// [`Camera::render_with_progress`], into the `hsize * vsize` row-major
+    /// pixels, the first row being the top of the image. This is the render
+    /// of the sizes known only at run time, which no canvas can hold.
+    pub fn render_pixels(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        observer: &mut dyn ProgressObserver,
+    ) -> Vec<Color<T>> {
+        let mut pixels = vec![Color::default(); self.hsize * self.vsize];
+        self.render_tiles(
+            observer,
+            |x, y| self.render_pixel(world, integrator, x, y),
+            |x, y, color| pixels[y * self.hsize + x] = color,
+        );
+        self.refine(&mut pixels, world, integrator);
+        self.composite(&mut pixels);
+        pixels
+    }
+
+    /// Renders `world` with `integrator` like [`Camera::render_with`], and
+    /// the other [`RenderLayers`] in the same pass over the pixels.
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render_layers<const W: usize, const H: usize>(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+    ) -> RenderLayers<W, H, T> {
+        self.assert_canvas_size::<W, H>();
+        let mut layers = RenderLayers {
+            beauty: RawCanvas::default(),
+            albedo: RawCanvas::default(),
+            normal: RawCanvas::default(),
+            depth: RawCanvas::default(),
+            object_id: RawCanvas::default(),
+        };
+        self.render_tiles(
+            &mut |_: &RenderProgress| {},
+            |x, y| {
+                // First, as it seeds the media of the pixel.
+                let color = self.render_pixel(world, integrator, x, y);
+                let surface = self.ray_for_pixel(x, y).and_then(|ray| {
+                    let xs = world.intersect(&ray);
+                    let hit = xs.hit()?;
+                    let comps = hit.prepare_computations_with_bias(&ray, world.shadow_bias());
+                    let t = hit.t();
+                    let n = comps.normalv;
+                    let id = world
+                        .objects()
+                        .iter()
+                        .position(|object| object.includes(comps.object))
+                        .map_or(T::zero(), |i| T::from_f64((i + 1) as f64));
+                    Some([
+                        comps.object.material().color_at(comps.object, comps.point),
+                        Color::new(n.x, n.y, n.z),
+                        Color::new(t, t, t),
+                        Color::new(id, id, id),
+                    ])
+                });
+                (color, surface)
+            },
+            |x, y, (color, surface)| {
+                layers.beauty.write_pixel(x, y, color).unwrap();
+                if let Some([albedo, normal, depth, id]) = surface {
+                    layers.albedo.write_pixel(x, y, albedo).unwrap();
+                    layers.normal.write_pixel(x, y, normal).unwrap();
+                    layers.depth.write_pixel(x, y, depth).unwrap();
+                    layers.object_id.write_pixel(x, y, id).unwrap();
+                }
+            },
+        );
+        self.refine(layers.beauty.pixels_mut(), world, integrator);
+        self.composite(layers.beauty.pixels_mut());
+        layers
+    }
+
+    /// Renders `world` with `integrator` like
+    /// [`Camera::render_with_progress`], saving the completed tiles to the
+    /// [`Checkpoint`] file at `path` as they complete, when at least
+    /// `interval` passed since the last save. A render stopped before its
+    /// end resumes from the tiles of that file, the progress reporting the
+    /// remaining tiles only; the tiles which were in flight are rendered
+    /// again in full. The file is removed once the render is complete.
+    /// # Errors
+    ///
+    /// This function returns a [`CheckpointError`] if the checkpoint cannot
+    /// be read or written, or if it is of another render, see
+    /// [`Camera::checkpoint`].
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render_with_checkpoint<const W: usize, const H: usize>(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        observer: &mut dyn ProgressObserver,
+        path: impl AsRef<Path>,
+        interval: Duration,
+    ) -> Result<RawCanvas<W, H, T>, CheckpointError> {
+        self.assert_canvas_size::<W, H>();
+        let path = path.as_ref();
+        let fresh = self.checkpoint(world, integrator);
+        let mut checkpoint = if path.exists() {
+            let checkpoint = Checkpoint::load(path)?;
+            if !checkpoint.is_of_same_render(&fresh) {
+                return Err(CheckpointError::Mismatch);
+            }
+            checkpoint
+        } else {
+            fresh
+        };
+        let remaining: Vec<usize> = (0..checkpoint.tiles().len())
+            .filter(|&index| !checkpoint.is_done(index))
+            .collect();
+        let pending: Vec<PixelBounds> = remaining
+            .iter()
+            .map(|&index| checkpoint.tiles()[index])
+            .collect();
+        let mut last_save = Instant::now();
+        let mut saved = Ok(());
+        self.schedule_tiles(
+            &pending,
+            observer,
+            |x, y| self.render_pixel(world, integrator, x, y),
+            |index, _, values| {
+                let mut values = values.into_iter();
+                checkpoint.complete_tile(remaining[index], |_, _| values.next().unwrap());
+                // The next tiles are still rendered after a failed save,
+                // the error being returned at the end.
+                if saved.is_ok() && last_save.elapsed() >= interval {
+                    saved = checkpoint.save(path);
+                    last_save = Instant::now();
+                }
+            },
+        );
+        saved?;
+        let mut image = RawCanvas::default();
+        for (x, y) in self.render_region().pixels() {
+            image.write_pixel(x, y, checkpoint.pixel_at(x, y)).unwrap();
+        }
+        self.refine(image.pixels_mut(), world, integrator);
+        self.composite(image.pixels_mut());
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(image)
+    }
+
+    /// Returns the [`Checkpoint`] of a render of `world` with `integrator`
+    /// which has not started yet: of the canvas, the tiles and the seed of
+    /// this camera, and of a hash of `world`, `integrator` and this camera.
+    /// The threads and the overlay of the settings are left out of the
+    /// hash, as they do not change the pixels of the checkpoint.
+    pub fn checkpoint(&self, world: &World<T>, integrator: &dyn Integrator<T>) -> Checkpoint<T> {
+        let camera = self.clone().with_settings(RenderSettings {
+            threads: 1,
+            overlay: None,
+            ..self.settings.clone()
+        });
+        let scene_hash = fingerprint(&[&world.fingerprint(), &integrator, &camera]);
+        Checkpoint::new(self.hsize, self.vsize, self.tiles())
+            .with_scene(self.settings.seed, scene_hash)
+    }
+
+    /// Returns the color of the pixel `(x, y)`, averaging the samples per
+    /// pixel of `integrator`: through its center for a single sample, or
+    /// else through random points of its area, smoothing the edges. The
+    /// pixel is black if none of its samples has a ray.
+    fn render_pixel(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        x: usize,
+        y: usize,
+    ) -> Color<T> {
+        let samples = integrator.samples_per_pixel().max(1);
+        let mut rng = self.pixel_rng(y * self.hsize + x);
+        let (sum, count) =
+            self.sample_pixel(world, integrator, (x, y), samples, samples > 1, &mut rng);
+        average(sum, count)
+    }
+
+    /// Refines the pixels of high contrast of the row-major `pixels` with
+    /// the adaptive sampling, if any.
+    fn refine(&self, pixels: &mut [Color<T>], world: &World<T>, integrator: &dyn Integrator<T>) {
+        let Some(adaptive) = self.adaptive.filter(|a| a.samples > 0) else {
+            return;
+        };
+        let samples = integrator.samples_per_pixel().max(1);
+        let region = self.render_region();
+        let refined: Vec<(usize, usize)> = region
+            .pixels()
+            .filter(|&(x, y)| contrast(pixels, self.hsize, &region, x, y) > adaptive.threshold)
+            .collect();
+        let total = T::from_f64((samples + adaptive.samples) as f64);
+        for (x, y) in refined {
+            // Seeded apart from the first pass.
+            let mut rng = self.pixel_rng(self.hsize * self.vsize + y * self.hsize + x);
+            let (sum, count) =
+                self.sample_pixel(world, integrator, (x, y), adaptive.samples, true, &mut rng);
+            if count == 0 {
+                continue;
+            }
+            // Both passes are averages, weighted by the samples drawn.
+            let first = pixels[y * self.hsize + x] * T::from_f64(samples as f64);
+            let second = average(sum, count) * T::from_f64(adaptive.samples as f64);
+            pixels[y * self.hsize + x] = (first + second) / total;
+        }
+    }
+
+    /// Stamps the overlay of the settings, if any, onto the row-major
+    /// `pixels` of a render.
+    fn composite(&self, pixels: &mut [Color<T>]) {
+        if let Some(overlay) = &self.settings.overlay {
+            overlay.apply(pixels, self.hsize);
+        }
+    }
+
+    /// Renders `world` with `integrator` like [`Camera::render_with`], the
+    /// samples of each pixel being drawn from `sampler`: their number and
+    /// their position in the pixel, on the lens and in the shutter
+    /// interval, in that order. The integrator still draws its own random
+    /// numbers from the seed of the settings, while the values of the
+    /// sampler follow its own seed. The adaptive sampling does not apply.
+    /// Like [`Camera::render_with`], a pixel averages the samples that have
+    /// a ray, and is black if none has.
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    pub fn render_with_sampler<const W: usize, const H: usize>(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        sampler: &dyn Sampler,
+    ) -> RawCanvas<W, H, T> {
+        let samples = sampler.samples_per_pixel().max(1);
+        let mut image = self.render_canvas(&mut |_: &RenderProgress| {}, |x, y| {
+            // The samplers restart at every pixel, each thread has its own.
+            let mut sampler = sampler.clone_box();
+            let mut rng = self.pixel_rng(y * self.hsize + x);
+            let (mut sum, mut count) = (Color::default(), 0);
+            for index in 0..samples {
+                sampler.start_pixel_sample((x, y), index);
+                let (dx, dy) = sampler.next_2d();
+                let (px, py) = (T::from_f64(x as f64 + dx), T::from_f64(y as f64 + dy));
+                let Some(ray) = self.sampler_ray(px, py, sampler.as_mut()) else {
+                    continue;
+                };
+                count_primary_ray();
+                sum += self.clamp(integrator.radiance(world, &ray, &mut rng));
+                count += 1;
+            }
+            average(sum, count)
+        });
+        self.composite(image.pixels_mut());
+        image
+    }
+
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    fn assert_canvas_size<const W: usize, const H: usize>(&self) {
+        assert_eq!(
+            (W, H),
+            (self.hsize, self.vsize),
+            "The canvas must have the size of the camera."
+        );
+    }
+
+    /// Returns the canvas of the colors given by `pixel` for the pixels of
+    /// the render region, see [`Camera::render_tiles`].
+    /// # Panics
+    ///
+    /// This function panics if the canvas is not `hsize` x `vsize`.
+    fn render_canvas<const W: usize, const H: usize>(
+        &self,
+        observer: &mut dyn ProgressObserver,
+        pixel: impl Fn(usize, usize) -> Color<T> + Sync,
+    ) -> RawCanvas<W, H, T> {
+        self.assert_canvas_size::<W, H>();
+        let mut image = RawCanvas::default();
+        self.render_tiles(observer, pixel, |x, y, color| {
+            image.write_pixel(x, y, color).unwrap();
+        });
+        image
+    }
+
+    /// Computes the values given by `pixel` for the pixels of the render
+    /// region tile by tile, on the threads of the settings, and passes
+    /// them to `write` on the calling thread. The tiles are completed and
+    /// reported to `observer` in their order, whatever the threads.
+    fn render_tiles<P: Send>(
+        &self,
+        observer: &mut dyn ProgressObserver,
+        pixel: impl Fn(usize, usize) -> P + Sync,
+        mut write: impl FnMut(usize, usize, P),
+    ) {
+        self.schedule_tiles(&self.tiles(), observer, pixel, |_, tile, values| {
+            for ((x, y), value) in tile.pixels().zip(values) {
+                write(x, y, value);
+            }
+        });
+    }
+
+    /// Computes the values given by `pixel` for the pixels of `tiles` on
+    /// the threads of the settings, and passes the values of each tile, in
+    /// the order of its pixels, to `complete` on the calling thread along
+    /// with the index of the tile. The tiles are completed and reported to
+    /// `observer` in their order, whatever the threads.
+    fn schedule_tiles<P: Send>(
+        &self,
+        tiles: &[PixelBounds],
+        observer: &mut dyn ProgressObserver,
+        pixel: impl Fn(usize, usize) -> P + Sync,
+        mut complete_tile: impl FnMut(usize, &PixelBounds, Vec<P>),
+    ) {
+        let start = Instant::now();
+        let mut progress = RenderProgress {
+            tiles_done: 0,
+            tiles_total: tiles.len(),
+            pixels_done: 0,
+            pixels_total: tiles.iter().map(PixelBounds::area).sum(),
+            elapsed: start.elapsed(),
+            rays: RayCounts::default(),
+        };
+        let mut complete = |index: usize, (values, rays): (Vec<P>, RayCounts)| {
+            let tile = &tiles[index];
+            complete_tile(index, tile, values);
+            progress.rays.primary += rays.primary;
+            progress.rays.shadow += rays.shadow;
+            progress.tiles_done += 1;
+            progress.pixels_done += tile.area();
+            progress.elapsed = start.elapsed();
+            observer.on_progress(&progress);
+        };
+        // Along with the rays the tile took, counted by its thread.
+        let render_tile = |tile: &PixelBounds| -> (Vec<P>, RayCounts) {
+            let before = rays_cast();
+            let values = tile.pixels().map(|(x, y)| pixel(x, y)).collect();
+            (values, rays_cast() - before)
+        };
+        let threads = self.settings.threads.min(tiles.len());
+        if threads <= 1 {
+            for (index, tile) in tiles.iter().enumerate() {
+                complete(index, render_tile(tile));
+            }
+            return;
+        }
+        // The threads take the tiles in order and send them back once
+        // rendered.
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                let sender = sender.clone();
+                let (next, render_tile) = (&next, &render_tile);
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(tile) = tiles.get(index) else {
+                        return;
+                    };
+                    if sender.send((index, render_tile(tile))).is_err() {
+                        return;
+                    }
+                });
+            }
+            drop(sender);
+            // The tiles rendered ahead of the next one to complete.
+            let mut pending: Vec<Option<_>> = tiles.iter().map(|_| None).collect();
+            let mut completed = 0;
+            for (index, tile) in receiver {
+                pending[index] = Some(tile);
+                while let Some(tile) = pending.get_mut(completed).and_then(Option::take) {
+                    complete(completed, tile);
+                    completed += 1;
+                }
+            }
+        });
+    }
+
+    /// Returns the random number generator of the pixel of index `index`,
+    /// derived from the seed of the settings, and restarts the generator
+    /// of the current thread for the media crossed by the rays of the
+    /// pixel. A pixel draws the same numbers whichever thread renders it.
+    fn pixel_rng(&self, index: usize) -> SmallRng {
+        let seed = index as u64 ^ self.settings.seed.wrapping_mul(SEED_MULTIPLIER);
+        // A different stream, not to correlate the media with the samples.
+        seed_thread_rng(!seed);
+        SmallRng::seed_from_u64(seed)
+    }
+
+    /// Returns `color` scaled down to the clamp of the settings, if any.
+    fn clamp(&self, color: Color<T>) -> Color<T> {
+        match self.settings.clamp {
+            Some(max) => clamp_radiance(color, T::from_f64(max)),
+            None => color,
+        }
+    }
+
+    /// Returns the sum of `samples` estimates of `integrator` for the pixel
+    /// `(x, y)`, through its center or through random points of its area
+    /// if `jitter` is set, and the number of them that had a ray.
+    fn sample_pixel(
+        &self,
+        world: &World<T>,
+        integrator: &dyn Integrator<T>,
+        (x, y): (usize, usize),
+        samples: usize,
+        jitter: bool,
+        rng: &mut SmallRng,
+    ) -> (Color<T>, usize) {
+        let half = T::one() / T::two();
+        let (mut sum, mut count) = (Color::default(), 0);
+        for _ in 0..samples {
+            let (dx, dy) = if jitter {
+                (T::from_f64(rng.gen()), T::from_f64(rng.gen()))
+            } else {
+                (half, half)
+            };
+            let (px, py) = (T::from_f64(x as f64) + dx, T::from_f64(y as f64) + dy);
+            let Some(ray) = self.sample_ray(px, py, rng) else {
+                continue;
+            };
+            count_primary_ray();
+            sum += self.clamp(integrator.radiance(world, &ray, rng));
+            count += 1;
+        }
+        (sum, count)
+    }
+}
+
+/// Returns the mean of `count` samples summing to `sum`, black for none.
+fn average<T: Float>(sum: Color<T>, count: usize) -> Color<T> {
+    if count == 0 {
+        return Color::default();
+    }
+    sum / T::from_f64(count as f64)
+}
+
+/// Returns the variance of the luminance of the row-major `pixels`,
+/// `width` wide, around `(x, y)` within `region`.
+fn contrast<T: Float>(
+    pixels: &[Color<T>],
+    width: usize,
+    region: &PixelBounds,
+    x: usize,
+    y: usize,
+) -> T {
+    let xs = x.saturating_sub(1).max(region.min_x)..(x + 2).min(region.max_x);
+    let ys = y.saturating_sub(1).max(region.min_y)..(y + 2).min(region.max_y);
+    let values: Vec<T> = ys
+        .flat_map(|j| xs.clone().map(move |i| (i, j)))
+        .map(|(i, j)| pixels[j * width + i].luminance())
+        .collect();
+    let n = T::from_f64(values.len() as f64);
+    let mean = values.iter().fold(T::zero(), |sum, &v| sum + v) / n;
+    values
+        .iter()
+        .fold(T::zero(), |sum, &v| sum + (v - mean) * (v - mean))
+        / n
+}
@@ -0,0 +1,138 @@
+//! Post-render compositing: stamping another canvas (a logo, a color bar...)
+//! onto the rendered output with alpha blending. An [`Overlay`] does the
+//! same for the images sized at run time, such as the ones of the render
+//! command.
+use crate::{
+    features::{colors::Color, patterns::uv_patterns::UvImage},
+    Float,
+};
+
+use super::{Canvas, CanvasFormat};
+
+impl<const W: usize, const H: usize, T: Float, F: CanvasFormat> Canvas<W, H, T, F> {
+    /// Stamps `src` onto this canvas with its top-left corner at `(x, y)`,
+    /// blending every pixel with the same `opacity` (`0` keeps the canvas,
+    /// `1` replaces it). Pixels falling outside of this canvas are clipped.
+    pub fn overlay<const SW: usize, const SH: usize, G: CanvasFormat>(
+        &mut self,
+        src: &Canvas<SW, SH, T, G>,
+        x: usize,
+        y: usize,
+        opacity: T,
+    ) {
+        self.blend_with(src, x, y, |_, _| opacity)
+    }
+
+    /// Stamps `src` onto this canvas with its top-left corner at `(x, y)`,
+    /// using a per-pixel alpha `mask` of the same dimension as `src`.
+    /// # Panics
+    /// This function panics if `mask` does not hold exactly `SW * SH` values.
+    pub fn overlay_masked<const SW: usize, const SH: usize, G: CanvasFormat>(
+        &mut self,
+        src: &Canvas<SW, SH, T, G>,
+        mask: &[T],
+        x: usize,
+        y: usize,
+    ) {
+        assert_eq!(
+            mask.len(),
+            SW * SH,
+            "The mask must cover the whole source canvas."
+        );
+        self.blend_with(src, x, y, |sx, sy| mask[sy * SW + sx])
+    }
+
+    /// Blends `src` into this canvas, the alpha of each source pixel is
+    /// given by `alpha_at(sx, sy)` and clamped into `[0, 1]`.
+    fn blend_with<const SW: usize, const SH: usize, G: CanvasFormat>(
+        &mut self,
+        src: &Canvas<SW, SH, T, G>,
+        x: usize,
+        y: usize,
+        alpha_at: impl Fn(usize, usize) -> T,
+    ) {
+        let top = |sx: usize, sy: usize| (src.pixels[sy * SW + sx], alpha_at(sx, sy));
+        blend_into(&mut self.pixels, W, (SW, SH), x, y, top);
+    }
+}
+
+/// Blends the `size` source pixels given by `top(sx, sy)`, a color and
+/// its alpha clamped into `[0, 1]`, into the row-major `pixels` of `width`
+/// columns, with the top-left corner at `(x, y)`. The source pixels
+/// falling off the canvas, even past `usize::MAX`, are skipped.
+fn blend_into<T: Float>(
+    pixels: &mut [Color<T>],
+    width: usize,
+    size: (usize, usize),
+    x: usize,
+    y: usize,
+    top: impl Fn(usize, usize) -> (Color<T>, T),
+) {
+    let height = pixels.len() / width.max(1);
+    for sy in 0..size.1 {
+        for sx in 0..size.0 {
+            let (Some(dx), Some(dy)) = (x.checked_add(sx), y.checked_add(sy)) else {
+                continue;
+            };
+            if dx >= width || dy >= height {
+                continue;
+            }
+            let (color, alpha) = top(sx, sy);
+            let alpha = alpha.max(T::zero()).min(T::one());
+            let bottom = pixels[dy * width + dx];
+            pixels[dy * width + dx] = color * alpha + bottom * (T::one() - alpha);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An image stamped onto the rendered pixels with its top-left corner at
+/// `(x, y)`, like [`Canvas::overlay`]. The [`RenderSettings`] of a camera
+/// may hold one, which its renders apply.
+///
+/// [`RenderSettings`]: crate::RenderSettings
+pub struct Overlay<T: Float> {
+    pub image: UvImage<T>,
+    pub x: usize,
+    pub y: usize,
+    /// `0` keeps the render, `1` replaces it.
+    pub opacity: T,
+}
+
+impl<T: Float> Overlay<T> {
+    /// Returns the overlay of `image` at the top-left corner, opaque.
+    pub fn new(image: UvImage<T>) -> Self {
+        Self {
+            image,
+            x: 0,
+            y: 0,
+            opacity: T::one(),
+        }
+    }
+
+    pub fn at(mut self, x: usize, y: usize) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: T) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    /// Stamps the image onto the row-major `pixels` of `width` columns,
+    /// of any precision. Pixels falling outside of them are clipped.
+    pub fn apply<U: Float>(&self, pixels: &mut [Color<U>], width: usize) {
+        let convert = |v: T| U::from_f64(v.to_f64().unwrap_or(0.0));
+        let size = (self.image.width(), self.image.height());
+        let top = |sx, sy| {
+            let c = self.image.pixel_at(sx, sy);
+            (
+                Color::new(convert(c.r), convert(c.g), convert(c.b)),
+                convert(self.opacity),
+            )
+        };
+        blend_into(pixels, width, size, self.x, self.y, top);
+    }
+}
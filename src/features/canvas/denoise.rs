@@ -0,0 +1,89 @@
+//! Post-render denoising: the edge-avoiding à-trous wavelet filter of
+//! Dammertz et al. (2010). Each pass blurs the canvas with a 5x5 B-spline
+//! kernel whose taps are spread twice as far apart as in the previous
+//! pass, so a few passes cover a wide neighborhood. The weight of each tap
+//! falls with the difference of color, normal and albedo to the center, so
+//! the noise is smoothed within the surfaces but not across their edges.
+use crate::{features::colors::Color, Float};
+
+use super::{Canvas, CanvasFormat};
+
+/// The weights of the B-spline kernel, from the center outwards.
+const KERNEL: [f64; 3] = [3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The settings of the denoising filter. The smaller a `sigma`, the more a
+/// difference of that feature stops the blur.
+pub struct Denoiser<T: Float> {
+    /// The number of passes, each one doubling the reach of the filter.
+    pub iterations: usize,
+    /// The tolerance to differences of color, halved at every pass as the
+    /// noise decreases.
+    pub color_sigma: T,
+    pub normal_sigma: T,
+    pub albedo_sigma: T,
+}
+
+impl<T: Float> Default for Denoiser<T> {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            color_sigma: T::one(),
+            normal_sigma: T::from_f64(0.1),
+            albedo_sigma: T::from_f64(0.1),
+        }
+    }
+}
+
+/// Returns the squared distance between the colors `a` and `b`.
+fn distance2<T: Float>(a: Color<T>, b: Color<T>) -> T {
+    let d = a - b;
+    d.r * d.r + d.g * d.g + d.b * d.b
+}
+
+impl<const W: usize, const H: usize, T: Float, F: CanvasFormat> Canvas<W, H, T, F> {
+    /// Denoises this canvas in place with `denoiser`, guided by the
+    /// `normal` and `albedo` layers of the same render, such as the ones of
+    /// [`RenderLayers`](crate::RenderLayers).
+    pub fn denoise<G: CanvasFormat, A: CanvasFormat>(
+        &mut self,
+        normal: &Canvas<W, H, T, G>,
+        albedo: &Canvas<W, H, T, A>,
+        denoiser: &Denoiser<T>,
+    ) {
+        let mut color_sigma = denoiser.color_sigma;
+        for pass in 0..denoiser.iterations {
+            let step = 1 << pass;
+            let source = self.pixels.clone();
+            for y in 0..H {
+                for x in 0..W {
+                    let center = y * W + x;
+                    let (mut sum, mut total) = (Color::default(), T::zero());
+                    for j in -2_isize..=2 {
+                        for i in -2_isize..=2 {
+                            let qx = x as isize + i * step;
+                            let qy = y as isize + j * step;
+                            if qx < 0 || qy < 0 || qx >= W as isize || qy >= H as isize {
+                                continue;
+                            }
+                            let q = qy as usize * W + qx as usize;
+                            let kernel = KERNEL[i.unsigned_abs()] * KERNEL[j.unsigned_abs()];
+                            let exponent = distance2(source[center], source[q])
+                                / (color_sigma * color_sigma)
+                                + distance2(normal.pixels[center], normal.pixels[q])
+                                    / (denoiser.normal_sigma * denoiser.normal_sigma)
+                                + distance2(albedo.pixels[center], albedo.pixels[q])
+                                    / (denoiser.albedo_sigma * denoiser.albedo_sigma);
+                            let weight = T::from_f64(kernel) * (-exponent).exp();
+                            sum += source[q] * weight;
+                            total += weight;
+                        }
+                    }
+                    // The center always weighs something.
+                    self.pixels[center] = sum / total;
+                }
+            }
+            color_sigma /= T::two();
+        }
+    }
+}
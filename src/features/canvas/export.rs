@@ -0,0 +1,457 @@
+//! Writers of the rendered pixels to image files, the format picked from
+//! the extension of the file or by name. PPM is always supported, plain or
+//! raw, QOI, and PNG with the `png` feature; both are lossless and far
+//! smaller than a PPM, and a PNG is opened by every viewer and browser,
+//! while a QOI is much faster to encode for previews. The Radiance
+//! HDR and PFM formats keep the colors above `1` of the highlights, to be
+//! tonemapped later by other tools, as does OpenEXR with the `exr`
+//! feature, see `ExrImage` for its layers. The single
+//! channel buffers, such as the depth of the [`RenderLayers`], are written
+//! as grayscale PGM files by [`to_pgm`].
+//!
+//! [`RenderLayers`]: crate::RenderLayers
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+use crate::features::colors::Color;
+
+use super::{
+    ppm_canvas::{to_ppm, to_ppm_binary},
+    Canvas, CanvasFormat,
+};
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    /// The extension or the name is not the one of a supported format.
+    UnsupportedFormat(String),
+    /// The PNG encoder failed.
+    #[cfg(feature = "png")]
+    Png(png::EncodingError),
+    /// The OpenEXR encoder failed.
+    #[cfg(feature = "exr")]
+    Exr(exr::error::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "Failed to write the image file: {e}"),
+            ExportError::UnsupportedFormat(extension) => {
+                write!(f, "The image format `{extension}` is not supported.")
+            }
+            #[cfg(feature = "png")]
+            ExportError::Png(e) => write!(f, "Failed to encode the PNG image: {e}"),
+            #[cfg(feature = "exr")]
+            ExportError::Exr(e) => write!(f, "Failed to encode the OpenEXR image: {e}"),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<io::Error> for ExportError {
+    fn from(e: io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+#[cfg(feature = "png")]
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::Png(e)
+    }
+}
+
+#[cfg(feature = "exr")]
+impl From<exr::error::Error> for ExportError {
+    fn from(e: exr::error::Error) -> Self {
+        ExportError::Exr(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The formats the pixels can be written to.
+pub enum ImageFormat {
+    /// The plain (`P3`) PPM of [`to_ppm`].
+    Ppm,
+    /// The raw (`P6`) PPM of [`to_ppm_binary`].
+    PpmBinary,
+    /// The 8-bit RGB QOI of [`to_qoi`].
+    Qoi,
+    /// The Radiance RGBE of [`to_hdr`].
+    Hdr,
+    /// The floating point PFM of [`to_pfm`].
+    Pfm,
+    /// An 8-bit RGB PNG.
+    #[cfg(feature = "png")]
+    Png,
+    /// An OpenEXR image of the `R`, `G` and `B` channels.
+    #[cfg(feature = "exr")]
+    Exr,
+}
+
+impl ImageFormat {
+    /// Returns the format of the extension of `path`, case insensitive. A
+    /// `.ppm` file is plain, see [`ImageFormat::from_name`] for a raw one.
+    /// # Errors
+    ///
+    /// This function will return an error if the extension is missing or
+    /// not the one of a supported format, such as `png` without the `png`
+    /// feature or `exr` without the `exr` one.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "qoi" => Ok(ImageFormat::Qoi),
+            "hdr" => Ok(ImageFormat::Hdr),
+            "pfm" => Ok(ImageFormat::Pfm),
+            #[cfg(feature = "png")]
+            "png" => Ok(ImageFormat::Png),
+            #[cfg(feature = "exr")]
+            "exr" => Ok(ImageFormat::Exr),
+            _ => Err(ExportError::UnsupportedFormat(extension)),
+        }
+    }
+
+    /// Returns the format named `name`, case insensitive: an extension
+    /// of [`ImageFormat::from_path`], `p3` for the plain PPM or `p6` for
+    /// the raw one.
+    /// # Errors
+    ///
+    /// This function will return an error if `name` is not the one of a
+    /// supported format.
+    pub fn from_name(name: &str) -> Result<Self, ExportError> {
+        match name.to_ascii_lowercase().as_str() {
+            "p3" => Ok(ImageFormat::Ppm),
+            "p6" => Ok(ImageFormat::PpmBinary),
+            extension => Self::from_path(format!("image.{extension}"))
+                .map_err(|_| ExportError::UnsupportedFormat(name.to_string())),
+        }
+    }
+
+    /// Returns the file of the `width` x `height` row-major `pixels` in
+    /// this format. The colors are clamped to `[0, 1]` as by
+    /// [`PPMCanvas`](crate::PPMCanvas), but for the HDR, PFM and EXR
+    /// formats.
+    /// # Errors
+    ///
+    /// This function will return an error if the encoder fails.
+    /// # Panics
+    ///
+    /// This function panics if `pixels` does not hold exactly
+    /// `width * height` colors.
+    pub fn encode(
+        self,
+        width: usize,
+        height: usize,
+        pixels: &[Color<f64>],
+    ) -> Result<Vec<u8>, ExportError> {
+        match self {
+            ImageFormat::Ppm => Ok(to_ppm(width, height, pixels).into_bytes()),
+            ImageFormat::PpmBinary => Ok(to_ppm_binary(width, height, pixels)),
+            ImageFormat::Qoi => Ok(to_qoi(width, height, pixels)),
+            ImageFormat::Hdr => Ok(to_hdr(width, height, pixels)),
+            ImageFormat::Pfm => Ok(to_pfm(width, height, pixels)),
+            #[cfg(feature = "png")]
+            ImageFormat::Png => to_png(width, height, pixels),
+            #[cfg(feature = "exr")]
+            ImageFormat::Exr => Ok(super::exr::ExrImage::new(width, height)
+                .with_layer("", pixels)
+                .to_bytes()?),
+        }
+    }
+
+    /// Writes the `width` x `height` row-major `pixels` to `path` in this
+    /// format, whatever its extension.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be encoded or
+    /// written.
+    /// # Panics
+    ///
+    /// This function panics if `pixels` does not hold exactly
+    /// `width * height` colors.
+    pub fn save(
+        self,
+        path: impl AsRef<Path>,
+        width: usize,
+        height: usize,
+        pixels: &[Color<f64>],
+    ) -> Result<(), ExportError> {
+        fs::write(path, self.encode(width, height, pixels)?)?;
+        Ok(())
+    }
+}
+
+/// Returns the 8-bit RGB PNG of the `width` x `height` row-major `pixels`.
+/// # Errors
+///
+/// This function will return an error if the encoder fails, such as for an
+/// empty image.
+/// # Panics
+///
+/// This function panics if `pixels` does not hold exactly `width * height`
+/// colors.
+#[cfg(feature = "png")]
+pub fn to_png(width: usize, height: usize, pixels: &[Color<f64>]) -> Result<Vec<u8>, ExportError> {
+    let data = to_rgb8(width, height, pixels);
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Returns the interleaved 8-bit samples of `pixels`.
+fn to_rgb8(width: usize, height: usize, pixels: &[Color<f64>]) -> Vec<u8> {
+    assert_pixels(width, height, pixels);
+    pixels
+        .iter()
+        .flat_map(|&pixel| {
+            let pixel: Color<u8> = pixel.into();
+            [pixel.r, pixel.g, pixel.b]
+        })
+        .collect()
+}
+
+/// Returns the QOI ("Quite OK Image") file of the `width` x `height`
+/// row-major `pixels`, in 8-bit RGB as by [`PPMCanvas`](crate::PPMCanvas).
+/// Each pixel is encoded as a run of the previous one, a reference to a
+/// recent one, a small difference to the previous one, or in full.
+/// # Panics
+///
+/// This function panics if `pixels` does not hold exactly `width * height`
+/// colors.
+pub fn to_qoi(width: usize, height: usize, pixels: &[Color<f64>]) -> Vec<u8> {
+    const OP_INDEX: u8 = 0x00;
+    const OP_DIFF: u8 = 0x40;
+    const OP_LUMA: u8 = 0x80;
+    const OP_RUN: u8 = 0xc0;
+    const OP_RGB: u8 = 0xfe;
+    let data = to_rgb8(width, height, pixels);
+    let mut out = b"qoif".to_vec();
+    out.extend((width as u32).to_be_bytes());
+    out.extend((height as u32).to_be_bytes());
+    // Three channels, in the sRGB space the 8-bit samples are shown in.
+    out.extend([3, 0]);
+    // Empty at first, as the transparent black of the decoders matches no
+    // pixel.
+    let mut seen = [None; 64];
+    let mut previous = [0u8; 3];
+    let mut run = 0u8;
+    let count = data.len() / 3;
+    for (i, rgb) in data.chunks_exact(3).enumerate() {
+        let pixel = [rgb[0], rgb[1], rgb[2]];
+        if pixel == previous {
+            run += 1;
+            if run == 62 || i + 1 == count {
+                out.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+        // The hash of the spec, with the alpha of 255 of every pixel.
+        let [r, g, b] = pixel.map(|c| c as usize);
+        let hash = (r * 3 + g * 5 + b * 7 + 255 * 11) % 64;
+        if seen[hash] == Some(pixel) {
+            out.push(OP_INDEX | hash as u8);
+        } else {
+            seen[hash] = Some(pixel);
+            let [dr, dg, db] = [0, 1, 2].map(|c| pixel[c].wrapping_sub(previous[c]) as i8);
+            let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+            if [dr, dg, db].iter().all(|d| (-2..=1).contains(d)) {
+                out.push(OP_DIFF | ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8);
+            } else if (-32..=31).contains(&dg)
+                && (-8..=7).contains(&dr_dg)
+                && (-8..=7).contains(&db_dg)
+            {
+                out.push(OP_LUMA | (dg + 32) as u8);
+                out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+            } else {
+                out.push(OP_RGB);
+                out.extend(pixel);
+            }
+        }
+        previous = pixel;
+    }
+    out.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+    out
+}
+
+/// Returns the Radiance HDR file of the `width` x `height` row-major
+/// `pixels`, a shared exponent byte for the three mantissa bytes of each
+/// color, from the top row. The negative samples are written as `0`.
+/// # Panics
+///
+/// This function panics if `pixels` does not hold exactly `width * height`
+/// colors.
+pub fn to_hdr(width: usize, height: usize, pixels: &[Color<f64>]) -> Vec<u8> {
+    assert_pixels(width, height, pixels);
+    let mut out =
+        format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {height} +X {width}\n").into_bytes();
+    for row in pixels.chunks(width.max(1)) {
+        let rgbe: Vec<[u8; 4]> = row.iter().map(|&pixel| to_rgbe(pixel)).collect();
+        // The readers only expect run-length encoded scanlines of these
+        // widths; a flat one could start like the marker of an encoded one.
+        if !(8..=0x7fff).contains(&width) {
+            out.extend(rgbe.iter().flatten());
+            continue;
+        }
+        out.extend_from_slice(&[2, 2, (width >> 8) as u8, width as u8]);
+        // Each component in turn, in runs of literal bytes.
+        for component in 0..4 {
+            for chunk in rgbe.chunks(128) {
+                out.push(chunk.len() as u8);
+                out.extend(chunk.iter().map(|bytes| bytes[component]));
+            }
+        }
+    }
+    out
+}
+
+/// Returns the mantissas and the exponent of `color`, such as the
+/// mantissa of its largest component is in `[128, 256)`.
+fn to_rgbe(color: Color<f64>) -> [u8; 4] {
+    let [r, g, b] = [color.r, color.g, color.b].map(|c| c.max(0.0));
+    let v = r.max(g).max(b);
+    if v < 1e-32 || !v.is_finite() {
+        return [0; 4];
+    }
+    // `v = m * 2^e` with `m` in `[0.5, 1)`.
+    let mut e = v.log2().floor() as i32 + 1;
+    if v / 2f64.powi(e) >= 1.0 {
+        e += 1;
+    }
+    let scale = 256.0 / 2f64.powi(e);
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (e + 128) as u8,
+    ]
+}
+
+/// Returns the PFM file of the `width` x `height` row-major `pixels`, their
+/// components as little-endian 32-bit floats, from the bottom row as the
+/// format orders them.
+/// # Panics
+///
+/// This function panics if `pixels` does not hold exactly `width * height`
+/// colors.
+pub fn to_pfm(width: usize, height: usize, pixels: &[Color<f64>]) -> Vec<u8> {
+    assert_pixels(width, height, pixels);
+    // The negative scale tells the little-endian order.
+    let mut out = format!("PF\n{width} {height}\n-1.0\n").into_bytes();
+    out.reserve(pixels.len() * 12);
+    for row in pixels.chunks(width.max(1)).rev() {
+        for pixel in row {
+            for c in [pixel.r, pixel.g, pixel.b] {
+                out.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+    }
+    out
+}
+
+fn assert_pixels(width: usize, height: usize, pixels: &[Color<f64>]) {
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "The pixels must cover the image."
+    );
+}
+
+/// Returns the raw (`P5`) 16-bit PGM of the `width` x `height` row-major
+/// single channel `values`, from black at `0` to white at `max`, such as
+/// the farthest depth. The values out of that range are clamped.
+/// # Panics
+///
+/// This function panics if `values` does not hold exactly `width * height`
+/// values, or if `max` is not positive.
+pub fn to_pgm(width: usize, height: usize, values: &[f64], max: f64) -> Vec<u8> {
+    assert_eq!(
+        values.len(),
+        width * height,
+        "The values must cover the image."
+    );
+    assert!(max > 0.0, "The white level must be positive.");
+    let mut out = format!("P5\n{} {}\n65535\n", width, height).into_bytes();
+    out.reserve(values.len() * 2);
+    for &value in values {
+        // NaN, from an empty pixel, is written as black.
+        let sample = (value / max).clamp(0.0, 1.0) * 65535.0;
+        out.extend_from_slice(&(sample.round() as u16).to_be_bytes());
+    }
+    out
+}
+
+/// Writes the PGM of [`to_pgm`] to `path`, whatever its extension.
+/// # Errors
+///
+/// This function will return an error if the file cannot be written.
+/// # Panics
+///
+/// This function panics if `values` does not hold exactly `width * height`
+/// values, or if `max` is not positive.
+pub fn save_pgm(
+    path: impl AsRef<Path>,
+    width: usize,
+    height: usize,
+    values: &[f64],
+    max: f64,
+) -> Result<(), ExportError> {
+    fs::write(path, to_pgm(width, height, values, max))?;
+    Ok(())
+}
+
+/// Writes the `width` x `height` row-major `pixels` to `path`, in the
+/// format of its extension, see [`ImageFormat::from_path`].
+/// # Errors
+///
+/// This function will return an error if the format is not supported, or
+/// the file cannot be encoded or written.
+/// # Panics
+///
+/// This function panics if `pixels` does not hold exactly `width * height`
+/// colors.
+pub fn save_pixels(
+    path: impl AsRef<Path>,
+    width: usize,
+    height: usize,
+    pixels: &[Color<f64>],
+) -> Result<(), ExportError> {
+    ImageFormat::from_path(&path)?.save(path, width, height, pixels)
+}
+
+impl<const W: usize, const H: usize, F: CanvasFormat> Canvas<W, H, f64, F> {
+    /// Writes this canvas to `path`, in the format of its extension, see
+    /// [`save_pixels`].
+    /// # Errors
+    ///
+    /// This function will return an error if the format is not supported, or
+    /// the file cannot be encoded or written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        save_pixels(path, W, H, self.pixels())
+    }
+
+    /// Writes this canvas to `path` in `format`, whatever its extension.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be encoded or
+    /// written.
+    pub fn save_as(&self, path: impl AsRef<Path>, format: ImageFormat) -> Result<(), ExportError> {
+        format.save(path, W, H, self.pixels())
+    }
+}
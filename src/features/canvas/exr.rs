@@ -0,0 +1,145 @@
+//! The OpenEXR files of the renders, with the `exr` feature: the beauty of
+//! a render and its other layers in the channels of a single image, as
+//! compositing tools expect them, written by the [`exr`] crate as
+//! uncompressed scanlines of 32-bit floats.
+use std::{io::Cursor, path::Path};
+
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, Image, Layer, LayerAttributes, WritableImage,
+};
+
+use crate::{
+    features::{camera::RenderLayers, colors::Color},
+    Float,
+};
+
+use super::export::ExportError;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The named channels of an OpenEXR image, such as `R`, `G` and `B` for
+/// the beauty and `albedo.R`, `albedo.G`... for a layer.
+pub struct ExrImage {
+    width: usize,
+    height: usize,
+    channels: Vec<(String, Vec<f32>)>,
+}
+
+impl ExrImage {
+    /// Returns an image of `width` x `height` pixels without channels.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            channels: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the names of the channels, sorted as they are written.
+    pub fn channel_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.channels.iter().map(|(n, _)| n.as_str()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns this image with the channel `name` of the row-major
+    /// `values`, replacing the one of the same name, if any.
+    /// # Panics
+    ///
+    /// This function panics if `name` is empty or if `values` does not hold
+    /// exactly `width * height` values.
+    pub fn with_channel<T: Float>(mut self, name: &str, values: &[T]) -> Self {
+        assert!(!name.is_empty(), "A channel needs a name.");
+        assert_eq!(
+            values.len(),
+            self.width * self.height,
+            "The values must cover the image."
+        );
+        let values = values
+            .iter()
+            .map(|v| v.to_f32().unwrap_or(f32::NAN))
+            .collect();
+        self.channels.retain(|(n, _)| n != name);
+        self.channels.push((name.to_string(), values));
+        self
+    }
+
+    /// Returns this image with the red, green and blue channels of the
+    /// row-major `pixels` in the layer `layer`, named `layer.R`, `layer.G`
+    /// and `layer.B`, or `R`, `G` and `B` if `layer` is empty as for the
+    /// beauty.
+    /// # Panics
+    ///
+    /// This function panics if `pixels` does not hold exactly
+    /// `width * height` colors.
+    pub fn with_layer<T: Float>(self, layer: &str, pixels: &[Color<T>]) -> Self {
+        let prefix = match layer {
+            "" => String::new(),
+            layer => format!("{layer}."),
+        };
+        let channel = |f: fn(&Color<T>) -> T| pixels.iter().map(f).collect::<Vec<T>>();
+        self.with_channel(&format!("{prefix}R"), &channel(|c| c.r))
+            .with_channel(&format!("{prefix}G"), &channel(|c| c.g))
+            .with_channel(&format!("{prefix}B"), &channel(|c| c.b))
+    }
+
+    /// Returns the OpenEXR image of this one, of a single layer holding
+    /// every channel.
+    fn to_image(&self) -> Image<Layer<AnyChannels<FlatSamples>>> {
+        let channels = self
+            .channels
+            .iter()
+            .map(|(name, values)| AnyChannel::new(name.as_str(), FlatSamples::F32(values.clone())))
+            .collect();
+        Image::from_layer(Layer::new(
+            (self.width, self.height),
+            LayerAttributes::default(),
+            Encoding::UNCOMPRESSED,
+            // The readers expect the channels in alphabetical order.
+            AnyChannels::sort(channels),
+        ))
+    }
+
+    /// Returns the OpenEXR file of this image.
+    /// # Errors
+    ///
+    /// This function will return an error if the image cannot be encoded,
+    /// such as an image without channels.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ExportError> {
+        let mut out = Cursor::new(Vec::new());
+        self.to_image().write().to_buffered(&mut out)?;
+        Ok(out.into_inner())
+    }
+
+    /// Writes the OpenEXR file of this image to `path`, whatever its
+    /// extension.
+    /// # Errors
+    ///
+    /// This function will return an error if the image cannot be encoded or
+    /// the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+impl<const W: usize, const H: usize, T: Float> RenderLayers<W, H, T> {
+    /// Returns the image of every layer: the beauty as `R`, `G` and `B`,
+    /// the `albedo` and `normal` layers, the depth as `Z` and the object
+    /// ids as `object_id`.
+    pub fn to_exr(&self) -> ExrImage {
+        ExrImage::new(W, H)
+            .with_layer("", self.beauty.pixels())
+            .with_layer("albedo", self.albedo.pixels())
+            .with_layer("normal", self.normal.pixels())
+            .with_channel("Z", &self.depth.channel(0))
+            .with_channel("object_id", &self.object_id.channel(0))
+    }
+}
@@ -8,6 +8,11 @@ use crate::Scalar;
 
 use super::colors::Color;
 
+pub mod compositing;
+pub mod denoise;
+pub mod export;
+#[cfg(feature = "exr")]
+pub mod exr;
 pub mod ppm_canvas;
 
 fn dimension<const W: usize, const H: usize>() -> usize {
@@ -20,7 +25,7 @@ pub struct Canvas<const W: usize, const H: usize, T: Scalar, F: CanvasFormat> {
     pixels: Vec<Color<T>>,
     _format: PhantomData<F>,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Plain;
 
 pub trait CanvasFormat: Debug {}
@@ -127,4 +132,31 @@ impl<const W: usize, const H: usize, T: Scalar, F: CanvasFormat> Canvas<W, H, T,
     pub fn pixels(&self) -> &[Color<T>] {
         &self.pixels
     }
+
+    /// Returns the canvas of the row-major `pixels`.
+    /// # Panics
+    ///
+    /// This function panics if `pixels` does not hold exactly `W * H` colors.
+    pub(crate) fn from_pixels(pixels: Vec<Color<T>>) -> Self {
+        assert_eq!(pixels.len(), W * H, "The pixels must cover the canvas.");
+        Self {
+            pixels,
+            _format: PhantomData,
+        }
+    }
+
+    /// Returns the row-major values of the channel of index `index` of the
+    /// pixels, `0` for red, `1` for green and `2` for blue, such as the
+    /// single channel of the depth layer.
+    /// # Panics
+    ///
+    /// This function panics if `index` is not below `3`.
+    pub fn channel(&self, index: usize) -> Vec<T> {
+        assert!(index < 3, "A color has three channels.");
+        self.pixels.iter().map(|pixel| pixel.0 .0[index]).collect()
+    }
+
+    pub(crate) fn pixels_mut(&mut self) -> &mut [Color<T>] {
+        &mut self.pixels
+    }
 }
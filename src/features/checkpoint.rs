@@ -0,0 +1,293 @@
+//! Checkpoints of the renders: the tiles already completed and their
+//! pixels, saved to disk while rendering so that a long render stopped by
+//! a crash or a reboot resumes where it was, see
+//! [`Camera::render_with_checkpoint`](crate::Camera::render_with_checkpoint).
+//!
+//! Only whole tiles are saved: the samples of the tiles still rendering
+//! when the render stops are lost, and those tiles are rendered again from
+//! scratch on resuming. The refinement of the adaptive sampling, which
+//! follows the last tile, is not saved either.
+//!
+//! A checkpoint records the seed of the render and a hash of its scene,
+//! camera and integrator besides the canvas and its tiles, so that it is
+//! not resumed by a render of something else.
+//!
+//! The file starts with the magic `RTCKPT2\n`, followed by little-endian
+//! values: the size of the canvas, the seed, the hash of the scene and the
+//! number of tiles as `u64`, the
+//! bounds of each tile as four `u64` and a byte set to `1` once it is
+//! completed, then the RGB components of every pixel as `f64`, row by row.
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    fs, io,
+    path::Path,
+};
+
+use crate::{
+    features::{camera::PixelBounds, colors::Color},
+    Float,
+};
+
+const MAGIC: &[u8] = b"RTCKPT2\n";
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    /// The file is not a checkpoint, or it is truncated.
+    InvalidData,
+    /// The checkpoint is of another canvas, other tiles, another seed or
+    /// another scene.
+    Mismatch,
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io(e) => write!(f, "Failed to access the checkpoint: {e}"),
+            CheckpointError::InvalidData => write!(f, "The checkpoint is truncated or invalid."),
+            CheckpointError::Mismatch => {
+                write!(f, "The checkpoint is of another render.")
+            }
+        }
+    }
+}
+
+impl Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> Self {
+        CheckpointError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The state of a render of a canvas of `hsize` x `vsize` pixels split
+/// into `tiles`: which tiles are completed, and the pixels they hold.
+pub struct Checkpoint<T: Float> {
+    hsize: usize,
+    vsize: usize,
+    seed: u64,
+    /// The hash of the scene, see [`fingerprint`].
+    scene_hash: u64,
+    tiles: Vec<PixelBounds>,
+    done: Vec<bool>,
+    /// Row-major pixels, black until their tile is completed.
+    pixels: Vec<Color<T>>,
+}
+
+/// Reads the little-endian `u64` at `pos`, moving past it.
+fn read_u64(data: &[u8], pos: &mut usize) -> Result<u64, CheckpointError> {
+    let bytes = data
+        .get(*pos..*pos + 8)
+        .ok_or(CheckpointError::InvalidData)?;
+    *pos += 8;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Returns the 64-bit FNV-1a hash of the debug text of `values`, which
+/// unlike the hashers of the standard library is the same from one build
+/// to the next.
+pub(crate) fn fingerprint(values: &[&dyn Debug]) -> u64 {
+    struct Fnv(u64);
+    impl fmt::Write for Fnv {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for byte in s.bytes() {
+                self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+            Ok(())
+        }
+    }
+    let mut hash = Fnv(0xcbf2_9ce4_8422_2325);
+    for value in values {
+        // Writing to the hash never fails.
+        let _ = fmt::write(&mut hash, format_args!("{value:?}\u{0}"));
+    }
+    hash.0
+}
+
+impl<T: Float> Checkpoint<T> {
+    /// Returns the state of a render which has not started yet, of seed
+    /// and scene hash zero, see [`Checkpoint::with_scene`].
+    pub fn new(hsize: usize, vsize: usize, tiles: Vec<PixelBounds>) -> Self {
+        Self {
+            hsize,
+            vsize,
+            seed: 0,
+            scene_hash: 0,
+            done: vec![false; tiles.len()],
+            tiles,
+            pixels: vec![Color::default(); hsize * vsize],
+        }
+    }
+
+    pub fn hsize(&self) -> usize {
+        self.hsize
+    }
+
+    pub fn vsize(&self) -> usize {
+        self.vsize
+    }
+
+    /// Returns this checkpoint as one of the render of `seed` of the scene
+    /// of hash `scene_hash`, see
+    /// [`Camera::checkpoint`](crate::Camera::checkpoint).
+    pub fn with_scene(mut self, seed: u64, scene_hash: u64) -> Self {
+        self.seed = seed;
+        self.scene_hash = scene_hash;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn scene_hash(&self) -> u64 {
+        self.scene_hash
+    }
+
+    /// Returns `true` if this checkpoint and `other` are of the same
+    /// render: the same canvas, tiles, seed and scene, whatever their
+    /// completed tiles.
+    pub fn is_of_same_render(&self, other: &Self) -> bool {
+        (self.hsize, self.vsize, self.seed, self.scene_hash)
+            == (other.hsize, other.vsize, other.seed, other.scene_hash)
+            && self.tiles == other.tiles
+    }
+
+    pub fn tiles(&self) -> &[PixelBounds] {
+        &self.tiles
+    }
+
+    /// Returns `true` if the tile at `index` is completed.
+    pub fn is_done(&self, index: usize) -> bool {
+        self.done[index]
+    }
+
+    /// Returns the number of completed tiles.
+    pub fn tiles_done(&self) -> usize {
+        self.done.iter().filter(|&&done| done).count()
+    }
+
+    /// Returns the pixel at `(x, y)`.
+    /// # Panics
+    ///
+    /// This function panics if `(x, y)` is outside of the canvas.
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color<T> {
+        assert!(x < self.hsize && y < self.vsize);
+        self.pixels[y * self.hsize + x]
+    }
+
+    /// Records the completed tile at `index`, its pixels being given by
+    /// `pixel`.
+    pub fn complete_tile(&mut self, index: usize, mut pixel: impl FnMut(usize, usize) -> Color<T>) {
+        for (x, y) in self.tiles[index].pixels() {
+            self.pixels[y * self.hsize + x] = pixel(x, y);
+        }
+        self.done[index] = true;
+    }
+
+    /// Returns the content of the checkpoint file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        let mut push = |value: u64| data.extend(value.to_le_bytes());
+        push(self.hsize as u64);
+        push(self.vsize as u64);
+        push(self.seed);
+        push(self.scene_hash);
+        push(self.tiles.len() as u64);
+        for (tile, &done) in self.tiles.iter().zip(&self.done) {
+            for value in [tile.min_x, tile.min_y, tile.max_x, tile.max_y] {
+                data.extend((value as u64).to_le_bytes());
+            }
+            data.push(u8::from(done));
+        }
+        for pixel in &self.pixels {
+            for c in [pixel.r, pixel.g, pixel.b] {
+                data.extend(c.to_f64().unwrap_or(0.0).to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// Reads a checkpoint from the content of its file.
+    /// # Errors
+    ///
+    /// This function returns [`CheckpointError::InvalidData`] if the
+    /// content is not a valid checkpoint.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, CheckpointError> {
+        if !data.starts_with(MAGIC) {
+            return Err(CheckpointError::InvalidData);
+        }
+        let mut pos = MAGIC.len();
+        let next = |pos: &mut usize| read_u64(data, pos).map(|value| value as usize);
+        let (hsize, vsize) = (next(&mut pos)?, next(&mut pos)?);
+        let (seed, scene_hash) = (read_u64(data, &mut pos)?, read_u64(data, &mut pos)?);
+        let count = next(&mut pos)?;
+        // Each tile takes 33 bytes, each pixel 24.
+        let size = hsize
+            .checked_mul(vsize)
+            .ok_or(CheckpointError::InvalidData)?;
+        if data.len() - pos
+            < count
+                .saturating_mul(33)
+                .saturating_add(size.saturating_mul(24))
+        {
+            return Err(CheckpointError::InvalidData);
+        }
+        let mut tiles = Vec::with_capacity(count);
+        let mut done = Vec::with_capacity(count);
+        for _ in 0..count {
+            let (min_x, min_y) = (next(&mut pos)?, next(&mut pos)?);
+            let (max_x, max_y) = (next(&mut pos)?, next(&mut pos)?);
+            if min_x > max_x || min_y > max_y || max_x > hsize || max_y > vsize {
+                return Err(CheckpointError::InvalidData);
+            }
+            tiles.push(PixelBounds::new(min_x, min_y, max_x, max_y));
+            done.push(data[pos] == 1);
+            pos += 1;
+        }
+        let pixels = data[pos..pos + size * 24]
+            .chunks_exact(24)
+            .map(|rgb| {
+                let c = |i: usize| {
+                    T::from_f64(f64::from_le_bytes(
+                        rgb[i * 8..i * 8 + 8].try_into().unwrap(),
+                    ))
+                };
+                Color::new(c(0), c(1), c(2))
+            })
+            .collect();
+        Ok(Self {
+            hsize,
+            vsize,
+            seed,
+            scene_hash,
+            tiles,
+            done,
+            pixels,
+        })
+    }
+
+    /// Writes this checkpoint to the file at `path`, through a temporary
+    /// file so that a crash while writing keeps the previous checkpoint.
+    /// # Errors
+    ///
+    /// This function returns an [`io::Error`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_bytes())?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Reads a checkpoint from the file at `path`.
+    /// # Errors
+    ///
+    /// This function returns a [`CheckpointError`] if the file cannot be
+    /// read or is not a valid checkpoint.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
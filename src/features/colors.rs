@@ -16,6 +16,14 @@ impl<T: Scalar> Color<T> {
     }
 }
 
+impl<T: Float> Color<T> {
+    /// Returns the perceived brightness of this color, with the Rec. 709
+    /// weights of the components.
+    pub fn luminance(&self) -> T {
+        self.r * T::from_f64(0.2126) + self.g * T::from_f64(0.7152) + self.b * T::from_f64(0.0722)
+    }
+}
+
 // `Zeroable` impls for "Color" types are sound:
 //
 // - They are inhabited: structs plus bound `T: Zeroable`.
@@ -0,0 +1,319 @@
+//! Tools for debugging scenes. [`World::debug_trace`] records the tree of
+//! rays behind the color of a pixel, with every intersection found and
+//! every term of the shading, following [`World::color_at`]. The
+//! [`DebugView`] integrators render the geometry instead of its shading.
+use std::fmt::{self, Display, Formatter};
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    features::{
+        camera::Camera,
+        colors::Color,
+        integrators::Integrator,
+        intersections::{Computations, Intersections},
+        lights::{phong_terms, PhongTerms, PointLight},
+        rays::Ray,
+        shapes::bounds::BoundingBox,
+        world::{World, MAX_DEPTH},
+    },
+    Float,
+};
+
+#[derive(Debug, Clone)]
+/// The trace of a ray: what it hits and the color it brings back.
+pub struct RayTrace<'a, T: Float> {
+    pub ray: Ray<T>,
+    /// The number of bounces still allowed to the reflected rays.
+    pub remaining: usize,
+    /// Every intersection of the ray, sorted by `t`.
+    pub intersections: Intersections<'a, T>,
+    /// The shading of the visible hit, `None` if the ray hits nothing.
+    pub hit: Option<HitTrace<'a, T>>,
+    /// The color seen along the ray, after the fog.
+    pub color: Color<T>,
+}
+
+#[derive(Debug, Clone)]
+/// The trace of the shading of a hit.
+pub struct HitTrace<'a, T: Float> {
+    pub comps: Computations<'a, T>,
+    /// The contribution of each light of the world, in their order.
+    pub lights: Vec<LightTrace<T>>,
+    pub emissive: Color<T>,
+    /// The trace of the reflected ray, if the surface is reflective and a
+    /// bounce remains. Refraction is not traced by the renderer.
+    pub reflection: Option<Box<RayTrace<'a, T>>>,
+    /// The color of the hit before the fog: the sum of the lights, the
+    /// emission and the reflection weighted by the reflectivity.
+    pub color: Color<T>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The contribution of a light to the shading of a hit.
+pub struct LightTrace<T: Float> {
+    pub light: PointLight<T>,
+    /// Whether an object casting shadows lies between the hit and the light.
+    pub in_shadow: bool,
+    pub terms: PhongTerms<T>,
+}
+
+impl<T: Float> World<T> {
+    /// Returns the trace of the ray through the center of the pixel `(x, y)`
+    /// of `camera`, as rendered by [`Camera::render`]; `None` if the
+    /// projection maps nothing there.
+    pub fn debug_trace(&self, camera: &Camera<T>, x: usize, y: usize) -> Option<RayTrace<'_, T>> {
+        Some(self.trace_ray(&camera.ray_for_pixel(x, y)?, camera.settings().max_depth))
+    }
+
+    /// Returns the trace of `ray`, whose color is the one of
+    /// [`World::color_at`] with the same bounces `remaining`.
+    pub fn trace_ray(&self, ray: &Ray<T>, remaining: usize) -> RayTrace<'_, T> {
+        let intersections = self.intersect(ray);
+        let (hit, color, distance) = match intersections.hit() {
+            Some(x) => {
+                let comps = x.prepare_computations_with_bias(ray, self.shadow_bias());
+                let hit = self.trace_hit(comps, remaining);
+                let color = hit.color;
+                (Some(hit), color, x.t() * ray.direction().magnitude())
+            }
+            None => (None, self.background_at(ray.direction()), T::infinity()),
+        };
+        RayTrace {
+            ray: *ray,
+            remaining,
+            intersections,
+            hit,
+            color: match self.fog() {
+                Some(fog) => fog.apply(color, distance),
+                None => color,
+            },
+        }
+    }
+
+    /// Returns the trace of the shading of `comps` by [`World::shade_hit`].
+    fn trace_hit<'a>(&'a self, comps: Computations<'a, T>, remaining: usize) -> HitTrace<'a, T> {
+        let material = comps.object.material();
+        let lights: Vec<_> = self
+            .lights()
+            .iter()
+            .map(|light| {
+                if comps.object.is_medium() {
+                    // See `World::shade_medium`.
+                    let in_shadow = self.is_shadowed_at(comps.point, light, comps.time);
+                    let color = material.color_at(comps.object, comps.point);
+                    let diffuse = if in_shadow {
+                        Color::default()
+                    } else {
+                        color * light.intensity_at(comps.point) * material.diffuse
+                    };
+                    return LightTrace {
+                        light: *light,
+                        in_shadow,
+                        terms: PhongTerms {
+                            ambient: color * light.intensity() * material.ambient,
+                            diffuse,
+                            specular: Color::default(),
+                        },
+                    };
+                }
+                let in_shadow = comps.object.receives_shadow()
+                    && self.is_shadowed_at(comps.over_point, light, comps.time);
+                let terms = phong_terms(
+                    material,
+                    comps.object,
+                    light,
+                    comps.over_point,
+                    comps.eyev,
+                    comps.normalv,
+                    in_shadow,
+                );
+                LightTrace {
+                    light: *light,
+                    in_shadow,
+                    terms,
+                }
+            })
+            .collect();
+        let reflection = (!comps.object.is_medium()
+            && remaining > 0
+            && material.reflective != T::zero())
+        .then(|| {
+            let ray = Ray::new(comps.over_point, comps.reflectv).with_time(comps.time);
+            Box::new(self.trace_ray(&ray, remaining - 1))
+        });
+        let lit = lights
+            .iter()
+            .fold(Color::default(), |color, light| color + light.terms.total());
+        let reflected = reflection
+            .as_ref()
+            .map_or(Color::default(), |r| r.color * material.reflective);
+        HitTrace {
+            comps,
+            lights,
+            emissive: material.emissive,
+            reflection,
+            color: lit + material.emissive + reflected,
+        }
+    }
+}
+
+impl<T: Float> Display for RayTrace<'_, T> {
+    /// Writes the tree of rays, one line per fact, each reflection indented
+    /// below its hit.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+impl<T: Float> RayTrace<'_, T> {
+    fn write_indented(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        let pad = "  ".repeat(depth);
+        writeln!(
+            f,
+            "{pad}ray from {:?} towards {:?} at time {:?}, {} bounces remaining",
+            self.ray.origin(),
+            self.ray.direction(),
+            self.ray.time(),
+            self.remaining
+        )?;
+        let ts: Vec<_> = self.intersections.iter().map(|x| x.t()).collect();
+        writeln!(f, "{pad}intersections at t = {ts:?}")?;
+        match &self.hit {
+            None => writeln!(f, "{pad}no hit, background")?,
+            Some(hit) => {
+                let comps = &hit.comps;
+                writeln!(
+                    f,
+                    "{pad}hit at t = {:?}, point {:?}, normal {:?}{}",
+                    comps.t,
+                    comps.point,
+                    comps.normalv,
+                    if comps.inside { ", inside" } else { "" }
+                )?;
+                for (i, light) in hit.lights.iter().enumerate() {
+                    writeln!(
+                        f,
+                        "{pad}light {i}{}: ambient {:?}, diffuse {:?}, specular {:?}",
+                        if light.in_shadow { " (in shadow)" } else { "" },
+                        light.terms.ambient,
+                        light.terms.diffuse,
+                        light.terms.specular
+                    )?;
+                }
+                writeln!(f, "{pad}emissive {:?}", hit.emissive)?;
+                if let Some(reflection) = &hit.reflection {
+                    writeln!(f, "{pad}reflection:")?;
+                    reflection.write_indented(f, depth + 1)?;
+                }
+            }
+        }
+        writeln!(f, "{pad}color {:?}", self.color)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Renders showing the geometry of a scene rather than its shading, to
+/// diagnose the modelling or the performance: pass one to
+/// [`Camera::render_with`]. The rays hitting nothing are black.
+pub enum DebugView {
+    /// The normal at the visible hit in world space, each component mapped
+    /// from `[-1, 1]` to `[0, 1]` as red, green and blue.
+    Normals,
+    /// The distance to the visible hit as a heat map, from blue at the
+    /// camera to red at `max_distance` and beyond.
+    Depth { max_distance: f64 },
+    /// The number of intersections along the whole ray as a heat map,
+    /// from blue for one to red for `max` and more.
+    IntersectionCount { max: usize },
+    /// The shading of [`World::color_at`] overlaid with the edges of the
+    /// boxes of the accelerator of the world, or of its objects without
+    /// one, seen through everything. The edges are `line_width` wide at
+    /// a distance of one from the camera, and widen with the distance.
+    Bounds { line_width: f64 },
+}
+
+impl<T: Float> Integrator<T> for DebugView {
+    fn radiance(&self, world: &World<T>, ray: &Ray<T>, _rng: &mut SmallRng) -> Color<T> {
+        let xs = world.intersect(ray);
+        match *self {
+            DebugView::Normals => xs.hit().map_or(Color::default(), |hit| {
+                let n = hit
+                    .prepare_computations_with_bias(ray, world.shadow_bias())
+                    .normalv;
+                let half = T::one() / T::two();
+                Color::new(
+                    (n.x + T::one()) * half,
+                    (n.y + T::one()) * half,
+                    (n.z + T::one()) * half,
+                )
+            }),
+            DebugView::Depth { max_distance } => xs.hit().map_or(Color::default(), |hit| {
+                let distance = hit.t() * ray.direction().magnitude();
+                heat(distance / T::from_f64(max_distance))
+            }),
+            DebugView::IntersectionCount { max } => match xs.len() {
+                0 => Color::default(),
+                n if max <= 1 => heat(T::from_f64(n as f64)),
+                n => heat(T::from_f64((n - 1) as f64 / (max - 1) as f64)),
+            },
+            DebugView::Bounds { line_width } => {
+                let boxes = match world.accelerator() {
+                    Some(accelerator) => accelerator.boxes(),
+                    None => world
+                        .objects()
+                        .iter()
+                        .map(|object| object.parent_space_bounds())
+                        .collect(),
+                };
+                let width = T::from_f64(line_width) * ray.direction().magnitude();
+                if boxes.iter().any(|b| on_edge(b, ray, width)) {
+                    Color::new(T::zero(), T::one(), T::zero())
+                } else {
+                    world.color_at(ray, MAX_DEPTH)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the color of `value` on a heat map going through blue, cyan,
+/// green, yellow and red from `0` to `1`.
+fn heat<T: Float>(value: T) -> Color<T> {
+    let v = value.max(T::zero()).min(T::one()) * T::four();
+    let (zero, one) = (T::zero(), T::one());
+    if v < one {
+        Color::new(zero, v, one)
+    } else if v < T::two() {
+        Color::new(zero, one, T::two() - v)
+    } else if v < T::three() {
+        Color::new(v - T::two(), one, zero)
+    } else {
+        Color::new(one, T::four() - v, zero)
+    }
+}
+
+/// Returns `true` if `ray` enters or leaves the finite box `b` within
+/// `width * t` of one of its edges, at some `t >= 0`.
+fn on_edge<T: Float>(b: &BoundingBox<T>, ray: &Ray<T>, width: T) -> bool {
+    if !b.is_finite() {
+        return false;
+    }
+    let Some((t_min, t_max)) = b.intersection_range(ray) else {
+        return false;
+    };
+    [t_min, t_max].into_iter().any(|t| {
+        if t < T::zero() {
+            return false;
+        }
+        let p = ray.position(t);
+        let tolerance = width * t;
+        let near_faces = (0..3)
+            .filter(|&axis| {
+                (p[axis] - b.min()[axis]).abs() <= tolerance
+                    || (p[axis] - b.max()[axis]).abs() <= tolerance
+            })
+            .count();
+        near_faces >= 2
+    })
+}
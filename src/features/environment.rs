@@ -0,0 +1,172 @@
+//! Implementation of `EnvironmentMap`, an equirectangular image of the
+//! light arriving from every direction, such as a photographed sky. It
+//! lights the scene in the path tracer, which samples its bright regions
+//! directly.
+use rand::Rng;
+
+use crate::{
+    features::{
+        colors::Color, patterns::uv_mapping::spherical_map, patterns::uv_patterns::UvImage,
+    },
+    Float, Point3, Vector3,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// The radiance arriving from every direction, read from an equirectangular
+/// image: the columns span the longitudes and the rows the latitudes, the
+/// top row being `+y`, as in [`spherical_map`].
+///
+/// The image is usually HDR, e.g. read with [`UvImage::from_pfm`]; the
+/// `intensity` scales its values.
+pub struct EnvironmentMap<T: Float> {
+    image: UvImage<T>,
+    intensity: T,
+    /// The probability of picking each pixel, proportional to the
+    /// luminance times the solid angle it covers.
+    pixel_pmf: Vec<T>,
+    /// The cumulative distribution of the rows.
+    row_cdf: Vec<T>,
+    /// The cumulative distribution of the pixels within each row.
+    col_cdfs: Vec<T>,
+}
+
+/// Returns the index of the first value of the cumulative distribution
+/// `cdf` above `r`.
+fn search<T: Float>(cdf: &[T], r: T) -> usize {
+    cdf.partition_point(|&c| c <= r).min(cdf.len() - 1)
+}
+
+/// Turns `values` into their cumulative sums divided by the total, and
+/// returns the total. All zero values give a uniform distribution.
+fn normalize_cdf<T: Float>(values: &mut [T]) -> T {
+    let mut total = T::zero();
+    for value in values.iter_mut() {
+        total += *value;
+        *value = total;
+    }
+    let n = T::from_f64(values.len() as f64);
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = if total > T::zero() {
+            *value / total
+        } else {
+            T::from_f64((i + 1) as f64) / n
+        };
+    }
+    total
+}
+
+impl<T: Float> EnvironmentMap<T> {
+    pub fn new(image: UvImage<T>) -> Self {
+        let (width, height) = (image.width(), image.height());
+        let mut weights = Vec::with_capacity(width * height);
+        for y in 0..height {
+            // The rows near the poles cover a smaller solid angle.
+            let theta = T::PI() * T::from_f64((y as f64 + 0.5) / height as f64);
+            for x in 0..width {
+                weights.push(image.pixel_at(x, y).luminance().max(T::zero()) * theta.sin());
+            }
+        }
+        let total = weights.iter().fold(T::zero(), |sum, &w| sum + w);
+        let pixel_pmf = weights
+            .iter()
+            .map(|&w| {
+                if total > T::zero() {
+                    w / total
+                } else {
+                    T::one() / T::from_f64((width * height) as f64)
+                }
+            })
+            .collect();
+        let mut col_cdfs = weights;
+        let mut row_cdf: Vec<T> = col_cdfs
+            .chunks_exact_mut(width)
+            .map(normalize_cdf)
+            .collect();
+        normalize_cdf(&mut row_cdf);
+        Self {
+            image,
+            intensity: T::one(),
+            pixel_pmf,
+            row_cdf,
+            col_cdfs,
+        }
+    }
+
+    /// Scales the radiance of the image by `intensity`.
+    pub fn with_intensity(mut self, intensity: T) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn image(&self) -> &UvImage<T> {
+        &self.image
+    }
+
+    pub fn intensity(&self) -> T {
+        self.intensity
+    }
+
+    /// Returns the pixel seen in the unit `direction`.
+    fn pixel_of(&self, direction: Vector3<T>) -> (usize, usize) {
+        let (u, v) = spherical_map(Point3::new(direction.x, direction.y, direction.z));
+        let index = |c: T, len: usize| c.floor().to_usize().unwrap_or(0).min(len - 1);
+        let x = index(
+            u * T::from_f64(self.image.width() as f64),
+            self.image.width(),
+        );
+        let y = index(
+            (T::one() - v) * T::from_f64(self.image.height() as f64),
+            self.image.height(),
+        );
+        (x, y)
+    }
+
+    /// Returns the radiance arriving from `direction`.
+    pub fn radiance(&self, direction: Vector3<T>) -> Color<T> {
+        let (x, y) = self.pixel_of(direction.normalized());
+        self.image.pixel_at(x, y) * self.intensity
+    }
+
+    /// Returns the density, by solid angle, of [`EnvironmentMap::sample`]
+    /// drawing the unit `direction`.
+    pub fn pdf(&self, direction: Vector3<T>) -> T {
+        let sin_theta = (T::one() - direction.y * direction.y).max(T::zero()).sqrt();
+        if sin_theta == T::zero() {
+            return T::zero();
+        }
+        let (x, y) = self.pixel_of(direction);
+        self.pixel_pdf(x, y, sin_theta)
+    }
+
+    /// Returns the density by solid angle of the directions in the pixel
+    /// `(x, y)` at the polar angle of sine `sin_theta`.
+    fn pixel_pdf(&self, x: usize, y: usize, sin_theta: T) -> T {
+        let (width, height) = (self.image.width(), self.image.height());
+        // The samples are uniform in (u, v) within the pixel, and a unit
+        // square of (u, v) spans 2π² sin(θ) steradians.
+        let pdf_uv = self.pixel_pmf[y * width + x] * T::from_f64((width * height) as f64);
+        pdf_uv / (T::two() * T::PI() * T::PI() * sin_theta)
+    }
+
+    /// Returns a random unit direction, drawn in proportion to the
+    /// brightness of the map, with its radiance and its density by solid
+    /// angle.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> (Vector3<T>, Color<T>, T) {
+        let (width, height) = (self.image.width(), self.image.height());
+        let y = search(&self.row_cdf, T::from_f64(rng.gen()));
+        let x = search(
+            &self.col_cdfs[y * width..(y + 1) * width],
+            T::from_f64(rng.gen()),
+        );
+        // A uniform point in the pixel.
+        let u = T::from_f64((x as f64 + rng.gen::<f64>()) / width as f64);
+        let v = T::one() - T::from_f64((y as f64 + rng.gen::<f64>()) / height as f64);
+        let theta = (T::one() - v) * T::PI();
+        let phi = T::PI() - T::two() * T::PI() * u;
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let direction = Vector3::new(sin_theta * sin_phi, cos_theta, sin_theta * cos_phi);
+        let radiance = self.image.pixel_at(x, y) * self.intensity;
+        (direction, radiance, self.pixel_pdf(x, y, sin_theta))
+    }
+}
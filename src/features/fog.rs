@@ -0,0 +1,69 @@
+//! Implementation of `Fog`, a homogeneous medium filling the scene: the
+//! farther a surface, the more its color fades into the color of the fog.
+use crate::{features::colors::Color, Float};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How the visibility through the fog decreases with the distance `d`.
+pub enum FogFalloff<T: Float> {
+    /// Fully visible before `start`, hidden after `end`, linear in between.
+    Linear { start: T, end: T },
+    /// The visibility is `exp(-density * d)`, the physical absorption.
+    Exponential { density: T },
+    /// The visibility is `exp(-(density * d)²)`, clearer near the eye and
+    /// thicker in the distance.
+    ExponentialSquared { density: T },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A fog of uniform `color`, fading surfaces by their distance to the eye.
+pub struct Fog<T: Float> {
+    pub color: Color<T>,
+    pub falloff: FogFalloff<T>,
+}
+
+impl<T: Float> Fog<T> {
+    /// Returns a fog hiding the surfaces linearly between `start` and `end`.
+    pub fn linear(color: Color<T>, start: T, end: T) -> Self {
+        Self {
+            color,
+            falloff: FogFalloff::Linear { start, end },
+        }
+    }
+
+    /// Returns a fog absorbing the light exponentially with `density`.
+    pub fn exponential(color: Color<T>, density: T) -> Self {
+        Self {
+            color,
+            falloff: FogFalloff::Exponential { density },
+        }
+    }
+
+    /// Returns the fraction of a surface still visible at `distance`, in `[0, 1]`.
+    pub fn visibility(&self, distance: T) -> T {
+        let visibility = match self.falloff {
+            FogFalloff::Linear { start, end } => {
+                if end <= start {
+                    return if distance < start {
+                        T::one()
+                    } else {
+                        T::zero()
+                    };
+                }
+                (end - distance) / (end - start)
+            }
+            FogFalloff::Exponential { density } => (-density * distance).exp(),
+            FogFalloff::ExponentialSquared { density } => {
+                let x = density * distance;
+                (-x * x).exp()
+            }
+        };
+        visibility.max(T::zero()).min(T::one())
+    }
+
+    /// Returns `color` seen through the fog at `distance`. Rays hitting
+    /// nothing are seen at an infinite distance: the color of the fog.
+    pub fn apply(&self, color: Color<T>, distance: T) -> Color<T> {
+        let visibility = self.visibility(distance);
+        color * visibility + self.color * (T::one() - visibility)
+    }
+}
@@ -0,0 +1,289 @@
+//! Readers of images into a [`UvImage`], to texture the shapes. The plain
+//! (`P3`) and raw (`P6`) PPM formats are always supported, PNG with the
+//! `png` feature. The samples are scaled by the maximum value of the file
+//! into `[0, 1]`, as written by [`PPMCanvas`](crate::PPMCanvas).
+//! The PFM format keeps the floating point values of HDR images, such as
+//! the [`EnvironmentMap`](crate::EnvironmentMap).
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+use crate::{
+    features::{colors::Color, patterns::uv_patterns::UvImage},
+    Float,
+};
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    /// The header is malformed.
+    InvalidHeader,
+    /// The magic number is not one of a supported format.
+    UnsupportedFormat(String),
+    /// The pixels are truncated or hold a value which cannot be read.
+    InvalidData,
+    /// The PNG decoder failed.
+    #[cfg(feature = "png")]
+    Png(png::DecodingError),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::Io(e) => write!(f, "Failed to read the image file: {e}"),
+            ImageError::InvalidHeader => write!(f, "Invalid image header."),
+            ImageError::UnsupportedFormat(magic) => {
+                write!(f, "The image format `{magic}` is not supported.")
+            }
+            ImageError::InvalidData => write!(f, "The image data is truncated or invalid."),
+            #[cfg(feature = "png")]
+            ImageError::Png(e) => write!(f, "Failed to decode the PNG image: {e}"),
+        }
+    }
+}
+
+impl Error for ImageError {}
+
+impl From<io::Error> for ImageError {
+    fn from(e: io::Error) -> Self {
+        ImageError::Io(e)
+    }
+}
+
+#[cfg(feature = "png")]
+impl From<png::DecodingError> for ImageError {
+    fn from(e: png::DecodingError) -> Self {
+        ImageError::Png(e)
+    }
+}
+
+/// Returns the next whitespace separated token of a PPM header from `pos`,
+/// skipping the `#` comments up to the end of their line.
+fn next_token<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a str> {
+    loop {
+        while *pos < data.len() && data[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if *pos < data.len() && data[*pos] == b'#' {
+            while *pos < data.len() && data[*pos] != b'\n' {
+                *pos += 1;
+            }
+        } else {
+            break;
+        }
+    }
+    let start = *pos;
+    while *pos < data.len() && !data[*pos].is_ascii_whitespace() && data[*pos] != b'#' {
+        *pos += 1;
+    }
+    std::str::from_utf8(&data[start..*pos])
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+impl<T: Float> UvImage<T> {
+    /// Reads an image from the content of a PPM file, either plain (`P3`)
+    /// or raw (`P6`).
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the content is not a
+    /// valid PPM image.
+    pub fn from_ppm(data: &[u8]) -> Result<Self, ImageError> {
+        let mut pos = 0;
+        let magic = next_token(data, &mut pos).ok_or(ImageError::InvalidHeader)?;
+        if magic != "P3" && magic != "P6" {
+            return Err(ImageError::UnsupportedFormat(magic.to_string()));
+        }
+        let mut header = [0usize; 3];
+        for value in header.iter_mut() {
+            *value = next_token(data, &mut pos)
+                .and_then(|token| token.parse().ok())
+                .ok_or(ImageError::InvalidHeader)?;
+        }
+        let [width, height, max_value] = header;
+        if width == 0 || height == 0 || max_value == 0 || max_value > 65535 {
+            return Err(ImageError::InvalidHeader);
+        }
+        let scale = T::from_f64(max_value as f64);
+        let samples: Vec<T> = if magic == "P3" {
+            (0..width * height * 3)
+                .map(|_| {
+                    next_token(data, &mut pos)
+                        .and_then(|token| token.parse::<u16>().ok())
+                        .map(|value| T::from_f64(value as f64) / scale)
+                        .ok_or(ImageError::InvalidData)
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            // A single whitespace separates the header from the raw samples,
+            // stored on two big-endian bytes above 255.
+            let body = data.get(pos + 1..).ok_or(ImageError::InvalidData)?;
+            let size = if max_value < 256 { 1 } else { 2 };
+            if body.len() < width * height * 3 * size {
+                return Err(ImageError::InvalidData);
+            }
+            body.chunks_exact(size)
+                .take(width * height * 3)
+                .map(|bytes| {
+                    let value = bytes.iter().fold(0u32, |acc, &b| acc << 8 | b as u32);
+                    T::from_f64(value as f64) / scale
+                })
+                .collect()
+        };
+        let pixels = samples
+            .chunks_exact(3)
+            .map(|rgb| Color::new(rgb[0], rgb[1], rgb[2]))
+            .collect();
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /// Reads an image from the PPM file at `path`.
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the file cannot be read or
+    /// is not a valid PPM image.
+    pub fn from_ppm_file(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Self::from_ppm(&fs::read(path)?)
+    }
+
+    /// Reads an HDR image from the content of a PFM file, either in color
+    /// (`PF`) or grayscale (`Pf`).
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the content is not a
+    /// valid PFM image.
+    pub fn from_pfm(data: &[u8]) -> Result<Self, ImageError> {
+        let mut pos = 0;
+        let magic = next_token(data, &mut pos).ok_or(ImageError::InvalidHeader)?;
+        let channels = match magic {
+            "PF" => 3,
+            "Pf" => 1,
+            _ => return Err(ImageError::UnsupportedFormat(magic.to_string())),
+        };
+        let mut size = [0usize; 2];
+        for value in size.iter_mut() {
+            *value = next_token(data, &mut pos)
+                .and_then(|token| token.parse().ok())
+                .ok_or(ImageError::InvalidHeader)?;
+        }
+        let [width, height] = size;
+        // The sign of the scale gives the byte order, negative for little-endian.
+        let scale: f32 = next_token(data, &mut pos)
+            .and_then(|token| token.parse().ok())
+            .filter(|&scale: &f32| scale != 0.0)
+            .ok_or(ImageError::InvalidHeader)?;
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidHeader);
+        }
+        let body = data.get(pos + 1..).ok_or(ImageError::InvalidData)?;
+        if body.len() < width * height * channels * 4 {
+            return Err(ImageError::InvalidData);
+        }
+        let samples: Vec<T> = body
+            .chunks_exact(4)
+            .take(width * height * channels)
+            .map(|bytes| {
+                let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+                let value = if scale < 0.0 {
+                    f32::from_le_bytes(bytes)
+                } else {
+                    f32::from_be_bytes(bytes)
+                };
+                T::from_f64(value as f64)
+            })
+            .collect();
+        // The rows are stored from the bottom of the image to the top.
+        let pixels = samples
+            .chunks_exact(width * channels)
+            .rev()
+            .flat_map(|row| row.chunks_exact(channels))
+            .map(|c| {
+                if channels == 1 {
+                    Color::new(c[0], c[0], c[0])
+                } else {
+                    Color::new(c[0], c[1], c[2])
+                }
+            })
+            .collect();
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /// Reads an HDR image from the PFM file at `path`.
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the file cannot be read or
+    /// is not a valid PFM image.
+    pub fn from_pfm_file(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Self::from_pfm(&fs::read(path)?)
+    }
+
+    /// Reads an image from the content of a PNG file. The alpha channel is
+    /// dropped.
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the content is not a
+    /// valid PNG image.
+    #[cfg(feature = "png")]
+    pub fn from_png(data: &[u8]) -> Result<Self, ImageError> {
+        let mut decoder = png::Decoder::new(data);
+        // Palettes and bit depths below 8 are expanded to 8 bits samples.
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)?;
+        let (size, max_value) = match info.bit_depth {
+            png::BitDepth::Sixteen => (2, 65535.0),
+            _ => (1, 255.0),
+        };
+        let channels = info.color_type.samples();
+        let scale = T::from_f64(max_value);
+        let sample = |bytes: &[u8]| {
+            let value = bytes.iter().fold(0u32, |acc, &b| acc << 8 | b as u32);
+            T::from_f64(value as f64) / scale
+        };
+        let (width, height) = (info.width as usize, info.height as usize);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in buf[..info.buffer_size()].chunks_exact(info.line_size) {
+            for pixel in row.chunks_exact(channels * size).take(width) {
+                let value = |c: usize| sample(&pixel[c * size..(c + 1) * size]);
+                pixels.push(if channels < 3 {
+                    // Grayscale, with or without alpha.
+                    let gray = value(0);
+                    Color::new(gray, gray, gray)
+                } else {
+                    Color::new(value(0), value(1), value(2))
+                });
+            }
+        }
+        if pixels.len() != width * height {
+            return Err(ImageError::InvalidData);
+        }
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /// Reads an image from the PNG file at `path`.
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the file cannot be read or
+    /// is not a valid PNG image.
+    #[cfg(feature = "png")]
+    pub fn from_png_file(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        Self::from_png(&fs::read(path)?)
+    }
+
+    /// Reads an image from the file at `path`, in the format given by its
+    /// extension: `ppm`, `pfm`, or `png` with the `png` feature.
+    /// # Errors
+    ///
+    /// This function returns an [`ImageError`] if the format is unknown, or
+    /// if the file cannot be read or is not a valid image.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension.to_ascii_lowercase().as_str() {
+            "ppm" => Self::from_ppm_file(path),
+            "pfm" => Self::from_pfm_file(path),
+            #[cfg(feature = "png")]
+            "png" => Self::from_png_file(path),
+            _ => Err(ImageError::UnsupportedFormat(extension.to_string())),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Importers of models and textures authored in other tools.
+pub mod image;
+pub mod mtl;
+pub mod obj;
+pub mod ply;
+pub mod stl;
@@ -0,0 +1,169 @@
+//! A parser of the Wavefront MTL format, the material libraries referenced
+//! by OBJ files with `mtllib`. Only the statements describing the colors
+//! and the diffuse texture are supported, every other statement is counted
+//! as ignored.
+use std::{fs, io, path::Path};
+
+use crate::{
+    features::{
+        colors::Color,
+        materials::Material,
+        patterns::{texture_map::ImageTexture, uv_mapping::UvMapping, uv_patterns::UvImage},
+    },
+    Float,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A material declared by `newmtl` in an MTL file, with the values of the
+/// statements understood. Those absent keep the defaults of the format.
+pub struct MtlMaterial<T: Float> {
+    pub name: String,
+    /// `Ka`, defaults to 0.2 gray.
+    pub ambient: Color<T>,
+    /// `Kd`, defaults to 0.8 gray.
+    pub diffuse: Color<T>,
+    /// `Ks`, defaults to black: no highlight.
+    pub specular: Color<T>,
+    /// `Ns`, the specular exponent, defaults to 0.
+    pub shininess: T,
+    /// `d`, the opacity, defaults to 1. `Tr` is its complement.
+    pub dissolve: T,
+    /// `map_Kd`, the path of the diffuse texture as written in the file.
+    pub diffuse_map: Option<String>,
+}
+
+impl<T: Float> MtlMaterial<T> {
+    pub fn new(name: &str) -> Self {
+        let gray = |v: f64| Color::new(T::from_f64(v), T::from_f64(v), T::from_f64(v));
+        Self {
+            name: name.to_string(),
+            ambient: gray(0.2),
+            diffuse: gray(0.8),
+            specular: gray(0.0),
+            shininess: T::zero(),
+            dissolve: T::one(),
+            diffuse_map: None,
+        }
+    }
+
+    /// Returns the [`Material`] described: `Kd` gives the color, `Ka` and
+    /// `Ks` the ambient and specular terms as the mean of their channels,
+    /// `Ns` the shininess and `d` the opacity. The `map_Kd` image is read
+    /// from `dir` and projected with [`UvMapping::Planar`], as the patterns
+    /// do not see the texture coordinates of the faces; an image which
+    /// cannot be read is skipped.
+    pub fn to_material(&self, dir: &Path) -> Material<T> {
+        let mean = |c: Color<T>| (c.r + c.g + c.b) / T::from_f64(3.0);
+        let material = Material::default()
+            .with_color(self.diffuse)
+            .with_ambient(mean(self.ambient))
+            .with_diffuse(T::one())
+            .with_specular(mean(self.specular))
+            .with_shininess(self.shininess)
+            .with_transparency(T::one() - self.dissolve);
+        match self
+            .diffuse_map
+            .as_ref()
+            .and_then(|map| UvImage::from_file(dir.join(map)).ok())
+        {
+            Some(image) => material.with_pattern(ImageTexture::new(image, UvMapping::Planar)),
+            None => material,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The materials of one or more MTL files.
+pub struct MtlLibrary<T: Float> {
+    materials: Vec<MtlMaterial<T>>,
+    ignored: usize,
+}
+
+impl<T: Float> Default for MtlLibrary<T> {
+    fn default() -> Self {
+        Self {
+            materials: Vec::new(),
+            ignored: 0,
+        }
+    }
+}
+
+impl<T: Float> MtlLibrary<T> {
+    /// Parses the content of an MTL file.
+    pub fn parse(src: &str) -> Self {
+        let mut library = Self::default();
+        for line in src.lines() {
+            let mut words = line.split_whitespace();
+            let parsed = match (words.next(), library.materials.last_mut()) {
+                // Blank lines and comments
+                (None, _) => Some(()),
+                (Some(word), _) if word.starts_with('#') => Some(()),
+                (Some("newmtl"), _) => words.next().map(|name| {
+                    library.materials.push(MtlMaterial::new(name));
+                }),
+                // The other statements need a material to apply to.
+                (_, None) => None,
+                (Some("Ka"), Some(m)) => parse_color(words).map(|c| m.ambient = c),
+                (Some("Kd"), Some(m)) => parse_color(words).map(|c| m.diffuse = c),
+                (Some("Ks"), Some(m)) => parse_color(words).map(|c| m.specular = c),
+                (Some("Ns"), Some(m)) => parse_number(words.next()).map(|v| m.shininess = v),
+                (Some("d"), Some(m)) => parse_number(words.next()).map(|v| m.dissolve = v),
+                (Some("Tr"), Some(m)) => {
+                    parse_number::<T>(words.next()).map(|v| m.dissolve = T::one() - v)
+                }
+                // Options may precede the file name, which comes last.
+                (Some("map_Kd"), Some(m)) => words.last().map(|path| {
+                    m.diffuse_map = Some(path.to_string());
+                }),
+                _ => None,
+            };
+            if parsed.is_none() {
+                library.ignored += 1;
+            }
+        }
+        library
+    }
+
+    /// Reads and parses an MTL file.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Adds the materials of `other` to this library.
+    pub fn extend(&mut self, other: Self) {
+        self.materials.extend(other.materials);
+        self.ignored += other.ignored;
+    }
+
+    /// Returns the materials, in the order they are declared.
+    pub fn materials(&self) -> &[MtlMaterial<T>] {
+        &self.materials
+    }
+
+    /// Returns the material `name`. When declared several times, the last
+    /// declaration wins.
+    pub fn get(&self, name: &str) -> Option<&MtlMaterial<T>> {
+        self.materials.iter().rev().find(|m| m.name == name)
+    }
+
+    /// Returns the number of lines which were not understood.
+    pub fn ignored(&self) -> usize {
+        self.ignored
+    }
+}
+
+fn parse_number<T: Float>(word: Option<&str>) -> Option<T> {
+    word?.parse::<f64>().ok().map(T::from_f64)
+}
+
+/// Parses `r g b`, or a single value for a gray.
+fn parse_color<'a, T: Float>(mut words: impl Iterator<Item = &'a str>) -> Option<Color<T>> {
+    let r = parse_number(words.next())?;
+    match words.next() {
+        None => Some(Color::new(r, r, r)),
+        g => Some(Color::new(r, parse_number(g)?, parse_number(words.next())?)),
+    }
+}
@@ -0,0 +1,400 @@
+//! A parser of the Wavefront OBJ format, which reads the polygons of a model
+//! into triangles. Only the statements needed for the geometry are
+//! supported, every other statement is counted as ignored.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    features::{
+        materials::Material,
+        shapes::{
+            group::Group,
+            triangle::{SmoothTriangle, Triangle},
+            Shape,
+        },
+    },
+    Float, Point3, Vector3,
+};
+
+use super::mtl::{MtlLibrary, MtlMaterial};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A triangle read from an OBJ file: flat, or smooth when its vertices
+/// come with normals (`vn`) or texture coordinates (`vt`).
+pub enum ObjTriangle<T: Float> {
+    Flat(Triangle<T>),
+    Smooth(SmoothTriangle<T>),
+}
+
+impl<T: Float> ObjTriangle<T> {
+    /// Returns the vertices of the triangle.
+    pub fn vertices(&self) -> [Point3<T>; 3] {
+        match self {
+            ObjTriangle::Flat(t) => t.vertices(),
+            ObjTriangle::Smooth(t) => t.vertices(),
+        }
+    }
+
+    /// Returns the triangle as a shape.
+    pub fn into_shape(self) -> Box<dyn Shape<T>> {
+        match self {
+            ObjTriangle::Flat(t) => Box::new(t),
+            ObjTriangle::Smooth(t) => Box::new(t),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The triangles of a group, with the material used by each of them as an
+/// index into the names given to `usemtl`.
+struct ObjGroup<T: Float> {
+    name: String,
+    triangles: Vec<ObjTriangle<T>>,
+    materials: Vec<Option<usize>>,
+}
+
+impl<T: Float> ObjGroup<T> {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            triangles: Vec::new(),
+            materials: Vec::new(),
+        }
+    }
+}
+
+/// The indices of a face element `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+#[derive(Debug, Clone, Copy)]
+struct FaceVertex {
+    vertex: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The content of an OBJ file.
+///
+/// The faces are split into triangles in a fan around their first vertex,
+/// and collected in the group named by the last `g` statement, or in the
+/// default group before any of them.
+///
+/// A face whose vertices all have a normal becomes smooth triangles; the
+/// texture coordinates of a face are kept in smooth triangles too, with the
+/// flat normal at every vertex if no normal is given.
+///
+/// The faces remember the material selected by the last `usemtl`
+/// statement, which is looked up in the libraries named by `mtllib`.
+pub struct ObjParser<T: Float> {
+    vertices: Vec<Point3<T>>,
+    normals: Vec<Vector3<T>>,
+    texture_coords: Vec<(T, T)>,
+    default_group: ObjGroup<T>,
+    /// The named groups, in the order they first appear.
+    groups: Vec<ObjGroup<T>>,
+    material_libraries: Vec<String>,
+    /// The names given to `usemtl`, in the order they first appear.
+    material_names: Vec<String>,
+    library: MtlLibrary<T>,
+    /// The directory of the file, where the textures are looked up.
+    directory: PathBuf,
+    ignored: usize,
+}
+
+impl<T: Float> ObjParser<T> {
+    /// Parses the content of an OBJ file.
+    pub fn parse(src: &str) -> Self {
+        let mut parser = Self {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            texture_coords: Vec::new(),
+            default_group: ObjGroup::new(""),
+            groups: Vec::new(),
+            material_libraries: Vec::new(),
+            material_names: Vec::new(),
+            library: MtlLibrary::default(),
+            directory: PathBuf::new(),
+            ignored: 0,
+        };
+        let mut current: Option<usize> = None;
+        let mut material: Option<usize> = None;
+        for line in src.lines() {
+            let mut words = line.split_whitespace();
+            let parsed = match words.next() {
+                // Blank lines and comments
+                None => Some(()),
+                Some(word) if word.starts_with('#') => Some(()),
+                Some("v") => parser.parse_vertex(words),
+                Some("vn") => parser.parse_normal(words),
+                Some("vt") => parser.parse_texture_coords(words),
+                Some("f") => parser.parse_face(words).map(|triangles| {
+                    let group = match current {
+                        Some(idx) => &mut parser.groups[idx],
+                        None => &mut parser.default_group,
+                    };
+                    group.materials.extend(triangles.iter().map(|_| material));
+                    group.triangles.extend(triangles);
+                }),
+                Some("g") => words.next().map(|name| {
+                    current = Some(parser.group_index(name));
+                }),
+                Some("usemtl") => words.next().map(|name| {
+                    material = Some(parser.material_index(name));
+                }),
+                Some("mtllib") => {
+                    let names: Vec<String> = words.map(str::to_string).collect();
+                    (!names.is_empty()).then(|| parser.material_libraries.extend(names))
+                }
+                _ => None,
+            };
+            if parsed.is_none() {
+                parser.ignored += 1;
+            }
+        }
+        parser
+    }
+
+    /// Reads and parses an OBJ file, and the material libraries it names
+    /// from the same directory. The libraries which cannot be read are
+    /// skipped, as models are often distributed without them.
+    /// # Errors
+    ///
+    /// This function will return an error if the OBJ file cannot be read.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut parser = Self::parse(&fs::read_to_string(path)?);
+        let dir = path.parent().unwrap_or(Path::new(""));
+        parser.directory = dir.to_path_buf();
+        let libraries: Vec<MtlLibrary<T>> = parser
+            .material_libraries
+            .iter()
+            .filter_map(|name| MtlLibrary::from_file(dir.join(name)).ok())
+            .collect();
+        for library in libraries {
+            parser.load_materials(library);
+        }
+        Ok(parser)
+    }
+
+    /// Adds the materials of `library` to the ones the faces can use.
+    pub fn load_materials(&mut self, library: MtlLibrary<T>) {
+        self.library.extend(library);
+    }
+
+    /// Returns the file names given to `mtllib`.
+    pub fn material_libraries(&self) -> &[String] {
+        &self.material_libraries
+    }
+
+    /// Returns the materials loaded.
+    pub fn materials(&self) -> &MtlLibrary<T> {
+        &self.library
+    }
+
+    /// Returns the material of the triangle `idx` in `group`, or in the
+    /// default group when `group` is `None`. Returns `None` if no material
+    /// was selected for it, or if the material is not loaded.
+    pub fn material_of(&self, group: Option<&str>, idx: usize) -> Option<&MtlMaterial<T>> {
+        let group = match group {
+            Some(name) => self.groups.iter().find(|g| g.name == name)?,
+            None => &self.default_group,
+        };
+        let name = &self.material_names[(*group.materials.get(idx)?)?];
+        self.library.get(name)
+    }
+
+    /// Returns the vertices, in the order of the file. Note that the faces
+    /// of an OBJ file count the vertices from one.
+    pub fn vertices(&self) -> &[Point3<T>] {
+        &self.vertices
+    }
+
+    /// Returns the vertex normals, in the order of the file.
+    pub fn normals(&self) -> &[Vector3<T>] {
+        &self.normals
+    }
+
+    /// Returns the texture coordinates, in the order of the file.
+    pub fn texture_coords(&self) -> &[(T, T)] {
+        &self.texture_coords
+    }
+
+    /// Returns the triangles outside of any named group.
+    pub fn default_group(&self) -> &[ObjTriangle<T>] {
+        &self.default_group.triangles
+    }
+
+    /// Returns the triangles of the group `name`, if any.
+    pub fn group(&self, name: &str) -> Option<&[ObjTriangle<T>]> {
+        self.groups
+            .iter()
+            .find(|g| g.name == name)
+            .map(|g| g.triangles.as_slice())
+    }
+
+    /// Returns the names of the groups, in the order they first appear.
+    pub fn group_names(&self) -> impl Iterator<Item = &str> {
+        self.groups.iter().map(|g| g.name.as_str())
+    }
+
+    /// Returns the number of lines which were not understood.
+    pub fn ignored(&self) -> usize {
+        self.ignored
+    }
+
+    /// Returns a group holding the triangles of the default group, and a
+    /// subgroup for each named group.
+    ///
+    /// Each triangle gets the [`Material`] converted from the MTL material
+    /// selected for it by `usemtl`, see [`MtlMaterial::to_material`], and
+    /// keeps the default one if none is loaded under that name.
+    pub fn into_group(self) -> Group<T> {
+        let materials: Vec<Option<Material<T>>> = self
+            .material_names
+            .iter()
+            .map(|name| Some(self.library.get(name)?.to_material(&self.directory)))
+            .collect();
+        let shapes = |g: ObjGroup<T>| {
+            g.triangles
+                .into_iter()
+                .zip(g.materials)
+                .map(|(triangle, idx)| {
+                    let mut shape = triangle.into_shape();
+                    if let Some(material) = idx.and_then(|idx| materials[idx].as_ref()) {
+                        shape.set_material(material.clone());
+                    }
+                    shape
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut group = Group::default();
+        for shape in shapes(self.default_group) {
+            group.add_boxed_child(shape);
+        }
+        for g in self.groups {
+            let mut subgroup = Group::default();
+            for shape in shapes(g) {
+                subgroup.add_boxed_child(shape);
+            }
+            group.add_child(subgroup);
+        }
+        group
+    }
+
+    fn group_index(&mut self, name: &str) -> usize {
+        match self.groups.iter().position(|g| g.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.groups.push(ObjGroup::new(name));
+                self.groups.len() - 1
+            }
+        }
+    }
+
+    fn material_index(&mut self, name: &str) -> usize {
+        match self.material_names.iter().position(|n| n == name) {
+            Some(idx) => idx,
+            None => {
+                self.material_names.push(name.to_string());
+                self.material_names.len() - 1
+            }
+        }
+    }
+
+    /// Parses `v x y z`, an optional fourth coordinate is ignored.
+    fn parse_vertex<'a>(&mut self, mut words: impl Iterator<Item = &'a str>) -> Option<()> {
+        let mut coord = || words.next()?.parse::<f64>().ok().map(T::from_f64);
+        let (x, y, z) = (coord()?, coord()?, coord()?);
+        self.vertices.push(Point3::new(x, y, z));
+        Some(())
+    }
+
+    /// Parses `vn x y z`.
+    fn parse_normal<'a>(&mut self, mut words: impl Iterator<Item = &'a str>) -> Option<()> {
+        let mut coord = || words.next()?.parse::<f64>().ok().map(T::from_f64);
+        let (x, y, z) = (coord()?, coord()?, coord()?);
+        self.normals.push(Vector3::new(x, y, z));
+        Some(())
+    }
+
+    /// Parses `vt u [v]`, an optional third coordinate is ignored.
+    fn parse_texture_coords<'a>(&mut self, mut words: impl Iterator<Item = &'a str>) -> Option<()> {
+        let u = words.next()?.parse::<f64>().ok().map(T::from_f64)?;
+        let v = match words.next() {
+            Some(word) => T::from_f64(word.parse::<f64>().ok()?),
+            None => T::zero(),
+        };
+        self.texture_coords.push((u, v));
+        Some(())
+    }
+
+    /// Parses `f v1 v2 v3 ...` into a fan of triangles.
+    fn parse_face<'a>(&self, words: impl Iterator<Item = &'a str>) -> Option<Vec<ObjTriangle<T>>> {
+        let elements = words
+            .map(|word| self.face_vertex(word))
+            .collect::<Option<Vec<FaceVertex>>>()?;
+        if elements.len() < 3 {
+            return None;
+        }
+        Some(
+            elements[1..]
+                .windows(2)
+                .map(|pair| self.triangle([elements[0], pair[0], pair[1]]))
+                .collect(),
+        )
+    }
+
+    fn triangle(&self, elements: [FaceVertex; 3]) -> ObjTriangle<T> {
+        let [p1, p2, p3] = elements.map(|e| self.vertices[e.vertex]);
+        let normals = elements.map(|e| e.normal.map(|i| self.normals[i]));
+        let texture_coords = elements.map(|e| e.texture.map(|i| self.texture_coords[i]));
+        let texture_coords = match texture_coords {
+            [Some(t1), Some(t2), Some(t3)] => Some([t1, t2, t3]),
+            _ => None,
+        };
+        let flat = Triangle::new(p1, p2, p3);
+        let [n1, n2, n3] = match normals {
+            [Some(n1), Some(n2), Some(n3)] => [n1, n2, n3],
+            _ if texture_coords.is_some() => [flat.normal(); 3],
+            _ => return ObjTriangle::Flat(flat),
+        };
+        let smooth = SmoothTriangle::new(p1, p2, p3, n1, n2, n3);
+        ObjTriangle::Smooth(match texture_coords {
+            Some(coords) => smooth.with_texture_coords(coords),
+            None => smooth,
+        })
+    }
+
+    /// Returns the indices of a face element `v`, `v/vt`, `v//vn` or `v/vt/vn`,
+    /// all of them referring to existing records.
+    fn face_vertex(&self, word: &str) -> Option<FaceVertex> {
+        let mut parts = word.split('/');
+        let vertex = resolve_index(parts.next()?, self.vertices.len())?;
+        let texture = match parts.next() {
+            None | Some("") => None,
+            Some(part) => Some(resolve_index(part, self.texture_coords.len())?),
+        };
+        let normal = match parts.next() {
+            None | Some("") => None,
+            Some(part) => Some(resolve_index(part, self.normals.len())?),
+        };
+        Some(FaceVertex {
+            vertex,
+            texture,
+            normal,
+        })
+    }
+}
+
+/// Converts an OBJ index into an index of a list of `len` records.
+/// Indices count from one, negative ones from the last record read.
+fn resolve_index(word: &str, len: usize) -> Option<usize> {
+    let idx: isize = word.parse().ok()?;
+    let idx = match idx {
+        0 => return None,
+        i if i > 0 => i as usize - 1,
+        i => len.checked_sub(i.unsigned_abs())?,
+    };
+    (idx < len).then_some(idx)
+}
@@ -0,0 +1,292 @@
+//! A reader of PLY (Stanford polygon) files into an indexed [`Mesh`],
+//! supporting the `ascii` and `binary_little_endian` formats. The vertices
+//! are read with their normals when the file has `nx`, `ny` and `nz`
+//! properties; the faces are split into triangles in a fan around their
+//! first vertex. The other elements are skipped.
+use std::{error::Error, fmt::Display, fs, io, path::Path, str::SplitWhitespace};
+
+use crate::{features::shapes::mesh::Mesh, Float, Point3, Vector3};
+
+#[derive(Debug)]
+pub enum PlyError {
+    Io(io::Error),
+    /// The header is malformed at the given line.
+    InvalidHeader {
+        line: usize,
+    },
+    /// The format is not `ascii` or `binary_little_endian`.
+    UnsupportedFormat(String),
+    /// The body ends early or holds a value which cannot be read.
+    InvalidData,
+    /// A face refers to a missing vertex.
+    InvalidIndex(usize),
+}
+
+impl Display for PlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlyError::Io(e) => write!(f, "Failed to read the PLY file: {e}"),
+            PlyError::InvalidHeader { line } => write!(f, "Invalid PLY header at line {line}."),
+            PlyError::UnsupportedFormat(format) => {
+                write!(f, "The PLY format `{format}` is not supported.")
+            }
+            PlyError::InvalidData => write!(f, "The PLY data is truncated or invalid."),
+            PlyError::InvalidIndex(idx) => write!(f, "A face refers to the missing vertex {idx}."),
+        }
+    }
+}
+
+impl Error for PlyError {}
+
+impl From<io::Error> for PlyError {
+    fn from(e: io::Error) -> Self {
+        PlyError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The scalar types of PLY properties.
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Property {
+    Scalar(String, ScalarType),
+    /// A list of `item` values preceded by their number as a `count`.
+    List {
+        name: String,
+        count: ScalarType,
+        item: ScalarType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// The body of a PLY file, read value by value.
+enum Body<'a> {
+    Ascii(SplitWhitespace<'a>),
+    BinaryLittleEndian(&'a [u8]),
+}
+
+impl Body<'_> {
+    fn read(&mut self, ty: ScalarType) -> Result<f64, PlyError> {
+        match self {
+            Body::Ascii(words) => words
+                .next()
+                .and_then(|word| word.parse::<f64>().ok())
+                .ok_or(PlyError::InvalidData),
+            Body::BinaryLittleEndian(bytes) => {
+                if bytes.len() < ty.size() {
+                    return Err(PlyError::InvalidData);
+                }
+                let (value, rest) = bytes.split_at(ty.size());
+                *bytes = rest;
+                let array = |v: &[u8]| -> [u8; 8] {
+                    let mut out = [0; 8];
+                    out[..v.len()].copy_from_slice(v);
+                    out
+                };
+                let b = array(value);
+                Ok(match ty {
+                    ScalarType::I8 => i8::from_le_bytes([b[0]]) as f64,
+                    ScalarType::U8 => b[0] as f64,
+                    ScalarType::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                    ScalarType::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                    ScalarType::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    ScalarType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    ScalarType::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                    ScalarType::F64 => f64::from_le_bytes(b),
+                })
+            }
+        }
+    }
+
+    /// Reads the values of a property: one for a scalar, all the items for a list.
+    fn read_property(&mut self, property: &Property) -> Result<Vec<f64>, PlyError> {
+        match property {
+            Property::Scalar(_, ty) => Ok(vec![self.read(*ty)?]),
+            Property::List { count, item, .. } => {
+                let n = self.read(*count)? as usize;
+                (0..n).map(|_| self.read(*item)).collect()
+            }
+        }
+    }
+}
+
+/// Splits the content of a PLY file after the `end_header` line.
+fn split_header(bytes: &[u8]) -> Option<(&str, &[u8])> {
+    let marker = b"end_header";
+    let start = bytes.windows(marker.len()).position(|w| w == marker)?;
+    let end = start + bytes[start..].iter().position(|&b| b == b'\n')? + 1;
+    Some((std::str::from_utf8(&bytes[..start]).ok()?, &bytes[end..]))
+}
+
+/// Returns the format and the elements declared by the header.
+fn parse_header(header: &str) -> Result<(String, Vec<Element>), PlyError> {
+    let mut lines = header.lines().enumerate();
+    if lines.next().map(|(_, l)| l.trim()) != Some("ply") {
+        return Err(PlyError::InvalidHeader { line: 1 });
+    }
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for (idx, line) in lines {
+        let invalid = PlyError::InvalidHeader { line: idx + 1 };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [] | ["comment", ..] | ["obj_info", ..] => {}
+            ["format", name, _] => format = Some(name.to_string()),
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| invalid)?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let property = Property::List {
+                    name: name.to_string(),
+                    count: ScalarType::parse(count).ok_or(invalid)?,
+                    item: ScalarType::parse(item)
+                        .ok_or(PlyError::InvalidHeader { line: idx + 1 })?,
+                };
+                let element = elements
+                    .last_mut()
+                    .ok_or(PlyError::InvalidHeader { line: idx + 1 })?;
+                element.properties.push(property);
+            }
+            ["property", ty, name] => {
+                let ty = ScalarType::parse(ty).ok_or(invalid)?;
+                let element = elements
+                    .last_mut()
+                    .ok_or(PlyError::InvalidHeader { line: idx + 1 })?;
+                element
+                    .properties
+                    .push(Property::Scalar(name.to_string(), ty));
+            }
+            _ => return Err(invalid),
+        }
+    }
+    let format = format.ok_or(PlyError::InvalidHeader { line: 1 })?;
+    Ok((format, elements))
+}
+
+impl<T: Float> Mesh<T> {
+    /// Reads the content of a PLY file into a mesh.
+    /// # Errors
+    ///
+    /// This function will return an error if the content is not valid PLY,
+    /// or is in the unsupported `binary_big_endian` format.
+    pub fn from_ply(bytes: &[u8]) -> Result<Self, PlyError> {
+        let (header, body) = split_header(bytes).ok_or(PlyError::InvalidHeader { line: 1 })?;
+        let (format, elements) = parse_header(header)?;
+        let ascii_body;
+        let mut body = match format.as_str() {
+            "ascii" => {
+                ascii_body = String::from_utf8_lossy(body);
+                Body::Ascii(ascii_body.split_whitespace())
+            }
+            "binary_little_endian" => Body::BinaryLittleEndian(body),
+            _ => return Err(PlyError::UnsupportedFormat(format)),
+        };
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut faces = Vec::new();
+        for element in elements.iter() {
+            for _ in 0..element.count {
+                let mut x = [0.0; 3];
+                let mut n = [0.0; 3];
+                let mut has_normal = false;
+                for property in element.properties.iter() {
+                    let values = body.read_property(property)?;
+                    match (element.name.as_str(), property) {
+                        ("vertex", Property::Scalar(name, _)) => match name.as_str() {
+                            "x" => x[0] = values[0],
+                            "y" => x[1] = values[0],
+                            "z" => x[2] = values[0],
+                            "nx" | "ny" | "nz" => {
+                                has_normal = true;
+                                n[(name.as_bytes()[1] - b'x') as usize] = values[0];
+                            }
+                            _ => {}
+                        },
+                        ("face", Property::List { name, .. })
+                            if name == "vertex_indices" || name == "vertex_index" =>
+                        {
+                            let indices: Vec<usize> = values.iter().map(|&v| v as usize).collect();
+                            if indices.len() >= 3 {
+                                faces.extend(
+                                    indices[1..]
+                                        .windows(2)
+                                        .map(|pair| [indices[0], pair[0], pair[1]]),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if element.name == "vertex" {
+                    let [x, y, z] = x.map(T::from_f64);
+                    vertices.push(Point3::new(x, y, z));
+                    if has_normal {
+                        let [x, y, z] = n.map(T::from_f64);
+                        normals.push(Vector3::new(x, y, z));
+                    }
+                }
+            }
+        }
+        if let Some(&idx) = faces.iter().flatten().find(|&&i| i >= vertices.len()) {
+            return Err(PlyError::InvalidIndex(idx));
+        }
+        let mesh = Mesh::new(vertices, faces.clone());
+        if !normals.is_empty() && normals.len() == mesh.vertices().len() {
+            Ok(mesh.with_normals(normals, faces))
+        } else {
+            Ok(mesh)
+        }
+    }
+
+    /// Reads a PLY file into a mesh.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read or is
+    /// not valid PLY.
+    pub fn from_ply_file(path: impl AsRef<Path>) -> Result<Self, PlyError> {
+        Self::from_ply(&fs::read(path)?)
+    }
+}
@@ -0,0 +1,129 @@
+//! A reader of STL files, in both their binary and ASCII flavors, into an
+//! indexed [`Mesh`]. The normals stored in the file are ignored: the mesh
+//! computes the flat normal of each face from its winding.
+use std::{collections::HashMap, error::Error, fmt::Display, fs, io, path::Path};
+
+use crate::{features::shapes::mesh::Mesh, Float, Point3};
+
+/// Size of the header of a binary STL file.
+const HEADER_SIZE: usize = 80;
+/// Size of a triangle record of a binary STL file: a normal and three
+/// vertices as `f32`, and a `u16` attribute.
+const RECORD_SIZE: usize = 50;
+
+#[derive(Debug)]
+pub enum StlError {
+    Io(io::Error),
+    /// The content is neither a well-sized binary file nor an ASCII file.
+    UnknownFormat,
+    /// An ASCII `vertex` statement at the given line is malformed.
+    InvalidVertex {
+        line: usize,
+    },
+    /// The number of vertices of an ASCII file is not a multiple of three.
+    IncompleteFacet,
+}
+
+impl Display for StlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StlError::Io(e) => write!(f, "Failed to read the STL file: {e}"),
+            StlError::UnknownFormat => write!(f, "The content is not a valid STL file."),
+            StlError::InvalidVertex { line } => write!(f, "Invalid vertex at line {line}."),
+            StlError::IncompleteFacet => write!(f, "The last facet has less than three vertices."),
+        }
+    }
+}
+
+impl Error for StlError {}
+
+impl From<io::Error> for StlError {
+    fn from(e: io::Error) -> Self {
+        StlError::Io(e)
+    }
+}
+
+impl<T: Float> Mesh<T> {
+    /// Reads the content of a binary or ASCII STL file into a mesh. The
+    /// vertices shared by several facets are stored once.
+    /// # Errors
+    ///
+    /// This function will return an error if the content is not valid STL.
+    pub fn from_stl(bytes: &[u8]) -> Result<Self, StlError> {
+        // An ASCII file also starts with "solid", but binary files do too
+        // sometimes: rely on the size announced by the binary header instead.
+        let triangles = match read_binary(bytes) {
+            Some(triangles) => triangles,
+            None if bytes.starts_with(b"solid") => read_ascii(&String::from_utf8_lossy(bytes))?,
+            None => return Err(StlError::UnknownFormat),
+        };
+        let mut vertices: Vec<Point3<T>> = Vec::new();
+        let mut indices: HashMap<[u64; 3], usize> = HashMap::new();
+        let faces = triangles
+            .into_iter()
+            .map(|triangle| {
+                triangle.map(|p| {
+                    *indices.entry(p.map(f64::to_bits)).or_insert_with(|| {
+                        let [x, y, z] = p.map(T::from_f64);
+                        vertices.push(Point3::new(x, y, z));
+                        vertices.len() - 1
+                    })
+                })
+            })
+            .collect();
+        Ok(Mesh::new(vertices, faces))
+    }
+
+    /// Reads an STL file into a mesh.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read or is
+    /// not valid STL.
+    pub fn from_stl_file(path: impl AsRef<Path>) -> Result<Self, StlError> {
+        Self::from_stl(&fs::read(path)?)
+    }
+}
+
+/// Returns the triangles of a binary STL file, or `None` if the size of the
+/// content does not match the number of triangles in its header.
+fn read_binary(bytes: &[u8]) -> Option<Vec<[[f64; 3]; 3]>> {
+    let count = bytes.get(HEADER_SIZE..HEADER_SIZE + 4)?;
+    let count = u32::from_le_bytes(count.try_into().ok()?) as usize;
+    let records = &bytes[HEADER_SIZE + 4..];
+    if records.len() != count.checked_mul(RECORD_SIZE)? {
+        return None;
+    }
+    let float_at = |record: &[u8], i: usize| {
+        f32::from_le_bytes(record[4 * i..4 * i + 4].try_into().unwrap()) as f64
+    };
+    Some(
+        records
+            .chunks_exact(RECORD_SIZE)
+            .map(|record| {
+                // Skip the normal, the three first floats.
+                [0, 1, 2].map(|v| [0, 1, 2].map(|c| float_at(record, 3 + 3 * v + c)))
+            })
+            .collect(),
+    )
+}
+
+/// Returns the triangles of an ASCII STL file, made of its `vertex`
+/// statements three at a time.
+fn read_ascii(src: &str) -> Result<Vec<[[f64; 3]; 3]>, StlError> {
+    let mut points = Vec::new();
+    for (idx, line) in src.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("vertex") {
+            continue;
+        }
+        let mut coord = || words.next()?.parse::<f64>().ok();
+        match (coord(), coord(), coord()) {
+            (Some(x), Some(y), Some(z)) => points.push([x, y, z]),
+            _ => return Err(StlError::InvalidVertex { line: idx + 1 }),
+        }
+    }
+    if points.len() % 3 != 0 {
+        return Err(StlError::IncompleteFacet);
+    }
+    Ok(points.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect())
+}
@@ -0,0 +1,271 @@
+//! Integrators: the ways of estimating the light arriving along a ray.
+//! [`Whitted`] is the recursive ray tracing of the book, [`PathTracer`]
+//! estimates the global illumination by Monte Carlo path tracing.
+use std::fmt::Debug;
+
+use rand::{rngs::SmallRng, Rng};
+
+use crate::{
+    features::{
+        colors::Color,
+        environment::EnvironmentMap,
+        intersections::Computations,
+        lights::PointLight,
+        math::sampling::{random_cosine_direction, random_unit_vector},
+        rays::Ray,
+        world::World,
+    },
+    Float,
+};
+
+/// An estimator of the light arriving along a ray.
+pub trait Integrator<T: Float>: Debug + Send + Sync {
+    /// Returns one estimate of the light arriving along `ray` from `world`.
+    /// Stochastic integrators draw their random numbers from `rng`.
+    fn radiance(&self, world: &World<T>, ray: &Ray<T>, rng: &mut SmallRng) -> Color<T>;
+    /// Returns the number of estimates averaged for each pixel.
+    fn samples_per_pixel(&self) -> usize {
+        1
+    }
+    /// Returns the average of [`Integrator::samples_per_pixel`] estimates
+    /// of the light arriving along `ray`.
+    fn estimate(&self, world: &World<T>, ray: &Ray<T>, rng: &mut SmallRng) -> Color<T> {
+        let n = self.samples_per_pixel().max(1);
+        let sum = (0..n).fold(Color::default(), |sum, _| {
+            sum + self.radiance(world, ray, rng)
+        });
+        sum / T::from_f64(n as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The deterministic shading of the book: Phong lighting from the point
+/// lights, shadows and mirror reflections.
+pub struct Whitted {
+    /// The number of bounces followed by the reflected rays.
+    pub max_depth: usize,
+    /// The number of rays averaged for each pixel, only useful when the
+    /// camera rays vary, e.g. with a depth of field.
+    pub samples_per_pixel: usize,
+}
+
+impl Default for Whitted {
+    fn default() -> Self {
+        Self {
+            max_depth: crate::features::world::MAX_DEPTH,
+            samples_per_pixel: 1,
+        }
+    }
+}
+
+impl<T: Float> Integrator<T> for Whitted {
+    fn radiance(&self, world: &World<T>, ray: &Ray<T>, _rng: &mut SmallRng) -> Color<T> {
+        world.color_at(ray, self.max_depth)
+    }
+
+    fn samples_per_pixel(&self) -> usize {
+        self.samples_per_pixel
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An unbiased Monte Carlo path tracer. The paths bounce off the surfaces
+/// in a cosine-weighted direction around the normal, or in the mirror
+/// direction with the probability given by `reflective`, and collect the
+/// light emitted by the surfaces they hit. The point lights of the world
+/// are sampled directly at every bounce with the Lambertian reflectance
+/// `albedo / π`, as no path can hit them. In media, the paths scatter in a uniform
+/// direction and see the lights whatever their direction.
+///
+/// The paths escaping the scene collect the background of the world. An
+/// [`EnvironmentMap`] is also sampled directly at every diffuse bounce,
+/// its bright regions first; both estimates are combined by multiple
+/// importance sampling, so that neither a small sun nor a broad sky is
+/// noisy.
+///
+/// The paths are ended by Russian roulette after `min_depth` bounces,
+/// which keeps the estimate unbiased, or after `max_depth` bounces as a
+/// safeguard.
+pub struct PathTracer {
+    pub samples_per_pixel: usize,
+    /// The number of bounces before the Russian roulette starts.
+    pub min_depth: usize,
+    /// The number of bounces after which a path always ends.
+    pub max_depth: usize,
+    /// The brightest component allowed to a single path, if any. Rare paths
+    /// finding a small bright light leave isolated white pixels (fireflies);
+    /// clamping them trades a slight darkening for a cleaner image.
+    pub max_radiance: Option<f64>,
+}
+
+impl Default for PathTracer {
+    fn default() -> Self {
+        Self {
+            samples_per_pixel: 64,
+            min_depth: 3,
+            max_depth: 64,
+            max_radiance: None,
+        }
+    }
+}
+
+impl PathTracer {
+    /// Returns a path tracer averaging `samples_per_pixel` paths per pixel.
+    pub fn new(samples_per_pixel: usize) -> Self {
+        Self {
+            samples_per_pixel,
+            ..Self::default()
+        }
+    }
+
+    /// Clamps every path to `max_radiance`, keeping its hue.
+    pub fn with_max_radiance(mut self, max_radiance: f64) -> Self {
+        self.max_radiance = Some(max_radiance);
+        self
+    }
+}
+
+impl<T: Float> Integrator<T> for PathTracer {
+    fn radiance(&self, world: &World<T>, ray: &Ray<T>, rng: &mut SmallRng) -> Color<T> {
+        let white = Color::new(T::one(), T::one(), T::one());
+        let mut color = Color::default();
+        let mut throughput = white;
+        let mut ray = *ray;
+        // The density of the cosine-weighted bounce which cast `ray`, when
+        // the environment map was sampled directly at that bounce as well.
+        let mut bounce_pdf: Option<T> = None;
+        for bounce in 0..self.max_depth {
+            let xs = world.intersect(&ray);
+            let Some(hit) = xs.hit() else {
+                let direction = ray.direction().normalized();
+                let weight = match (bounce_pdf, world.environment()) {
+                    (Some(pdf), Some(environment)) => {
+                        power_heuristic(pdf, environment.pdf(direction))
+                    }
+                    _ => T::one(),
+                };
+                color += throughput * world.background_at(direction) * weight;
+                break;
+            };
+            bounce_pdf = None;
+            let comps = hit.prepare_computations_with_bias(&ray, world.shadow_bias());
+            let material = comps.object.material();
+            color += throughput * material.emissive;
+            let albedo = material.color_at(comps.object, comps.point) * material.diffuse;
+            let (direction, weight) = if comps.object.is_medium() {
+                // Isotropic scattering: lit by every light it sees.
+                let direct = world.lights().iter().fold(Color::default(), |sum, light| {
+                    let transmittance =
+                        world.light_transmittance_at(comps.point, light, comps.time);
+                    sum + light.intensity_at(comps.point) * transmittance
+                });
+                color += throughput * albedo * direct;
+                (random_unit_vector(rng), albedo)
+            } else if T::from_f64(rng.gen()) < material.reflective {
+                (comps.reflectv, white)
+            } else {
+                let direct = world.lights().iter().fold(Color::default(), |sum, light| {
+                    sum + direct_light(world, light, &comps)
+                });
+                if let Some(environment) = world.environment() {
+                    let light = environment_light(world, environment, &comps, rng);
+                    color += throughput * albedo * (direct + light);
+                } else {
+                    color += throughput * albedo * direct;
+                }
+                let direction = random_cosine_direction(comps.normalv, rng);
+                if world.environment().is_some() {
+                    bounce_pdf = Some(direction.dot(&comps.normalv).max(T::zero()) / T::PI());
+                }
+                (direction, albedo)
+            };
+            throughput = throughput * weight;
+            if bounce >= self.min_depth {
+                let survival = throughput
+                    .r
+                    .max(throughput.g)
+                    .max(throughput.b)
+                    .min(T::one());
+                if survival <= T::zero() || T::from_f64(rng.gen()) >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+            let origin = if comps.object.is_medium() {
+                comps.point
+            } else {
+                comps.over_point
+            };
+            ray = Ray::new(origin, direction).with_time(comps.time);
+        }
+        match self.max_radiance.map(T::from_f64) {
+            Some(max) => clamp_radiance(color, max),
+            None => color,
+        }
+    }
+
+    fn samples_per_pixel(&self) -> usize {
+        self.samples_per_pixel
+    }
+}
+
+/// Returns the light of `light` reaching the prepared surface point
+/// `comps`, weighted by the cosine of its incidence, divided by `π` for a
+/// diffuse surface and dimmed by the objects in its way, see
+/// [`World::light_transmittance_at`].
+fn direct_light<T: Float>(
+    world: &World<T>,
+    light: &PointLight<T>,
+    comps: &Computations<'_, T>,
+) -> Color<T> {
+    let point = comps.over_point;
+    let cos = (light.position() - point).normalized().dot(&comps.normalv);
+    if cos <= T::zero() {
+        return Color::default();
+    }
+    light.intensity_at(point)
+        * (cos / T::PI())
+        * world.light_transmittance_at(point, light, comps.time)
+}
+
+/// Returns the light of `environment` reaching the prepared surface point
+/// `comps` along one direction drawn from the map, divided by `π` for a
+/// diffuse surface and weighted against the cosine-weighted bounces.
+fn environment_light<T: Float>(
+    world: &World<T>,
+    environment: &EnvironmentMap<T>,
+    comps: &Computations<'_, T>,
+    rng: &mut SmallRng,
+) -> Color<T> {
+    let (direction, radiance, pdf) = environment.sample(rng);
+    let cos = direction.dot(&comps.normalv);
+    if cos <= T::zero() || pdf <= T::zero() || !pdf.is_finite() {
+        return Color::default();
+    }
+    let ray = Ray::new(comps.over_point, direction).with_time(comps.time);
+    if world.is_occluded(&ray, T::infinity()) {
+        return Color::default();
+    }
+    let bounce_pdf = cos / T::PI();
+    radiance * (bounce_pdf / pdf * power_heuristic(pdf, bounce_pdf))
+}
+
+/// Returns the weight of an estimate drawn with the density `pdf` when
+/// another strategy could have drawn it with `other_pdf`.
+fn power_heuristic<T: Float>(pdf: T, other_pdf: T) -> T {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b == T::zero() {
+        return T::zero();
+    }
+    a / (a + b)
+}
+
+/// Scales `color` down so that its brightest component is at most `max`.
+pub(crate) fn clamp_radiance<T: Float>(color: Color<T>, max: T) -> Color<T> {
+    let brightest = color.r.max(color.g).max(color.b);
+    if brightest > max {
+        color * (max / brightest)
+    } else {
+        color
+    }
+}
@@ -0,0 +1,263 @@
+//! Implementation of `Intersection`, the record of a ray hitting an object,
+//! and `Intersections`, the sorted collection from which the visible hit
+//! is selected.
+use std::{cmp::Ordering, ops::Deref};
+
+use crate::{Float, Point3, Vector3, EPSILON};
+
+use super::{rays::Ray, shapes::Shape};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The intersection of a ray with an `object` at the distance `t` along the ray.
+pub struct Intersection<'a, T: Float> {
+    t: T,
+    object: &'a dyn Shape<T>,
+    /// Where the intersection lies on the surface, used by shapes
+    /// interpolating over their surface such as triangles.
+    uv: Option<(T, T)>,
+    /// Which face of the object is hit, for shapes made of many faces such
+    /// as meshes.
+    face: Option<usize>,
+    /// The shape hit within the object, for objects whose intersections
+    /// are with the shapes they hold such as instances.
+    inner: Option<&'a dyn Shape<T>>,
+}
+
+impl<'a, T: Float> Intersection<'a, T> {
+    pub fn new(t: T, object: &'a dyn Shape<T>) -> Self {
+        Self {
+            t,
+            object,
+            uv: None,
+            face: None,
+            inner: None,
+        }
+    }
+
+    /// Returns an intersection recording the surface coordinates `(u, v)`.
+    pub fn with_uv(t: T, object: &'a dyn Shape<T>, u: T, v: T) -> Self {
+        Self {
+            t,
+            object,
+            uv: Some((u, v)),
+            face: None,
+            inner: None,
+        }
+    }
+
+    /// Returns an intersection recording the surface coordinates `(u, v)`
+    /// within the `face` of the object.
+    pub fn with_face(t: T, object: &'a dyn Shape<T>, face: usize, u: T, v: T) -> Self {
+        Self {
+            t,
+            object,
+            uv: Some((u, v)),
+            face: Some(face),
+            inner: None,
+        }
+    }
+
+    /// Returns the distance along the ray of this [`Intersection`].
+    pub fn t(&self) -> T {
+        self.t
+    }
+
+    /// Returns the object intersected.
+    pub fn object(&self) -> &'a dyn Shape<T> {
+        self.object
+    }
+
+    /// Returns the surface coordinates `(u, v)` if the shape records them.
+    pub fn uv(&self) -> Option<(T, T)> {
+        self.uv
+    }
+
+    /// Returns the index of the face hit if the shape records it.
+    pub fn face(&self) -> Option<usize> {
+        self.face
+    }
+
+    /// Returns the shape hit within the object if the object records it,
+    /// as an [`Instance`](crate::Instance) does for its geometry.
+    pub fn inner(&self) -> Option<&'a dyn Shape<T>> {
+        self.inner
+    }
+
+    /// Returns this intersection as one with `object`, which holds the
+    /// shape hit.
+    pub(crate) fn within(self, object: &'a dyn Shape<T>) -> Self {
+        Self {
+            object,
+            inner: Some(self.object),
+            ..self
+        }
+    }
+
+    /// Returns this intersection as one with the shape hit within the
+    /// object, if recorded.
+    pub(crate) fn unwrapped(&self) -> Option<Self> {
+        self.inner.map(|object| Self {
+            object,
+            inner: None,
+            ..*self
+        })
+    }
+
+    /// Precomputes the values needed to shade this intersection of `ray`,
+    /// lifting the `over_point` by the default bias [`EPSILON`].
+    pub fn prepare_computations(&self, ray: &Ray<T>) -> Computations<'a, T> {
+        self.prepare_computations_with_bias(ray, T::from_f64(EPSILON))
+    }
+
+    /// Precomputes the values needed to shade this intersection of `ray`,
+    /// lifting the `over_point` by `bias` along the normal. The bias should
+    /// follow the scale of the scene.
+    pub fn prepare_computations_with_bias(&self, ray: &Ray<T>, bias: T) -> Computations<'a, T> {
+        let point = ray.position(self.t);
+        let eyev = -ray.direction();
+        // A medium has no surface, face the eye.
+        let mut normalv = if self.object.is_medium() {
+            eyev.normalized()
+        } else {
+            self.object.normal_at_hit_time(point, self, ray.time())
+        };
+        // The normal points away from the eye when the hit is inside the object.
+        let inside = normalv.dot(&eyev) < T::zero();
+        if inside {
+            normalv = -normalv;
+        }
+        Computations {
+            t: self.t,
+            time: ray.time(),
+            object: self.object,
+            point,
+            eyev,
+            normalv,
+            inside,
+            over_point: point + normalv * bias,
+            reflectv: ray.direction().reflect(&normalv),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The state of an intersection needed to shade it, computed once by
+/// [`Intersection::prepare_computations`].
+pub struct Computations<'a, T: Float> {
+    pub t: T,
+    /// The time at which the ray was cast, for the secondary rays.
+    pub time: T,
+    pub object: &'a dyn Shape<T>,
+    /// The point hit, in world space.
+    pub point: Point3<T>,
+    /// The vector pointing back to the eye.
+    pub eyev: Vector3<T>,
+    /// The normal at the point, flipped to face the eye.
+    pub normalv: Vector3<T>,
+    /// `true` if the hit is on the inside of the object.
+    pub inside: bool,
+    /// The point slightly lifted along the normal, so that the shadow
+    /// test does not find the surface itself and cause acne.
+    pub over_point: Point3<T>,
+    /// The direction of the ray reflected off the surface.
+    pub reflectv: Vector3<T>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A collection of intersections, always sorted by ascending `t`.
+pub struct Intersections<'a, T: Float>(Vec<Intersection<'a, T>>);
+
+impl<T: Float> Default for Intersections<'_, T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+/// Orders intersections by `t`, `NaN` being considered equal to anything.
+fn by_t<T: Float>(a: &Intersection<'_, T>, b: &Intersection<'_, T>) -> Ordering {
+    a.t.partial_cmp(&b.t).unwrap_or(Ordering::Equal)
+}
+
+impl<'a, T: Float> Intersections<'a, T> {
+    /// Returns the sorted collection of the given intersections.
+    pub fn new(mut xs: Vec<Intersection<'a, T>>) -> Self {
+        xs.sort_by(by_t);
+        Self(xs)
+    }
+
+    /// Inserts an intersection, keeping the collection sorted.
+    pub fn push(&mut self, x: Intersection<'a, T>) {
+        let idx = self.0.partition_point(|i| by_t(i, &x) != Ordering::Greater);
+        self.0.insert(idx, x);
+    }
+
+    /// Removes every intersection, keeping the memory for the next ray.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Replaces the intersections by the ones `fill` appends to the empty
+    /// buffer, then sorts them.
+    pub(crate) fn refill(&mut self, fill: impl FnOnce(&mut Vec<Intersection<'a, T>>)) {
+        self.0.clear();
+        fill(&mut self.0);
+        self.0.sort_by(by_t);
+    }
+
+    /// Returns the visible intersection: the one with the lowest non-negative `t`.
+    /// Intersections behind the origin of the ray are never a hit.
+    pub fn hit(&self) -> Option<&Intersection<'a, T>> {
+        self.0.iter().find(|i| i.t >= T::zero())
+    }
+}
+
+impl<'a, T: Float> Deref for Intersections<'a, T> {
+    type Target = [Intersection<'a, T>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, T: Float> From<Vec<Intersection<'a, T>>> for Intersections<'a, T> {
+    fn from(src: Vec<Intersection<'a, T>>) -> Self {
+        Self::new(src)
+    }
+}
+
+impl<'a, T: Float> FromIterator<Intersection<'a, T>> for Intersections<'a, T> {
+    fn from_iter<I: IntoIterator<Item = Intersection<'a, T>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl<'a, T: Float> Extend<Intersection<'a, T>> for Intersections<'a, T> {
+    fn extend<I: IntoIterator<Item = Intersection<'a, T>>>(&mut self, iter: I) {
+        self.0.extend(iter);
+        self.0.sort_by(by_t);
+    }
+}
+
+impl<'a, T: Float> IntoIterator for Intersections<'a, T> {
+    type Item = Intersection<'a, T>;
+    type IntoIter = std::vec::IntoIter<Intersection<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Aggregates intersections into a sorted [`Intersections`].
+/// ```
+/// use raytracer_rust::{intersections, Intersection, Sphere};
+///
+/// let s = Sphere::<f64>::default();
+/// let xs = intersections![Intersection::new(2.0, &s), Intersection::new(1.0, &s)];
+/// assert_eq!(xs[0].t(), 1.0);
+/// ```
+#[macro_export]
+macro_rules! intersections {
+    ($($x:expr),* $(,)?) => {
+        $crate::features::intersections::Intersections::new(vec![$($x),*])
+    };
+}
@@ -0,0 +1,187 @@
+//! Light sources and the Phong reflection model shading a point lit by them.
+use crate::{
+    features::{colors::Color, materials::Material, shapes::Shape},
+    Float, Point3, Vector3,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// How the light of a source falls off with the distance `d` to the lit
+/// point. The intensity is divided by `constant + linear * d + quadratic * d²`.
+pub enum Attenuation<T: Float> {
+    /// The light reaches every distance with its full intensity.
+    #[default]
+    None,
+    /// Divides the intensity by `1 + k * d`.
+    Linear(T),
+    /// Divides the intensity by `1 + k * d²`, the physical falloff.
+    Quadratic(T),
+    /// Divides the intensity by `constant + linear * d + quadratic * d²`.
+    Custom {
+        constant: T,
+        linear: T,
+        quadratic: T,
+    },
+}
+
+impl<T: Float> Attenuation<T> {
+    /// Returns the fraction of the intensity reaching the `distance`.
+    pub fn factor(&self, distance: T) -> T {
+        let (constant, linear, quadratic) = match *self {
+            Attenuation::None => return T::one(),
+            Attenuation::Linear(k) => (T::one(), k, T::zero()),
+            Attenuation::Quadratic(k) => (T::one(), T::zero(), k),
+            Attenuation::Custom {
+                constant,
+                linear,
+                quadratic,
+            } => (constant, linear, quadratic),
+        };
+        T::one() / (constant + linear * distance + quadratic * distance * distance)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A light source with no size, radiating from a single point.
+pub struct PointLight<T: Float> {
+    position: Point3<T>,
+    intensity: Color<T>,
+    attenuation: Attenuation<T>,
+}
+
+impl<T: Float> PointLight<T> {
+    pub fn new(position: Point3<T>, intensity: Color<T>) -> Self {
+        Self {
+            position,
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    /// Sets how the light falls off with the distance.
+    pub fn with_attenuation(mut self, attenuation: Attenuation<T>) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Returns the falloff of this [`PointLight`].
+    pub fn attenuation(&self) -> Attenuation<T> {
+        self.attenuation
+    }
+
+    /// Returns the intensity of this [`PointLight`] reaching `point`.
+    pub fn intensity_at(&self, point: Point3<T>) -> Color<T> {
+        let distance = (self.position - point).magnitude();
+        self.intensity * self.attenuation.factor(distance)
+    }
+
+    /// Returns the position of this [`PointLight`].
+    pub fn position(&self) -> Point3<T> {
+        self.position
+    }
+
+    /// Returns the intensity (the color and brightness) of this [`PointLight`].
+    pub fn intensity(&self) -> Color<T> {
+        self.intensity
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The three terms of the Phong model, whose sum is the color of a lit
+/// point.
+pub struct PhongTerms<T: Float> {
+    pub ambient: Color<T>,
+    pub diffuse: Color<T>,
+    pub specular: Color<T>,
+}
+
+impl<T: Float> PhongTerms<T> {
+    /// Returns the sum of the three terms.
+    pub fn total(&self) -> Color<T> {
+        self.ambient + self.diffuse + self.specular
+    }
+}
+
+/// Returns the color of `point` on a surface of `material` lit by `light`,
+/// seen along the eye vector `eyev` with the surface normal `normalv`,
+/// by summing the ambient, diffuse and specular terms of the Phong model.
+/// Both vectors are expected to be normalized. The pattern of the material
+/// is placed by the transformation of `object`.
+///
+/// A point `in_shadow` of the light only receives the ambient term. The
+/// attenuation of the light only applies to the diffuse and specular terms.
+pub fn lighting<T: Float>(
+    material: &Material<T>,
+    object: &dyn Shape<T>,
+    light: &PointLight<T>,
+    point: Point3<T>,
+    eyev: Vector3<T>,
+    normalv: Vector3<T>,
+    in_shadow: bool,
+) -> Color<T> {
+    phong_terms(material, object, light, point, eyev, normalv, in_shadow).total()
+}
+
+/// Returns the terms of the Phong model summed by [`lighting`], apart.
+pub fn phong_terms<T: Float>(
+    material: &Material<T>,
+    object: &dyn Shape<T>,
+    light: &PointLight<T>,
+    point: Point3<T>,
+    eyev: Vector3<T>,
+    normalv: Vector3<T>,
+    in_shadow: bool,
+) -> PhongTerms<T> {
+    let color = material.color_at(object, point);
+    let effective_color = color * light.intensity();
+    let lightv = (light.position() - point).normalized();
+    let ambient = effective_color * material.ambient;
+    let only_ambient = PhongTerms {
+        ambient,
+        diffuse: Color::default(),
+        specular: Color::default(),
+    };
+    if in_shadow {
+        return only_ambient;
+    }
+    // The cosine of the angle between the light and the normal, negative
+    // when the light is on the other side of the surface.
+    let light_dot_normal = lightv.dot(&normalv);
+    if light_dot_normal < T::zero() {
+        return only_ambient;
+    }
+    let intensity = light.intensity_at(point);
+    let diffuse = color * intensity * material.diffuse * light_dot_normal;
+    // The cosine of the angle between the reflection and the eye, negative
+    // when the light reflects away from the eye.
+    let reflect_dot_eye = (-lightv).reflect(&normalv).dot(&eyev);
+    let specular = if reflect_dot_eye <= T::zero() {
+        Color::default()
+    } else {
+        let factor = reflect_dot_eye.powf(material.shininess);
+        intensity * material.specular * factor
+    };
+    PhongTerms {
+        ambient,
+        diffuse,
+        specular,
+    }
+}
+
+/// Returns the color of `point` lit by every light of `lights`: the sum of
+/// [`lighting`] for each of them, including its ambient term.
+/// `is_shadowed` tells whether the point is in the shadow of a given light,
+/// so that each light is tested for its own shadow.
+pub fn lighting_from<T: Float>(
+    material: &Material<T>,
+    object: &dyn Shape<T>,
+    lights: &[PointLight<T>],
+    point: Point3<T>,
+    eyev: Vector3<T>,
+    normalv: Vector3<T>,
+    is_shadowed: impl Fn(&PointLight<T>) -> bool,
+) -> Color<T> {
+    lights.iter().fold(Color::default(), |color, light| {
+        let in_shadow = is_shadowed(light);
+        color + lighting(material, object, light, point, eyev, normalv, in_shadow)
+    })
+}
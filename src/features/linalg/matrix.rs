@@ -0,0 +1,278 @@
+//! Implementation of `Matrix`, a row-major matrix with `R` rows and `C` columns.
+//! Each row is stored as a `Tuple<T, C>`, so the matrix interoperates with
+//! the `Tuple`, `Point` and `Vector` types directly.
+//! Like `Vector<T, N>`, the sizes are const generics; the square sizes
+//! involved in the cofactor expansion have their own aliases `Matrix2`,
+//! `Matrix3` and `Matrix4`.
+use std::{
+    array,
+    ops::{Index, IndexMut, Mul},
+};
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use crate::{Float, Point, Scalar, Vector};
+
+use super::tuple::Tuple;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+/// A matrix with `R` rows and `C` columns with scalar type `T`.
+pub struct Matrix<T: Scalar, const R: usize, const C: usize>(pub(crate) [Tuple<T, C>; R]);
+
+/// A 2x2 matrix, the base case of the cofactor expansion.
+pub type Matrix2<T> = Matrix<T, 2, 2>;
+/// A 3x3 matrix, the submatrix of a `Matrix4`.
+pub type Matrix3<T> = Matrix<T, 3, 3>;
+/// In the context of this project, transformations of the 3D homogeneous
+/// coordinates are expressed with 4x4 matrices.
+pub type Matrix4<T> = Matrix<T, 4, 4>;
+
+impl<T: Scalar, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Returns a matrix built from the given rows.
+    pub fn new(rows: [[T; C]; R]) -> Self {
+        Self(rows.map(Tuple))
+    }
+    /// Returns the `i`-th row of this matrix.
+    pub fn row(&self, i: usize) -> Tuple<T, C> {
+        self.0[i]
+    }
+    /// Returns the `j`-th column of this matrix.
+    pub fn col(&self, j: usize) -> Tuple<T, R> {
+        Tuple(array::from_fn(|i| self.0[i][j]))
+    }
+    /// Returns the transpose of this matrix, the rows become the columns.
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        Matrix(array::from_fn(|j| self.col(j)))
+    }
+}
+
+impl<T: Scalar, const N: usize> Matrix<T, N, N> {
+    /// Returns the identity matrix.
+    pub fn identity() -> Self {
+        let mut m = Self::default();
+        for i in 0..N {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+}
+
+impl<T: Scalar> Matrix2<T> {
+    /// Returns the determinant of a 2x2 matrix, the base case of the
+    /// cofactor expansion of larger matrices.
+    pub fn determinant(&self) -> T {
+        self[(0, 0)] * self[(1, 1)] - self[(0, 1)] * self[(1, 0)]
+    }
+}
+
+// Due to the lack of arithmetic on const generics in stable rust, we cannot
+// express a `Matrix<T, N, N>` -> `Matrix<T, N - 1, N - 1>` generically.
+// Implement the cofactor expansion for every concrete size instead.
+macro_rules! impl_cofactor_expansion {
+    ($ty:ident, $n:expr, $sub_ty:ident) => {
+        impl<T: Scalar> $ty<T> {
+            /// Returns a copy of this matrix with the given `row` and `col` removed.
+            pub fn submatrix(&self, row: usize, col: usize) -> $sub_ty<T> {
+                let mut out = Matrix::default();
+                for (i, r) in (0..$n).filter(|&r| r != row).enumerate() {
+                    for (j, c) in (0..$n).filter(|&c| c != col).enumerate() {
+                        out[(i, j)] = self[(r, c)];
+                    }
+                }
+                out
+            }
+            /// Returns the minor at `(row, col)`: the determinant of the submatrix.
+            pub fn minor(&self, row: usize, col: usize) -> T {
+                self.submatrix(row, col).determinant()
+            }
+            /// Returns the cofactor at `(row, col)`: the minor with its sign
+            /// flipped when `row + col` is odd.
+            pub fn cofactor(&self, row: usize, col: usize) -> T {
+                let minor = self.minor(row, col);
+                if (row + col) % 2 == 1 {
+                    T::zero() - minor
+                } else {
+                    minor
+                }
+            }
+            /// Returns the determinant by the cofactor expansion along the first row.
+            pub fn determinant(&self) -> T {
+                (0..$n).fold(T::zero(), |acc, col| {
+                    acc + self[(0, col)] * self.cofactor(0, col)
+                })
+            }
+            /// Returns `true` if the determinant is not zero.
+            pub fn is_invertible(&self) -> bool {
+                self.determinant() != T::zero()
+            }
+            /// Returns the inverse of this matrix, or `None` when the matrix
+            /// is singular (determinant is zero).
+            pub fn inverse(&self) -> Option<Self>
+            where
+                T: Float,
+            {
+                let det = self.determinant();
+                if det == T::zero() {
+                    return None;
+                }
+                // The transpose of the cofactor matrix divided by the determinant.
+                Some(Self(array::from_fn(|row| {
+                    Tuple(array::from_fn(|col| self.cofactor(col, row) / det))
+                })))
+            }
+        }
+    };
+}
+
+impl_cofactor_expansion!(Matrix3, 3, Matrix2);
+impl_cofactor_expansion!(Matrix4, 4, Matrix3);
+
+impl<T: Scalar, const R: usize, const C: usize> Default for Matrix<T, R, C> {
+    /// Returns a matrix with all elements being zero.
+    fn default() -> Self {
+        Self([(); R].map(|_| Tuple([(); C].map(|_| T::zero()))))
+    }
+}
+
+/// Access an element by `matrix[(row, col)]`.
+impl<T: Scalar, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.0[row][col]
+    }
+}
+
+impl<T: Scalar, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        &mut self.0[row][col]
+    }
+}
+
+/// Returns the dot product of two tuples.
+fn dot<T: Scalar, const N: usize>(lhs: &Tuple<T, N>, rhs: &Tuple<T, N>) -> T {
+    let mut prod = T::zero();
+    for (l, r) in IntoIterator::into_iter(lhs.0).zip(rhs.0) {
+        prod += l * r;
+    }
+    prod
+}
+
+/// Matrix multiplication: `Matrix<T, R, K> * Matrix<T, K, C>` = `Matrix<T, R, C>`.
+impl<T: Scalar, const R: usize, const K: usize, const C: usize> Mul<Matrix<T, K, C>>
+    for Matrix<T, R, K>
+{
+    type Output = Matrix<T, R, C>;
+
+    fn mul(self, rhs: Matrix<T, K, C>) -> Self::Output {
+        Matrix(array::from_fn(|i| {
+            Tuple(array::from_fn(|j| dot(&self.0[i], &rhs.col(j))))
+        }))
+    }
+}
+
+/// Multiply a matrix with a tuple (treated as a column vector):
+/// `Matrix<T, R, C> * Tuple<T, C>` = `Tuple<T, R>`.
+impl<T: Scalar, const R: usize, const C: usize> Mul<Tuple<T, C>> for Matrix<T, R, C> {
+    type Output = Tuple<T, R>;
+
+    fn mul(self, rhs: Tuple<T, C>) -> Self::Output {
+        Tuple(array::from_fn(|i| dot(&self.0[i], &rhs)))
+    }
+}
+
+/// Transform a point: `Matrix * Point` = `Point`.
+/// The last component stays one as a homogeneous point requires.
+impl<T: Scalar, const N: usize> Mul<Point<T, N>> for Matrix<T, N, N> {
+    type Output = Point<T, N>;
+
+    fn mul(self, rhs: Point<T, N>) -> Self::Output {
+        let Point(tp) = rhs;
+        let mut p = Point(self * tp);
+        p[N - 1] = T::one();
+        p
+    }
+}
+
+/// Transform a vector: `Matrix * Vector` = `Vector`.
+/// The last component stays zero as a homogeneous vector requires, so
+/// that translations never affect vectors.
+impl<T: Scalar, const N: usize> Mul<Vector<T, N>> for Matrix<T, N, N> {
+    type Output = Vector<T, N>;
+
+    fn mul(self, rhs: Vector<T, N>) -> Self::Output {
+        let Vector(tv) = rhs;
+        let mut v = Vector(self * tv);
+        v[N - 1] = T::zero();
+        v
+    }
+}
+
+// Implementation of construction from `[[T; C]; R]` with `From`.
+impl<T: Scalar, const R: usize, const C: usize> From<[[T; C]; R]> for Matrix<T, R, C> {
+    fn from(src: [[T; C]; R]) -> Self {
+        Self::new(src)
+    }
+}
+
+// Implementation of construction from `Matrix<T, R, C>` to `[[T; C]; R]` with `From`.
+impl<T: Scalar, const R: usize, const C: usize> From<Matrix<T, R, C>> for [[T; C]; R] {
+    fn from(src: Matrix<T, R, C>) -> Self {
+        src.0.map(|row| row.0)
+    }
+}
+
+impl<T: Scalar + AbsDiffEq, const R: usize, const C: usize> AbsDiffEq for Matrix<T, R, C>
+where
+    T::Epsilon: Copy,
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| lhs.abs_diff_eq(rhs, epsilon))
+    }
+}
+
+impl<T: Scalar + RelativeEq, const R: usize, const C: usize> RelativeEq for Matrix<T, R, C>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| lhs.relative_eq(rhs, epsilon, max_relative))
+    }
+}
+
+impl<T: Scalar + UlpsEq, const R: usize, const C: usize> UlpsEq for Matrix<T, R, C>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| lhs.ulps_eq(rhs, epsilon, max_ulps))
+    }
+}
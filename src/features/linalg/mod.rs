@@ -1,3 +1,7 @@
+pub mod matrix;
 pub(crate) mod point;
+pub mod quaternion;
+mod simd;
+pub mod transform;
 pub mod tuple;
 pub(crate) mod vector;
@@ -1,5 +1,6 @@
 use std::ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign};
 
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
 use bytemuck::{Pod, Zeroable};
 
 use crate::{Scalar, Vector};
@@ -171,3 +172,49 @@ impl<T: Scalar, const N: usize> AsMut<[T; N]> for Point<T, N> {
         &mut self.0 .0
     }
 }
+
+impl<T: Scalar + AbsDiffEq, const N: usize> AbsDiffEq for Point<T, N>
+where
+    T::Epsilon: Copy,
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.0.abs_diff_eq(&other.0, epsilon)
+    }
+}
+
+impl<T: Scalar + RelativeEq, const N: usize> RelativeEq for Point<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.0.relative_eq(&other.0, epsilon, max_relative)
+    }
+}
+
+impl<T: Scalar + UlpsEq, const N: usize> UlpsEq for Point<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.0.ulps_eq(&other.0, epsilon, max_ulps)
+    }
+}
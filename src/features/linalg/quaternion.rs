@@ -0,0 +1,287 @@
+//! Implementation of `Quaternion`, the representation of rotations used to
+//! animate orientations without the gimbal lock of Euler angles.
+use std::ops::{Add, Deref, DerefMut, Mul, Neg, Sub};
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+use bytemuck::{Pod, Zeroable};
+
+use crate::{Float, Matrix4, Vector3};
+
+use super::tuple::{Tuple, View4};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+#[repr(transparent)]
+/// A quaternion `xi + yj + zk + w` with scalar type `T`.
+/// The components are accessible by `.x`, `.y`, `.z` and `.w`.
+pub struct Quaternion<T: Float>(pub(crate) Tuple<T, 4>);
+
+// `Zeroable` impls for "Quaternion" types are sound:
+//
+// - They are inhabited: structs plus bound `T: Zeroable`.
+// - They only consists of `Zeroable` fields, thus zero bit pattern is fine.
+unsafe impl<T: Float + Zeroable> Zeroable for Quaternion<T> {}
+
+// `Pod` impls for "Quaternion" types are sound:
+//
+// - "The type must be inhabited": guaranteed by all being structs and the bound `T: Pod`.
+// - "The type must not contain any padding bytes": this is true according to [1].
+// - "The type needs to have all fields also be `Pod`": trivially true due to `T: Pod`.
+// - "The type must allow any bit pattern": true based on the previous two facts.
+// - "The type needs to be `repr(C)` or `repr(transparent)`": trivially true.
+//
+// [1] https://doc.rust-lang.org/reference/type-layout.html#reprc-structs
+unsafe impl<T: Float + Pod> Pod for Quaternion<T> {}
+
+/// Enable access by `.x`, `.y`, `.z` and `.w`
+impl<T: Float> Deref for Quaternion<T> {
+    type Target = View4<T>;
+
+    fn deref(&self) -> &Self::Target {
+        bytemuck::cast_ref(self)
+    }
+}
+
+impl<T: Float> DerefMut for Quaternion<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        bytemuck::cast_mut(self)
+    }
+}
+
+impl<T: Float> Default for Quaternion<T> {
+    /// The identity rotation.
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Float> Quaternion<T> {
+    /// Returns the quaternion `xi + yj + zk + w`.
+    pub fn new(x: T, y: T, z: T, w: T) -> Self {
+        Self(Tuple([x, y, z, w]))
+    }
+    /// Returns the quaternion representing no rotation.
+    pub fn identity() -> Self {
+        Self::new(T::zero(), T::zero(), T::zero(), T::one())
+    }
+    /// Returns the unit quaternion rotating by `angle` radians around `axis`,
+    /// following the same handedness as `Matrix4::rotation_x` and friends.
+    pub fn from_axis_angle(axis: Vector3<T>, angle: T) -> Self {
+        let axis = axis.normalized();
+        let (sin, cos) = (angle / T::two()).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+    /// Returns the dot product of two quaternions.
+    pub fn dot(&self, rhs: &Self) -> T {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z + self.w * rhs.w
+    }
+    /// Returns the magnitude (or norm) of this quaternion.
+    pub fn magnitude(&self) -> T {
+        self.dot(self).sqrt()
+    }
+    /// Returns a normalized version of this quaternion.
+    #[must_use = "to normalize in-place, use `Quaternion::normalize`, not `normalized`"]
+    pub fn normalized(mut self) -> Self {
+        self.normalize();
+        self
+    }
+    /// Normalizes the quaternion *in place*.
+    pub fn normalize(&mut self) {
+        let m = self.magnitude();
+        self.0 /= m;
+    }
+    /// Returns the conjugate `-xi - yj - zk + w`, which is also the inverse
+    /// rotation of a unit quaternion.
+    pub fn conjugate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+    /// Spherical linear interpolation between `self` (`t = 0`) and `other`
+    /// (`t = 1`) along the shortest arc. Both are expected to be unit quaternions.
+    pub fn slerp(&self, other: &Self, t: T) -> Self {
+        let mut other = *other;
+        let mut cos_theta = self.dot(&other);
+        // `q` and `-q` are the same rotation, take the shortest path.
+        if cos_theta < T::zero() {
+            other = -other;
+            cos_theta = -cos_theta;
+        }
+        // Nearly parallel quaternions: fall back to normalized linear interpolation
+        // to avoid the division by `sin(theta) ~ 0`.
+        let threshold = T::from_f64(0.9995);
+        if cos_theta > threshold {
+            return (*self + (other - *self) * t).normalized();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let s0 = ((T::one() - t) * theta).sin() / sin_theta;
+        let s1 = (t * theta).sin() / sin_theta;
+        *self * s0 + other * s1
+    }
+    /// Returns the rotation matrix of this (unit) quaternion.
+    pub fn to_matrix(&self) -> Matrix4<T> {
+        let Self(Tuple([x, y, z, w])) = *self;
+        let (one, two, zero) = (T::one(), T::two(), T::zero());
+        Matrix4::new([
+            [
+                one - two * (y * y + z * z),
+                two * (x * y - z * w),
+                two * (x * z + y * w),
+                zero,
+            ],
+            [
+                two * (x * y + z * w),
+                one - two * (x * x + z * z),
+                two * (y * z - x * w),
+                zero,
+            ],
+            [
+                two * (x * z - y * w),
+                two * (y * z + x * w),
+                one - two * (x * x + y * y),
+                zero,
+            ],
+            [zero, zero, zero, one],
+        ])
+    }
+    /// Returns the unit quaternion of the rotation part (the upper-left 3x3)
+    /// of `m`. The matrix is expected to be a pure rotation.
+    pub fn from_matrix(m: &Matrix4<T>) -> Self {
+        let quarter = T::one() / T::four();
+        let trace = m[(0, 0)] + m[(1, 1)] + m[(2, 2)];
+        // Pick the numerically stable branch by the largest diagonal element.
+        let q = if trace > T::zero() {
+            let s = (trace + T::one()).sqrt() * T::two();
+            Self::new(
+                (m[(2, 1)] - m[(1, 2)]) / s,
+                (m[(0, 2)] - m[(2, 0)]) / s,
+                (m[(1, 0)] - m[(0, 1)]) / s,
+                quarter * s,
+            )
+        } else if m[(0, 0)] > m[(1, 1)] && m[(0, 0)] > m[(2, 2)] {
+            let s = (T::one() + m[(0, 0)] - m[(1, 1)] - m[(2, 2)]).sqrt() * T::two();
+            Self::new(
+                quarter * s,
+                (m[(0, 1)] + m[(1, 0)]) / s,
+                (m[(0, 2)] + m[(2, 0)]) / s,
+                (m[(2, 1)] - m[(1, 2)]) / s,
+            )
+        } else if m[(1, 1)] > m[(2, 2)] {
+            let s = (T::one() + m[(1, 1)] - m[(0, 0)] - m[(2, 2)]).sqrt() * T::two();
+            Self::new(
+                (m[(0, 1)] + m[(1, 0)]) / s,
+                quarter * s,
+                (m[(1, 2)] + m[(2, 1)]) / s,
+                (m[(0, 2)] - m[(2, 0)]) / s,
+            )
+        } else {
+            let s = (T::one() + m[(2, 2)] - m[(0, 0)] - m[(1, 1)]).sqrt() * T::two();
+            Self::new(
+                (m[(0, 2)] + m[(2, 0)]) / s,
+                (m[(1, 2)] + m[(2, 1)]) / s,
+                quarter * s,
+                (m[(1, 0)] - m[(0, 1)]) / s,
+            )
+        };
+        q.normalized()
+    }
+}
+
+impl<T: Float> From<Quaternion<T>> for Matrix4<T> {
+    fn from(src: Quaternion<T>) -> Self {
+        src.to_matrix()
+    }
+}
+
+impl<T: Float> Add for Quaternion<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<T: Float> Sub for Quaternion<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<T: Float> Neg for Quaternion<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+/// Scalar multiplication: `quaternion * scalar`.
+impl<T: Float> Mul<T> for Quaternion<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+
+/// Hamilton product: `a * b` is the rotation `b` followed by `a`.
+impl<T: Float> Mul for Quaternion<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let Self(Tuple([x1, y1, z1, w1])) = self;
+        let Self(Tuple([x2, y2, z2, w2])) = rhs;
+        Self::new(
+            w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+            w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+            w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+        )
+    }
+}
+
+impl<T: Float + AbsDiffEq> AbsDiffEq for Quaternion<T>
+where
+    T::Epsilon: Copy,
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.0.abs_diff_eq(&other.0, epsilon)
+    }
+}
+
+impl<T: Float + RelativeEq> RelativeEq for Quaternion<T>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.0.relative_eq(&other.0, epsilon, max_relative)
+    }
+}
+
+impl<T: Float + UlpsEq> UlpsEq for Quaternion<T>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.0.ulps_eq(&other.0, epsilon, max_ulps)
+    }
+}
@@ -0,0 +1,220 @@
+//! SIMD kernels for the 4-component tuples of `f32` and `f64`, the ones
+//! behind [`Vector3`](crate::Vector3) and [`Point3`](crate::Point3).
+//!
+//! Rust has no specialization, so the generic operations of the tuples ask
+//! these functions first: they return `None` unless the scalar type is
+//! `f32` or `f64` with 4 components on `x86_64`, and the callers fall back
+//! to their generic loops. The other architectures only see fallbacks
+//! returning `None`. The checks on the type are resolved when the
+//! generic code is instantiated and cost nothing at run time. SSE2 is part
+//! of every `x86_64` CPU, which makes the calls to the kernels sound: an
+//! `f32` tuple fits a single register, an `f64` tuple two of them.
+#[cfg(target_arch = "x86_64")]
+use std::any::TypeId;
+
+#[cfg(target_arch = "x86_64")]
+use crate::Scalar;
+
+/// Returns `value` as an `[f32; 4]` if it is one.
+#[cfg(target_arch = "x86_64")]
+fn as_f32<T: Scalar, const N: usize>(value: &[T; N]) -> Option<[f32; 4]> {
+    (N == 4 && TypeId::of::<T>() == TypeId::of::<f32>())
+        .then(|| bytemuck::cast_slice(value).try_into().unwrap())
+}
+
+/// Returns `value` as an `[f64; 4]` if it is one.
+#[cfg(target_arch = "x86_64")]
+fn as_f64<T: Scalar, const N: usize>(value: &[T; N]) -> Option<[f64; 4]> {
+    (N == 4 && TypeId::of::<T>() == TypeId::of::<f64>())
+        .then(|| bytemuck::cast_slice(value).try_into().unwrap())
+}
+
+/// Returns the result of a kernel as an `[T; N]`, `T` being its type.
+#[cfg(target_arch = "x86_64")]
+fn from_kernel<T: Scalar, U: Scalar, const N: usize>(value: [U; 4]) -> [T; N] {
+    bytemuck::cast_slice(&value).try_into().unwrap()
+}
+
+/// Returns the dot product of `a` and `b`. The products are summed as
+/// `(x + z) + (y + w)`, not from `x` to `w` as the generic loop does, so
+/// the result may differ from it in the last bit; the renders are only
+/// reproducible to the bit on the same architecture.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn dot<T: Scalar, const N: usize>(a: &[T; N], b: &[T; N]) -> Option<T> {
+    if let (Some(a), Some(b)) = (as_f32(a), as_f32(b)) {
+        return Some(bytemuck::cast(unsafe { kernels::dot_f32(a, b) }));
+    }
+    if let (Some(a), Some(b)) = (as_f64(a), as_f64(b)) {
+        return Some(bytemuck::cast(unsafe { kernels::dot_f64(a, b) }));
+    }
+    None
+}
+
+/// Returns the component-wise sum of `a` and `b`, or their difference if
+/// `negate` is set.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn add<T: Scalar, const N: usize>(
+    a: &[T; N],
+    b: &[T; N],
+    negate: bool,
+) -> Option<[T; N]> {
+    if let (Some(a), Some(b)) = (as_f32(a), as_f32(b)) {
+        return Some(from_kernel(unsafe { kernels::add_f32(a, b, negate) }));
+    }
+    if let (Some(a), Some(b)) = (as_f64(a), as_f64(b)) {
+        return Some(from_kernel(unsafe { kernels::add_f64(a, b, negate) }));
+    }
+    None
+}
+
+/// Returns `a` with every component multiplied by `s`.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn scale<T: Scalar, const N: usize>(a: &[T; N], s: T) -> Option<[T; N]> {
+    if let Some(a) = as_f32(a) {
+        return Some(from_kernel(unsafe {
+            kernels::scale_f32(a, bytemuck::cast(s))
+        }));
+    }
+    if let Some(a) = as_f64(a) {
+        return Some(from_kernel(unsafe {
+            kernels::scale_f64(a, bytemuck::cast(s))
+        }));
+    }
+    None
+}
+
+/// Returns the cross product of the vectors `a` and `b`, whose last
+/// component is zero. Only `f32` is accelerated: the shuffles across the
+/// two registers of an `f64` tuple cost as much as the scalar code.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn cross<T: Scalar>(a: &[T; 4], b: &[T; 4]) -> Option<[T; 4]> {
+    let (a, b) = (as_f32(a)?, as_f32(b)?);
+    Some(from_kernel(unsafe { kernels::cross_f32(a, b) }))
+}
+
+/// The kernels, which require SSE2.
+#[cfg(target_arch = "x86_64")]
+mod kernels {
+    use std::arch::x86_64::*;
+
+    #[target_feature(enable = "sse2")]
+    fn load_f32(a: [f32; 4]) -> __m128 {
+        _mm_set_ps(a[3], a[2], a[1], a[0])
+    }
+
+    #[target_feature(enable = "sse2")]
+    fn store_f32(v: __m128) -> [f32; 4] {
+        // SAFETY: `__m128` and `[f32; 4]` have the same size, and any bit
+        // pattern is a valid `f32`.
+        unsafe { std::mem::transmute(v) }
+    }
+
+    /// Loads the low and high halves of `a`.
+    #[target_feature(enable = "sse2")]
+    fn load_f64(a: [f64; 4]) -> (__m128d, __m128d) {
+        (_mm_set_pd(a[1], a[0]), _mm_set_pd(a[3], a[2]))
+    }
+
+    #[target_feature(enable = "sse2")]
+    fn store_f64((low, high): (__m128d, __m128d)) -> [f64; 4] {
+        // SAFETY: `__m128d` and `[f64; 2]` have the same size, and any bit
+        // pattern is a valid `f64`.
+        let (low, high) = unsafe {
+            (
+                std::mem::transmute::<__m128d, [f64; 2]>(low),
+                std::mem::transmute::<__m128d, [f64; 2]>(high),
+            )
+        };
+        [low[0], low[1], high[0], high[1]]
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn dot_f32(a: [f32; 4], b: [f32; 4]) -> f32 {
+        let m = _mm_mul_ps(load_f32(a), load_f32(b));
+        // (x + z, y + w), then the sum of both.
+        let s = _mm_add_ps(m, _mm_movehl_ps(m, m));
+        let s = _mm_add_ss(s, _mm_shuffle_ps::<0b01>(s, s));
+        _mm_cvtss_f32(s)
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn dot_f64(a: [f64; 4], b: [f64; 4]) -> f64 {
+        let ((a_low, a_high), (b_low, b_high)) = (load_f64(a), load_f64(b));
+        let m = _mm_add_pd(_mm_mul_pd(a_low, b_low), _mm_mul_pd(a_high, b_high));
+        _mm_cvtsd_f64(_mm_add_sd(m, _mm_unpackhi_pd(m, m)))
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn add_f32(a: [f32; 4], b: [f32; 4], negate: bool) -> [f32; 4] {
+        let (a, b) = (load_f32(a), load_f32(b));
+        store_f32(if negate {
+            _mm_sub_ps(a, b)
+        } else {
+            _mm_add_ps(a, b)
+        })
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn add_f64(a: [f64; 4], b: [f64; 4], negate: bool) -> [f64; 4] {
+        let ((a_low, a_high), (b_low, b_high)) = (load_f64(a), load_f64(b));
+        store_f64(if negate {
+            (_mm_sub_pd(a_low, b_low), _mm_sub_pd(a_high, b_high))
+        } else {
+            (_mm_add_pd(a_low, b_low), _mm_add_pd(a_high, b_high))
+        })
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn scale_f32(a: [f32; 4], s: f32) -> [f32; 4] {
+        store_f32(_mm_mul_ps(load_f32(a), _mm_set1_ps(s)))
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn scale_f64(a: [f64; 4], s: f64) -> [f64; 4] {
+        let ((low, high), s) = (load_f64(a), _mm_set1_pd(s));
+        store_f64((_mm_mul_pd(low, s), _mm_mul_pd(high, s)))
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn cross_f32(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+        // (y, z, x, w) of each operand.
+        const YZXW: i32 = 0b11_00_10_01;
+        let (a, b) = (load_f32(a), load_f32(b));
+        let a_yzx = _mm_shuffle_ps::<YZXW>(a, a);
+        let b_yzx = _mm_shuffle_ps::<YZXW>(b, b);
+        let c = _mm_sub_ps(_mm_mul_ps(a, b_yzx), _mm_mul_ps(a_yzx, b));
+        let mut c = store_f32(_mm_shuffle_ps::<YZXW>(c, c));
+        c[3] = 0.0;
+        c
+    }
+}
+
+/// The fallbacks of the other architectures, where the callers always take
+/// their generic loops.
+#[cfg(not(target_arch = "x86_64"))]
+mod fallback {
+    use crate::Scalar;
+
+    pub(crate) fn dot<T: Scalar, const N: usize>(_: &[T; N], _: &[T; N]) -> Option<T> {
+        None
+    }
+
+    pub(crate) fn add<T: Scalar, const N: usize>(
+        _: &[T; N],
+        _: &[T; N],
+        _: bool,
+    ) -> Option<[T; N]> {
+        None
+    }
+
+    pub(crate) fn scale<T: Scalar, const N: usize>(_: &[T; N], _: T) -> Option<[T; N]> {
+        None
+    }
+
+    pub(crate) fn cross<T: Scalar>(_: &[T; 4], _: &[T; 4]) -> Option<[T; 4]> {
+        None
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) use fallback::{add, cross, dot, scale};
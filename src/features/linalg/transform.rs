@@ -0,0 +1,189 @@
+//! Constructors of the 4x4 transformation matrices acting on the
+//! homogeneous `Point3` and `Vector3`.
+use crate::{Float, Point3, Quaternion, Scalar, Vector3};
+
+use super::matrix::{Matrix3, Matrix4};
+
+/// The components of an affine transformation: the matrix is recomposed
+/// as `translation * rotation * scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposition<T: Float> {
+    pub translation: Vector3<T>,
+    pub rotation: Quaternion<T>,
+    pub scale: Vector3<T>,
+}
+
+impl<T: Float> Decomposition<T> {
+    /// Recomposes the transformation matrix.
+    pub fn to_matrix(&self) -> Matrix4<T> {
+        let Self {
+            translation: t,
+            rotation,
+            scale: s,
+        } = self;
+        Matrix4::translation(t.x, t.y, t.z) * rotation.to_matrix() * Matrix4::scaling(s.x, s.y, s.z)
+    }
+}
+
+/// A transformation in 3-dimensional space is a 4x4 matrix acting on
+/// homogeneous coordinates.
+pub type Transform<T> = Matrix4<T>;
+
+impl<T: Scalar> Matrix4<T> {
+    /// Returns a matrix translating points by `(x, y, z)`.
+    /// Vectors are left unchanged since their `w` is zero.
+    pub fn translation(x: T, y: T, z: T) -> Self {
+        let mut m = Self::identity();
+        m[(0, 3)] = x;
+        m[(1, 3)] = y;
+        m[(2, 3)] = z;
+        m
+    }
+    /// Returns a matrix scaling points and vectors by `(x, y, z)`.
+    /// A negative component reflects along that axis.
+    pub fn scaling(x: T, y: T, z: T) -> Self {
+        let mut m = Self::identity();
+        m[(0, 0)] = x;
+        m[(1, 1)] = y;
+        m[(2, 2)] = z;
+        m
+    }
+}
+
+impl<T: Float> Matrix4<T> {
+    /// Returns a matrix rotating around the x axis by `rad` radians,
+    /// following the left-hand rule.
+    pub fn rotation_x(rad: T) -> Self {
+        let (sin, cos) = rad.sin_cos();
+        let mut m = Self::identity();
+        m[(1, 1)] = cos;
+        m[(1, 2)] = -sin;
+        m[(2, 1)] = sin;
+        m[(2, 2)] = cos;
+        m
+    }
+    /// Returns a matrix rotating around the y axis by `rad` radians,
+    /// following the left-hand rule.
+    pub fn rotation_y(rad: T) -> Self {
+        let (sin, cos) = rad.sin_cos();
+        let mut m = Self::identity();
+        m[(0, 0)] = cos;
+        m[(0, 2)] = sin;
+        m[(2, 0)] = -sin;
+        m[(2, 2)] = cos;
+        m
+    }
+    /// Returns a matrix rotating around the z axis by `rad` radians,
+    /// following the left-hand rule.
+    pub fn rotation_z(rad: T) -> Self {
+        let (sin, cos) = rad.sin_cos();
+        let mut m = Self::identity();
+        m[(0, 0)] = cos;
+        m[(0, 1)] = -sin;
+        m[(1, 0)] = sin;
+        m[(1, 1)] = cos;
+        m
+    }
+    /// Returns a matrix rotating by `rad` radians around an arbitrary `axis`
+    /// passing through the origin (Rodrigues' rotation formula). The rotation
+    /// follows the same handedness as `rotation_x`, `rotation_y` and `rotation_z`.
+    pub fn rotation_around_axis(axis: Vector3<T>, rad: T) -> Self {
+        let axis = axis.normalized();
+        let (x, y, z) = (axis.x, axis.y, axis.z);
+        let (sin, cos) = rad.sin_cos();
+        let t = T::one() - cos;
+        let mut m = Self::identity();
+        m[(0, 0)] = t * x * x + cos;
+        m[(0, 1)] = t * x * y - sin * z;
+        m[(0, 2)] = t * x * z + sin * y;
+        m[(1, 0)] = t * x * y + sin * z;
+        m[(1, 1)] = t * y * y + cos;
+        m[(1, 2)] = t * y * z - sin * x;
+        m[(2, 0)] = t * x * z - sin * y;
+        m[(2, 1)] = t * y * z + sin * x;
+        m[(2, 2)] = t * z * z + cos;
+        m
+    }
+    /// Transforms a surface `normal` by this transformation: multiplies by the
+    /// inverse-transpose, so that the normal stays perpendicular to the surface
+    /// under non-uniform scaling, then re-normalizes it.
+    ///
+    /// Returns `None` if this matrix is not invertible.
+    pub fn transform_normal(&self, normal: Vector3<T>) -> Option<Vector3<T>> {
+        // `Matrix * Vector` discards the `w` polluted by the transposed translation.
+        Some((self.inverse()?.transpose() * normal).normalized())
+    }
+    /// Returns the transformation orienting the world relative to an eye
+    /// at `from` looking at `to`, with `up` roughly pointing upwards.
+    pub fn view_transform(from: Point3<T>, to: Point3<T>, up: Vector3<T>) -> Self {
+        let forward = (to - from).normalized();
+        let left = forward.cross(&up.normalized());
+        let true_up = left.cross(&forward);
+        let orientation = Self::new([
+            [left.x, left.y, left.z, T::zero()],
+            [true_up.x, true_up.y, true_up.z, T::zero()],
+            [-forward.x, -forward.y, -forward.z, T::zero()],
+            [T::zero(), T::zero(), T::zero(), T::one()],
+        ]);
+        orientation * Self::translation(-from.x, -from.y, -from.z)
+    }
+    /// Decomposes an affine transformation into its translation, rotation
+    /// and scale components. A reflection is expressed as a negative scale
+    /// along x. Any shearing is lost in the process.
+    ///
+    /// Returns `None` if the matrix is not affine (the last row is not
+    /// `[0, 0, 0, 1]`) or if one of the axes is scaled to zero.
+    pub fn decompose(&self) -> Option<Decomposition<T>> {
+        if self.row(3).0 != [T::zero(), T::zero(), T::zero(), T::one()] {
+            return None;
+        }
+        let translation = Vector3::new(self[(0, 3)], self[(1, 3)], self[(2, 3)]);
+        // The length of each column of the linear part is the scale on that axis.
+        let col = |j: usize| Vector3::new(self[(0, j)], self[(1, j)], self[(2, j)]);
+        let mut scale = Vector3::new(col(0).magnitude(), col(1).magnitude(), col(2).magnitude());
+        if scale.x == T::zero() || scale.y == T::zero() || scale.z == T::zero() {
+            return None;
+        }
+        let linear = Matrix3::new([
+            [self[(0, 0)], self[(0, 1)], self[(0, 2)]],
+            [self[(1, 0)], self[(1, 1)], self[(1, 2)]],
+            [self[(2, 0)], self[(2, 1)], self[(2, 2)]],
+        ]);
+        if linear.determinant() < T::zero() {
+            scale.x = -scale.x;
+        }
+        let mut rotation = Self::identity();
+        for i in 0..3 {
+            for j in 0..3 {
+                rotation[(i, j)] = self[(i, j)] / scale[j];
+            }
+        }
+        Some(Decomposition {
+            translation,
+            rotation: Quaternion::from_matrix(&rotation),
+            scale,
+        })
+    }
+    /// Returns the transformation between `start` at `t = 0` and `end` at
+    /// `t = 1`: the components of [`Matrix4::decompose`] are blended, the
+    /// translations and scales linearly and the rotations spherically, so
+    /// that a spinning object keeps its size on the way. Matrices which
+    /// cannot be decomposed are blended element by element.
+    pub fn interpolate(start: &Self, end: &Self, t: T) -> Self {
+        if let (Some(a), Some(b)) = (start.decompose(), end.decompose()) {
+            let blend = Decomposition {
+                translation: a.translation + (b.translation - a.translation) * t,
+                rotation: a.rotation.slerp(&b.rotation, t),
+                scale: a.scale + (b.scale - a.scale) * t,
+            };
+            return blend.to_matrix();
+        }
+        let mut m = *start;
+        for row in 0..4 {
+            for col in 0..4 {
+                m[(row, col)] = start[(row, col)] + (end[(row, col)] - start[(row, col)]) * t;
+            }
+        }
+        m
+    }
+}
@@ -2,6 +2,7 @@
 //! Implement access by name (`x`, `y`, `z` and `w`) referring to `lina`.
 //! Due to the context of this lib, we only deal with 3D homogeneous points or vectors,
 //! therefore, we just need to implement the traits for `Tuple<T,4>`.
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
 use bytemuck::{Pod, Zeroable};
 use std::{
     array,
@@ -11,6 +12,7 @@ use std::{
     },
 };
 
+use super::simd;
 use crate::{Point, Scalar, Vector};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
@@ -177,6 +179,9 @@ impl<T: Scalar, const N: usize> Add for Tuple<T, N> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
+        if let Some(sum) = simd::add(&self.0, &rhs.0, false) {
+            return Self(sum);
+        }
         Self(array::from_fn(|i| self[i] + rhs[i]))
     }
 }
@@ -193,6 +198,9 @@ impl<T: Scalar, const N: usize> Sub for Tuple<T, N> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
+        if let Some(difference) = simd::add(&self.0, &rhs.0, true) {
+            return Self(difference);
+        }
         Self(array::from_fn(|i| self[i] - rhs[i]))
     }
 }
@@ -220,6 +228,9 @@ impl<T: Scalar, const N: usize> Mul<T> for Tuple<T, N> {
     type Output = Tuple<T, N>;
     fn mul(self, rhs: T) -> Self::Output {
         let Self(t) = self;
+        if let Some(product) = simd::scale(&t, rhs) {
+            return Tuple(product);
+        }
         Tuple(t.map(|c| c * rhs))
     }
 }
@@ -248,3 +259,58 @@ impl<T: Scalar, const N: usize> DivAssign<T> for Tuple<T, N> {
         }
     }
 }
+
+impl<T: Scalar + AbsDiffEq, const N: usize> AbsDiffEq for Tuple<T, N>
+where
+    T::Epsilon: Copy,
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| T::abs_diff_eq(lhs, rhs, epsilon))
+    }
+}
+
+impl<T: Scalar + RelativeEq, const N: usize> RelativeEq for Tuple<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| T::relative_eq(lhs, rhs, epsilon, max_relative))
+    }
+}
+
+impl<T: Scalar + UlpsEq, const N: usize> UlpsEq for Tuple<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .all(|(lhs, rhs)| T::ulps_eq(lhs, rhs, epsilon, max_ulps))
+    }
+}
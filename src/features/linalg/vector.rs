@@ -3,11 +3,15 @@ use std::{
     ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
 use bytemuck::{Pod, Zeroable};
 
 use crate::{Float, Point, Scalar};
 
-use super::tuple::{HasX, HasY, HasZ, Tuple};
+use super::{
+    simd,
+    tuple::{HasX, HasY, HasZ, Tuple},
+};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
 #[repr(transparent)]
@@ -46,6 +50,9 @@ impl<T: Scalar, const N: usize> Vector<T, N> {
     }
     /// Returns the *squared* length of this vector.
     pub fn length2(&self) -> T {
+        if let Some(length2) = simd::dot(&self.0 .0, &self.0 .0) {
+            return length2;
+        }
         self.0
              .0
             .iter()
@@ -104,6 +111,9 @@ impl<T: Scalar, const N: usize> Vector<T, N> {
     }
     /// Returns the dot product of this vector and another.
     pub fn dot(&self, rhs: &Vector<T, N>) -> T {
+        if let Some(prod) = simd::dot(&self.0 .0, &rhs.0 .0) {
+            return prod;
+        }
         let mut prod = T::zero();
         for (lhs, rhs) in IntoIterator::into_iter(self.0 .0).zip(rhs.0 .0) {
             prod += lhs * rhs;
@@ -135,6 +145,9 @@ impl<T: Scalar> Vector<T, 4> {
         Self(Tuple([x, y, z, T::zero()]))
     }
     pub fn cross(&self, rhs: &Vector<T, 4>) -> Self {
+        if let Some(cross) = simd::cross(&self.0 .0, &rhs.0 .0) {
+            return Self(Tuple(cross));
+        }
         Self(Tuple([
             self.y * rhs.z - self.z * rhs.y,
             self.z * rhs.x - self.x * rhs.z,
@@ -142,6 +155,12 @@ impl<T: Scalar> Vector<T, 4> {
             T::zero(),
         ]))
     }
+    /// Returns this vector reflected around `normal`, as a ray bounces off
+    /// a surface. `normal` is expected to be normalized.
+    pub fn reflect(&self, normal: &Vector<T, 4>) -> Self {
+        let two = T::one() + T::one();
+        *self - *normal * (two * self.dot(normal))
+    }
 }
 
 impl<T: Scalar, const N: usize> Default for Vector<T, N> {
@@ -318,3 +337,49 @@ impl<T: Scalar, const N: usize> AsMut<[T; N]> for Vector<T, N> {
         &mut self.0 .0
     }
 }
+
+impl<T: Scalar + AbsDiffEq, const N: usize> AbsDiffEq for Vector<T, N>
+where
+    T::Epsilon: Copy,
+{
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.0.abs_diff_eq(&other.0, epsilon)
+    }
+}
+
+impl<T: Scalar + RelativeEq, const N: usize> RelativeEq for Vector<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.0.relative_eq(&other.0, epsilon, max_relative)
+    }
+}
+
+impl<T: Scalar + UlpsEq, const N: usize> UlpsEq for Vector<T, N>
+where
+    T::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        T::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.0.ulps_eq(&other.0, epsilon, max_ulps)
+    }
+}
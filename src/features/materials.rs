@@ -0,0 +1,187 @@
+//! Implementation of `Material`, the surface properties used by the Phong
+//! reflection model.
+use std::sync::Arc;
+
+use crate::{
+    features::{colors::Color, patterns::Pattern, shapes::Shape},
+    Float, Point3,
+};
+
+#[derive(Debug, Clone)]
+/// The surface attributes of the Phong reflection model. Every shape has
+/// one, the default is a white surface with a tight highlight:
+///
+/// ```
+/// use raytracer_rust::{features::colors::Color, Material};
+///
+/// let red = Material::default()
+///     .with_color(Color::new(1.0, 0.0, 0.0))
+///     .with_shininess(50.0);
+/// assert_eq!(red.ambient, 0.1);
+/// ```
+pub struct Material<T: Float> {
+    pub color: Color<T>,
+    /// The pattern coloring the surface instead of `color`, if any. It is
+    /// shared by the clones of the material.
+    pub pattern: Option<Arc<dyn Pattern<T>>>,
+    /// The light reflected from other objects in the environment, in `[0, 1]`.
+    pub ambient: T,
+    /// The light reflected from a matte surface, in `[0, 1]`.
+    pub diffuse: T,
+    /// The reflection of the light source itself, in `[0, 1]`.
+    pub specular: T,
+    /// The larger, the smaller and tighter the specular highlight.
+    pub shininess: T,
+    /// The fraction of the light mirrored by the surface, `0` for a matte
+    /// surface and `1` for a perfect mirror.
+    pub reflective: T,
+    /// The fraction of the light let through the surface, `0` for an
+    /// opaque surface. The lights shine through it onto the surfaces
+    /// behind, see [`World::light_transmittance_at`]; the rays of the
+    /// camera are not refracted yet.
+    ///
+    /// [`World::light_transmittance_at`]: crate::World::light_transmittance_at
+    pub transparency: T,
+    /// How much the light bends entering the surface: 1 for vacuum, 1.5
+    /// for glass.
+    pub refractive_index: T,
+    /// The light given off by the surface itself, black for surfaces which
+    /// are not light sources.
+    pub emissive: Color<T>,
+}
+
+/// Materials are equal when their values are, and they share the same
+/// pattern if any.
+impl<T: Float> PartialEq for Material<T> {
+    fn eq(&self, other: &Self) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_pattern
+            && self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
+            && self.emissive == other.emissive
+    }
+}
+
+impl<T: Float> Default for Material<T> {
+    fn default() -> Self {
+        Self {
+            color: Color::new(T::one(), T::one(), T::one()),
+            pattern: None,
+            ambient: T::from_f64(0.1),
+            diffuse: T::from_f64(0.9),
+            specular: T::from_f64(0.9),
+            shininess: T::from_f64(200.0),
+            reflective: T::zero(),
+            transparency: T::zero(),
+            refractive_index: T::one(),
+            emissive: Color::default(),
+        }
+    }
+}
+
+impl<T: Float> Material<T> {
+    /// Returns a clear glass: a dark, transparent and reflective surface with
+    /// a sharp highlight and the refractive index of glass.
+    pub fn glass() -> Self {
+        Self::default()
+            .with_ambient(T::zero())
+            .with_diffuse(T::from_f64(0.1))
+            .with_specular(T::one())
+            .with_shininess(T::from_f64(300.0))
+            .with_reflective(T::from_f64(0.9))
+            .with_transparency(T::from_f64(0.9))
+            .with_refractive_index(T::from_f64(1.5))
+    }
+    /// Returns a perfect mirror, whose color is entirely given by what it reflects.
+    pub fn mirror() -> Self {
+        Self::default()
+            .with_color(Color::default())
+            .with_ambient(T::zero())
+            .with_diffuse(T::zero())
+            .with_specular(T::one())
+            .with_shininess(T::from_f64(300.0))
+            .with_reflective(T::one())
+    }
+    /// Returns a matte surface of `color`, with no highlight.
+    pub fn matte(color: Color<T>) -> Self {
+        Self::default().with_color(color).with_specular(T::zero())
+    }
+    /// Returns a polished metal of `color`: a dimmer diffuse term, a broad
+    /// strong highlight and some reflection.
+    pub fn metal(color: Color<T>) -> Self {
+        Self::default()
+            .with_color(color)
+            .with_diffuse(T::from_f64(0.6))
+            .with_specular(T::from_f64(0.9))
+            .with_shininess(T::from_f64(100.0))
+            .with_reflective(T::from_f64(0.3))
+    }
+    /// Sets the color of the surface.
+    pub fn with_color(mut self, color: Color<T>) -> Self {
+        self.color = color;
+        self
+    }
+    /// Sets the pattern coloring the surface instead of its color.
+    pub fn with_pattern(mut self, pattern: impl Pattern<T> + 'static) -> Self {
+        self.pattern = Some(Arc::new(pattern));
+        self
+    }
+    /// Returns the color of the surface at `world_point` on `object`: the
+    /// color of the pattern if any, the plain color otherwise.
+    pub fn color_at(&self, object: &dyn Shape<T>, world_point: Point3<T>) -> Color<T> {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(object, world_point),
+            None => self.color,
+        }
+    }
+    /// Sets the ambient reflection.
+    pub fn with_ambient(mut self, ambient: T) -> Self {
+        self.ambient = ambient;
+        self
+    }
+    /// Sets the diffuse reflection.
+    pub fn with_diffuse(mut self, diffuse: T) -> Self {
+        self.diffuse = diffuse;
+        self
+    }
+    /// Sets the specular reflection.
+    pub fn with_specular(mut self, specular: T) -> Self {
+        self.specular = specular;
+        self
+    }
+    /// Sets the shininess, the exponent of the specular highlight.
+    pub fn with_shininess(mut self, shininess: T) -> Self {
+        self.shininess = shininess;
+        self
+    }
+    /// Sets the fraction of the light mirrored by the surface.
+    pub fn with_reflective(mut self, reflective: T) -> Self {
+        self.reflective = reflective;
+        self
+    }
+    /// Sets the fraction of the light let through the surface.
+    pub fn with_transparency(mut self, transparency: T) -> Self {
+        self.transparency = transparency;
+        self
+    }
+    /// Sets the refractive index of the surface.
+    pub fn with_refractive_index(mut self, refractive_index: T) -> Self {
+        self.refractive_index = refractive_index;
+        self
+    }
+    /// Sets the light given off by the surface.
+    pub fn with_emissive(mut self, emissive: Color<T>) -> Self {
+        self.emissive = emissive;
+        self
+    }
+}
@@ -0,0 +1,160 @@
+//! Real roots of polynomials up to degree four, in closed form following
+//! Jochen Schwarze's "Cubic and Quartic Roots" (Graphics Gems I).
+//! The roots are returned sorted in ascending order; repeated roots are
+//! reported once per multiplicity found by the closed form.
+use std::f64::consts::FRAC_PI_3;
+
+use crate::Float;
+
+/// Tolerance below which a coefficient is considered to be zero.
+fn is_zero<T: Float>(x: T) -> bool {
+    x.abs() < T::from_f64(1e-9)
+}
+
+fn sorted<T: Float>(mut roots: Vec<T>) -> Vec<T> {
+    roots.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    roots
+}
+
+/// Returns the real roots of `a x^2 + b x + c = 0`.
+/// Degenerates to the linear equation when `a` is zero.
+pub fn solve_quadratic<T: Float>(a: T, b: T, c: T) -> Vec<T> {
+    if is_zero(a) {
+        return if is_zero(b) { Vec::new() } else { vec![-c / b] };
+    }
+    // Normal form: x^2 + 2px + q = 0
+    let p = b / (T::two() * a);
+    let q = c / a;
+    let d = p * p - q;
+    if is_zero(d) {
+        vec![-p]
+    } else if d < T::zero() {
+        Vec::new()
+    } else {
+        let sqrt_d = d.sqrt();
+        vec![-sqrt_d - p, sqrt_d - p]
+    }
+}
+
+/// Returns the real roots of `a x^3 + b x^2 + c x + d = 0`.
+/// Degenerates to the quadratic equation when `a` is zero.
+pub fn solve_cubic<T: Float>(a: T, b: T, c: T, d: T) -> Vec<T> {
+    if is_zero(a) {
+        return solve_quadratic(b, c, d);
+    }
+    // Normal form: x^3 + Ax^2 + Bx + C = 0
+    let (a, b, c) = (b / a, c / a, d / a);
+    let three = T::three();
+    // Substitute x = y - A/3 to eliminate the quadric term: y^3 + 3py + 2q = 0
+    let sq_a = a * a;
+    let p = (-sq_a / three + b) / three;
+    let q = (T::two() / T::from_f64(27.0) * a * sq_a - a * b / three + c) / T::two();
+    // Cardano's formula
+    let cb_p = p * p * p;
+    let det = q * q + cb_p;
+    let mut roots = if is_zero(det) {
+        if is_zero(q) {
+            // One triple solution
+            vec![T::zero()]
+        } else {
+            // One single and one double solution
+            let u = (-q).cbrt();
+            vec![T::two() * u, -u]
+        }
+    } else if det < T::zero() {
+        // Three real solutions (casus irreducibilis)
+        let phi = (-q / (-cb_p).sqrt()).acos() / three;
+        let t = T::two() * (-p).sqrt();
+        let pi_3 = T::from_f64(FRAC_PI_3);
+        vec![
+            t * phi.cos(),
+            -t * (phi + pi_3).cos(),
+            -t * (phi - pi_3).cos(),
+        ]
+    } else {
+        // One real solution
+        let sqrt_det = det.sqrt();
+        vec![(sqrt_det - q).cbrt() - (sqrt_det + q).cbrt()]
+    };
+    let sub = a / three;
+    for root in roots.iter_mut() {
+        *root -= sub;
+    }
+    sorted(roots)
+}
+
+/// Returns the real roots of `a x^4 + b x^3 + c x^2 + d x + e = 0`.
+/// Degenerates to the cubic equation when `a` is zero.
+///
+/// The closed form loses precision when the roots are far apart, so each
+/// root is polished by a few Newton-Raphson iterations on the original
+/// polynomial.
+pub fn solve_quartic<T: Float>(a: T, b: T, c: T, d: T, e: T) -> Vec<T> {
+    if is_zero(a) {
+        return solve_cubic(b, c, d, e);
+    }
+    let coeffs = [a, b, c, d, e];
+    // Normal form: x^4 + Ax^3 + Bx^2 + Cx + D = 0
+    let (a, b, c, d) = (b / a, c / a, d / a, e / a);
+    let (two, four, eight) = (T::two(), T::four(), T::four() * T::two());
+    // Substitute x = y - A/4 to eliminate the cubic term: y^4 + py^2 + qy + r = 0
+    let sq_a = a * a;
+    let p = -T::three() / eight * sq_a + b;
+    let q = sq_a * a / eight - a * b / two + c;
+    let r = -T::three() / T::from_f64(256.0) * sq_a * sq_a + sq_a * b / T::from_f64(16.0)
+        - a * c / four
+        + d;
+    let mut roots = if is_zero(r) {
+        // No absolute term: y(y^3 + py + q) = 0
+        let mut roots = solve_cubic(T::one(), T::zero(), p, q);
+        roots.push(T::zero());
+        roots
+    } else {
+        // Solve the resolvent cubic and take its first real root.
+        let z = solve_cubic(T::one(), -p / two, -r, r * p / two - q * q / eight)[0];
+        // Build two quadratic equations from it.
+        let u = z * z - r;
+        let v = two * z - p;
+        let u = if is_zero(u) {
+            T::zero()
+        } else if u > T::zero() {
+            u.sqrt()
+        } else {
+            return Vec::new();
+        };
+        let v = if is_zero(v) {
+            T::zero()
+        } else if v > T::zero() {
+            v.sqrt()
+        } else {
+            return Vec::new();
+        };
+        let v = if q < T::zero() { -v } else { v };
+        let mut roots = solve_quadratic(T::one(), v, z - u);
+        roots.extend(solve_quadratic(T::one(), -v, z + u));
+        roots
+    };
+    let sub = a / four;
+    for root in roots.iter_mut() {
+        *root = polish(&coeffs, *root - sub);
+    }
+    sorted(roots)
+}
+
+/// Refines a root of the polynomial with the given coefficients (highest
+/// degree first) by Newton-Raphson iterations.
+fn polish<T: Float, const N: usize>(coeffs: &[T; N], mut x: T) -> T {
+    for _ in 0..4 {
+        // Horner's scheme for the polynomial and its derivative.
+        let (mut f, mut df) = (T::zero(), T::zero());
+        for &c in coeffs {
+            df = df * x + f;
+            f = f * x + c;
+        }
+        if is_zero(df) {
+            break;
+        }
+        x -= f / df;
+    }
+    x
+}
@@ -0,0 +1,85 @@
+//! Random directions for the stochastic features: the bounces of the path
+//! tracer, ambient occlusion and soft shadows. Every function draws from
+//! the given random number generator, so a seeded generator reproduces
+//! the same directions.
+use std::{cell::RefCell, f64::consts::TAU};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{Float, Vector3};
+
+/// Returns a random unit vector, uniformly distributed on the unit sphere.
+pub fn random_unit_vector<T: Float, R: Rng + ?Sized>(rng: &mut R) -> Vector3<T> {
+    // Archimedes: the height on the sphere is uniform, as is the azimuth.
+    let z: f64 = rng.gen_range(-1.0..=1.0);
+    let phi: f64 = rng.gen_range(0.0..TAU);
+    let r = (1.0 - z * z).sqrt();
+    let (sin, cos) = phi.sin_cos();
+    Vector3::new(T::from_f64(r * cos), T::from_f64(r * sin), T::from_f64(z))
+}
+
+/// Returns a random unit vector uniformly distributed in the hemisphere
+/// around `normal`.
+pub fn random_in_hemisphere<T: Float, R: Rng + ?Sized>(
+    normal: Vector3<T>,
+    rng: &mut R,
+) -> Vector3<T> {
+    let v: Vector3<T> = random_unit_vector(rng);
+    if v.dot(&normal) < T::zero() {
+        -v
+    } else {
+        v
+    }
+}
+
+/// Returns a random unit vector in the hemisphere around the unit `normal`,
+/// with a density proportional to the cosine to the normal: `cos / π`.
+/// This is the importance sampling of a diffuse surface.
+pub fn random_cosine_direction<T: Float, R: Rng + ?Sized>(
+    normal: Vector3<T>,
+    rng: &mut R,
+) -> Vector3<T> {
+    // A uniform point on the unit sphere tangent to the surface at the
+    // normal gives a cosine-weighted direction.
+    let direction = normal + random_unit_vector(rng);
+    if direction.length2() < T::from_f64(1e-12) {
+        return normal;
+    }
+    direction.normalized()
+}
+
+/// Returns a random point `(x, y)` uniformly distributed in the unit disk,
+/// e.g. on the lens of a camera.
+pub fn random_in_unit_disk<T: Float, R: Rng + ?Sized>(rng: &mut R) -> (T, T) {
+    // The square root keeps the density uniform over the area.
+    let r = rng.gen::<f64>().sqrt();
+    let phi: f64 = rng.gen_range(0.0..TAU);
+    let (sin, cos) = phi.sin_cos();
+    (T::from_f64(r * cos), T::from_f64(r * sin))
+}
+
+/// Maps the point `(u, v)` of the unit square to the unit disk, keeping the
+/// density uniform, for the values of a [`Sampler`](crate::Sampler).
+pub fn square_to_unit_disk<T: Float>(u: f64, v: f64) -> (T, T) {
+    let r = u.sqrt();
+    let (sin, cos) = (v * TAU).sin_cos();
+    (T::from_f64(r * cos), T::from_f64(r * sin))
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<SmallRng> = RefCell::new(SmallRng::seed_from_u64(0));
+}
+
+/// Calls `f` with the random number generator of the current thread, for
+/// the shapes drawing random numbers while being intersected, such as
+/// [`crate::Volume`]. It starts from the seed 0 on every thread, and the
+/// [`Camera`](crate::Camera) restarts it at every pixel from the seed of
+/// its [`RenderSettings`](crate::RenderSettings).
+pub fn with_thread_rng<R>(f: impl FnOnce(&mut SmallRng) -> R) -> R {
+    THREAD_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Restarts the random number generator of the current thread from `seed`.
+pub fn seed_thread_rng(seed: u64) {
+    THREAD_RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(seed));
+}
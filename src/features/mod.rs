@@ -1,3 +1,24 @@
+pub mod animation;
+pub mod camera;
 pub mod canvas;
+pub mod checkpoint;
 pub mod colors;
+pub mod debug;
+pub mod environment;
+pub mod fog;
+pub mod importers;
+pub mod integrators;
+pub mod intersections;
+pub mod lights;
 pub mod linalg;
+pub mod materials;
+pub mod math;
+pub mod patterns;
+pub mod progress;
+pub mod rays;
+pub mod samplers;
+pub mod scene;
+pub mod settings;
+pub mod shapes;
+pub mod tiles;
+pub mod world;
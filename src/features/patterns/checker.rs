@@ -0,0 +1,59 @@
+use crate::{
+    features::{
+        colors::Color,
+        scene::{two_colors_value, value::SceneValue, SceneError},
+    },
+    Float, Point3,
+};
+
+use super::{Pattern, PatternBase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Cubes of unit size alternating between `a` and `b` in the three
+/// dimensions, like a checkerboard on a plane.
+pub struct CheckerPattern<T: Float> {
+    base: PatternBase<T>,
+    a: Color<T>,
+    b: Color<T>,
+}
+
+impl<T: Float> CheckerPattern<T> {
+    pub fn new(a: Color<T>, b: Color<T>) -> Self {
+        Self {
+            base: PatternBase::default(),
+            a,
+            b,
+        }
+    }
+
+    pub fn a(&self) -> Color<T> {
+        self.a
+    }
+
+    pub fn b(&self) -> Color<T> {
+        self.b
+    }
+}
+
+impl<T: Float> Pattern<T> for CheckerPattern<T> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        let sum = pattern_point.x.floor() + pattern_point.y.floor() + pattern_point.z.floor();
+        if sum % T::two() == T::zero() {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(two_colors_value("checkers", self.a, self.b))
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{features::colors::Color, Float, Point3};
+
+use super::{
+    uv_mapping::{cube_map, CubeFace},
+    uv_patterns::UvPattern,
+    Pattern, PatternBase,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A distinct [`UvPattern`] on every face of the cube from `-1` to `1`,
+/// typically six images forming a skybox.
+///
+/// Points off the cube are projected on it from the origin, so the cube
+/// map can be applied to any shape or looked up by a direction.
+pub struct CubeMap<T: Float, P: UvPattern<T>> {
+    base: PatternBase<T>,
+    /// The faces in the order of [`CubeFace::ALL`].
+    faces: [P; 6],
+}
+
+impl<T: Float, P: UvPattern<T>> CubeMap<T, P> {
+    pub fn new(left: P, front: P, right: P, back: P, up: P, down: P) -> Self {
+        Self {
+            base: PatternBase::default(),
+            faces: [left, right, front, back, up, down],
+        }
+    }
+
+    /// Returns the pattern of `face`.
+    pub fn face(&self, face: CubeFace) -> &P {
+        let index = CubeFace::ALL.iter().position(|&f| f == face).unwrap();
+        &self.faces[index]
+    }
+}
+
+impl<T: Float, P: UvPattern<T>> Pattern<T> for CubeMap<T, P> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        let scale = pattern_point
+            .x
+            .abs()
+            .max(pattern_point.y.abs())
+            .max(pattern_point.z.abs());
+        if scale == T::zero() {
+            return Color::default();
+        }
+        let on_cube = Point3::new(
+            pattern_point.x / scale,
+            pattern_point.y / scale,
+            pattern_point.z / scale,
+        );
+        let (face, u, v) = cube_map(on_cube);
+        self.face(face).uv_pattern_at(u, v)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    features::{
+        colors::Color,
+        scene::{two_colors_value, value::SceneValue, SceneError},
+    },
+    Float, Point3,
+};
+
+use super::{Pattern, PatternBase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A gradient blending linearly from `a` at `x = 0` to `b` at `x = 1`,
+/// repeated on every unit along `x`.
+pub struct GradientPattern<T: Float> {
+    base: PatternBase<T>,
+    a: Color<T>,
+    b: Color<T>,
+}
+
+impl<T: Float> GradientPattern<T> {
+    pub fn new(a: Color<T>, b: Color<T>) -> Self {
+        Self {
+            base: PatternBase::default(),
+            a,
+            b,
+        }
+    }
+
+    pub fn a(&self) -> Color<T> {
+        self.a
+    }
+
+    pub fn b(&self) -> Color<T> {
+        self.b
+    }
+}
+
+impl<T: Float> Pattern<T> for GradientPattern<T> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        let fraction = pattern_point.x - pattern_point.x.floor();
+        self.a + (self.b - self.a) * fraction
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(two_colors_value("gradient", self.a, self.b))
+    }
+}
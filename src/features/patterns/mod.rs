@@ -0,0 +1,107 @@
+//! Patterns coloring the surfaces point by point. Like shapes, every
+//! pattern implements [`Pattern`] in its own pattern space, placed on the
+//! shape by its transformation: a point in world space is converted to the
+//! object space of the shape, then to the pattern space.
+use std::fmt::Debug;
+
+use crate::{
+    features::{
+        colors::Color,
+        scene::{unsupported, value::SceneValue, SceneError},
+        shapes::Shape,
+    },
+    Float, Matrix4, Point3,
+};
+
+pub mod checker;
+pub mod cube_map;
+pub mod gradient;
+pub mod ring;
+pub mod stripe;
+pub mod test_pattern;
+pub mod texture_map;
+pub mod uv_mapping;
+pub mod uv_patterns;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The properties shared by every pattern.
+pub struct PatternBase<T: Float> {
+    transform: Matrix4<T>,
+    /// Cached inverse of `transform`, applied to every point.
+    inverse: Matrix4<T>,
+}
+
+impl<T: Float> Default for PatternBase<T> {
+    fn default() -> Self {
+        Self {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+        }
+    }
+}
+
+impl<T: Float> PatternBase<T> {
+    /// Returns the transformation from pattern space to object space.
+    pub fn transform(&self) -> Matrix4<T> {
+        self.transform
+    }
+
+    /// Returns the transformation from object space to pattern space.
+    pub fn inverse(&self) -> Matrix4<T> {
+        self.inverse
+    }
+
+    /// Sets the transformation from pattern space to object space.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.inverse = transform
+            .inverse()
+            .expect("The transformation of a pattern must be invertible.");
+        self.transform = transform;
+    }
+}
+
+/// A color varying over the space, applied to a shape by its material.
+///
+/// Implementors only deal with their pattern space in
+/// [`Pattern::local_pattern_at`]; [`Pattern::pattern_at_shape`] takes care
+/// of the conversion from world space.
+pub trait Pattern<T: Float>: Debug + Send + Sync {
+    /// Returns the properties shared by every pattern.
+    fn base(&self) -> &PatternBase<T>;
+    /// Returns the mutable properties shared by every pattern.
+    fn base_mut(&mut self) -> &mut PatternBase<T>;
+    /// Returns the color at a point in pattern space.
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T>;
+
+    /// Returns the transformation of this pattern.
+    fn transform(&self) -> Matrix4<T> {
+        self.base().transform()
+    }
+    /// Sets the transformation of this pattern, relative to the shape.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base_mut().set_transform(transform)
+    }
+    /// Returns the color at a point in the object space of the shape.
+    fn pattern_at(&self, object_point: Point3<T>) -> Color<T> {
+        self.local_pattern_at(self.base().inverse() * object_point)
+    }
+    /// Returns the color at `world_point` on the surface of `object`.
+    fn pattern_at_shape(&self, object: &dyn Shape<T>, world_point: Point3<T>) -> Color<T> {
+        self.pattern_at(object.world_to_object(world_point))
+    }
+    /// Returns the description of this pattern in a scene file, without
+    /// its transformation, see [`Scene::to_value`](crate::Scene::to_value).
+    /// # Errors
+    ///
+    /// The default returns an error, for the patterns no scene file
+    /// describes.
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Err(unsupported::<Self>())
+    }
+}
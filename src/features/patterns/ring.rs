@@ -0,0 +1,60 @@
+use crate::{
+    features::{
+        colors::Color,
+        scene::{two_colors_value, value::SceneValue, SceneError},
+    },
+    Float, Point3,
+};
+
+use super::{Pattern, PatternBase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Concentric rings around the `y` axis alternating between `a` and `b`
+/// on every unit of distance in the xz plane.
+pub struct RingPattern<T: Float> {
+    base: PatternBase<T>,
+    a: Color<T>,
+    b: Color<T>,
+}
+
+impl<T: Float> RingPattern<T> {
+    pub fn new(a: Color<T>, b: Color<T>) -> Self {
+        Self {
+            base: PatternBase::default(),
+            a,
+            b,
+        }
+    }
+
+    pub fn a(&self) -> Color<T> {
+        self.a
+    }
+
+    pub fn b(&self) -> Color<T> {
+        self.b
+    }
+}
+
+impl<T: Float> Pattern<T> for RingPattern<T> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        let distance =
+            (pattern_point.x * pattern_point.x + pattern_point.z * pattern_point.z).sqrt();
+        if distance.floor() % T::two() == T::zero() {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(two_colors_value("rings", self.a, self.b))
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    features::{
+        colors::Color,
+        scene::{two_colors_value, value::SceneValue, SceneError},
+    },
+    Float, Point3,
+};
+
+use super::{Pattern, PatternBase};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Stripes alternating between two colors along `x`: `a` on `[0, 1)`, `b`
+/// on `[1, 2)`, and so on. They are constant in `y` and `z`.
+pub struct StripePattern<T: Float> {
+    base: PatternBase<T>,
+    a: Color<T>,
+    b: Color<T>,
+}
+
+impl<T: Float> StripePattern<T> {
+    pub fn new(a: Color<T>, b: Color<T>) -> Self {
+        Self {
+            base: PatternBase::default(),
+            a,
+            b,
+        }
+    }
+
+    pub fn a(&self) -> Color<T> {
+        self.a
+    }
+
+    pub fn b(&self) -> Color<T> {
+        self.b
+    }
+}
+
+impl<T: Float> Pattern<T> for StripePattern<T> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        if pattern_point.x.floor() % T::two() == T::zero() {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(two_colors_value("stripes", self.a, self.b))
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{features::colors::Color, Float, Point3};
+
+use super::{Pattern, PatternBase};
+
+/// A pattern whose color is the point in pattern space it is evaluated at.
+/// It is meant to verify the world/object/pattern space plumbing of the
+/// [`Pattern`] trait, for example when implementing custom patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TestPattern<T: Float> {
+    base: PatternBase<T>,
+}
+
+impl<T: Float> Pattern<T> for TestPattern<T> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        Color::new(pattern_point.x, pattern_point.y, pattern_point.z)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{features::colors::Color, Float, Point3};
+
+use super::{
+    uv_mapping::UvMapping,
+    uv_patterns::{UvImage, UvPattern},
+    Pattern, PatternBase,
+};
+
+/// An image wrapped around the shapes, e.g. loaded with
+/// [`UvImage::from_ppm_file`] and mapped with [`UvMapping::Spherical`].
+pub type ImageTexture<T> = TextureMap<T, UvImage<T>>;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A 2D [`UvPattern`] wrapped around the shape by a [`UvMapping`], e.g. an
+/// image of the earth with [`UvMapping::Spherical`] on a sphere.
+pub struct TextureMap<T: Float, P: UvPattern<T>> {
+    base: PatternBase<T>,
+    uv_pattern: P,
+    mapping: UvMapping,
+}
+
+impl<T: Float, P: UvPattern<T>> TextureMap<T, P> {
+    pub fn new(uv_pattern: P, mapping: UvMapping) -> Self {
+        Self {
+            base: PatternBase::default(),
+            uv_pattern,
+            mapping,
+        }
+    }
+
+    pub fn uv_pattern(&self) -> &P {
+        &self.uv_pattern
+    }
+
+    pub fn mapping(&self) -> UvMapping {
+        self.mapping
+    }
+}
+
+impl<T: Float, P: UvPattern<T>> Pattern<T> for TextureMap<T, P> {
+    fn base(&self) -> &PatternBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut PatternBase<T> {
+        &mut self.base
+    }
+
+    fn local_pattern_at(&self, pattern_point: Point3<T>) -> Color<T> {
+        let (u, v) = self.mapping.map(pattern_point);
+        self.uv_pattern.uv_pattern_at(u, v)
+    }
+}
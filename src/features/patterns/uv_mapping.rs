@@ -0,0 +1,138 @@
+//! Mappings from a point in pattern space to the `(u, v)` coordinates of a
+//! 2D texture, both in `[0, 1]`. `u` grows to the right and `v` upwards.
+use crate::{Float, Point3};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How a [`TextureMap`](super::texture_map::TextureMap) wraps its 2D
+/// pattern around a shape.
+pub enum UvMapping {
+    /// Around the unit sphere, like the latitude and longitude on a globe.
+    Spherical,
+    /// Onto the xz plane, repeating on every unit in `x` and `z`.
+    Planar,
+    /// Around the `y` axis, repeating on every unit in `y`.
+    Cylindrical,
+    /// Onto each face of the cube from `-1` to `1`, every face showing the
+    /// whole pattern.
+    Cube,
+}
+
+impl UvMapping {
+    /// Returns the `(u, v)` of `point` under this mapping.
+    pub fn map<T: Float>(&self, point: Point3<T>) -> (T, T) {
+        match self {
+            UvMapping::Spherical => spherical_map(point),
+            UvMapping::Planar => planar_map(point),
+            UvMapping::Cylindrical => cylindrical_map(point),
+            UvMapping::Cube => {
+                let (_, u, v) = cube_map(point);
+                (u, v)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A face of the cube from `-1` to `1`: `Front` is the face at `z = 1`,
+/// `Right` at `x = 1` and `Up` at `y = 1`.
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    /// Every face, in the order of the variants.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::Left,
+        CubeFace::Right,
+        CubeFace::Front,
+        CubeFace::Back,
+        CubeFace::Up,
+        CubeFace::Down,
+    ];
+
+    /// Returns the face of the cube `point` lies on, that is the one of its
+    /// largest component.
+    pub fn from_point<T: Float>(point: Point3<T>) -> Self {
+        let coord = point.x.abs().max(point.y.abs()).max(point.z.abs());
+        if coord == point.x {
+            CubeFace::Right
+        } else if coord == -point.x {
+            CubeFace::Left
+        } else if coord == point.y {
+            CubeFace::Up
+        } else if coord == -point.y {
+            CubeFace::Down
+        } else if coord == point.z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+
+    /// Returns the `(u, v)` of `point` on this face, as seen from the
+    /// outside of the cube.
+    pub fn uv<T: Float>(&self, point: Point3<T>) -> (T, T) {
+        let wrap = |c: T| euclid_rem(c, T::two()) / T::two();
+        let one = T::one();
+        let (x, y, z) = (point.x, point.y, point.z);
+        match self {
+            CubeFace::Front => (wrap(x + one), wrap(y + one)),
+            CubeFace::Back => (wrap(one - x), wrap(y + one)),
+            CubeFace::Left => (wrap(z + one), wrap(y + one)),
+            CubeFace::Right => (wrap(one - z), wrap(y + one)),
+            CubeFace::Up => (wrap(x + one), wrap(one - z)),
+            CubeFace::Down => (wrap(x + one), wrap(z + one)),
+        }
+    }
+}
+
+/// Returns the remainder of `a / b` in `[0, b)`, even for negative `a`.
+fn euclid_rem<T: Float>(a: T, b: T) -> T {
+    let r = a % b;
+    if r < T::zero() {
+        r + b
+    } else {
+        r
+    }
+}
+
+/// Maps `point` on a sphere centered at the origin: `u` is the longitude,
+/// starting at `-z` and going counter-clockwise seen from above, `v` is the
+/// latitude from the south pole (`0`) to the north pole (`1`).
+pub fn spherical_map<T: Float>(point: Point3<T>) -> (T, T) {
+    let theta = point.x.atan2(point.z);
+    let radius = (point.x * point.x + point.y * point.y + point.z * point.z).sqrt();
+    let phi = (point.y / radius).acos();
+    let raw_u = theta / (T::two() * T::PI());
+    let u = T::one() - (raw_u + T::one() / T::two());
+    let v = T::one() - phi / T::PI();
+    (u, v)
+}
+
+/// Maps `point` onto the xz plane, repeating on every unit: `u` follows `x`
+/// and `v` follows `z`.
+pub fn planar_map<T: Float>(point: Point3<T>) -> (T, T) {
+    (euclid_rem(point.x, T::one()), euclid_rem(point.z, T::one()))
+}
+
+/// Maps `point` around the `y` axis: `u` is the angle as in
+/// [`spherical_map`], `v` follows `y` and repeats on every unit.
+pub fn cylindrical_map<T: Float>(point: Point3<T>) -> (T, T) {
+    let theta = point.x.atan2(point.z);
+    let raw_u = theta / (T::two() * T::PI());
+    let u = T::one() - (raw_u + T::one() / T::two());
+    (u, euclid_rem(point.y, T::one()))
+}
+
+/// Maps `point` onto the cube from `-1` to `1`, returning the face it lies
+/// on together with the `(u, v)` on that face.
+pub fn cube_map<T: Float>(point: Point3<T>) -> (CubeFace, T, T) {
+    let face = CubeFace::from_point(point);
+    let (u, v) = face.uv(point);
+    (face, u, v)
+}
@@ -0,0 +1,216 @@
+//! 2D patterns defined on the `(u, v)` texture coordinates, wrapped around
+//! the shapes by a [`TextureMap`](super::texture_map::TextureMap).
+use std::fmt::Debug;
+
+use crate::{
+    features::{
+        canvas::{Canvas, CanvasFormat},
+        colors::Color,
+    },
+    Float,
+};
+
+/// A color varying over the unit square of texture coordinates.
+pub trait UvPattern<T: Float>: Debug + Send + Sync {
+    /// Returns the color at `(u, v)`, both expected in `[0, 1]`.
+    fn uv_pattern_at(&self, u: T, v: T) -> Color<T>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A checkerboard of `width` squares along `u` and `height` squares along
+/// `v`, starting with `a` at `(0, 0)`.
+pub struct UvChecker<T: Float> {
+    width: T,
+    height: T,
+    a: Color<T>,
+    b: Color<T>,
+}
+
+impl<T: Float> UvChecker<T> {
+    pub fn new(width: T, height: T, a: Color<T>, b: Color<T>) -> Self {
+        Self {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+
+    pub fn width(&self) -> T {
+        self.width
+    }
+
+    pub fn height(&self) -> T {
+        self.height
+    }
+
+    pub fn a(&self) -> Color<T> {
+        self.a
+    }
+
+    pub fn b(&self) -> Color<T> {
+        self.b
+    }
+}
+
+impl<T: Float> UvPattern<T> for UvChecker<T> {
+    fn uv_pattern_at(&self, u: T, v: T) -> Color<T> {
+        let u2 = (u * self.width).floor();
+        let v2 = (v * self.height).floor();
+        if (u2 + v2) % T::two() == T::zero() {
+            self.a
+        } else {
+            self.b
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How a [`UvImage`] picks the color between the centers of its pixels.
+pub enum TextureFilter {
+    /// The color of the closest pixel, blocky under magnification.
+    #[default]
+    Nearest,
+    /// The blend of the four closest pixels, weighted by the distance.
+    Bilinear,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An image stretched over the unit square: `(0, 0)` is the bottom-left
+/// pixel and `(1, 1)` the top-right one.
+///
+/// Images are usually loaded from files, see
+/// [`importers::image`](crate::features::importers::image).
+pub struct UvImage<T: Float> {
+    width: usize,
+    height: usize,
+    /// Row-major pixels, the first row being the top of the image.
+    pixels: Vec<Color<T>>,
+    filter: TextureFilter,
+}
+
+impl<T: Float> UvImage<T> {
+    /// Returns an image of `width` x `height` row-major `pixels`, the first
+    /// row being the top of the image.
+    /// # Panics
+    ///
+    /// This function panics if the image is empty or if `pixels` does not
+    /// hold exactly `width * height` colors.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color<T>>) -> Self {
+        assert!(width > 0 && height > 0, "An image cannot be empty.");
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "The pixels must cover the whole image."
+        );
+        Self {
+            width,
+            height,
+            pixels,
+            filter: TextureFilter::default(),
+        }
+    }
+
+    /// Sets the filtering of the lookups, [`TextureFilter::Nearest`] by default.
+    pub fn with_filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Returns an image with a copy of the pixels of `canvas`.
+    pub fn from_canvas<const W: usize, const H: usize, F: CanvasFormat>(
+        canvas: &Canvas<W, H, T, F>,
+    ) -> Self {
+        Self::new(W, H, canvas.pixels().to_vec())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn filter(&self) -> TextureFilter {
+        self.filter
+    }
+
+    /// Returns the pixel at `(x, y)`, `y` counting from the top.
+    /// # Panics
+    ///
+    /// This function panics if `(x, y)` is outside of the image.
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color<T> {
+        assert!(x < self.width && y < self.height);
+        self.pixels[y * self.width + x]
+    }
+}
+
+impl<T: Float> UvPattern<T> for UvImage<T> {
+    fn uv_pattern_at(&self, u: T, v: T) -> Color<T> {
+        // Flip `v`: the rows of the image go downwards.
+        let v = T::one() - v;
+        let last_x = T::from_f64((self.width - 1) as f64);
+        let last_y = T::from_f64((self.height - 1) as f64);
+        let x = (u * last_x).max(T::zero()).min(last_x);
+        let y = (v * last_y).max(T::zero()).min(last_y);
+        let index = |c: T| c.to_usize().unwrap_or(0);
+        match self.filter {
+            TextureFilter::Nearest => self.pixel_at(index(x.round()), index(y.round())),
+            TextureFilter::Bilinear => {
+                let (x0, y0) = (index(x.floor()), index(y.floor()));
+                let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+                let (fx, fy) = (x - x.floor(), y - y.floor());
+                let lerp = |a: Color<T>, b: Color<T>, t: T| a * (T::one() - t) + b * t;
+                let top = lerp(self.pixel_at(x0, y0), self.pixel_at(x1, y0), fx);
+                let bottom = lerp(self.pixel_at(x0, y1), self.pixel_at(x1, y1), fx);
+                lerp(top, bottom, fy)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A `main` color with a distinct color in each corner, to check the
+/// orientation of a mapping: `ul` (upper left), `ur`, `bl` and `br`.
+pub struct UvAlignCheck<T: Float> {
+    main: Color<T>,
+    ul: Color<T>,
+    ur: Color<T>,
+    bl: Color<T>,
+    br: Color<T>,
+}
+
+impl<T: Float> UvAlignCheck<T> {
+    pub fn new(main: Color<T>, ul: Color<T>, ur: Color<T>, bl: Color<T>, br: Color<T>) -> Self {
+        Self {
+            main,
+            ul,
+            ur,
+            bl,
+            br,
+        }
+    }
+}
+
+impl<T: Float> UvPattern<T> for UvAlignCheck<T> {
+    fn uv_pattern_at(&self, u: T, v: T) -> Color<T> {
+        let (low, high) = (T::from_f64(0.2), T::from_f64(0.8));
+        if v > high {
+            if u < low {
+                return self.ul;
+            }
+            if u > high {
+                return self.ur;
+            }
+        } else if v < low {
+            if u < low {
+                return self.bl;
+            }
+            if u > high {
+                return self.br;
+            }
+        }
+        self.main
+    }
+}
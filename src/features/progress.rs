@@ -0,0 +1,125 @@
+//! The progress of a render, reported to an observer as the tiles are
+//! completed so that applications can show a progress bar and an estimate
+//! of the remaining time.
+use std::{cell::Cell, ops::Sub, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The numbers of rays cast by a render.
+pub struct RayCounts {
+    /// The rays from the camera, one per sample.
+    pub primary: u64,
+    /// The rays testing whether a point sees a light.
+    pub shadow: u64,
+}
+
+impl RayCounts {
+    /// Returns the number of rays of every kind.
+    pub fn total(&self) -> u64 {
+        self.primary + self.shadow
+    }
+}
+
+impl Sub for RayCounts {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            primary: self.primary - rhs.primary,
+            shadow: self.shadow - rhs.shadow,
+        }
+    }
+}
+
+thread_local! {
+    /// The rays cast by each thread, counted apart so that the threads do
+    /// not contend on them.
+    static RAYS_CAST: Cell<RayCounts> = const {
+        Cell::new(RayCounts {
+            primary: 0,
+            shadow: 0,
+        })
+    };
+}
+
+/// Returns the rays cast so far by the calling thread.
+pub(crate) fn rays_cast() -> RayCounts {
+    RAYS_CAST.with(Cell::get)
+}
+
+pub(crate) fn count_primary_ray() {
+    RAYS_CAST.with(|rays| {
+        let mut counts = rays.get();
+        counts.primary += 1;
+        rays.set(counts);
+    });
+}
+
+pub(crate) fn count_shadow_ray() {
+    RAYS_CAST.with(|rays| {
+        let mut counts = rays.get();
+        counts.shadow += 1;
+        rays.set(counts);
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The state of a render after a tile is completed.
+pub struct RenderProgress {
+    pub tiles_done: usize,
+    pub tiles_total: usize,
+    pub pixels_done: usize,
+    pub pixels_total: usize,
+    /// The time since the start of the render.
+    pub elapsed: Duration,
+    /// The rays cast for the tiles rendered.
+    pub rays: RayCounts,
+}
+
+impl RenderProgress {
+    /// Returns the fraction of the pixels rendered, in `[0, 1]`.
+    pub fn fraction(&self) -> f64 {
+        if self.pixels_total == 0 {
+            return 1.0;
+        }
+        self.pixels_done as f64 / self.pixels_total as f64
+    }
+
+    /// Returns the estimated time left, assuming the remaining pixels take
+    /// as long as the ones rendered so far; `None` before any pixel is.
+    pub fn remaining(&self) -> Option<Duration> {
+        if self.pixels_done == 0 {
+            return None;
+        }
+        let left = self.pixels_total.saturating_sub(self.pixels_done) as f64;
+        Some(self.elapsed.mul_f64(left / self.pixels_done as f64))
+    }
+
+    /// Returns the number of rays cast per second so far, `0` before any
+    /// time elapsed.
+    pub fn rays_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.rays.total() as f64 / seconds
+    }
+
+    /// Returns `true` once every tile is rendered.
+    pub fn is_done(&self) -> bool {
+        self.tiles_done == self.tiles_total
+    }
+}
+
+/// An observer of the progress of a render, e.g. a progress bar.
+///
+/// Closures taking a [`RenderProgress`] are observers.
+pub trait ProgressObserver {
+    /// Called after each completed tile.
+    fn on_progress(&mut self, progress: &RenderProgress);
+}
+
+impl<F: FnMut(&RenderProgress)> ProgressObserver for F {
+    fn on_progress(&mut self, progress: &RenderProgress) {
+        self(progress)
+    }
+}
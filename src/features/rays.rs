@@ -0,0 +1,187 @@
+//! Implementation of `Ray`, a half-line starting at an origin and going
+//! along a direction. Every intersection is computed against rays.
+//!
+//! Rays can also be traced in packets of up to [`PACKET_SIZE`] rays, their
+//! components laid out by lane so that a test against the same box or
+//! transformation is done on every ray at once.
+use crate::{Matrix4, Point3, Scalar, Vector3};
+
+/// The maximum number of rays in a [`RayPacket`].
+pub const PACKET_SIZE: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A ray with an `origin` point and a `direction` vector, cast at a `time`
+/// within the shutter interval of the camera, `0` by default. Moving
+/// shapes are intersected where they are at that time.
+pub struct Ray<T: Scalar> {
+    origin: Point3<T>,
+    direction: Vector3<T>,
+    time: T,
+}
+
+impl<T: Scalar> Ray<T> {
+    pub fn new(origin: Point3<T>, direction: Vector3<T>) -> Self {
+        Self {
+            origin,
+            direction,
+            time: T::zero(),
+        }
+    }
+
+    /// Sets the time at which this ray is cast.
+    pub fn with_time(mut self, time: T) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Returns the origin of this [`Ray`].
+    pub fn origin(&self) -> Point3<T> {
+        self.origin
+    }
+
+    /// Returns the direction of this [`Ray`].
+    pub fn direction(&self) -> Vector3<T> {
+        self.direction
+    }
+
+    /// Returns the time at which this [`Ray`] is cast.
+    pub fn time(&self) -> T {
+        self.time
+    }
+
+    /// Returns the point at the distance `t` along the ray.
+    pub fn position(&self, t: T) -> Point3<T> {
+        self.origin + self.direction * t
+    }
+
+    /// Returns a new ray transformed by the matrix `m`, cast at the same time.
+    pub fn transform(&self, m: &Matrix4<T>) -> Self {
+        Self::new(*m * self.origin, *m * self.direction).with_time(self.time)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Up to [`PACKET_SIZE`] rays traced together, usually the 4 or 8 primary
+/// rays of neighbouring pixels or the shadow rays towards the same light.
+///
+/// The components are stored by axis, one lane per ray, so the loops over
+/// the lanes compile to SIMD instructions. The lanes beyond [`len`] are
+/// unused.
+///
+/// [`len`]: RayPacket::len
+pub struct RayPacket<T: Scalar> {
+    len: usize,
+    origins: [[T; PACKET_SIZE]; 3],
+    directions: [[T; PACKET_SIZE]; 3],
+    times: [T; PACKET_SIZE],
+}
+
+impl<T: Scalar> RayPacket<T> {
+    /// Returns the packet of `rays`.
+    /// # Panics
+    ///
+    /// This function panics if there are no rays or more than [`PACKET_SIZE`].
+    pub fn new(rays: &[Ray<T>]) -> Self {
+        assert!(
+            !rays.is_empty() && rays.len() <= PACKET_SIZE,
+            "A packet holds from 1 to {PACKET_SIZE} rays."
+        );
+        let mut packet = Self {
+            len: rays.len(),
+            origins: [[T::zero(); PACKET_SIZE]; 3],
+            directions: [[T::zero(); PACKET_SIZE]; 3],
+            times: [T::zero(); PACKET_SIZE],
+        };
+        for (lane, ray) in rays.iter().enumerate() {
+            for axis in 0..3 {
+                packet.origins[axis][lane] = ray.origin[axis];
+                packet.directions[axis][lane] = ray.direction[axis];
+            }
+            packet.times[lane] = ray.time;
+        }
+        packet
+    }
+
+    /// Returns the number of rays in this packet.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this packet holds no ray.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the ray in `lane`.
+    /// # Panics
+    ///
+    /// This function panics if `lane` is not below [`len`](RayPacket::len).
+    pub fn ray(&self, lane: usize) -> Ray<T> {
+        assert!(lane < self.len, "The packet has no ray in lane {lane}.");
+        let origin = Point3::new(
+            self.origins[0][lane],
+            self.origins[1][lane],
+            self.origins[2][lane],
+        );
+        let direction = Vector3::new(
+            self.directions[0][lane],
+            self.directions[1][lane],
+            self.directions[2][lane],
+        );
+        Ray::new(origin, direction).with_time(self.times[lane])
+    }
+
+    /// Returns the rays of this packet.
+    pub fn rays(&self) -> impl Iterator<Item = Ray<T>> + '_ {
+        (0..self.len).map(|lane| self.ray(lane))
+    }
+
+    /// Returns the `axis` component of the origins, one per ray.
+    pub fn origins(&self, axis: usize) -> &[T] {
+        &self.origins[axis][..self.len]
+    }
+
+    /// Returns the `axis` component of the directions, one per ray.
+    pub fn directions(&self, axis: usize) -> &[T] {
+        &self.directions[axis][..self.len]
+    }
+
+    /// Returns the time shared by every ray, or `None` if they are cast at
+    /// different times.
+    pub fn common_time(&self) -> Option<T> {
+        let time = self.times[0];
+        self.times[..self.len]
+            .iter()
+            .all(|&t| t == time)
+            .then_some(time)
+    }
+
+    /// Returns the packet transformed by the affine matrix `m`, whose last
+    /// row is `(0, 0, 0, 1)` like the transformations of the shapes.
+    pub fn transform(&self, m: &Matrix4<T>) -> Self {
+        let mut packet = *self;
+        for row in 0..3 {
+            for lane in 0..self.len {
+                let (o, d) = (&self.origins, &self.directions);
+                packet.origins[row][lane] = m[(row, 0)] * o[0][lane]
+                    + m[(row, 1)] * o[1][lane]
+                    + m[(row, 2)] * o[2][lane]
+                    + m[(row, 3)];
+                packet.directions[row][lane] =
+                    m[(row, 0)] * d[0][lane] + m[(row, 1)] * d[1][lane] + m[(row, 2)] * d[2][lane];
+            }
+        }
+        packet
+    }
+
+    /// Returns the packet of the rays in the lanes where `mask` is set,
+    /// with the lanes they come from, or `None` if none is set.
+    pub fn select(&self, mask: &[bool]) -> Option<(Self, Vec<usize>)> {
+        let lanes: Vec<usize> = (0..self.len).filter(|&lane| mask[lane]).collect();
+        if lanes.is_empty() {
+            return None;
+        }
+        let rays: Vec<Ray<T>> = lanes.iter().map(|&lane| self.ray(lane)).collect();
+        Some((Self::new(&rays), lanes))
+    }
+}
@@ -0,0 +1,407 @@
+//! Samplers: the sources of the sample values in `[0, 1)` used by the
+//! stochastic features of a render, such as the position of the rays in
+//! the pixels (anti-aliasing), on the lens (depth of field) and in the
+//! shutter interval (motion blur).
+//!
+//! The values of a sample depend only on the seed, the pixel, the index
+//! of the sample and the order in which they are drawn, so renders are
+//! reproducible whatever the order of the pixels.
+use std::fmt::Debug;
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+/// A generator of the sample values of every pixel.
+///
+/// A sample is a sequence of dimensions, each drawn by
+/// [`Sampler::next_1d`] or [`Sampler::next_2d`]; the samples of a pixel
+/// are well distributed over each of these dimensions.
+pub trait Sampler: Debug + Send + Sync {
+    /// Returns the number of samples of each pixel.
+    fn samples_per_pixel(&self) -> usize;
+    /// Starts the sample `index` of the pixel `(x, y)`, from its first
+    /// dimension.
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: usize);
+    /// Returns the value of the next dimension of the current sample.
+    fn next_1d(&mut self) -> f64;
+    /// Returns the values of the next two dimensions of the current sample.
+    fn next_2d(&mut self) -> (f64, f64);
+    /// Returns a copy of this sampler, to draw the samples of a render.
+    fn clone_box(&self) -> Box<dyn Sampler>;
+}
+
+/// Returns a well mixed 64 bits hash of `values` (SplitMix64).
+fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e37_79b9_7f4a_7c15, |h: u64, &v| {
+        let mut z = (h ^ v).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    })
+}
+
+#[derive(Debug, Clone)]
+/// Independent uniform random values: the simplest sampler, and the
+/// noisiest for a given number of samples.
+pub struct RandomSampler {
+    samples: usize,
+    seed: u64,
+    rng: SmallRng,
+}
+
+impl RandomSampler {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            seed: 0,
+            rng: SmallRng::seed_from_u64(0),
+        }
+    }
+
+    /// Sets the seed of the values, `0` by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn samples_per_pixel(&self) -> usize {
+        self.samples
+    }
+
+    fn start_pixel_sample(&mut self, (x, y): (usize, usize), index: usize) {
+        self.rng = SmallRng::seed_from_u64(hash(&[self.seed, x as u64, y as u64, index as u64]));
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        self.rng.gen()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        (self.rng.gen(), self.rng.gen())
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The samples of a pixel cover a grid of `x_strata` x `y_strata` cells,
+/// one sample per cell, in a different random order for every dimension.
+/// The 1D dimensions are split into as many strata.
+///
+/// The samples lie at the center of their cell, or at a random point of
+/// it when jittered; jittering avoids the aliasing of a regular grid.
+pub struct StratifiedSampler {
+    x_strata: usize,
+    y_strata: usize,
+    jitter: bool,
+    seed: u64,
+    pixel: (usize, usize),
+    index: usize,
+    dimension: usize,
+}
+
+impl StratifiedSampler {
+    /// Returns a sampler placing the samples at the center of the cells.
+    /// # Panics
+    ///
+    /// This function panics if there are no strata.
+    pub fn new(x_strata: usize, y_strata: usize) -> Self {
+        assert!(
+            x_strata > 0 && y_strata > 0,
+            "A stratified sampler needs at least one stratum."
+        );
+        Self {
+            x_strata,
+            y_strata,
+            jitter: false,
+            seed: 0,
+            pixel: (0, 0),
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    /// Returns a sampler placing the samples at random within the cells.
+    /// # Panics
+    ///
+    /// This function panics if there are no strata.
+    pub fn jittered(x_strata: usize, y_strata: usize) -> Self {
+        Self {
+            jitter: true,
+            ..Self::new(x_strata, y_strata)
+        }
+    }
+
+    /// Sets the seed of the values, `0` by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn is_jittered(&self) -> bool {
+        self.jitter
+    }
+
+    /// Returns the random generator of the current dimension, `salt`
+    /// telling apart its uses.
+    fn rng(&self, salt: u64) -> SmallRng {
+        let (x, y) = self.pixel;
+        SmallRng::seed_from_u64(hash(&[
+            self.seed,
+            x as u64,
+            y as u64,
+            self.dimension as u64,
+            salt,
+        ]))
+    }
+
+    /// Returns the cell of the current sample in the current dimension,
+    /// through a permutation shared by all the samples of the pixel.
+    fn stratum(&self) -> usize {
+        let mut order: Vec<usize> = (0..self.samples_per_pixel()).collect();
+        order.shuffle(&mut self.rng(0));
+        order[self.index % order.len()]
+    }
+
+    /// Returns the offset of the current sample within its cell.
+    fn offset(&self) -> (f64, f64) {
+        if !self.jitter {
+            return (0.5, 0.5);
+        }
+        let mut rng = self.rng(1 + self.index as u64);
+        (rng.gen(), rng.gen())
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn samples_per_pixel(&self) -> usize {
+        self.x_strata * self.y_strata
+    }
+
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: usize) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let stratum = self.stratum();
+        let (offset, _) = self.offset();
+        self.dimension += 1;
+        (stratum as f64 + offset) / self.samples_per_pixel() as f64
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let stratum = self.stratum();
+        let (dx, dy) = self.offset();
+        self.dimension += 1;
+        let (sx, sy) = (stratum % self.x_strata, stratum / self.x_strata);
+        (
+            (sx as f64 + dx) / self.x_strata as f64,
+            (sy as f64 + dy) / self.y_strata as f64,
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+/// The bases of the dimensions of the [`HaltonSampler`].
+const PRIMES: [u64; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// Returns the radical inverse of `index` in `base`: its digits mirrored
+/// around the decimal point, e.g. `6 = 110₂` gives `0.011₂ = 0.375`.
+pub fn radical_inverse(base: u64, mut index: u64) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let (mut reversed, mut scale) = (0.0, inv_base);
+    while index > 0 {
+        reversed += (index % base) as f64 * scale;
+        index /= base;
+        scale *= inv_base;
+    }
+    reversed
+}
+
+/// Returns the value of a pseudo random `hash` in `[0, 1)`.
+fn unit_value(hash: u64) -> f64 {
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Debug, Clone)]
+/// The low-discrepancy Halton sequence: each dimension is the radical
+/// inverse of the index of the sample in the next prime base. The points
+/// fill the space more evenly than random ones, for any number of samples,
+/// so the noise fades faster.
+///
+/// The points of each pixel are shifted by a random offset per dimension
+/// (a Cranley-Patterson rotation), so that neighboring pixels do not
+/// repeat the same pattern. The bases cycle after 32 dimensions.
+pub struct HaltonSampler {
+    samples: usize,
+    seed: u64,
+    pixel: (usize, usize),
+    index: usize,
+    dimension: usize,
+}
+
+impl HaltonSampler {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            seed: 0,
+            pixel: (0, 0),
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    /// Sets the seed of the offsets, `0` by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    fn next(&mut self) -> f64 {
+        let (x, y) = self.pixel;
+        let base = PRIMES[self.dimension % PRIMES.len()];
+        let offset = unit_value(hash(&[
+            self.seed,
+            x as u64,
+            y as u64,
+            self.dimension as u64,
+        ]));
+        self.dimension += 1;
+        let value = radical_inverse(base, self.index as u64) + offset;
+        // Rounding may give exactly 1.
+        (value - value.floor()).min(1.0 - f64::EPSILON)
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn samples_per_pixel(&self) -> usize {
+        self.samples
+    }
+
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: usize) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        self.next()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        (self.next(), self.next())
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Returns the sample `index` of the 2D Sobol sequence, both dimensions
+/// as 32 bits fractions.
+fn sobol_2d(index: u32) -> (u32, u32) {
+    let (mut x, mut y) = (0, 0);
+    // The direction numbers of the first dimension are the powers of two,
+    // the ones of the second are generated by the polynomial `x + 1`.
+    let mut v = 1 << 31;
+    for bit in 0..32 {
+        if index & (1 << bit) != 0 {
+            x ^= 1 << (31 - bit);
+            y ^= v;
+        }
+        v ^= v >> 1;
+    }
+    (x, y)
+}
+
+#[derive(Debug, Clone)]
+/// The low-discrepancy Sobol sequence, as 2D points: the `2ⁿ` samples of a
+/// pixel hit every cell of any grid of `2ⁿ` cells of equal size, such as
+/// 4x4 or 2x8, exactly once.
+///
+/// Every pair of dimensions uses the same 2D sequence, its samples shuffled
+/// and its bits randomly flipped (scrambled) per pixel and dimension, which
+/// keeps this property while decorrelating the dimensions. The number of
+/// samples is rounded up to a power of two.
+pub struct SobolSampler {
+    samples: usize,
+    seed: u64,
+    pixel: (usize, usize),
+    index: usize,
+    dimension: usize,
+}
+
+impl SobolSampler {
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples: samples.max(1).next_power_of_two(),
+            seed: 0,
+            pixel: (0, 0),
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    /// Sets the seed of the scrambling, `0` by default.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the shuffled index of the current sample and the scrambling
+    /// of the current dimension.
+    fn next_point(&mut self) -> (u32, u64) {
+        let (x, y) = self.pixel;
+        let h = hash(&[self.seed, x as u64, y as u64, self.dimension as u64]);
+        let mut order: Vec<usize> = (0..self.samples).collect();
+        order.shuffle(&mut SmallRng::seed_from_u64(h));
+        self.dimension += 1;
+        (order[self.index % self.samples] as u32, hash(&[h]))
+    }
+}
+
+/// Returns the 32 bits fraction `bits` in `[0, 1)`.
+fn fraction(bits: u32) -> f64 {
+    bits as f64 / (1u64 << 32) as f64
+}
+
+impl Sampler for SobolSampler {
+    fn samples_per_pixel(&self) -> usize {
+        self.samples
+    }
+
+    fn start_pixel_sample(&mut self, pixel: (usize, usize), index: usize) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let (index, scramble) = self.next_point();
+        fraction(sobol_2d(index).0 ^ scramble as u32)
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let (index, scramble) = self.next_point();
+        let (x, y) = sobol_2d(index);
+        (
+            fraction(x ^ scramble as u32),
+            fraction(y ^ (scramble >> 32) as u32),
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,243 @@
+//! Functions assembling small scenes in a few lines of Rust:
+//!
+//! ```
+//! use raytracer_rust::{features::scene::builder::*, Point3, Scene};
+//! use raytracer_rust::features::colors::Color;
+//!
+//! let scene = Scene::new()
+//!     .light(point_light(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0)))
+//!     .add(plane().material(matte(Color::new(0.5, 0.5, 0.5))))
+//!     .add(sphere().radius(1.0).at(0.0, 1.0, 0.0).material(glass()))
+//!     .look(Point3::new(0.0, 1.5, -5.0), Point3::new(0.0, 1.0, 0.0));
+//! assert_eq!(scene.world.objects().len(), 2);
+//! ```
+//!
+//! The shapes are placed by chaining steps such as
+//! [`ShapeBuilder::radius`] or [`ShapeBuilder::at`], applied in the order
+//! they are called.
+use crate::{
+    features::{
+        camera::Camera,
+        colors::Color,
+        lights::PointLight,
+        materials::Material,
+        shapes::{
+            csg::{Csg, CsgOperation},
+            cylinder::Cylinder,
+            disk::Disk,
+            group::Group,
+            plane::Plane,
+            sphere::Sphere,
+            torus::Torus,
+            Shape,
+        },
+        world::World,
+    },
+    Float, Matrix4, Point3, Transform, Vector3,
+};
+
+use super::Scene;
+
+/// A shape being placed and dressed before it is added to a scene.
+pub struct ShapeBuilder<T: Float> {
+    shape: Box<dyn Shape<T>>,
+}
+
+impl<T: Float, S: Shape<T> + 'static> From<S> for ShapeBuilder<T> {
+    fn from(shape: S) -> Self {
+        Self {
+            shape: Box::new(shape),
+        }
+    }
+}
+
+impl<T: Float> ShapeBuilder<T> {
+    /// Applies `transform` after the steps so far.
+    pub fn transform(mut self, transform: Matrix4<T>) -> Self {
+        let transform = transform * self.shape.transform();
+        self.shape.set_transform(transform);
+        self
+    }
+
+    /// Scales the shape by `radius` in every direction.
+    pub fn radius(self, radius: T) -> Self {
+        self.scale(radius, radius, radius)
+    }
+
+    pub fn scale(self, x: T, y: T, z: T) -> Self {
+        self.transform(Transform::scaling(x, y, z))
+    }
+
+    /// Moves the shape by `(x, y, z)`, which places at this point a shape
+    /// centered on the origin.
+    pub fn at(self, x: T, y: T, z: T) -> Self {
+        self.transform(Transform::translation(x, y, z))
+    }
+
+    pub fn rotate_x(self, angle: T) -> Self {
+        self.transform(Transform::rotation_x(angle))
+    }
+
+    pub fn rotate_y(self, angle: T) -> Self {
+        self.transform(Transform::rotation_y(angle))
+    }
+
+    pub fn rotate_z(self, angle: T) -> Self {
+        self.transform(Transform::rotation_z(angle))
+    }
+
+    pub fn material(mut self, material: Material<T>) -> Self {
+        self.shape.set_material(material);
+        self
+    }
+
+    /// Sets the color of the material of the shape, keeping its other
+    /// fields.
+    pub fn color(self, color: Color<T>) -> Self {
+        let material = self.shape.material().clone().with_color(color);
+        self.material(material)
+    }
+
+    /// Stops the shape from casting shadows.
+    pub fn no_shadow(mut self) -> Self {
+        self.shape.set_casts_shadow(false);
+        self
+    }
+
+    /// Returns the shape built.
+    pub fn build(self) -> Box<dyn Shape<T>> {
+        self.shape
+    }
+}
+
+/// A unit sphere at the origin.
+pub fn sphere<T: Float>() -> ShapeBuilder<T> {
+    Sphere::default().into()
+}
+
+/// The plane `xz`.
+pub fn plane<T: Float>() -> ShapeBuilder<T> {
+    Plane::default().into()
+}
+
+/// A cylinder of radius 1 around the `y` axis between `minimum` and
+/// `maximum`.
+pub fn cylinder<T: Float>(minimum: T, maximum: T, closed: bool) -> ShapeBuilder<T> {
+    Cylinder::truncated(minimum, maximum, closed).into()
+}
+
+/// A disk of `radius` in the plane `xz`.
+pub fn disk<T: Float>(radius: T) -> ShapeBuilder<T> {
+    Disk::new(radius).into()
+}
+
+/// A torus around the `y` axis.
+pub fn torus<T: Float>(major_radius: T, minor_radius: T) -> ShapeBuilder<T> {
+    Torus::new(major_radius, minor_radius).into()
+}
+
+/// A group of the `children`, placed together by the steps of the group.
+pub fn group<T: Float>(children: impl IntoIterator<Item = ShapeBuilder<T>>) -> ShapeBuilder<T> {
+    let mut group = Group::default();
+    for child in children {
+        group.add_boxed_child(child.build());
+    }
+    group.into()
+}
+
+fn csg<T: Float>(
+    operation: CsgOperation,
+    left: ShapeBuilder<T>,
+    right: ShapeBuilder<T>,
+) -> ShapeBuilder<T> {
+    Csg::from_boxed(operation, left.build(), right.build()).into()
+}
+
+/// The space inside either shape.
+pub fn union<T: Float>(left: ShapeBuilder<T>, right: ShapeBuilder<T>) -> ShapeBuilder<T> {
+    csg(CsgOperation::Union, left, right)
+}
+
+/// The space inside both shapes.
+pub fn intersection<T: Float>(left: ShapeBuilder<T>, right: ShapeBuilder<T>) -> ShapeBuilder<T> {
+    csg(CsgOperation::Intersection, left, right)
+}
+
+/// The space inside `left` but not `right`.
+pub fn difference<T: Float>(left: ShapeBuilder<T>, right: ShapeBuilder<T>) -> ShapeBuilder<T> {
+    csg(CsgOperation::Difference, left, right)
+}
+
+pub fn point_light<T: Float>(at: Point3<T>, intensity: Color<T>) -> PointLight<T> {
+    PointLight::new(at, intensity)
+}
+
+/// See [`Material::glass`].
+pub fn glass<T: Float>() -> Material<T> {
+    Material::glass()
+}
+
+/// See [`Material::mirror`].
+pub fn mirror<T: Float>() -> Material<T> {
+    Material::mirror()
+}
+
+/// See [`Material::matte`].
+pub fn matte<T: Float>(color: Color<T>) -> Material<T> {
+    Material::matte(color)
+}
+
+/// See [`Material::metal`].
+pub fn metal<T: Float>(color: Color<T>) -> Material<T> {
+    Material::metal(color)
+}
+
+impl<T: Float> Default for Scene<T> {
+    /// An empty world, seen by a camera of 400×200 pixels from
+    /// `(0, 1.5, -5)` looking at `(0, 1, 0)`, as in the book.
+    fn default() -> Self {
+        let camera = Camera::new(400, 200, T::from_f64(std::f64::consts::FRAC_PI_3));
+        let scene = Self {
+            world: World::new(),
+            camera,
+        };
+        scene.look(
+            Point3::new(T::zero(), T::from_f64(1.5), T::from_f64(-5.0)),
+            Point3::new(T::zero(), T::one(), T::zero()),
+        )
+    }
+}
+
+impl<T: Float> Scene<T> {
+    /// Returns the empty scene of [`Scene::default`], to be assembled with
+    /// the functions of [`super::builder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn light(mut self, light: PointLight<T>) -> Self {
+        self.world.add_light(light);
+        self
+    }
+
+    // Named after the `add` command of the scene files, not `+`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, shape: impl Into<ShapeBuilder<T>>) -> Self {
+        self.world.add_boxed_object(shape.into().build());
+        self
+    }
+
+    pub fn camera(mut self, camera: Camera<T>) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    /// Points the camera from `from` to `to`, upright.
+    pub fn look(mut self, from: Point3<T>, to: Point3<T>) -> Self {
+        let up = Vector3::new(T::zero(), T::one(), T::zero());
+        self.camera = self
+            .camera
+            .with_transform(Transform::view_transform(from, to, up));
+        self
+    }
+}
@@ -0,0 +1,257 @@
+//! A parser of the JSON scene files, for the scenes generated by other
+//! tools. See [`super`] for the schema.
+use std::{fmt::Write, fs, path::Path};
+
+use crate::Float;
+
+use super::{value::SceneValue, write_items, Scene, SceneError};
+
+/// Parses the JSON text `src` into a value.
+/// # Errors
+///
+/// This function will return an error if `src` is not a single JSON value.
+pub fn parse(src: &str) -> Result<SceneValue, SceneError> {
+    let mut parser = JsonParser {
+        src: src.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.src.len() {
+        return Err(parser.error());
+    }
+    Ok(value)
+}
+
+/// Writes `value` as indented JSON text. The numbers which are not
+/// finite, which JSON cannot represent, are written as `null`.
+pub fn to_string(value: &SceneValue) -> String {
+    let mut out = write_value(value, 0);
+    out.push('\n');
+    out
+}
+
+fn write_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn write_value(value: &SceneValue, indent: usize) -> String {
+    match value {
+        SceneValue::Null => "null".to_string(),
+        SceneValue::Bool(b) => b.to_string(),
+        SceneValue::Number(v) if !v.is_finite() => "null".to_string(),
+        SceneValue::Number(v) if v.fract() == 0.0 && v.abs() < 1e15 => format!("{v}"),
+        SceneValue::Number(v) => format!("{v:?}"),
+        SceneValue::String(s) => write_string(s),
+        SceneValue::Array(values) => {
+            let items = values.iter().map(|v| write_value(v, indent + 4)).collect();
+            write_items("[", items, ']', indent)
+        }
+        SceneValue::Object(entries) => {
+            let items = entries
+                .iter()
+                .map(|(key, v)| format!("{}: {}", write_string(key), write_value(v, indent + 4)))
+                .collect();
+            write_items("{", items, '}', indent)
+        }
+    }
+}
+
+impl<T: Float> Scene<T> {
+    /// Builds the scene described by the JSON text `src`.
+    /// # Errors
+    ///
+    /// This function will return an error if `src` is not valid JSON or
+    /// does not describe a valid scene.
+    pub fn from_json(src: &str) -> Result<Self, SceneError> {
+        Self::from_value(&parse(src)?, None)
+    }
+
+    /// Reads the scene of a JSON file. The models it refers to are looked
+    /// up from the same directory.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read or
+    /// does not describe a valid scene.
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        Self::from_value(&parse(&fs::read_to_string(path)?)?, path.parent())
+    }
+}
+
+struct JsonParser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    /// Returns the syntax error at the current position.
+    fn error(&self) -> SceneError {
+        let consumed = &self.src[..self.pos.min(self.src.len())];
+        let line = consumed.iter().filter(|&&b| b == b'\n').count() + 1;
+        SceneError::Syntax { line }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    /// Moves past `byte` if it comes next, after any whitespace.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.src.get(self.pos) == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Moves past `byte`, which must come next.
+    fn expect(&mut self, byte: u8) -> Result<(), SceneError> {
+        match self.eat(byte) {
+            true => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    fn value(&mut self) -> Result<SceneValue, SceneError> {
+        self.skip_whitespace();
+        let keyword = |word: &str| self.src[self.pos..].starts_with(word.as_bytes());
+        let (value, len) = match self.src.get(self.pos) {
+            Some(b'{') => return self.object(),
+            Some(b'[') => return self.array(),
+            Some(b'"') => return self.string().map(SceneValue::String),
+            Some(b'-' | b'0'..=b'9') => return self.number(),
+            _ if keyword("true") => (SceneValue::Bool(true), 4),
+            _ if keyword("false") => (SceneValue::Bool(false), 5),
+            _ if keyword("null") => (SceneValue::Null, 4),
+            _ => return Err(self.error()),
+        };
+        self.pos += len;
+        Ok(value)
+    }
+
+    fn object(&mut self) -> Result<SceneValue, SceneError> {
+        self.expect(b'{')?;
+        let mut entries = Vec::new();
+        if self.eat(b'}') {
+            return Ok(SceneValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            entries.push((key, self.value()?));
+            if self.eat(b'}') {
+                return Ok(SceneValue::Object(entries));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn array(&mut self) -> Result<SceneValue, SceneError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.eat(b']') {
+            return Ok(SceneValue::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            if self.eat(b']') {
+                return Ok(SceneValue::Array(values));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn number(&mut self) -> Result<SceneValue, SceneError> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.src[start..self.pos])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .map(SceneValue::Number)
+            .ok_or_else(|| self.error())
+    }
+
+    fn string(&mut self) -> Result<String, SceneError> {
+        if self.src.get(self.pos) != Some(&b'"') {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.src.get(self.pos) {
+                None | Some(b'\n') => return Err(self.error()),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let unescaped = match self.src.get(self.pos) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error()),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(unescaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(&b) => bytes.push(b),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    /// Reads the code point of `\uXXXX`, with the `u` at the current
+    /// position, and of the low surrogate which follows a high one. The
+    /// position is left on the last digit.
+    fn unicode_escape(&mut self) -> Result<char, SceneError> {
+        let hex = |parser: &mut Self| {
+            let digits = parser.src.get(parser.pos + 1..parser.pos + 5);
+            let code = digits
+                .and_then(|d| std::str::from_utf8(d).ok())
+                .and_then(|d| u32::from_str_radix(d, 16).ok());
+            parser.pos += 4;
+            code.ok_or_else(|| parser.error())
+        };
+        let high = hex(self)?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !self.src[self.pos + 1..].starts_with(b"\\u") {
+                return Err(self.error());
+            }
+            self.pos += 2;
+            let low = hex(self)?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error());
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error())
+    }
+}
@@ -0,0 +1,780 @@
+//! Scene description files: the world and the camera of a render written
+//! as data instead of code.
+//!
+//! The scenes are JSON files; YAML is not read. A scene is a list of
+//! commands, each one an object:
+//!
+//! - `{"add": "camera", "width", "height", "field-of-view", "from", "to",
+//!   "up"}` sets the camera; a scene needs one.
+//! - `{"add": "light", "at", "intensity"}` adds a point light.
+//! - `{"add": "overlay", "file", "at", "opacity"}` stamps an image onto the
+//!   render, its top-left corner at the pixel `at` (`[0, 0]` by default),
+//!   see [`Overlay`](crate::Overlay).
+//! - `{"define": name, "value", "extend"}` names a material, a list of
+//!   transformations or a shape, optionally extending a previous definition:
+//!   the keys of objects are overridden, lists are appended.
+//! - `{"add": shape, "material", "transform", "shadow"}` adds a `sphere`, a
+//!   `plane`, a `cylinder` (`min`, `max`, `closed`), a `disk` (`radius`,
+//!   `inner-radius`), a `torus` (`major-radius`, `minor-radius`), a
+//!   `triangle` (`p1`, `p2`, `p3`), a `smooth-triangle` (`p1`, `p2`, `p3`,
+//!   `n1`, `n2`, `n3`), a `group` (`children`), a `csg` (`operation`,
+//!   `left`, `right`), an `obj` model (`file`), or a shape defined earlier.
+//!
+//! The transformations are lists such as `["translate", 1, 2, 3]`,
+//! `["scale", ...]`, `["rotate-x", rad]`, `["rotate-y", ...]`,
+//! `["rotate-z", ...]`, `["shear", xy, xz, yx, yz, zx, zy]` or
+//! `["matrix", ...]` with the 16 values of a matrix row by row, applied in
+//! their order, or the names of lists defined earlier. The camera may take
+//! a `transform` instead of `from`, `to` and `up`. The materials take
+//! the fields of [`Material`] in kebab case, and a `pattern` of type
+//! `stripes`, `checkers`, `gradient` or `rings` with two `colors` and a
+//! `transform`. The keys not understood are ignored.
+//!
+//! The key `type` may replace `add`, and a command of `type` `define` takes
+//! the name of the definition as `name`: this is how the structs of a RON
+//! file are read, see [`ron`].
+pub mod builder;
+pub mod json;
+pub mod ron;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod value;
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{Display, Write},
+    fs, io,
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    features::{
+        camera::Camera,
+        canvas::compositing::Overlay,
+        colors::Color,
+        importers::obj::ObjParser,
+        lights::PointLight,
+        materials::Material,
+        patterns::{
+            checker::CheckerPattern, gradient::GradientPattern, ring::RingPattern,
+            stripe::StripePattern, uv_patterns::UvImage, Pattern,
+        },
+        settings::RenderSettings,
+        shapes::{
+            csg::{Csg, CsgOperation},
+            cylinder::Cylinder,
+            disk::Disk,
+            group::Group,
+            plane::Plane,
+            sphere::Sphere,
+            torus::Torus,
+            triangle::{SmoothTriangle, Triangle},
+            Shape,
+        },
+        world::World,
+    },
+    Float, Matrix4, Point3, Transform, Vector3,
+};
+
+use self::value::SceneValue;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    /// The file is not well-formed at the given line.
+    Syntax {
+        line: usize,
+    },
+    /// The file is well-formed, but does not describe a valid scene.
+    Invalid(String),
+    /// The scene holds something no scene file describes, or the format of
+    /// a file is unknown.
+    Unsupported(String),
+}
+
+impl Display for SceneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneError::Io(e) => write!(f, "Failed to read the scene: {e}"),
+            SceneError::Syntax { line } => write!(f, "Invalid scene syntax at line {line}."),
+            SceneError::Invalid(message) => write!(f, "Invalid scene: {message}."),
+            SceneError::Unsupported(message) => write!(f, "Unsupported scene: {message}."),
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+impl From<io::Error> for SceneError {
+    fn from(e: io::Error) -> Self {
+        SceneError::Io(e)
+    }
+}
+
+/// Returns the error of the type `S`, which no scene file describes.
+pub(crate) fn unsupported<S: ?Sized>() -> SceneError {
+    SceneError::Unsupported(format!(
+        "`{}` cannot be written to a scene file",
+        std::any::type_name::<S>()
+    ))
+}
+
+/// Returns the description of a pattern of the `kind` alternating
+/// between the colors `a` and `b`.
+pub(crate) fn two_colors_value<T: Float>(kind: &str, a: Color<T>, b: Color<T>) -> SceneValue {
+    let colors = [a, b].map(|c| SceneValue::numbers([c.r, c.g, c.b]));
+    SceneValue::object([
+        ("type", kind.into()),
+        ("colors", SceneValue::Array(colors.into())),
+    ])
+}
+
+/// Joins the items of a struct, an object or a list between `open` and `close`, on
+/// one line if they are short enough, or one per line otherwise.
+fn write_items(open: &str, items: Vec<String>, close: char, indent: usize) -> String {
+    let one_line = items.iter().all(|item| !item.contains('\n'))
+        && indent + open.len() + items.iter().map(|item| item.len() + 2).sum::<usize>() <= 80;
+    if items.is_empty() || one_line {
+        return format!("{open}{}{close}", items.join(", "));
+    }
+    let pad = " ".repeat(indent + 4);
+    let mut out = format!("{open}\n");
+    let last = items.len() - 1;
+    for (i, item) in items.iter().enumerate() {
+        let comma = if i < last { "," } else { "" };
+        writeln!(out, "{pad}{item}{comma}").unwrap();
+    }
+    out.push_str(&" ".repeat(indent));
+    out.push(close);
+    out
+}
+
+/// Returns the error of an invalid scene.
+fn invalid<R>(message: impl Into<String>) -> Result<R, SceneError> {
+    Err(SceneError::Invalid(message.into()))
+}
+
+#[derive(Debug)]
+/// A world with the camera looking at it, as described by a scene file.
+pub struct Scene<T: Float> {
+    pub world: World<T>,
+    pub camera: Camera<T>,
+}
+
+impl<T: Float> Scene<T> {
+    /// Builds the scene described by `value`, a list of commands. The
+    /// relative paths of the models are resolved against `dir`, or the
+    /// current directory if `None`.
+    /// # Errors
+    ///
+    /// This function will return an error if `value` does not describe a
+    /// valid scene, or if a model cannot be read.
+    pub fn from_value(value: &SceneValue, dir: Option<&Path>) -> Result<Self, SceneError> {
+        let Some(commands) = value.as_array() else {
+            return invalid("a scene must be a list of commands");
+        };
+        let mut loader = Loader {
+            definitions: HashMap::new(),
+            expanding: RefCell::new(HashSet::new()),
+            dir,
+        };
+        let mut world = World::new();
+        let mut camera: Option<Camera<T>> = None;
+        let mut overlay = None;
+        for command in commands {
+            let name = match tag(command) {
+                Some("define") => command.get("name"),
+                _ => command.get("define"),
+            };
+            if let Some(name) = name.and_then(SceneValue::as_str) {
+                loader.define(name, command)?;
+                continue;
+            }
+            match tag(command) {
+                Some("camera") => camera = Some(loader.camera(command)?),
+                Some("light") => world.add_light(loader.light(command)?),
+                Some("overlay") => overlay = Some(Arc::new(loader.overlay(command)?)),
+                Some(_) => world.add_boxed_object(loader.shape(command)?),
+                None => return invalid("a command must `add` or `define` something"),
+            }
+        }
+        let Some(mut camera) = camera else {
+            return invalid("the scene has no camera");
+        };
+        if overlay.is_some() {
+            let settings = camera.settings().clone();
+            camera = camera.with_settings(RenderSettings {
+                overlay,
+                ..settings
+            });
+        }
+        Ok(Self { world, camera })
+    }
+
+    /// Describes this scene as a list of commands building it again: the
+    /// camera, the lights, then the objects. The transformations are kept
+    /// exactly, as a scaling and a translation when this is all they do,
+    /// or as matrices.
+    ///
+    /// Only what the schema describes is written: the size, field of view
+    /// and transformation of the camera, the position and intensity of the
+    /// lights, and the shapes with their material. The other settings of
+    /// the camera, the attenuation of the lights, the motion of the shapes
+    /// and the fog and background of the world are left out.
+    /// # Errors
+    ///
+    /// This function will return an error if a shape or a pattern cannot be
+    /// written to a scene file, see [`Shape::scene_value`].
+    pub fn to_value(&self) -> Result<SceneValue, SceneError> {
+        let camera = SceneValue::object([
+            ("add", "camera".into()),
+            ("width", SceneValue::number(self.camera.hsize() as f64)),
+            ("height", SceneValue::number(self.camera.vsize() as f64)),
+            (
+                "field-of-view",
+                SceneValue::number(self.camera.field_of_view()),
+            ),
+            ("transform", transform_value(&self.camera.transform())),
+        ]);
+        let mut commands = vec![camera];
+        for light in self.world.lights() {
+            let (at, intensity) = (light.position(), light.intensity());
+            commands.push(SceneValue::object([
+                ("add", "light".into()),
+                ("at", SceneValue::numbers([at.x, at.y, at.z])),
+                (
+                    "intensity",
+                    SceneValue::numbers([intensity.r, intensity.g, intensity.b]),
+                ),
+            ]));
+        }
+        for object in self.world.objects() {
+            commands.push(shape_value(object.as_ref())?);
+        }
+        Ok(SceneValue::Array(commands))
+    }
+
+    /// Reads the scene of a file in the format given by its extension,
+    /// `json` or `ron`, or runs the script of a `script` file with the
+    /// `scripting` feature, see [`Scene::from_script`].
+    /// # Errors
+    ///
+    /// This function will return an error if the format is unknown, or if
+    /// the file cannot be read or does not describe a valid scene.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json_file(path),
+            Some("ron") => Self::from_ron_file(path),
+            #[cfg(feature = "scripting")]
+            Some("script") => Self::from_script_file(path),
+            _ => Err(unknown_format(path)),
+        }
+    }
+
+    /// Writes this scene to a file in the format given by its extension,
+    /// `json` or `ron`, see [`Scene::to_value`].
+    /// # Errors
+    ///
+    /// This function will return an error if the format is unknown, if the
+    /// scene cannot be written to a scene file, or if the file cannot be
+    /// written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SceneError> {
+        let path = path.as_ref();
+        let text = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => json::to_string(&self.to_value()?),
+            Some("ron") => ron::to_string(&self.to_value()?),
+            _ => return Err(unknown_format(path)),
+        };
+        Ok(fs::write(path, text)?)
+    }
+}
+
+fn unknown_format(path: &Path) -> SceneError {
+    SceneError::Unsupported(format!(
+        "the format of `{}` is neither JSON nor RON",
+        path.display()
+    ))
+}
+
+/// Returns the description of `shape` in a scene file, with its
+/// transformation, material and shadow flag when they are not the
+/// defaults.
+pub(crate) fn shape_value<T: Float>(shape: &dyn Shape<T>) -> Result<SceneValue, SceneError> {
+    let mut value = shape.scene_value()?;
+    let transform = shape.transform();
+    if transform != Matrix4::identity() {
+        value.push("transform", transform_value(&transform));
+    }
+    let material = material_value(shape.material())?;
+    if material
+        .as_object()
+        .is_some_and(|entries| !entries.is_empty())
+    {
+        value.push("material", material);
+    }
+    if !shape.casts_shadow() {
+        value.push("shadow", false.into());
+    }
+    Ok(value)
+}
+
+/// Returns the list of transformations giving exactly `m`.
+fn transform_value<T: Float>(m: &Matrix4<T>) -> SceneValue {
+    let step = |name: &str, args: &[T]| {
+        let args = args.iter().map(|&arg| SceneValue::number(arg));
+        SceneValue::Array(std::iter::once(name.into()).chain(args).collect())
+    };
+    let (zero, one) = (T::zero(), T::one());
+    let scales_and_translates =
+        m[(3, 3)] == one && (0..4).all(|r| (0..3).all(|c| r == c || m[(r, c)] == zero));
+    if !scales_and_translates {
+        let values: Vec<T> = (0..16).map(|i| m[(i / 4, i % 4)]).collect();
+        return SceneValue::Array(vec![step("matrix", &values)]);
+    }
+    let scale = [m[(0, 0)], m[(1, 1)], m[(2, 2)]];
+    let translation = [m[(0, 3)], m[(1, 3)], m[(2, 3)]];
+    let mut steps = Vec::new();
+    if scale != [one; 3] {
+        steps.push(step("scale", &scale));
+    }
+    if translation != [zero; 3] {
+        steps.push(step("translate", &translation));
+    }
+    SceneValue::Array(steps)
+}
+
+/// Returns the fields of `material` which are not the defaults.
+fn material_value<T: Float>(material: &Material<T>) -> Result<SceneValue, SceneError> {
+    let default = Material::default();
+    let mut value = SceneValue::object([]);
+    let rgb = |c: Color<T>| SceneValue::numbers([c.r, c.g, c.b]);
+    if material.color != default.color {
+        value.push("color", rgb(material.color));
+    }
+    for (key, field, default) in [
+        ("ambient", material.ambient, default.ambient),
+        ("diffuse", material.diffuse, default.diffuse),
+        ("specular", material.specular, default.specular),
+        ("shininess", material.shininess, default.shininess),
+        ("reflective", material.reflective, default.reflective),
+        ("transparency", material.transparency, default.transparency),
+        (
+            "refractive-index",
+            material.refractive_index,
+            default.refractive_index,
+        ),
+    ] {
+        if field != default {
+            value.push(key, SceneValue::number(field));
+        }
+    }
+    if material.emissive != default.emissive {
+        value.push("emissive", rgb(material.emissive));
+    }
+    if let Some(pattern) = &material.pattern {
+        let mut pattern_value = pattern.scene_value()?;
+        if pattern.transform() != Matrix4::identity() {
+            pattern_value.push("transform", transform_value(&pattern.transform()));
+        }
+        value.push("pattern", pattern_value);
+    }
+    Ok(value)
+}
+
+/// The state of the loading of a scene: the definitions seen so far.
+struct Loader<'a> {
+    definitions: HashMap<String, SceneValue>,
+    /// The names of the definitions being built, which a definition
+    /// naming itself, directly or not, would expand again forever.
+    expanding: RefCell<HashSet<String>>,
+    dir: Option<&'a Path>,
+}
+
+impl Loader<'_> {
+    /// Records the definition `name` of `command`.
+    fn define(&mut self, name: &str, command: &SceneValue) -> Result<(), SceneError> {
+        let Some(value) = command.get("value") else {
+            return invalid(format!("the definition `{name}` has no value"));
+        };
+        let value = match command.get("extend").and_then(SceneValue::as_str) {
+            Some(base) => match self.definitions.get(base) {
+                Some(base) => base.extended(value),
+                None => return invalid(format!("`{name}` extends the unknown `{base}`")),
+            },
+            None => value.clone(),
+        };
+        // A shape defined from another one is expanded now, to be built
+        // without recursion.
+        let value = match self.expanded(&value) {
+            Some(expanded) => expanded,
+            None => value,
+        };
+        self.definitions.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Returns the definition named by `value`, or `value` itself.
+    fn resolve<'v>(&'v self, value: &'v SceneValue) -> &'v SceneValue {
+        value
+            .as_str()
+            .and_then(|name| self.definitions.get(name))
+            .unwrap_or(value)
+    }
+
+    /// Returns `build` of the definition named by `value`, or of `value`
+    /// itself, marking the definition as being expanded meanwhile.
+    /// # Errors
+    ///
+    /// This function will return an error if the definition is already
+    /// being expanded, as it contains itself, or if `build` fails.
+    fn expand<R>(
+        &self,
+        value: &SceneValue,
+        build: impl FnOnce(&SceneValue) -> Result<R, SceneError>,
+    ) -> Result<R, SceneError> {
+        let Some((name, definition)) = value
+            .as_str()
+            .and_then(|name| Some((name, self.definitions.get(name)?)))
+        else {
+            return build(value);
+        };
+        if !self.expanding.borrow_mut().insert(name.to_string()) {
+            return invalid(format!("the definition `{name}` contains itself"));
+        }
+        let result = build(definition);
+        self.expanding.borrow_mut().remove(name);
+        result
+    }
+
+    fn camera<T: Float>(&self, command: &SceneValue) -> Result<Camera<T>, SceneError> {
+        let size = |key| match command.get(key).and_then(SceneValue::as_f64) {
+            Some(v) if v >= 1.0 && v.fract() == 0.0 => Ok(v as usize),
+            _ => invalid(format!("the camera `{key}` must be a positive integer")),
+        };
+        let (width, height) = (size("width")?, size("height")?);
+        let field_of_view = number(command, "field-of-view")?;
+        let transform = match command.get("transform") {
+            Some(transform) => self.transform(Some(transform))?,
+            None => Transform::view_transform(
+                point(command, "from")?,
+                point(command, "to")?,
+                vector(command, "up")?,
+            ),
+        };
+        Ok(Camera::new(width, height, field_of_view).with_transform(transform))
+    }
+
+    fn overlay(&self, command: &SceneValue) -> Result<Overlay<f64>, SceneError> {
+        let Some(file) = command.get("file").and_then(SceneValue::as_str) else {
+            return invalid("an overlay needs a `file`");
+        };
+        let path = match self.dir {
+            Some(dir) => dir.join(file),
+            None => file.into(),
+        };
+        let image = UvImage::from_file(path)
+            .map_err(|e| SceneError::Invalid(format!("the overlay `{file}`: {e}")))?;
+        let pixel = |v: &SceneValue| {
+            v.as_f64()
+                .filter(|v| *v >= 0.0 && v.fract() == 0.0)
+                .map(|v| v as usize)
+        };
+        let at = match command.get("at").map(SceneValue::as_array) {
+            None => Some((0, 0)),
+            Some(Some([x, y])) => pixel(x).zip(pixel(y)),
+            Some(_) => None,
+        };
+        let Some((x, y)) = at else {
+            return invalid("the overlay `at` must be two pixel coordinates");
+        };
+        let opacity = optional(command, "opacity", 1.0)?;
+        if !(0.0..=1.0).contains(&opacity) {
+            return invalid("the overlay `opacity` must be within [0, 1]");
+        }
+        Ok(Overlay::new(image).at(x, y).with_opacity(opacity))
+    }
+
+    fn light<T: Float>(&self, command: &SceneValue) -> Result<PointLight<T>, SceneError> {
+        Ok(PointLight::new(
+            point(command, "at")?,
+            color(command, "intensity")?,
+        ))
+    }
+
+    /// Returns `command` merged into the definition of the shape it adds,
+    /// if it adds a defined shape. The transformations of the command apply
+    /// after the ones of the definition, its other keys override the
+    /// definition's.
+    fn expanded(&self, command: &SceneValue) -> Option<SceneValue> {
+        let definition = self.definitions.get(tag(command)?)?;
+        Some(definition.extended(&command.without("add").without("type")))
+    }
+
+    /// Returns the shape added by `command`.
+    fn shape<T: Float>(&self, command: &SceneValue) -> Result<Box<dyn Shape<T>>, SceneError> {
+        let expanded = self.expanded(command);
+        let command = expanded.as_ref().unwrap_or(command);
+        let Some(kind) = tag(command) else {
+            return invalid("a shape must name what it `add`s");
+        };
+        let transform = self.transform(command.get("transform"))?;
+        let mut shape: Box<dyn Shape<T>> = match kind {
+            "sphere" => Box::new(Sphere::default()),
+            "plane" => Box::new(Plane::default()),
+            "cylinder" => Box::new(Cylinder::truncated(
+                optional(command, "min", T::neg_infinity())?,
+                optional(command, "max", T::infinity())?,
+                command
+                    .get("closed")
+                    .and_then(SceneValue::as_bool)
+                    .unwrap_or(false),
+            )),
+            "disk" => Box::new(Disk::annulus(
+                optional(command, "inner-radius", T::zero())?,
+                optional(command, "radius", T::one())?,
+            )),
+            "torus" => Box::new(Torus::new(
+                optional(command, "major-radius", T::one())?,
+                optional(command, "minor-radius", T::one() / T::four())?,
+            )),
+            "triangle" => Box::new(Triangle::new(
+                point(command, "p1")?,
+                point(command, "p2")?,
+                point(command, "p3")?,
+            )),
+            "smooth-triangle" => Box::new(SmoothTriangle::new(
+                point(command, "p1")?,
+                point(command, "p2")?,
+                point(command, "p3")?,
+                vector(command, "n1")?,
+                vector(command, "n2")?,
+                vector(command, "n3")?,
+            )),
+            "group" => {
+                let mut group = Group::new(transform);
+                let children = command.get("children").map(|c| self.resolve(c));
+                for child in children.and_then(SceneValue::as_array).unwrap_or(&[]) {
+                    group.add_boxed_child(self.expand(child, |child| self.shape(child))?);
+                }
+                Box::new(group)
+            }
+            "csg" => {
+                let operation = match command.get("operation").and_then(SceneValue::as_str) {
+                    Some("union") => CsgOperation::Union,
+                    Some("intersection") => CsgOperation::Intersection,
+                    Some("difference") => CsgOperation::Difference,
+                    _ => return invalid(
+                        "the `operation` of a csg must be `union`, `intersection` or `difference`",
+                    ),
+                };
+                let operand = |key| match command.get(key) {
+                    Some(operand) => self.expand(operand, |operand| self.shape(operand)),
+                    None => invalid(format!("a csg needs a `{key}` shape")),
+                };
+                Box::new(Csg::from_boxed(
+                    operation,
+                    operand("left")?,
+                    operand("right")?,
+                ))
+            }
+            "obj" => {
+                let Some(file) = command.get("file").and_then(SceneValue::as_str) else {
+                    return invalid("an obj needs a `file`");
+                };
+                let path = match self.dir {
+                    Some(dir) => dir.join(file),
+                    None => file.into(),
+                };
+                let mut group = ObjParser::from_file(path)?.into_group();
+                group.set_transform(transform);
+                Box::new(group)
+            }
+            _ => return invalid(format!("unknown shape `{kind}`")),
+        };
+        if kind != "group" && kind != "obj" {
+            shape.set_transform(transform);
+        }
+        if let Some(material) = command.get("material") {
+            shape.set_material(self.material(material)?);
+        }
+        if let Some(shadow) = command.get("shadow").and_then(SceneValue::as_bool) {
+            shape.set_casts_shadow(shadow);
+        }
+        Ok(shape)
+    }
+
+    /// Returns the product of the list of transformations `value`, the
+    /// identity if `None`.
+    fn transform<T: Float>(&self, value: Option<&SceneValue>) -> Result<Matrix4<T>, SceneError> {
+        let mut transform = Matrix4::identity();
+        let Some(value) = value else {
+            return Ok(transform);
+        };
+        self.expand(value, |value| {
+            let Some(steps) = value.as_array() else {
+                return invalid("a `transform` must be a list");
+            };
+            for step in steps {
+                transform = self.transform_step(step)? * transform;
+            }
+            if transform.inverse().is_none() {
+                return invalid("a transformation is not invertible");
+            }
+            Ok(transform)
+        })
+    }
+
+    /// Returns the transformation `step` of a list: an operation, a defined
+    /// name, or a list nested in the list.
+    fn transform_step<T: Float>(&self, step: &SceneValue) -> Result<Matrix4<T>, SceneError> {
+        let Some([op, args @ ..]) = self.resolve(step).as_array() else {
+            return invalid("a transformation must be a list or a defined name");
+        };
+        let op = match op.as_str() {
+            Some(op) if !self.definitions.contains_key(op) => op,
+            // A list nested in the list.
+            _ => return self.transform(Some(step)),
+        };
+        let args: Vec<T> = args
+            .iter()
+            .map(|arg| arg.as_f64().map(T::from_f64))
+            .collect::<Option<_>>()
+            .ok_or_else(|| SceneError::Invalid("transformations take numbers".into()))?;
+        let m = match (op, args.as_slice()) {
+            ("translate", &[x, y, z]) => Transform::translation(x, y, z),
+            ("scale", &[x, y, z]) => Transform::scaling(x, y, z),
+            ("rotate-x", &[rad]) => Transform::rotation_x(rad),
+            ("rotate-y", &[rad]) => Transform::rotation_y(rad),
+            ("rotate-z", &[rad]) => Transform::rotation_z(rad),
+            ("shear", &[xy, xz, yx, yz, zx, zy]) => {
+                let mut m = Matrix4::identity();
+                m[(0, 1)] = xy;
+                m[(0, 2)] = xz;
+                m[(1, 0)] = yx;
+                m[(1, 2)] = yz;
+                m[(2, 0)] = zx;
+                m[(2, 1)] = zy;
+                m
+            }
+            ("matrix", values) if values.len() == 16 => {
+                let mut m = Matrix4::identity();
+                for (i, &v) in values.iter().enumerate() {
+                    m[(i / 4, i % 4)] = v;
+                }
+                m
+            }
+            (op, _) => return invalid(format!("invalid transformation `{op}`")),
+        };
+        Ok(m)
+    }
+
+    fn material<T: Float>(&self, value: &SceneValue) -> Result<Material<T>, SceneError> {
+        let value = self.resolve(value);
+        if value.as_object().is_none() {
+            return invalid("a `material` must be an object or a defined name");
+        }
+        let mut material = Material::default();
+        if value.get("color").is_some() {
+            material.color = color(value, "color")?;
+        }
+        if value.get("emissive").is_some() {
+            material.emissive = color(value, "emissive")?;
+        }
+        for (key, field) in [
+            ("ambient", &mut material.ambient),
+            ("diffuse", &mut material.diffuse),
+            ("specular", &mut material.specular),
+            ("shininess", &mut material.shininess),
+            ("reflective", &mut material.reflective),
+            ("transparency", &mut material.transparency),
+            ("refractive-index", &mut material.refractive_index),
+        ] {
+            *field = optional(value, key, *field)?;
+        }
+        if let Some(pattern) = value.get("pattern") {
+            material.pattern = Some(self.pattern(self.resolve(pattern))?.into());
+        }
+        Ok(material)
+    }
+
+    fn pattern<T: Float>(&self, value: &SceneValue) -> Result<Box<dyn Pattern<T>>, SceneError> {
+        let colors = match value.get("colors").and_then(SceneValue::as_array) {
+            Some([a, b]) => triple(a).zip(triple(b)),
+            _ => None,
+        };
+        let Some(([ar, ag, ab], [br, bg, bb])) = colors else {
+            return invalid("a pattern needs two `colors` of three numbers");
+        };
+        let (a, b) = (Color::new(ar, ag, ab), Color::new(br, bg, bb));
+        let mut pattern: Box<dyn Pattern<T>> =
+            match value.get("type").and_then(SceneValue::as_str) {
+                Some("stripes") => Box::new(StripePattern::new(a, b)),
+                Some("checkers") => Box::new(CheckerPattern::new(a, b)),
+                Some("gradient") => Box::new(GradientPattern::new(a, b)),
+                Some("rings") => Box::new(RingPattern::new(a, b)),
+                _ => return invalid(
+                    "the `type` of a pattern must be `stripes`, `checkers`, `gradient` or `rings`",
+                ),
+            };
+        pattern.set_transform(self.transform(value.get("transform"))?);
+        Ok(pattern)
+    }
+}
+
+/// Returns what `command` adds, given by its `add` or `type` key.
+fn tag(command: &SceneValue) -> Option<&str> {
+    command
+        .get("add")
+        .or_else(|| command.get("type"))
+        .and_then(SceneValue::as_str)
+}
+
+/// Returns the three numbers of `value`.
+fn triple<T: Float>(value: &SceneValue) -> Option<[T; 3]> {
+    match value.as_array()? {
+        [x, y, z] => Some([
+            T::from_f64(x.as_f64()?),
+            T::from_f64(y.as_f64()?),
+            T::from_f64(z.as_f64()?),
+        ]),
+        _ => None,
+    }
+}
+
+fn number<T: Float>(object: &SceneValue, key: &str) -> Result<T, SceneError> {
+    match object.get(key).and_then(SceneValue::as_f64) {
+        Some(v) => Ok(T::from_f64(v)),
+        None => invalid(format!("`{key}` must be a number")),
+    }
+}
+
+/// Returns the number `key` of `object`, or `default` if it is absent.
+fn optional<T: Float>(object: &SceneValue, key: &str, default: T) -> Result<T, SceneError> {
+    match object.get(key) {
+        Some(_) => number(object, key),
+        None => Ok(default),
+    }
+}
+
+fn components<T: Float>(object: &SceneValue, key: &str) -> Result<[T; 3], SceneError> {
+    match object.get(key).and_then(triple) {
+        Some(v) => Ok(v),
+        None => invalid(format!("`{key}` must be three numbers")),
+    }
+}
+
+fn point<T: Float>(object: &SceneValue, key: &str) -> Result<Point3<T>, SceneError> {
+    components(object, key).map(|[x, y, z]| Point3::new(x, y, z))
+}
+
+fn vector<T: Float>(object: &SceneValue, key: &str) -> Result<Vector3<T>, SceneError> {
+    components(object, key).map(|[x, y, z]| Vector3::new(x, y, z))
+}
+
+fn color<T: Float>(object: &SceneValue, key: &str) -> Result<Color<T>, SceneError> {
+    components(object, key).map(|[r, g, b]| Color::new(r, g, b))
+}
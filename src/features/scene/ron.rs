@@ -0,0 +1,510 @@
+//! A parser and a writer of the RON scene files, the Rusty Object
+//! Notation, for the scenes written by hand. A RON scene reads like the
+//! Rust code building it:
+//!
+//! ```text
+//! [
+//!     Camera(width: 100, height: 50, field_of_view: 0.785,
+//!            from: (0, 1.5, -5), to: (0, 1, 0), up: (0, 1, 0)),
+//!     Light(at: (-10, 10, -10), intensity: (1, 1, 1)),
+//!     Define(name: "glass", value: (transparency: 0.9, refractive_index: 1.5)),
+//!     Sphere(material: "glass", transform: [Scale(0.5, 0.5, 0.5), Translate(0, 1, 0)]),
+//! ]
+//! ```
+//!
+//! A struct `Name(field: ...)` is read as an object of `type` `name` and a
+//! tuple struct `Name(...)` as a list starting with `name`, the names and
+//! the fields in kebab case; tuples are lists, `Some(x)` is `x` and `None`
+//! is null. See [`super`] for the schema.
+use std::{fmt::Write, fs, path::Path};
+
+use crate::Float;
+
+use super::{value::SceneValue, write_items, Scene, SceneError};
+
+/// Parses the RON text `src` into a value.
+/// # Errors
+///
+/// This function will return an error if `src` is not a single RON value.
+pub fn parse(src: &str) -> Result<SceneValue, SceneError> {
+    let mut parser = RonParser {
+        src: src.as_bytes(),
+        pos: 0,
+    };
+    parser.skip_whitespace();
+    // The extensions declared by `#![enable(...)]` change nothing here.
+    while parser.src[parser.pos..].starts_with(b"#!") {
+        while !matches!(parser.src.get(parser.pos), None | Some(b']')) {
+            parser.pos += 1;
+        }
+        parser.expect(b']')?;
+        parser.skip_whitespace();
+    }
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.src.len() {
+        return Err(parser.error());
+    }
+    Ok(value)
+}
+
+/// Writes `value` as indented RON text, the inverse of [`parse`]. The
+/// objects naming what they `add` or `define` are written as structs, the
+/// lists of a name followed by numbers, such as the transformations, as
+/// tuple structs, and the lists of numbers as tuples.
+pub fn to_string(value: &SceneValue) -> String {
+    let mut out = write_value(value, 0);
+    out.push('\n');
+    out
+}
+
+impl<T: Float> Scene<T> {
+    /// Builds the scene described by the RON text `src`.
+    /// # Errors
+    ///
+    /// This function will return an error if `src` is not valid RON or
+    /// does not describe a valid scene.
+    pub fn from_ron(src: &str) -> Result<Self, SceneError> {
+        Self::from_value(&parse(src)?, None)
+    }
+
+    /// Reads the scene of a RON file. The models it refers to are looked
+    /// up from the same directory.
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read or
+    /// does not describe a valid scene.
+    pub fn from_ron_file(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+        Self::from_value(&parse(&fs::read_to_string(path)?)?, path.parent())
+    }
+}
+
+/// Returns `PascalCase` as `pascal-case`.
+fn to_kebab(name: &str) -> String {
+    let mut kebab = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            kebab.push('-');
+        }
+        kebab.push(c.to_ascii_lowercase());
+    }
+    kebab
+}
+
+/// Returns `kebab-case` as `KebabCase`, if it is a name which comes back
+/// from [`to_kebab`] unchanged.
+fn to_pascal(name: &str) -> Option<String> {
+    let pascal: String = name
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |c| {
+                c.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect();
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    (valid && to_kebab(&pascal) == name).then_some(pascal)
+}
+
+/// Returns the field `key` of a struct, if the key can be written as one.
+fn to_field(key: &str) -> Option<String> {
+    let valid = key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    valid.then(|| key.replace('-', "_"))
+}
+
+fn write_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn write_number(v: f64) -> String {
+    match v {
+        v if v.is_nan() => "NaN".to_string(),
+        v if v.is_infinite() => if v > 0.0 { "inf" } else { "-inf" }.to_string(),
+        v if v.fract() == 0.0 && v.abs() < 1e15 => format!("{v}"),
+        // The shortest form, with an exponent for the very large or small.
+        v => format!("{v:?}"),
+    }
+}
+
+fn write_value(value: &SceneValue, indent: usize) -> String {
+    match value {
+        SceneValue::Null => "None".to_string(),
+        SceneValue::Bool(b) => b.to_string(),
+        SceneValue::Number(v) => write_number(*v),
+        SceneValue::String(s) => write_string(s),
+        SceneValue::Array(values) => {
+            if let [SceneValue::String(name), args @ ..] = values.as_slice() {
+                let numbers = args.iter().all(|arg| arg.as_f64().is_some());
+                if let Some(name) = to_pascal(name).filter(|_| numbers && !args.is_empty()) {
+                    let items = args.iter().map(|arg| write_value(arg, indent)).collect();
+                    return write_items(&format!("{name}("), items, ')', indent);
+                }
+            }
+            let items = values.iter().map(|v| write_value(v, indent + 4)).collect();
+            // The points, vectors and colors are tuples.
+            match values.iter().all(|v| v.as_f64().is_some()) && !values.is_empty() {
+                true => write_items("(", items, ')', indent),
+                false => write_items("[", items, ']', indent),
+            }
+        }
+        SceneValue::Object(entries) => write_object(value, entries, indent),
+    }
+}
+
+fn write_object(value: &SceneValue, entries: &[(String, SceneValue)], indent: usize) -> String {
+    let tag = ["define", "add", "type"]
+        .into_iter()
+        .find_map(|key| Some((key, value.get(key)?.as_str()?)));
+    let (name, skipped, extra) = match tag {
+        Some(("define", name)) => ("Define".to_string(), "define", Some(name)),
+        Some((key, kind)) => match to_pascal(kind) {
+            Some(name) => (name, key, None),
+            None => (String::new(), "", None),
+        },
+        None => (String::new(), "", None),
+    };
+    let fields: Option<Vec<(String, &SceneValue)>> = entries
+        .iter()
+        .filter(|(key, _)| key != skipped)
+        .map(|(key, v)| Some((to_field(key)?, v)))
+        .collect();
+    let fields = fields.filter(|fields| !(name.is_empty() && fields.is_empty()));
+    // Keys which are not fields make a map, and so does an empty object,
+    // which would be read back as a unit `()`.
+    let Some(fields) = fields else {
+        let items = entries
+            .iter()
+            .map(|(key, v)| format!("{}: {}", write_string(key), write_value(v, indent + 4)))
+            .collect();
+        return write_items("{", items, '}', indent);
+    };
+    let mut items: Vec<String> = extra
+        .map(|name| format!("name: {}", write_string(name)))
+        .into_iter()
+        .collect();
+    items.extend(
+        fields
+            .iter()
+            .map(|(field, v)| format!("{field}: {}", write_value(v, indent + 4))),
+    );
+    write_items(&format!("{name}("), items, ')', indent)
+}
+
+struct RonParser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl RonParser<'_> {
+    /// Returns the syntax error at the current position.
+    fn error(&self) -> SceneError {
+        let consumed = &self.src[..self.pos.min(self.src.len())];
+        let line = consumed.iter().filter(|&&b| b == b'\n').count() + 1;
+        SceneError::Syntax { line }
+    }
+
+    /// Skips the whitespace and the comments, `// ...` and `/* ... */`.
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.src[self.pos..];
+            if let Some(b' ' | b'\t' | b'\n' | b'\r') = rest.first() {
+                self.pos += 1;
+            } else if rest.starts_with(b"//") {
+                while !matches!(self.src.get(self.pos), None | Some(b'\n')) {
+                    self.pos += 1;
+                }
+            } else if rest.starts_with(b"/*") {
+                let end = rest.windows(2).skip(2).position(|w| w == b"*/");
+                self.pos += end.map_or(rest.len(), |end| end + 4);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Moves past `byte` if it comes next, after any whitespace.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.src.get(self.pos) == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Moves past `byte`, which must come next.
+    fn expect(&mut self, byte: u8) -> Result<(), SceneError> {
+        match self.eat(byte) {
+            true => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    /// Reads the items up to `close`, separated by commas, the last one
+    /// optionally followed by one.
+    fn items<R>(
+        &mut self,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> Result<R, SceneError>,
+    ) -> Result<Vec<R>, SceneError> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(item(self)?);
+            if !self.eat(b',') {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn value(&mut self) -> Result<SceneValue, SceneError> {
+        self.skip_whitespace();
+        match self.src.get(self.pos) {
+            Some(b'[') => {
+                self.pos += 1;
+                self.items(b']', Self::value).map(SceneValue::Array)
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let entries = self.items(b'}', |parser| {
+                    let key = parser.value()?;
+                    parser.expect(b':')?;
+                    match key {
+                        SceneValue::String(key) => Ok((key, parser.value()?)),
+                        _ => Err(parser.error()),
+                    }
+                })?;
+                Ok(SceneValue::Object(entries))
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                self.fields(None)
+            }
+            Some(b'"') => self.string().map(SceneValue::String),
+            Some(b'\'') => self.char().map(|c| SceneValue::String(c.to_string())),
+            Some(b'r') if matches!(self.src.get(self.pos + 1), Some(b'"' | b'#')) => {
+                self.raw_string().map(SceneValue::String)
+            }
+            Some(b'-' | b'+' | b'.' | b'0'..=b'9') => self.number(),
+            Some(c) if c.is_ascii_alphabetic() || *c == b'_' => self.identified(),
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Reads the contents of a struct or a tuple after its `(`: a struct
+    /// is an object, tagged by the `type` `name` if it is named, and a
+    /// tuple is a list, starting with `name` if it is named.
+    fn fields(&mut self, name: Option<String>) -> Result<SceneValue, SceneError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        // A named struct may have no field, but a tuple has a value.
+        let is_struct = (self.identifier().is_some() && self.eat(b':'))
+            || (name.is_some() && self.src.get(start) == Some(&b')'));
+        self.pos = start;
+        if !is_struct {
+            let values = self.items(b')', Self::value)?;
+            return Ok(match name {
+                Some(name) => SceneValue::Array(
+                    std::iter::once(SceneValue::String(name))
+                        .chain(values)
+                        .collect(),
+                ),
+                None if values.is_empty() => SceneValue::Null,
+                None => SceneValue::Array(values),
+            });
+        }
+        let mut entries: Vec<_> = name
+            .map(|name| ("type".to_string(), SceneValue::String(name)))
+            .into_iter()
+            .collect();
+        entries.extend(self.items(b')', |parser| {
+            parser.skip_whitespace();
+            let field = parser.identifier().ok_or_else(|| parser.error())?;
+            parser.expect(b':')?;
+            Ok((field.replace('_', "-"), parser.value()?))
+        })?);
+        Ok(SceneValue::Object(entries))
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let start = self.pos;
+        while let Some(c) = self.src.get(self.pos) {
+            if !(c.is_ascii_alphanumeric() || *c == b'_') {
+                break;
+            }
+            self.pos += 1;
+        }
+        let ident = std::str::from_utf8(&self.src[start..self.pos]).ok()?;
+        (!ident.is_empty()).then(|| ident.to_string())
+    }
+
+    /// Reads a value starting with an identifier: a keyword, a named
+    /// struct or tuple, or a unit variant, which is read as its name.
+    fn identified(&mut self) -> Result<SceneValue, SceneError> {
+        let ident = self.identifier().ok_or_else(|| self.error())?;
+        match ident.as_str() {
+            "true" => return Ok(SceneValue::Bool(true)),
+            "false" => return Ok(SceneValue::Bool(false)),
+            "None" => return Ok(SceneValue::Null),
+            "inf" => return Ok(SceneValue::Number(f64::INFINITY)),
+            "NaN" => return Ok(SceneValue::Number(f64::NAN)),
+            _ => {}
+        }
+        if !self.eat(b'(') {
+            return Ok(SceneValue::String(to_kebab(&ident)));
+        }
+        if ident == "Some" {
+            let value = self.value()?;
+            self.eat(b',');
+            self.expect(b')')?;
+            return Ok(value);
+        }
+        self.fields(Some(to_kebab(&ident)))
+    }
+
+    fn number(&mut self) -> Result<SceneValue, SceneError> {
+        let start = self.pos;
+        if let Some(b'-' | b'+') = self.src.get(self.pos) {
+            self.pos += 1;
+        }
+        if self.src[self.pos..].starts_with(b"inf") {
+            self.pos += 3;
+            let negative = self.src[start] == b'-';
+            return Ok(SceneValue::Number(if negative {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }));
+        }
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'_' | b'0'..=b'9') =
+            self.src.get(self.pos)
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.src[start..self.pos])
+            .ok()
+            .and_then(|text| text.replace('_', "").parse().ok())
+            .map(SceneValue::Number)
+            .ok_or_else(|| self.error())
+    }
+
+    /// Reads the character after a `\`, at the current position, leaving
+    /// the position on its last byte.
+    fn escape(&mut self) -> Result<char, SceneError> {
+        let c = match self.src.get(self.pos) {
+            Some(b'"') => '"',
+            Some(b'\'') => '\'',
+            Some(b'\\') => '\\',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'0') => '\0',
+            // `\u{XXXX}`
+            Some(b'u') if self.src.get(self.pos + 1) == Some(&b'{') => {
+                let start = self.pos + 2;
+                let len = self.src[start..].iter().position(|&b| b == b'}');
+                let code = len
+                    .and_then(|len| std::str::from_utf8(&self.src[start..start + len]).ok())
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(char::from_u32);
+                match (code, len) {
+                    (Some(c), Some(len)) => {
+                        self.pos = start + len;
+                        c
+                    }
+                    _ => return Err(self.error()),
+                }
+            }
+            _ => return Err(self.error()),
+        };
+        Ok(c)
+    }
+
+    fn string(&mut self) -> Result<String, SceneError> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.src.get(self.pos) {
+                None => return Err(self.error()),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(self.escape()?.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(&b) => bytes.push(b),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    /// Reads `r"..."`, or `r#"..."#` with any number of `#`.
+    fn raw_string(&mut self) -> Result<String, SceneError> {
+        self.pos += 1;
+        let hashes = self.src[self.pos..]
+            .iter()
+            .take_while(|&&b| b == b'#')
+            .count();
+        self.pos += hashes + 1;
+        if self.src.get(self.pos - 1) != Some(&b'"') {
+            return Err(self.error());
+        }
+        let mut end = vec![b'"'];
+        end.extend(std::iter::repeat_n(b'#', hashes));
+        let len = self.src[self.pos..]
+            .windows(end.len())
+            .position(|w| w == end.as_slice())
+            .ok_or_else(|| self.error())?;
+        let text = std::str::from_utf8(&self.src[self.pos..self.pos + len])
+            .map_err(|_| self.error())?
+            .to_string();
+        self.pos += len + end.len();
+        Ok(text)
+    }
+
+    fn char(&mut self) -> Result<char, SceneError> {
+        self.pos += 1;
+        let c = match self.src.get(self.pos) {
+            Some(b'\\') => {
+                self.pos += 1;
+                self.escape()?
+            }
+            _ => {
+                // The source is a `str`, the position is at a character.
+                let rest = std::str::from_utf8(&self.src[self.pos..]).map_err(|_| self.error())?;
+                let c = rest.chars().next().ok_or_else(|| self.error())?;
+                self.pos += c.len_utf8() - 1;
+                c
+            }
+        };
+        if self.src.get(self.pos + 1) != Some(&b'\'') {
+            return Err(self.error());
+        }
+        self.pos += 2;
+        Ok(c)
+    }
+}
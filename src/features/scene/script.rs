@@ -0,0 +1,330 @@
+//! Scripts building scenes procedurally, with the `scripting` feature: the
+//! loops and the random numbers of a script place what a scene file would
+//! have to list one by one.
+//!
+//! ```text
+//! // 500 random spheres on a floor.
+//! seed(7);
+//! light(point_light(point(-10, 10, -10), color(1, 1, 1)));
+//! add(plane().material(matte(color(0.5, 0.5, 0.5))));
+//! for i in 0..500 {
+//!     let r = rand(0.1, 0.3);
+//!     add(sphere().radius(r).at(rand(-10, 10), r, rand(0, 20)).color(color(rand(), rand(), rand())));
+//! }
+//! look(point(0, 1.5, -5), point(0, 1, 0));
+//! ```
+//!
+//! The scripts are written in [rhai](https://rhai.rs), with its statements,
+//! operators and math functions, the constant `PI`, and:
+//!
+//! - the functions of [`super::builder`], with `point`, `vector` and
+//!   `color` taking three numbers, integers or not;
+//! - `rand()` in `[0, 1)` or `rand(a, b)` in `[a, b)`, drawn from a
+//!   generator seeded by `seed(n)`, `0` by default;
+//! - the steps of [`ShapeBuilder`] as methods of the shapes, and the names
+//!   of their fields as methods of the materials, such as
+//!   `glass().reflective(0.9)`;
+//! - `add(shape)`, `light(light)`, `look(from, to)` and
+//!   `camera(width, height, field_of_view)` building the scene, which
+//!   starts as [`Scene::new`].
+//!
+//! A shape is moved where it is used: a variable holding one cannot be
+//! added twice.
+use std::{cell::RefCell, fs, mem, path::Path, rc::Rc};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope};
+
+use crate::{
+    features::{camera::Camera, colors::Color, lights::PointLight, materials::Material},
+    Float, Point3, Vector3,
+};
+
+use super::{
+    builder::{self, ShapeBuilder},
+    Scene, SceneError,
+};
+
+type ScriptResult<R> = Result<R, Box<EvalAltResult>>;
+/// A step of a shape taking an argument.
+type Step<T, A> = fn(ShapeBuilder<T>, A) -> ShapeBuilder<T>;
+/// The accessor of a field of a material.
+type Field<T> = fn(&mut Material<T>) -> &mut T;
+
+/// A shape of a script, taken out of it by the step or the function using
+/// it, as the shapes cannot be cloned.
+#[derive(Clone)]
+struct ScriptShape<T: Float>(Rc<RefCell<Option<ShapeBuilder<T>>>>);
+
+impl<T: Float> ScriptShape<T> {
+    fn new(shape: ShapeBuilder<T>) -> Self {
+        Self(Rc::new(RefCell::new(Some(shape))))
+    }
+
+    fn take(&self) -> ScriptResult<ShapeBuilder<T>> {
+        self.0
+            .borrow_mut()
+            .take()
+            .ok_or_else(|| "the shape was already used".into())
+    }
+
+    /// Returns the shape of the `step` applied to this one.
+    fn then(&self, step: impl FnOnce(ShapeBuilder<T>) -> ShapeBuilder<T>) -> ScriptResult<Self> {
+        Ok(Self::new(step(self.take()?)))
+    }
+}
+
+/// Returns the number held by `value`, an integer or not.
+fn number(value: &Dynamic) -> ScriptResult<f64> {
+    match value.as_int() {
+        Ok(n) => Ok(n as f64),
+        Err(_) => value
+            .as_float()
+            .map_err(|kind| format!("expected a number, found {kind}").into()),
+    }
+}
+
+fn scalar<T: Float>(value: &Dynamic) -> ScriptResult<T> {
+    number(value).map(T::from_f64)
+}
+
+fn triple<T: Float>(x: &Dynamic, y: &Dynamic, z: &Dynamic) -> ScriptResult<[T; 3]> {
+    Ok([scalar(x)?, scalar(y)?, scalar(z)?])
+}
+
+/// Registers the types of the scenes and the functions building their
+/// shapes, materials and lights.
+fn register_builder<T: Float>(engine: &mut Engine) {
+    engine
+        .register_type_with_name::<Point3<T>>("point")
+        .register_type_with_name::<Vector3<T>>("vector")
+        .register_type_with_name::<Color<T>>("color")
+        .register_type_with_name::<Material<T>>("material")
+        .register_type_with_name::<PointLight<T>>("light")
+        .register_type_with_name::<ScriptShape<T>>("shape");
+    engine
+        .register_fn("point", |x: Dynamic, y: Dynamic, z: Dynamic| {
+            triple::<T>(&x, &y, &z).map(|[x, y, z]| Point3::new(x, y, z))
+        })
+        .register_fn("vector", |x: Dynamic, y: Dynamic, z: Dynamic| {
+            triple::<T>(&x, &y, &z).map(|[x, y, z]| Vector3::new(x, y, z))
+        })
+        .register_fn("color", |r: Dynamic, g: Dynamic, b: Dynamic| {
+            triple::<T>(&r, &g, &b).map(|[r, g, b]| Color::new(r, g, b))
+        });
+
+    engine
+        .register_fn("sphere", || ScriptShape::new(builder::sphere::<T>()))
+        .register_fn("plane", || ScriptShape::new(builder::plane::<T>()))
+        .register_fn(
+            "cylinder",
+            |min: Dynamic, max: Dynamic, closed: bool| -> ScriptResult<_> {
+                Ok(ScriptShape::new(builder::cylinder::<T>(
+                    scalar(&min)?,
+                    scalar(&max)?,
+                    closed,
+                )))
+            },
+        )
+        .register_fn("disk", |radius: Dynamic| -> ScriptResult<_> {
+            Ok(ScriptShape::new(builder::disk::<T>(scalar(&radius)?)))
+        })
+        .register_fn(
+            "torus",
+            |major: Dynamic, minor: Dynamic| -> ScriptResult<_> {
+                Ok(ScriptShape::new(builder::torus::<T>(
+                    scalar(&major)?,
+                    scalar(&minor)?,
+                )))
+            },
+        )
+        .register_fn("group", |children: Array| -> ScriptResult<_> {
+            let children = children
+                .into_iter()
+                .map(|child| match child.try_cast::<ScriptShape<T>>() {
+                    Some(shape) => shape.take(),
+                    None => Err("a group holds shapes only".into()),
+                })
+                .collect::<ScriptResult<Vec<_>>>()?;
+            Ok(ScriptShape::new(builder::group(children)))
+        });
+    let operations: [(&str, Step<T, ShapeBuilder<T>>); 3] = [
+        ("union", builder::union),
+        ("intersection", builder::intersection),
+        ("difference", builder::difference),
+    ];
+    for (name, operation) in operations {
+        engine.register_fn(
+            name,
+            move |left: ScriptShape<T>, right: ScriptShape<T>| -> ScriptResult<_> {
+                Ok(ScriptShape::new(operation(left.take()?, right.take()?)))
+            },
+        );
+    }
+
+    engine
+        .register_fn("radius", |s: ScriptShape<T>, radius: Dynamic| {
+            let radius = scalar(&radius)?;
+            s.then(|s| s.radius(radius))
+        })
+        .register_fn(
+            "scale",
+            |s: ScriptShape<T>, x: Dynamic, y: Dynamic, z: Dynamic| {
+                let [x, y, z] = triple(&x, &y, &z)?;
+                s.then(|s| s.scale(x, y, z))
+            },
+        )
+        .register_fn(
+            "at",
+            |s: ScriptShape<T>, x: Dynamic, y: Dynamic, z: Dynamic| {
+                let [x, y, z] = triple(&x, &y, &z)?;
+                s.then(|s| s.at(x, y, z))
+            },
+        )
+        .register_fn("material", |s: ScriptShape<T>, material: Material<T>| {
+            s.then(|s| s.material(material))
+        })
+        .register_fn("color", |s: ScriptShape<T>, color: Color<T>| {
+            s.then(|s| s.color(color))
+        })
+        .register_fn("no_shadow", |s: ScriptShape<T>| {
+            s.then(ShapeBuilder::no_shadow)
+        });
+    let rotations: [(&str, Step<T, T>); 3] = [
+        ("rotate_x", ShapeBuilder::rotate_x),
+        ("rotate_y", ShapeBuilder::rotate_y),
+        ("rotate_z", ShapeBuilder::rotate_z),
+    ];
+    for (name, rotation) in rotations {
+        engine.register_fn(name, move |s: ScriptShape<T>, angle: Dynamic| {
+            let angle = scalar(&angle)?;
+            s.then(|s| rotation(s, angle))
+        });
+    }
+
+    engine
+        .register_fn("glass", builder::glass::<T>)
+        .register_fn("mirror", builder::mirror::<T>)
+        .register_fn("matte", builder::matte::<T>)
+        .register_fn("metal", builder::metal::<T>)
+        .register_fn("color", |m: Material<T>, color: Color<T>| {
+            m.with_color(color)
+        })
+        .register_fn("point_light", builder::point_light::<T>);
+    let fields: [(&str, Field<T>); 7] = [
+        ("ambient", |m| &mut m.ambient),
+        ("diffuse", |m| &mut m.diffuse),
+        ("specular", |m| &mut m.specular),
+        ("shininess", |m| &mut m.shininess),
+        ("reflective", |m| &mut m.reflective),
+        ("transparency", |m| &mut m.transparency),
+        ("refractive_index", |m| &mut m.refractive_index),
+    ];
+    for (name, field) in fields {
+        engine.register_fn(name, move |mut m: Material<T>, value: Dynamic| {
+            *field(&mut m) = scalar(&value)?;
+            ScriptResult::Ok(m)
+        });
+    }
+}
+
+/// Registers the functions drawing random numbers from `rng`.
+fn register_random(engine: &mut Engine, rng: &Rc<RefCell<SmallRng>>) {
+    let (uniform, range, seed) = (rng.clone(), rng.clone(), rng.clone());
+    engine
+        .register_fn("rand", move || uniform.borrow_mut().gen::<f64>())
+        .register_fn("rand", move |a: Dynamic, b: Dynamic| -> ScriptResult<_> {
+            let (a, b) = (number(&a)?, number(&b)?);
+            Ok(a + (b - a) * range.borrow_mut().gen::<f64>())
+        })
+        .register_fn("seed", move |n: Dynamic| -> ScriptResult<_> {
+            *seed.borrow_mut() = SmallRng::seed_from_u64(number(&n)? as u64);
+            Ok(())
+        });
+}
+
+/// Registers the functions adding to `scene`.
+fn register_scene<T: Float>(engine: &mut Engine, scene: &Rc<RefCell<Scene<T>>>) {
+    // The steps of the scene take it by value.
+    let update = |scene: &Rc<RefCell<Scene<T>>>| {
+        let scene = scene.clone();
+        move |step: Box<dyn FnOnce(Scene<T>) -> Scene<T>>| {
+            let current = mem::take(&mut *scene.borrow_mut());
+            *scene.borrow_mut() = step(current);
+        }
+    };
+    let (add, light, look, camera) = (update(scene), update(scene), update(scene), update(scene));
+    engine
+        .register_fn("add", move |shape: ScriptShape<T>| -> ScriptResult<_> {
+            let shape = shape.take()?;
+            add(Box::new(|scene| scene.add(shape)));
+            Ok(())
+        })
+        .register_fn("light", move |l: PointLight<T>| {
+            light(Box::new(move |scene| scene.light(l)));
+        })
+        .register_fn("look", move |from: Point3<T>, to: Point3<T>| {
+            look(Box::new(move |scene| scene.look(from, to)));
+        })
+        .register_fn(
+            "camera",
+            move |width: Dynamic, height: Dynamic, field_of_view: Dynamic| -> ScriptResult<_> {
+                let (width, height) = (number(&width)?, number(&height)?);
+                if width < 1.0 || height < 1.0 {
+                    return Err("the camera needs at least one pixel".into());
+                }
+                let field_of_view = scalar(&field_of_view)?;
+                camera(Box::new(move |scene| {
+                    let transform = scene.camera.transform();
+                    scene.camera(
+                        Camera::new(width as usize, height as usize, field_of_view)
+                            .with_transform(transform),
+                    )
+                }));
+                Ok(())
+            },
+        );
+}
+
+impl<T: Float> Scene<T> {
+    /// Builds the scene of the script `src`, see [`super::script`].
+    /// # Errors
+    ///
+    /// This function will return an error if `src` is not a valid script,
+    /// or fails while running, its message giving the line.
+    pub fn from_script(src: &str) -> Result<Self, SceneError> {
+        let scene = Rc::new(RefCell::new(Scene::new()));
+        let mut engine = Engine::new();
+        register_builder::<T>(&mut engine);
+        register_random(
+            &mut engine,
+            &Rc::new(RefCell::new(SmallRng::seed_from_u64(0))),
+        );
+        register_scene(&mut engine, &scene);
+        let ast = engine.compile(src).map_err(|e| SceneError::Syntax {
+            line: e.position().line().unwrap_or(0),
+        })?;
+        let mut scope = Scope::new();
+        scope.push_constant("PI", std::f64::consts::PI);
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|mut e| {
+                let line = e.take_position().line().unwrap_or(0);
+                let message = match *e {
+                    EvalAltResult::ErrorRuntime(message, _) => message.to_string(),
+                    e => e.to_string(),
+                };
+                SceneError::Invalid(format!("line {line}: {message}"))
+            })?;
+        Ok(scene.take())
+    }
+
+    /// Reads and runs the script file at `path`, see [`Scene::from_script`].
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read, or if
+    /// the script is not valid.
+    pub fn from_script_file(path: impl AsRef<Path>) -> Result<Self, SceneError> {
+        Self::from_script(&fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,134 @@
+//! Implementation of `SceneValue`, the data of a scene file before it is
+//! interpreted, whatever its format.
+use crate::Float;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A value of a scene file: the data model shared by the formats.
+pub enum SceneValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<SceneValue>),
+    /// The keys and values of an object, in the order they are written.
+    Object(Vec<(String, SceneValue)>),
+}
+
+impl From<bool> for SceneValue {
+    fn from(b: bool) -> Self {
+        SceneValue::Bool(b)
+    }
+}
+
+impl From<&str> for SceneValue {
+    fn from(s: &str) -> Self {
+        SceneValue::String(s.to_string())
+    }
+}
+
+impl SceneValue {
+    pub fn number<T: Float>(v: T) -> Self {
+        SceneValue::Number(v.to_f64().unwrap_or(f64::NAN))
+    }
+
+    /// Returns the list of `values`, such as the coordinates of a point.
+    pub fn numbers<T: Float>(values: impl IntoIterator<Item = T>) -> Self {
+        SceneValue::Array(values.into_iter().map(Self::number).collect())
+    }
+
+    pub fn object<'a>(entries: impl IntoIterator<Item = (&'a str, SceneValue)>) -> Self {
+        SceneValue::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// Appends the entry `key` to this value, if it is an object.
+    pub fn push(&mut self, key: &str, value: SceneValue) {
+        if let SceneValue::Object(entries) = self {
+            entries.push((key.to_string(), value));
+        }
+    }
+
+    /// Returns the value of `key` if this is an object holding it. When a
+    /// key is written several times, the last value wins.
+    pub fn get(&self, key: &str) -> Option<&SceneValue> {
+        self.as_object()?
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SceneValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SceneValue::Number(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SceneValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[SceneValue]> {
+        match self {
+            SceneValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, SceneValue)]> {
+        match self {
+            SceneValue::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Returns this value extended by `other`: the keys of two objects are
+    /// merged, those of `other` winning, except for the `transform` lists
+    /// which are appended like any two lists. Otherwise `other` replaces
+    /// this value.
+    pub fn extended(&self, other: &SceneValue) -> SceneValue {
+        match (self, other) {
+            (SceneValue::Object(base), SceneValue::Object(entries)) => {
+                let mut merged = base.clone();
+                for (key, value) in entries {
+                    match merged.iter_mut().find(|(k, _)| k == key) {
+                        Some((_, old)) if key == "transform" => *old = old.extended(value),
+                        Some((_, old)) => *old = value.clone(),
+                        None => merged.push((key.clone(), value.clone())),
+                    }
+                }
+                SceneValue::Object(merged)
+            }
+            (SceneValue::Array(base), SceneValue::Array(values)) => {
+                SceneValue::Array(base.iter().chain(values).cloned().collect())
+            }
+            _ => other.clone(),
+        }
+    }
+
+    /// Returns this object without `key`, or this value if it is not an
+    /// object.
+    pub fn without(&self, key: &str) -> SceneValue {
+        match self {
+            SceneValue::Object(entries) => {
+                SceneValue::Object(entries.iter().filter(|(k, _)| k != key).cloned().collect())
+            }
+            _ => self.clone(),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Implementation of `RenderSettings`, the knobs of a render gathered in
+//! one place instead of constants spread over the modules.
+use std::sync::Arc;
+
+use crate::{
+    features::{
+        canvas::compositing::Overlay,
+        integrators::{PathTracer, Whitted},
+        world::MAX_DEPTH,
+    },
+    EPSILON,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// The settings of a render. A [`Camera`](crate::Camera) takes them with
+/// [`Camera::with_settings`](crate::Camera::with_settings) and renders
+/// with them; [`World::apply_settings`](crate::World::apply_settings)
+/// takes the `epsilon`.
+///
+/// The lights are points, whose shadows are found by a single ray, so
+/// there is no number of shadow samples.
+pub struct RenderSettings {
+    /// The number of bounces followed by the secondary rays.
+    pub max_depth: usize,
+    /// The number of rays averaged for each pixel by the default
+    /// integrators, see [`RenderSettings::whitted`] and
+    /// [`RenderSettings::path_tracer`].
+    pub samples_per_pixel: usize,
+    /// The number of threads rendering the tiles, the calling one included.
+    pub threads: usize,
+    /// The offset of the points tested for shadows and casting the
+    /// secondary rays off the surfaces, against self-intersections.
+    pub epsilon: f64,
+    /// The brightest component allowed to a single sample, if any; rare
+    /// bright samples are scaled down to it, keeping their hue.
+    pub clamp: Option<f64>,
+    /// The seed from which the random numbers of every pixel are derived:
+    /// the jitter, the lens, the shutter, the paths and the media. The same
+    /// settings render the same image, to the bit, whatever the threads.
+    pub seed: u64,
+    /// The image stamped onto the pixels once rendered, if any, such as a
+    /// logo; shared, as the settings are cloned with the cameras.
+    pub overlay: Option<Arc<Overlay<f64>>>,
+}
+
+impl Default for RenderSettings {
+    /// The settings of the book: one ray per pixel, [`MAX_DEPTH`] bounces,
+    /// [`EPSILON`], no clamp and no overlay, on every available core.
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_DEPTH,
+            samples_per_pixel: 1,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            epsilon: EPSILON,
+            clamp: None,
+            seed: 0,
+            overlay: None,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Returns the settings rendering on the calling thread only.
+    pub fn single_threaded() -> Self {
+        Self {
+            threads: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the [`Whitted`] integrator with these bounces and samples.
+    pub fn whitted(&self) -> Whitted {
+        Whitted {
+            max_depth: self.max_depth,
+            samples_per_pixel: self.samples_per_pixel,
+        }
+    }
+
+    /// Returns the [`PathTracer`] with these bounces, samples and clamp.
+    pub fn path_tracer(&self) -> PathTracer {
+        PathTracer {
+            samples_per_pixel: self.samples_per_pixel,
+            max_depth: self.max_depth,
+            max_radiance: self.clamp,
+            ..PathTracer::default()
+        }
+    }
+}
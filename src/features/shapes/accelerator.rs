@@ -0,0 +1,75 @@
+//! The spatial structures letting a ray skip most of the children of a
+//! group, the faces of a mesh or the objects of a world. Which one is
+//! faster depends on the scene: a [`Bvh`] suits scattered objects, a
+//! [`KdTree`] the large overlapping primitives of architectural scenes.
+use crate::{features::rays::Ray, Float};
+
+use super::{bounds::BoundingBox, bvh::Bvh, kd_tree::KdTree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The kind of spatial structure built over the primitives.
+pub enum AcceleratorKind {
+    /// A bounding volume hierarchy, see [`Bvh`].
+    #[default]
+    Bvh,
+    /// A kd-tree, see [`KdTree`].
+    KdTree,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A spatial structure over primitives given by their bounds.
+pub enum Accelerator<T: Float> {
+    Bvh(Bvh<T>),
+    KdTree(KdTree<T>),
+}
+
+impl<T: Float> Accelerator<T> {
+    /// Returns the structure of the given `kind` over the primitives whose
+    /// boxes are `bounds`, with about `leaf_size` primitives per leaf.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build(kind: AcceleratorKind, bounds: &[BoundingBox<T>], leaf_size: usize) -> Self {
+        match kind {
+            AcceleratorKind::Bvh => Accelerator::Bvh(Bvh::build(bounds, leaf_size)),
+            AcceleratorKind::KdTree => Accelerator::KdTree(KdTree::build(bounds, leaf_size)),
+        }
+    }
+
+    pub fn kind(&self) -> AcceleratorKind {
+        match self {
+            Accelerator::Bvh(_) => AcceleratorKind::Bvh,
+            Accelerator::KdTree(_) => AcceleratorKind::KdTree,
+        }
+    }
+
+    /// Returns the number of primitives in this structure.
+    pub fn len(&self) -> usize {
+        match self {
+            Accelerator::Bvh(bvh) => bvh.len(),
+            Accelerator::KdTree(tree) => tree.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the boxes splitting the space: the boxes of the nodes of a
+    /// [`Bvh`], or the cells of the leaves of a [`KdTree`].
+    pub fn boxes(&self) -> Vec<BoundingBox<T>> {
+        match self {
+            Accelerator::Bvh(bvh) => bvh.boxes().collect(),
+            Accelerator::KdTree(tree) => tree.cells(),
+        }
+    }
+
+    /// Calls `f` once with every primitive which `ray` may hit, until it
+    /// returns `true`. Returns `true` if the visit was stopped.
+    pub fn visit(&self, ray: &Ray<T>, f: impl FnMut(usize) -> bool) -> bool {
+        match self {
+            Accelerator::Bvh(bvh) => bvh.visit(ray, f),
+            Accelerator::KdTree(tree) => tree.visit(ray, f),
+        }
+    }
+}
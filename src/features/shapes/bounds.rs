@@ -0,0 +1,206 @@
+//! Implementation of `BoundingBox`, the axis-aligned box enclosing a shape.
+//! Testing a ray against the box of a group is much cheaper than against
+//! all of its children, and lets whole subtrees be skipped.
+use crate::{
+    features::rays::{Ray, RayPacket, PACKET_SIZE},
+    Float, Matrix4, Point3, EPSILON,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An axis-aligned box between the corners `min` and `max`.
+/// The bounds may be infinite, as for planes.
+pub struct BoundingBox<T: Float> {
+    min: Point3<T>,
+    max: Point3<T>,
+}
+
+impl<T: Float> Default for BoundingBox<T> {
+    /// An empty box, which contains nothing and grows with the first point added.
+    fn default() -> Self {
+        Self {
+            min: Point3::new(T::infinity(), T::infinity(), T::infinity()),
+            max: Point3::new(T::neg_infinity(), T::neg_infinity(), T::neg_infinity()),
+        }
+    }
+}
+
+impl<T: Float> BoundingBox<T> {
+    pub fn new(min: Point3<T>, max: Point3<T>) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the box covering the whole space.
+    pub fn infinite() -> Self {
+        Self::new(
+            Point3::new(T::neg_infinity(), T::neg_infinity(), T::neg_infinity()),
+            Point3::new(T::infinity(), T::infinity(), T::infinity()),
+        )
+    }
+
+    /// Returns the smallest box containing all `points`.
+    pub fn from_points(points: impl IntoIterator<Item = Point3<T>>) -> Self {
+        let mut bounds = Self::default();
+        for p in points {
+            bounds.add_point(p);
+        }
+        bounds
+    }
+
+    pub fn min(&self) -> Point3<T> {
+        self.min
+    }
+
+    pub fn max(&self) -> Point3<T> {
+        self.max
+    }
+
+    /// Returns `true` if nothing has been added to this box.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+
+    /// Returns `true` if the box is bounded along all axes.
+    pub fn is_finite(&self) -> bool {
+        (0..3).all(|i| self.min[i].is_finite() && self.max[i].is_finite())
+    }
+
+    /// Returns the area of the faces of this box, zero if it is empty.
+    pub fn surface_area(&self) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        let d = self.max - self.min;
+        T::two() * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// Returns the center of this box.
+    pub fn centroid(&self) -> Point3<T> {
+        self.min + (self.max - self.min) / T::two()
+    }
+
+    /// Grows this box to contain `point`.
+    pub fn add_point(&mut self, point: Point3<T>) {
+        for i in 0..3 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+    }
+
+    /// Grows this box to contain `other`.
+    pub fn merge(&mut self, other: &Self) {
+        if !other.is_empty() {
+            self.add_point(other.min);
+            self.add_point(other.max);
+        }
+    }
+
+    /// Returns `true` if `point` is inside this box, boundaries included.
+    pub fn contains_point(&self, point: Point3<T>) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Returns `true` if `other` is entirely inside this box.
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Returns the box containing this box transformed by `m`, by
+    /// transforming its eight corners. An unbounded box stays unbounded.
+    pub fn transform(&self, m: &Matrix4<T>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        if !self.is_finite() {
+            return Self::infinite();
+        }
+        let (min, max) = (self.min, self.max);
+        Self::from_points((0..8).map(|corner| {
+            let pick = |axis: usize| {
+                if corner & (1 << axis) == 0 {
+                    min[axis]
+                } else {
+                    max[axis]
+                }
+            };
+            *m * Point3::new(pick(0), pick(1), pick(2))
+        }))
+    }
+
+    /// Splits this box in two halves across its longest axis.
+    pub fn split(&self) -> (Self, Self) {
+        let extent = |i: usize| self.max[i] - self.min[i];
+        let axis = (1..3).fold(0, |longest, i| {
+            if extent(i) > extent(longest) {
+                i
+            } else {
+                longest
+            }
+        });
+        let middle = self.min[axis] + extent(axis) / T::two();
+        let (mut left_max, mut right_min) = (self.max, self.min);
+        left_max[axis] = middle;
+        right_min[axis] = middle;
+        (
+            Self::new(self.min, left_max),
+            Self::new(right_min, self.max),
+        )
+    }
+
+    /// Returns `true` if the ray hits this box, by the slab method.
+    pub fn intersects(&self, ray: &Ray<T>) -> bool {
+        self.intersection_range(ray).is_some()
+    }
+
+    /// Returns the range of `t` over which the ray lies within this box,
+    /// by the slab method, or `None` if it misses the box.
+    pub fn intersection_range(&self, ray: &Ray<T>) -> Option<(T, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (origin, direction) = (ray.origin(), ray.direction());
+        let (mut t_min, mut t_max) = (T::neg_infinity(), T::infinity());
+        for i in 0..3 {
+            if direction[i].abs() < T::from_f64(EPSILON) {
+                // Parallel to the slab: the ray must start within it.
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let t0 = (self.min[i] - origin[i]) / direction[i];
+            let t1 = (self.max[i] - origin[i]) / direction[i];
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+        (t_min <= t_max).then_some((t_min, t_max))
+    }
+
+    /// Returns, for each ray of `packet`, `true` if it hits this box. The
+    /// lanes beyond the rays of the packet are `false`.
+    pub fn intersects_packet(&self, packet: &RayPacket<T>) -> [bool; PACKET_SIZE] {
+        let mut hits = [false; PACKET_SIZE];
+        if self.is_empty() {
+            return hits;
+        }
+        let mut t_min = [T::neg_infinity(); PACKET_SIZE];
+        let mut t_max = [T::infinity(); PACKET_SIZE];
+        let mut inside = [true; PACKET_SIZE];
+        for i in 0..3 {
+            let (origins, directions) = (packet.origins(i), packet.directions(i));
+            for (lane, (&origin, &direction)) in origins.iter().zip(directions).enumerate() {
+                if direction.abs() < T::from_f64(EPSILON) {
+                    inside[lane] &= origin >= self.min[i] && origin <= self.max[i];
+                    continue;
+                }
+                let t0 = (self.min[i] - origin) / direction;
+                let t1 = (self.max[i] - origin) / direction;
+                t_min[lane] = t_min[lane].max(t0.min(t1));
+                t_max[lane] = t_max[lane].min(t0.max(t1));
+            }
+        }
+        for (lane, hit) in hits.iter_mut().enumerate().take(packet.len()) {
+            *hit = inside[lane] && t_min[lane] <= t_max[lane];
+        }
+        hits
+    }
+}
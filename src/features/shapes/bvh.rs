@@ -0,0 +1,302 @@
+//! Implementation of `Bvh`, a bounding volume hierarchy over the primitives
+//! of a group or a mesh. A ray only tests the primitives of the leaves
+//! whose boxes it hits.
+//!
+//! The tree is built with the surface area heuristic: a set of primitives
+//! is split where the expected cost of a ray through both halves, the area
+//! of each half times the number of its primitives, is the lowest. The
+//! candidate splits are the boundaries of 12 bins along each axis.
+//!
+//! Once a set is split, its halves are independent: the large ones are
+//! built on their own threads, the tree being the same whatever their
+//! number.
+use crate::{features::rays::Ray, Float};
+
+use super::bounds::BoundingBox;
+
+/// The number of bins the centroids are sorted into along an axis.
+const BINS: usize = 12;
+/// The cost of testing a box relative to the one of testing a primitive.
+const TRAVERSAL_COST: f64 = 0.125;
+/// The number of primitives below which a subtree is not worth a thread.
+const PARALLEL_THRESHOLD: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A node of the flattened tree. The left child of an interior node
+/// follows it, the right child is at `right`.
+struct BvhNode<T: Float> {
+    bounds: BoundingBox<T>,
+    /// The first primitive of a leaf in `Bvh::indices`.
+    first: usize,
+    /// The number of primitives of a leaf, zero for an interior node.
+    count: usize,
+    right: usize,
+}
+
+/// A node of the tree while it is being built.
+enum BuildNode<T: Float> {
+    Leaf {
+        bounds: BoundingBox<T>,
+        first: usize,
+        count: usize,
+    },
+    Interior {
+        bounds: BoundingBox<T>,
+        children: Box<[BuildNode<T>; 2]>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A bounding volume hierarchy over primitives given by their bounds.
+pub struct Bvh<T: Float> {
+    nodes: Vec<BvhNode<T>>,
+    /// The primitives, ordered so that each leaf holds a contiguous range.
+    indices: Vec<usize>,
+    /// The primitives without finite bounds, such as planes, tested by
+    /// every ray.
+    unbounded: Vec<usize>,
+}
+
+impl<T: Float> Bvh<T> {
+    /// Returns the hierarchy over the primitives whose boxes are `bounds`,
+    /// with at most `leaf_size` primitives per leaf unless their centroids
+    /// coincide. Leaves smaller than `leaf_size` are kept when splitting
+    /// them would not pay off. The primitives with empty bounds are left
+    /// out, no ray hits them.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build(bounds: &[BoundingBox<T>], leaf_size: usize) -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::build_with_threads(bounds, leaf_size, threads)
+    }
+
+    /// Returns the same hierarchy as [`build`](Self::build), built on at
+    /// most `threads` threads, the calling one included.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build_with_threads(bounds: &[BoundingBox<T>], leaf_size: usize, threads: usize) -> Self {
+        assert!(leaf_size > 0, "The leaves cannot be empty.");
+        let (mut indices, mut unbounded) = (Vec::new(), Vec::new());
+        for (i, b) in bounds.iter().enumerate() {
+            if b.is_empty() {
+                continue;
+            }
+            if b.is_finite() {
+                indices.push(i);
+            } else {
+                unbounded.push(i);
+            }
+        }
+        let mut nodes = Vec::new();
+        if !indices.is_empty() {
+            let root = build_node(bounds, &mut indices, 0, leaf_size, threads.max(1));
+            flatten(root, &mut nodes);
+        }
+        Self {
+            nodes,
+            indices,
+            unbounded,
+        }
+    }
+
+    /// Returns the number of primitives in this hierarchy.
+    pub fn len(&self) -> usize {
+        self.indices.len() + self.unbounded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of nodes of the tree.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the box enclosing the primitives with finite bounds.
+    pub fn bounds(&self) -> BoundingBox<T> {
+        self.nodes.first().map(|n| n.bounds).unwrap_or_default()
+    }
+
+    /// Returns the box of every node, the root first and each node before
+    /// its children.
+    pub fn boxes(&self) -> impl Iterator<Item = BoundingBox<T>> + '_ {
+        self.nodes.iter().map(|node| node.bounds)
+    }
+
+    /// Returns the primitives of each leaf, from left to right.
+    pub fn leaves(&self) -> impl Iterator<Item = &[usize]> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| node.count > 0)
+            .map(|node| &self.indices[node.first..node.first + node.count])
+    }
+
+    /// Calls `f` with every primitive which `ray` may hit: the ones of the
+    /// leaves whose boxes it hits, and the unbounded ones. The visit stops
+    /// as soon as `f` returns `true`, and so does this function.
+    pub fn visit(&self, ray: &Ray<T>, mut f: impl FnMut(usize) -> bool) -> bool {
+        self.unbounded.iter().any(|&i| f(i))
+            || (!self.nodes.is_empty() && self.visit_node(0, ray, &mut f))
+    }
+
+    fn visit_node(&self, index: usize, ray: &Ray<T>, f: &mut impl FnMut(usize) -> bool) -> bool {
+        let node = &self.nodes[index];
+        if !node.bounds.intersects(ray) {
+            return false;
+        }
+        if node.count > 0 {
+            return self.indices[node.first..node.first + node.count]
+                .iter()
+                .any(|&i| f(i));
+        }
+        self.visit_node(index + 1, ray, f) || self.visit_node(node.right, ray, f)
+    }
+}
+
+/// Builds the subtree over `indices`, which start at `first` in the
+/// final order of the primitives, on at most `threads` threads.
+fn build_node<T: Float>(
+    bounds: &[BoundingBox<T>],
+    indices: &mut [usize],
+    first: usize,
+    leaf_size: usize,
+    threads: usize,
+) -> BuildNode<T> {
+    let mut node_bounds = BoundingBox::default();
+    for &i in indices.iter() {
+        node_bounds.merge(&bounds[i]);
+    }
+    let Some(middle) = partition(bounds, indices, &node_bounds, leaf_size) else {
+        return BuildNode::Leaf {
+            bounds: node_bounds,
+            first,
+            count: indices.len(),
+        };
+    };
+    let (left, right) = indices.split_at_mut(middle);
+    let children = if threads > 1 && left.len().min(right.len()) >= PARALLEL_THRESHOLD {
+        // The left half on a new thread, the right one on this thread.
+        let left_threads = threads / 2;
+        std::thread::scope(|scope| {
+            let left = scope.spawn(|| build_node(bounds, left, first, leaf_size, left_threads));
+            let right = build_node(
+                bounds,
+                right,
+                first + middle,
+                leaf_size,
+                threads - left_threads,
+            );
+            [left.join().unwrap(), right]
+        })
+    } else {
+        [
+            build_node(bounds, left, first, leaf_size, threads),
+            build_node(bounds, right, first + middle, leaf_size, threads),
+        ]
+    };
+    BuildNode::Interior {
+        bounds: node_bounds,
+        children: Box::new(children),
+    }
+}
+
+/// Reorders `indices` into the two halves of the cheapest split by the
+/// surface area heuristic and returns the size of the first one, or
+/// `None` if the primitives are better left in a leaf.
+fn partition<T: Float>(
+    bounds: &[BoundingBox<T>],
+    indices: &mut [usize],
+    node_bounds: &BoundingBox<T>,
+    leaf_size: usize,
+) -> Option<usize> {
+    let count = indices.len();
+    if count <= 1 {
+        return None;
+    }
+    let centroids = BoundingBox::from_points(indices.iter().map(|&i| bounds[i].centroid()));
+    let bin_of = |i: usize, axis: usize| {
+        let (min, max) = (centroids.min()[axis], centroids.max()[axis]);
+        let offset = (bounds[i].centroid()[axis] - min) / (max - min);
+        (offset * T::from_f64(BINS as f64))
+            .to_usize()
+            .unwrap_or(0)
+            .min(BINS - 1)
+    };
+    // (cost, axis, first bin of the right half)
+    let mut best: Option<(T, usize, usize)> = None;
+    for axis in 0..3 {
+        if centroids.max()[axis] <= centroids.min()[axis] {
+            continue;
+        }
+        let mut bins = [(0, BoundingBox::default()); BINS];
+        for &i in indices.iter() {
+            let bin = &mut bins[bin_of(i, axis)];
+            bin.0 += 1;
+            bin.1.merge(&bounds[i]);
+        }
+        for split in 1..BINS {
+            let side = |bins: &[(usize, BoundingBox<T>)]| {
+                bins.iter()
+                    .fold((0, BoundingBox::default()), |(n, mut b), bin| {
+                        b.merge(&bin.1);
+                        (n + bin.0, b)
+                    })
+            };
+            let ((n_left, left), (n_right, right)) = (side(&bins[..split]), side(&bins[split..]));
+            if n_left == 0 || n_right == 0 {
+                continue;
+            }
+            let cost = left.surface_area() * T::from_f64(n_left as f64)
+                + right.surface_area() * T::from_f64(n_right as f64);
+            if best.is_none_or(|(best_cost, ..)| cost < best_cost) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+    let (cost, axis, split) = best?;
+    let area = node_bounds.surface_area();
+    let leaf_cost = area * T::from_f64(count as f64);
+    if count <= leaf_size && cost + area * T::from_f64(TRAVERSAL_COST) >= leaf_cost {
+        return None;
+    }
+    let mut middle = 0;
+    for j in 0..count {
+        if bin_of(indices[j], axis) < split {
+            indices.swap(j, middle);
+            middle += 1;
+        }
+    }
+    Some(middle)
+}
+
+/// Appends the subtree depth first to `nodes`.
+fn flatten<T: Float>(node: BuildNode<T>, nodes: &mut Vec<BvhNode<T>>) {
+    match node {
+        BuildNode::Leaf {
+            bounds,
+            first,
+            count,
+        } => nodes.push(BvhNode {
+            bounds,
+            first,
+            count,
+            right: 0,
+        }),
+        BuildNode::Interior { bounds, children } => {
+            let index = nodes.len();
+            nodes.push(BvhNode {
+                bounds,
+                first: 0,
+                count: 0,
+                right: 0,
+            });
+            let [left, right] = *children;
+            flatten(left, nodes);
+            nodes[index].right = nodes.len();
+            flatten(right, nodes);
+        }
+    }
+}
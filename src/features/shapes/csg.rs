@@ -0,0 +1,212 @@
+use std::cmp::Ordering;
+
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{shape_value, value::SceneValue, SceneError},
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{accelerator::AcceleratorKind, bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The boolean operation combining the two children of a [`Csg`].
+pub enum CsgOperation {
+    /// The points inside either child.
+    Union,
+    /// The points inside both children.
+    Intersection,
+    /// The points inside the left child but not the right one.
+    Difference,
+}
+
+impl CsgOperation {
+    /// Returns `true` if an intersection is part of the combined surface.
+    /// - `left_hit`: the intersection is with the left child.
+    /// - `in_left`: the intersection is inside the left child.
+    /// - `in_right`: the intersection is inside the right child.
+    pub fn intersection_allowed(&self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Constructive solid geometry: two shapes combined by a [`CsgOperation`].
+/// Like a [`super::group::Group`], the children are transformed relative to it.
+pub struct Csg<T: Float> {
+    base: ShapeBase<T>,
+    operation: CsgOperation,
+    left: Box<dyn Shape<T>>,
+    right: Box<dyn Shape<T>>,
+    /// Cached union of the bounds of the children, in object space.
+    bounds: BoundingBox<T>,
+}
+
+impl<T: Float> Csg<T> {
+    pub fn new(
+        operation: CsgOperation,
+        left: impl Shape<T> + 'static,
+        right: impl Shape<T> + 'static,
+    ) -> Self {
+        Self::from_boxed(operation, Box::new(left), Box::new(right))
+    }
+
+    /// Returns the combination of two shapes already boxed.
+    pub fn from_boxed(
+        operation: CsgOperation,
+        left: Box<dyn Shape<T>>,
+        right: Box<dyn Shape<T>>,
+    ) -> Self {
+        let mut bounds = left.parent_space_bounds();
+        bounds.merge(&right.parent_space_bounds());
+        Self {
+            base: ShapeBase::default(),
+            operation,
+            left,
+            right,
+            bounds,
+        }
+    }
+
+    pub fn union(left: impl Shape<T> + 'static, right: impl Shape<T> + 'static) -> Self {
+        Self::new(CsgOperation::Union, left, right)
+    }
+
+    pub fn intersection(left: impl Shape<T> + 'static, right: impl Shape<T> + 'static) -> Self {
+        Self::new(CsgOperation::Intersection, left, right)
+    }
+
+    pub fn difference(left: impl Shape<T> + 'static, right: impl Shape<T> + 'static) -> Self {
+        Self::new(CsgOperation::Difference, left, right)
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn left(&self) -> &dyn Shape<T> {
+        self.left.as_ref()
+    }
+
+    pub fn right(&self) -> &dyn Shape<T> {
+        self.right.as_ref()
+    }
+
+    /// Keeps the intersections, sorted by `t`, which belong to the combined surface.
+    /// Walking along the ray, each intersection toggles whether the ray is
+    /// inside the child it belongs to.
+    pub fn filter_intersections<'a>(
+        &self,
+        mut xs: Vec<Intersection<'a, T>>,
+    ) -> Vec<Intersection<'a, T>> {
+        self.retain_allowed(&mut xs, 0);
+        xs
+    }
+
+    /// Keeps, among the intersections of `xs` from `start` on, the ones
+    /// which belong to the combined surface, in place.
+    fn retain_allowed(&self, xs: &mut Vec<Intersection<'_, T>>, start: usize) {
+        let (mut in_left, mut in_right) = (false, false);
+        let mut kept = start;
+        for i in start..xs.len() {
+            let x = xs[i];
+            let left_hit = self.left.includes(x.object());
+            let allowed = self
+                .operation
+                .intersection_allowed(left_hit, in_left, in_right);
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+            if allowed {
+                xs[kept] = x;
+                kept += 1;
+            }
+        }
+        xs.truncate(kept);
+    }
+
+    /// Passes the world transformation down to the children.
+    fn update_children(&mut self) {
+        let world_transform = self.base.world_transform();
+        self.left.set_parent_transform(world_transform);
+        self.right.set_parent_transform(world_transform);
+    }
+}
+
+impl<T: Float> Shape<T> for Csg<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base.set_transform(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base.set_parent_transform(parent_transform);
+        self.update_children();
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    fn divide(&mut self, threshold: usize) {
+        self.left.divide(threshold);
+        self.right.divide(threshold);
+    }
+
+    fn build_accelerator(&mut self, kind: AcceleratorKind, leaf_size: usize) {
+        self.left.build_accelerator(kind, leaf_size);
+        self.right.build_accelerator(kind, leaf_size);
+    }
+
+    fn includes(&self, other: &dyn Shape<T>) -> bool {
+        std::ptr::addr_eq(self, other) || self.left.includes(other) || self.right.includes(other)
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        if !self.bounds.intersects(local_ray) {
+            return;
+        }
+        let start = xs.len();
+        self.left.append_intersections(local_ray, xs);
+        self.right.append_intersections(local_ray, xs);
+        xs[start..].sort_by(|a, b| a.t().partial_cmp(&b.t()).unwrap_or(Ordering::Equal));
+        self.retain_allowed(xs, start);
+    }
+
+    /// # Panics
+    ///
+    /// The intersections name the children, the normal must be asked to them.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        panic!("The normal of a CSG shape is given by its children.")
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        let operation = match self.operation {
+            CsgOperation::Union => "union",
+            CsgOperation::Intersection => "intersection",
+            CsgOperation::Difference => "difference",
+        };
+        Ok(SceneValue::object([
+            ("add", "csg".into()),
+            ("operation", operation.into()),
+            ("left", shape_value(self.left.as_ref())?),
+            ("right", shape_value(self.right.as_ref())?),
+        ]))
+    }
+}
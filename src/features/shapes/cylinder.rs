@@ -0,0 +1,149 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Point3, Vector3, EPSILON,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A cylinder of radius one around the y axis of its object space.
+/// It can be truncated between `minimum` and `maximum` (both excluded),
+/// and a truncated cylinder can be `closed` by caps at both ends.
+pub struct Cylinder<T: Float> {
+    base: ShapeBase<T>,
+    minimum: T,
+    maximum: T,
+    closed: bool,
+}
+
+impl<T: Float> Default for Cylinder<T> {
+    /// An infinite cylinder.
+    fn default() -> Self {
+        Self {
+            base: ShapeBase::default(),
+            minimum: T::neg_infinity(),
+            maximum: T::infinity(),
+            closed: false,
+        }
+    }
+}
+
+impl<T: Float> Cylinder<T> {
+    /// Returns a cylinder truncated between `minimum` and `maximum` on the y axis.
+    pub fn truncated(minimum: T, maximum: T, closed: bool) -> Self {
+        Self {
+            minimum,
+            maximum,
+            closed,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the lower bound on the y axis of this [`Cylinder`].
+    pub fn minimum(&self) -> T {
+        self.minimum
+    }
+
+    /// Returns the upper bound on the y axis of this [`Cylinder`].
+    pub fn maximum(&self) -> T {
+        self.maximum
+    }
+
+    /// Returns `true` if this [`Cylinder`] is capped at both ends.
+    pub fn closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Checks if the intersection at `t` is within the radius of a cap.
+    fn check_cap(ray: &Ray<T>, t: T) -> bool {
+        let p = ray.position(t);
+        p.x * p.x + p.z * p.z <= T::one()
+    }
+
+    /// Appends the intersections with the end caps, if the cylinder is closed.
+    fn intersect_caps<'a>(&'a self, ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let dy = ray.direction().y;
+        if !self.closed || dy.abs() < T::from_f64(EPSILON) {
+            return;
+        }
+        for cap in [self.minimum, self.maximum] {
+            let t = (cap - ray.origin().y) / dy;
+            if Self::check_cap(ray, t) {
+                xs.push(Intersection::new(t, self));
+            }
+        }
+    }
+}
+
+impl<T: Float> Shape<T> for Cylinder<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::new(
+            Point3::new(-T::one(), self.minimum, -T::one()),
+            Point3::new(T::one(), self.maximum, T::one()),
+        )
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let (o, d) = (local_ray.origin(), local_ray.direction());
+        let a = d.x * d.x + d.z * d.z;
+        // A ray parallel to the y axis can only hit the caps.
+        if a.abs() >= T::from_f64(EPSILON) {
+            let b = T::two() * (o.x * d.x + o.z * d.z);
+            let c = o.x * o.x + o.z * o.z - T::one();
+            let disc = b * b - T::four() * a * c;
+            // Missing the infinite cylinder means missing the caps as well.
+            if disc < T::zero() {
+                return;
+            }
+            let sqrt_disc = disc.sqrt();
+            let t0 = (-b - sqrt_disc) / (T::two() * a);
+            let t1 = (-b + sqrt_disc) / (T::two() * a);
+            for t in [t0.min(t1), t0.max(t1)] {
+                let y = o.y + t * d.y;
+                if self.minimum < y && y < self.maximum {
+                    xs.push(Intersection::new(t, self));
+                }
+            }
+        }
+        self.intersect_caps(local_ray, xs);
+    }
+
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        let dist = local_point.x * local_point.x + local_point.z * local_point.z;
+        let eps = T::from_f64(EPSILON);
+        if dist < T::one() && local_point.y >= self.maximum - eps {
+            Vector3::unit_y()
+        } else if dist < T::one() && local_point.y <= self.minimum + eps {
+            -Vector3::<T>::unit_y()
+        } else {
+            Vector3::new(local_point.x, T::zero(), local_point.z)
+        }
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        let mut value = SceneValue::object([("add", "cylinder".into())]);
+        // The infinite bounds are the defaults, which JSON cannot write.
+        if self.minimum.is_finite() {
+            value.push("min", SceneValue::number(self.minimum));
+        }
+        if self.maximum.is_finite() {
+            value.push("max", SceneValue::number(self.maximum));
+        }
+        if self.closed {
+            value.push("closed", true.into());
+        }
+        Ok(value)
+    }
+}
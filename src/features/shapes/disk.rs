@@ -0,0 +1,99 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Point3, Vector3, EPSILON,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A flat disk centered at the origin of its object space, lying in the xz
+/// plane and facing `+y`. With a non-zero `inner_radius` it becomes an
+/// annulus: the points closer to the center than `inner_radius` are cut out.
+pub struct Disk<T: Float> {
+    base: ShapeBase<T>,
+    inner_radius: T,
+    radius: T,
+}
+
+impl<T: Float> Default for Disk<T> {
+    /// A unit disk without a hole.
+    fn default() -> Self {
+        Self::new(T::one())
+    }
+}
+
+impl<T: Float> Disk<T> {
+    /// Returns a disk of the given `radius`.
+    pub fn new(radius: T) -> Self {
+        Self::annulus(T::zero(), radius)
+    }
+
+    /// Returns an annulus covering the radii between `inner_radius` and `radius`.
+    pub fn annulus(inner_radius: T, radius: T) -> Self {
+        Self {
+            base: ShapeBase::default(),
+            inner_radius,
+            radius,
+        }
+    }
+
+    pub fn inner_radius(&self) -> T {
+        self.inner_radius
+    }
+
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+}
+
+impl<T: Float> Shape<T> for Disk<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::new(
+            Point3::new(-self.radius, T::zero(), -self.radius),
+            Point3::new(self.radius, T::zero(), self.radius),
+        )
+    }
+
+    /// Intersects the xz plane like `Plane`, then keeps the hit only when it
+    /// falls between the inner and the outer radius.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let direction = local_ray.direction();
+        if direction.y.abs() < T::from_f64(EPSILON) {
+            return;
+        }
+        let t = -local_ray.origin().y / direction.y;
+        let p = local_ray.position(t);
+        let dist_sqrd = p.x * p.x + p.z * p.z;
+        if dist_sqrd > self.radius * self.radius
+            || dist_sqrd < self.inner_radius * self.inner_radius
+        {
+            return;
+        }
+        xs.push(Intersection::new(t, self));
+    }
+
+    /// The normal is the same everywhere on the disk.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        Vector3::unit_y()
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(SceneValue::object([
+            ("add", "disk".into()),
+            ("radius", SceneValue::number(self.radius)),
+            ("inner-radius", SceneValue::number(self.inner_radius)),
+        ]))
+    }
+}
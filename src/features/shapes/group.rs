@@ -0,0 +1,283 @@
+use std::cmp::Ordering;
+
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::{Ray, RayPacket},
+        scene::{shape_value, value::SceneValue, SceneError},
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{
+    accelerator::{Accelerator, AcceleratorKind},
+    bounds::BoundingBox,
+    bvh::Bvh,
+    Shape, ShapeBase,
+};
+
+/// The shapes held by a group.
+pub type Children<T> = Vec<Box<dyn Shape<T>>>;
+
+#[derive(Debug)]
+/// A collection of shapes transformed together. The transformation of the
+/// children is relative to the group, groups can be nested to build
+/// hierarchical scenes.
+pub struct Group<T: Float> {
+    base: ShapeBase<T>,
+    children: Children<T>,
+    /// Cached union of the bounds of the children, in object space.
+    bounds: BoundingBox<T>,
+    /// The spatial structure over the children, dropped when they change.
+    accelerator: Option<Accelerator<T>>,
+}
+
+impl<T: Float> Default for Group<T> {
+    fn default() -> Self {
+        Self {
+            base: ShapeBase::default(),
+            children: Vec::new(),
+            bounds: BoundingBox::default(),
+            accelerator: None,
+        }
+    }
+}
+
+impl<T: Float> Group<T> {
+    pub fn new(transform: Matrix4<T>) -> Self {
+        let mut group = Self::default();
+        group.set_transform(transform);
+        group
+    }
+
+    /// Adds a shape to this group, the group becomes its parent.
+    pub fn add_child(&mut self, child: impl Shape<T> + 'static) {
+        self.add_boxed_child(Box::new(child))
+    }
+
+    /// Adds a shape already boxed to this group, the group becomes its parent.
+    pub fn add_boxed_child(&mut self, mut child: Box<dyn Shape<T>>) {
+        child.set_parent_transform(self.base.world_transform());
+        self.bounds.merge(&child.parent_space_bounds());
+        self.children.push(child);
+        self.accelerator = None;
+    }
+
+    /// Returns the children of this [`Group`].
+    pub fn children(&self) -> &[Box<dyn Shape<T>>] {
+        &self.children
+    }
+
+    /// Removes the children fitting entirely in either half of the bounds of
+    /// this group, and returns them as `(left, right)`. The children
+    /// straddling both halves stay in the group.
+    pub fn partition_children(&mut self) -> (Children<T>, Children<T>) {
+        self.accelerator = None;
+        let (left_bounds, right_bounds) = self.bounds.split();
+        let (mut left, mut right, mut rest) = (Vec::new(), Vec::new(), Vec::new());
+        for child in self.children.drain(..) {
+            let bounds = child.parent_space_bounds();
+            if left_bounds.contains_box(&bounds) {
+                left.push(child);
+            } else if right_bounds.contains_box(&bounds) {
+                right.push(child);
+            } else {
+                rest.push(child);
+            }
+        }
+        self.children = rest;
+        (left, right)
+    }
+
+    /// Builds a bounding volume hierarchy over the children with the
+    /// surface area heuristic, at most `leaf_size` children per leaf. The
+    /// children stay in this group; the rays only test the ones whose
+    /// leaves they hit. Unlike [`Shape::divide`], nested groups are left
+    /// as they are. Adding a child drops the hierarchy.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build_bvh(&mut self, leaf_size: usize) {
+        self.accelerate(AcceleratorKind::Bvh, leaf_size);
+    }
+
+    /// Returns the hierarchy built by [`Group::build_bvh`].
+    pub fn bvh(&self) -> Option<&Bvh<T>> {
+        match &self.accelerator {
+            Some(Accelerator::Bvh(bvh)) => Some(bvh),
+            _ => None,
+        }
+    }
+
+    /// Returns the spatial structure over the children, if built.
+    pub fn accelerator(&self) -> Option<&Accelerator<T>> {
+        self.accelerator.as_ref()
+    }
+
+    /// Builds the spatial structure of the given `kind` over the children.
+    fn accelerate(&mut self, kind: AcceleratorKind, leaf_size: usize) {
+        let bounds: Vec<BoundingBox<T>> = self
+            .children
+            .iter()
+            .map(|child| child.parent_space_bounds())
+            .collect();
+        self.accelerator = Some(Accelerator::build(kind, &bounds, leaf_size));
+    }
+
+    /// Adds a new group holding `children` as a child of this group.
+    pub fn make_subgroup(&mut self, children: Children<T>) {
+        let mut subgroup = Group::default();
+        for child in children {
+            subgroup.add_boxed_child(child);
+        }
+        self.add_child(subgroup);
+    }
+
+    /// Passes the world transformation down to the children.
+    fn update_children(&mut self) {
+        let world_transform = self.base.world_transform();
+        for child in self.children.iter_mut() {
+            child.set_parent_transform(world_transform);
+        }
+    }
+}
+
+impl<T: Float> Shape<T> for Group<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base.set_transform(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base.set_parent_transform(parent_transform);
+        self.update_children();
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    /// Splits the children into two subgroups by the halves of the bounds,
+    /// recursively, as long as there are at least `threshold` children.
+    fn divide(&mut self, threshold: usize) {
+        if threshold <= self.children.len() {
+            let (left, right) = self.partition_children();
+            if !left.is_empty() {
+                self.make_subgroup(left);
+            }
+            if !right.is_empty() {
+                self.make_subgroup(right);
+            }
+        }
+        for child in self.children.iter_mut() {
+            child.divide(threshold);
+        }
+    }
+
+    /// Builds the structures of the children first, then the one over them.
+    fn build_accelerator(&mut self, kind: AcceleratorKind, leaf_size: usize) {
+        for child in self.children.iter_mut() {
+            child.build_accelerator(kind, leaf_size);
+        }
+        self.accelerate(kind, leaf_size);
+    }
+
+    fn includes(&self, other: &dyn Shape<T>) -> bool {
+        std::ptr::addr_eq(self, other) || self.children.iter().any(|c| c.includes(other))
+    }
+
+    /// Appends the intersections with all children, sorted by `t`.
+    /// The children are not tested when the ray misses the bounds of the group.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        if !self.bounds.intersects(local_ray) {
+            return;
+        }
+        let start = xs.len();
+        match &self.accelerator {
+            Some(accelerator) => {
+                accelerator.visit(local_ray, |i| {
+                    self.children[i].append_intersections(local_ray, xs);
+                    false
+                });
+            }
+            None => {
+                for child in &self.children {
+                    child.append_intersections(local_ray, xs);
+                }
+            }
+        }
+        xs[start..].sort_by(|a, b| a.t().partial_cmp(&b.t()).unwrap_or(Ordering::Equal));
+    }
+
+    /// Stops at the first child occluding the ray. The distances along the
+    /// ray are the same in every space, so `max_t` holds for the children.
+    fn occludes(&self, ray: &Ray<T>, max_t: T) -> bool {
+        let local_ray = ray.transform(&self.base.inverse_at(ray.time()));
+        if !self.bounds.intersects(&local_ray) {
+            return false;
+        }
+        match &self.accelerator {
+            Some(accelerator) => {
+                accelerator.visit(&local_ray, |i| self.children[i].occludes(&local_ray, max_t))
+            }
+            None => self
+                .children
+                .iter()
+                .any(|child| child.occludes(&local_ray, max_t)),
+        }
+    }
+
+    /// Returns the intersections of each ray of the packet with all
+    /// children, sorted by `t`. Only the rays hitting the bounds of the
+    /// group are traced through the children, as a smaller packet.
+    fn local_intersect_packet<'a>(
+        &'a self,
+        local_packet: &RayPacket<T>,
+    ) -> Vec<Vec<Intersection<'a, T>>> {
+        let mut xs: Vec<Vec<Intersection<'a, T>>> =
+            (0..local_packet.len()).map(|_| Vec::new()).collect();
+        let Some((packet, lanes)) =
+            local_packet.select(&self.bounds.intersects_packet(local_packet))
+        else {
+            return xs;
+        };
+        for child in &self.children {
+            for (&lane, hits) in lanes.iter().zip(child.intersect_packet(&packet)) {
+                xs[lane].extend(hits);
+            }
+        }
+        for lane in &mut xs {
+            lane.sort_by(|a, b| a.t().partial_cmp(&b.t()).unwrap_or(Ordering::Equal));
+        }
+        xs
+    }
+
+    /// # Panics
+    ///
+    /// The intersections name the children, the normal must be asked to them.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        panic!("The normal of a group is given by its children.")
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        let children = self
+            .children
+            .iter()
+            .map(|child| shape_value(child.as_ref()));
+        Ok(SceneValue::object([
+            ("add", "group".into()),
+            (
+                "children",
+                SceneValue::Array(children.collect::<Result<_, _>>()?),
+            ),
+        ]))
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    features::{
+        canvas::{Canvas, CanvasFormat},
+        intersections::Intersection,
+        materials::Material,
+        rays::Ray,
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{
+    bounds::BoundingBox, parametric::tessellate, triangle::SmoothTriangle, Shape, ShapeBase,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A terrain displaced from a grid of heights, spanning `[0, 1]` along
+/// both `x` and `z` in its object space, with the heights along `y`.
+/// Scale it with its transformation to the size of the scene.
+///
+/// Like [`super::parametric::ParametricSurface`], it is tessellated into
+/// smooth triangles whose parent space is its object space.
+pub struct HeightField<T: Float> {
+    base: ShapeBase<T>,
+    columns: usize,
+    rows: usize,
+    triangles: Vec<SmoothTriangle<T>>,
+    bounds: BoundingBox<T>,
+}
+
+impl<T: Float> HeightField<T> {
+    /// Returns the height field of `heights`, given row by row: `columns`
+    /// samples along `x` for each of the `rows` along `z`.
+    /// # Panics
+    ///
+    /// This function panics if there are less than two rows or columns, or
+    /// if `heights` does not hold exactly `columns * rows` values.
+    pub fn from_heights(heights: &[T], columns: usize, rows: usize) -> Self {
+        assert!(
+            columns > 1 && rows > 1,
+            "A height field needs at least two rows and two columns."
+        );
+        assert_eq!(
+            heights.len(),
+            columns * rows,
+            "The heights must cover the whole grid."
+        );
+        let to_coord = |i: usize, n: usize| T::from_f64(i as f64 / (n - 1) as f64);
+        let points: Vec<Point3<T>> = (0..columns)
+            .flat_map(|x| (0..rows).map(move |z| (x, z)))
+            .map(|(x, z)| {
+                Point3::new(
+                    to_coord(x, columns),
+                    heights[z * columns + x],
+                    to_coord(z, rows),
+                )
+            })
+            .collect();
+        Self {
+            base: ShapeBase::default(),
+            columns,
+            rows,
+            triangles: tessellate(&points, columns - 1, rows - 1),
+            bounds: BoundingBox::from_points(points),
+        }
+    }
+
+    /// Returns the height field of a grayscale canvas: the height of each
+    /// pixel is the mean of its channels, the rows of the canvas are laid
+    /// along `z`.
+    pub fn from_canvas<const W: usize, const H: usize, F: CanvasFormat>(
+        canvas: &Canvas<W, H, T, F>,
+    ) -> Self {
+        let heights: Vec<T> = canvas
+            .pixels()
+            .iter()
+            .map(|c| (c.r + c.g + c.b) / T::three())
+            .collect();
+        Self::from_heights(&heights, W, H)
+    }
+
+    /// Returns the number of samples along `x` and `z`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.columns, self.rows)
+    }
+
+    /// Returns the triangles tessellating this height field.
+    pub fn triangles(&self) -> &[SmoothTriangle<T>] {
+        &self.triangles
+    }
+
+    /// Passes the world transformation down to the triangles.
+    fn update_triangles(&mut self) {
+        let world_transform = self.base.world_transform();
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_parent_transform(world_transform);
+        }
+    }
+}
+
+impl<T: Float> Shape<T> for HeightField<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    /// The triangles are in the object space of the height field, which is
+    /// their parent space.
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base.set_transform(transform);
+        self.update_triangles();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base.set_parent_transform(parent_transform);
+        self.update_triangles();
+    }
+
+    /// The intersections name the triangles, which are shaded with the
+    /// material of the height field.
+    fn set_material(&mut self, material: Material<T>) {
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_material(material.clone());
+        }
+        self.base.set_material(material);
+    }
+
+    fn set_casts_shadow(&mut self, casts_shadow: bool) {
+        self.base.set_casts_shadow(casts_shadow);
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_casts_shadow(casts_shadow);
+        }
+    }
+
+    fn set_receives_shadow(&mut self, receives_shadow: bool) {
+        self.base.set_receives_shadow(receives_shadow);
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_receives_shadow(receives_shadow);
+        }
+    }
+
+    fn includes(&self, other: &dyn Shape<T>) -> bool {
+        std::ptr::addr_eq(self, other) || self.triangles.iter().any(|t| std::ptr::addr_eq(t, other))
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        if !self.bounds.intersects(local_ray) {
+            return;
+        }
+        for triangle in &self.triangles {
+            triangle.local_intersect_into(local_ray, xs);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// The intersections name the triangles, the normal must be asked to them.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        panic!("The normal of a height field is given by its triangles.")
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use crate::{
+    features::{intersections::Intersection, rays::Ray},
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone)]
+/// A placement of a shape shared with other instances, such as a mesh
+/// repeated across a scene: the geometry is stored once behind an [`Arc`],
+/// each instance only adds its transformation and material.
+///
+/// The geometry is transformed relative to the instance, like the children
+/// of a [`super::group::Group`]. The intersections name the instance and
+/// record the shape hit within the geometry, see [`Intersection::inner`].
+/// The whole instance is shaded with its own material, which is the one of
+/// the geometry unless set, and casts shadows unless told otherwise.
+///
+/// The geometry cannot be changed through its instances: its accelerator
+/// must be built before it is shared. Instances of instances are not
+/// supported, the normals being computed by the shape hit.
+pub struct Instance<T: Float> {
+    base: ShapeBase<T>,
+    geometry: Arc<dyn Shape<T>>,
+}
+
+impl<T: Float> Instance<T> {
+    /// Returns an instance of `geometry` placed by `transform`.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    pub fn new(geometry: Arc<dyn Shape<T>>, transform: Matrix4<T>) -> Self {
+        let mut base = ShapeBase::default();
+        base.set_transform(transform);
+        base.set_material(geometry.material().clone());
+        base.set_casts_shadow(geometry.casts_shadow());
+        base.set_receives_shadow(geometry.receives_shadow());
+        Self { base, geometry }
+    }
+
+    /// Returns the shared geometry.
+    pub fn geometry(&self) -> &Arc<dyn Shape<T>> {
+        &self.geometry
+    }
+}
+
+impl<T: Float> Shape<T> for Instance<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.geometry.parent_space_bounds()
+    }
+
+    fn is_medium(&self) -> bool {
+        self.geometry.is_medium()
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let start = xs.len();
+        self.geometry.append_intersections(local_ray, xs);
+        for x in &mut xs[start..] {
+            *x = x.within(self);
+        }
+    }
+
+    /// The geometry decides whether it is hit, this instance whether it
+    /// casts shadows.
+    fn occludes(&self, ray: &Ray<T>, max_t: T) -> bool {
+        self.casts_shadow()
+            && self
+                .geometry
+                .occludes(&ray.transform(&self.base.inverse_at(ray.time())), max_t)
+    }
+
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        self.geometry.normal_at(local_point)
+    }
+
+    /// The shape hit within the geometry sees the object space of this
+    /// instance as its world space.
+    fn local_normal_at_hit(&self, local_point: Point3<T>, hit: &Intersection<'_, T>) -> Vector3<T> {
+        match hit.unwrapped() {
+            Some(inner) => inner.object().normal_at_hit(local_point, &inner),
+            None => self.local_normal_at(local_point),
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Implementation of `KdTree`, a spatial subdivision of the primitives of a
+//! group or a mesh by planes along the axes. Unlike the boxes of a
+//! [`Bvh`](super::bvh::Bvh), the cells of a kd-tree never overlap: a ray
+//! walks through them front to back, but a primitive crossing a plane is
+//! referenced by the cells on both sides.
+//!
+//! The planes are chosen with the surface area heuristic among 12
+//! candidates along each axis of a cell.
+use crate::{features::rays::Ray, Float};
+
+use super::bounds::BoundingBox;
+
+/// The number of candidate planes along an axis, plus one.
+const BINS: usize = 12;
+/// The cost of walking through a node relative to the one of testing a
+/// primitive.
+const TRAVERSAL_COST: f64 = 0.125;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A node of the flattened tree. The cell below the plane of an interior
+/// node follows it, the cell above is at `above`.
+struct KdNode<T: Float> {
+    /// The axis of the plane, `None` for a leaf.
+    axis: Option<usize>,
+    split: T,
+    /// The first reference of a leaf in `KdTree::references`.
+    first: usize,
+    count: usize,
+    above: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A kd-tree over primitives given by their bounds.
+pub struct KdTree<T: Float> {
+    nodes: Vec<KdNode<T>>,
+    /// The primitives of the leaves, each leaf holding a contiguous range,
+    /// and whether the primitive is in other leaves as well.
+    references: Vec<(usize, bool)>,
+    /// The cell of the root, enclosing the primitives with finite bounds.
+    bounds: BoundingBox<T>,
+    /// The primitives without finite bounds, such as planes, tested by
+    /// every ray.
+    unbounded: Vec<usize>,
+    len: usize,
+}
+
+impl<T: Float> KdTree<T> {
+    /// Returns the kd-tree over the primitives whose boxes are `bounds`.
+    /// The cells with more than `leaf_size` primitives are split unless no
+    /// plane pays off or the tree is already deep. The primitives with
+    /// empty bounds are left out, no ray hits them.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build(bounds: &[BoundingBox<T>], leaf_size: usize) -> Self {
+        assert!(leaf_size > 0, "The leaves cannot be empty.");
+        let (mut finite, mut unbounded) = (Vec::new(), Vec::new());
+        for (i, b) in bounds.iter().enumerate() {
+            if b.is_empty() {
+                continue;
+            }
+            if b.is_finite() {
+                finite.push(i);
+            } else {
+                unbounded.push(i);
+            }
+        }
+        let mut root = BoundingBox::default();
+        for &i in &finite {
+            root.merge(&bounds[i]);
+        }
+        let mut tree = Self {
+            nodes: Vec::new(),
+            references: Vec::new(),
+            bounds: root,
+            len: finite.len() + unbounded.len(),
+            unbounded,
+        };
+        if !finite.is_empty() {
+            let max_depth = 8 + (1.3 * (finite.len() as f64).log2()) as usize;
+            tree.build_node(bounds, finite, root, max_depth, leaf_size);
+            let mut counts = vec![0; bounds.len()];
+            for &(i, _) in &tree.references {
+                counts[i] += 1;
+            }
+            for (i, shared) in &mut tree.references {
+                *shared = counts[*i] > 1;
+            }
+        }
+        tree
+    }
+
+    /// Returns the number of primitives in this tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of nodes of the tree.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the box enclosing the primitives with finite bounds.
+    pub fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    /// Returns the primitives of each leaf, from below to above the planes.
+    pub fn leaves(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| node.axis.is_none())
+            .map(|node| {
+                self.references[node.first..node.first + node.count]
+                    .iter()
+                    .map(|&(i, _)| i)
+                    .collect()
+            })
+    }
+
+    /// Returns the cell of each leaf, from below to above the planes.
+    pub fn cells(&self) -> Vec<BoundingBox<T>> {
+        let mut cells = Vec::new();
+        if !self.nodes.is_empty() {
+            self.collect_cells(0, self.bounds, &mut cells);
+        }
+        cells
+    }
+
+    fn collect_cells(&self, index: usize, cell: BoundingBox<T>, cells: &mut Vec<BoundingBox<T>>) {
+        let node = &self.nodes[index];
+        let Some(axis) = node.axis else {
+            cells.push(cell);
+            return;
+        };
+        let (below, above) = split_cell(&cell, axis, node.split);
+        self.collect_cells(index + 1, below, cells);
+        self.collect_cells(node.above, above, cells);
+    }
+
+    /// Calls `f` once with every primitive which `ray` may hit: the ones of
+    /// the cells it goes through, and the unbounded ones. The visit stops
+    /// as soon as `f` returns `true`, and so does this function.
+    pub fn visit(&self, ray: &Ray<T>, mut f: impl FnMut(usize) -> bool) -> bool {
+        if self.unbounded.iter().any(|&i| f(i)) {
+            return true;
+        }
+        let Some((t_min, t_max)) = self.bounds.intersection_range(ray) else {
+            return false;
+        };
+        // The primitives in several cells already given to `f`.
+        let mut seen = Vec::new();
+        !self.nodes.is_empty() && self.visit_node(0, ray, (t_min, t_max), &mut f, &mut seen)
+    }
+
+    fn visit_node(
+        &self,
+        index: usize,
+        ray: &Ray<T>,
+        (t_min, t_max): (T, T),
+        f: &mut impl FnMut(usize) -> bool,
+        seen: &mut Vec<usize>,
+    ) -> bool {
+        let node = &self.nodes[index];
+        let Some(axis) = node.axis else {
+            return self.references[node.first..node.first + node.count]
+                .iter()
+                .any(|&(i, shared)| {
+                    if shared {
+                        if seen.contains(&i) {
+                            return false;
+                        }
+                        seen.push(i);
+                    }
+                    f(i)
+                });
+        };
+        let (origin, direction) = (ray.origin()[axis], ray.direction()[axis]);
+        let (below, above) = (index + 1, node.above);
+        if direction == T::zero() {
+            let cell = if origin <= node.split { below } else { above };
+            return self.visit_node(cell, ray, (t_min, t_max), f, seen);
+        }
+        // The cells in the order of increasing `t` along the whole line,
+        // the intersections behind the origin being reported as well.
+        let (first, second) = if direction > T::zero() {
+            (below, above)
+        } else {
+            (above, below)
+        };
+        let t_split = (node.split - origin) / direction;
+        if t_split > t_max {
+            return self.visit_node(first, ray, (t_min, t_max), f, seen);
+        }
+        if t_split < t_min {
+            return self.visit_node(second, ray, (t_min, t_max), f, seen);
+        }
+        self.visit_node(first, ray, (t_min, t_split), f, seen)
+            || self.visit_node(second, ray, (t_split, t_max), f, seen)
+    }
+
+    /// Appends the subtree over `primitives` in `cell` depth first.
+    fn build_node(
+        &mut self,
+        bounds: &[BoundingBox<T>],
+        primitives: Vec<usize>,
+        cell: BoundingBox<T>,
+        depth: usize,
+        leaf_size: usize,
+    ) {
+        let index = self.nodes.len();
+        let split = (primitives.len() > leaf_size && depth > 0)
+            .then(|| best_plane(bounds, &primitives, &cell))
+            .flatten();
+        let Some((axis, plane)) = split else {
+            self.nodes.push(KdNode {
+                axis: None,
+                split: T::zero(),
+                first: self.references.len(),
+                count: primitives.len(),
+                above: 0,
+            });
+            self.references
+                .extend(primitives.into_iter().map(|i| (i, false)));
+            return;
+        };
+        self.nodes.push(KdNode {
+            axis: Some(axis),
+            split: plane,
+            first: 0,
+            count: 0,
+            above: 0,
+        });
+        let (below, above): (Vec<usize>, Vec<usize>) = (
+            primitives
+                .iter()
+                .copied()
+                .filter(|&i| bounds[i].min()[axis] <= plane)
+                .collect(),
+            primitives
+                .iter()
+                .copied()
+                .filter(|&i| bounds[i].max()[axis] > plane)
+                .collect(),
+        );
+        let (below_cell, above_cell) = split_cell(&cell, axis, plane);
+        self.build_node(bounds, below, below_cell, depth - 1, leaf_size);
+        self.nodes[index].above = self.nodes.len();
+        self.build_node(bounds, above, above_cell, depth - 1, leaf_size);
+    }
+}
+
+/// Returns the cells below and above the `plane` across `axis`.
+fn split_cell<T: Float>(
+    cell: &BoundingBox<T>,
+    axis: usize,
+    plane: T,
+) -> (BoundingBox<T>, BoundingBox<T>) {
+    let (mut below_max, mut above_min) = (cell.max(), cell.min());
+    below_max[axis] = plane;
+    above_min[axis] = plane;
+    (
+        BoundingBox::new(cell.min(), below_max),
+        BoundingBox::new(above_min, cell.max()),
+    )
+}
+
+/// Returns the axis and position of the cheapest plane splitting `cell`
+/// by the surface area heuristic, or `None` if no plane costs less than
+/// leaving the primitives in a leaf.
+fn best_plane<T: Float>(
+    bounds: &[BoundingBox<T>],
+    primitives: &[usize],
+    cell: &BoundingBox<T>,
+) -> Option<(usize, T)> {
+    let area = cell.surface_area();
+    let count = T::from_f64(primitives.len() as f64);
+    let mut best: Option<(T, usize, T)> = None;
+    for axis in 0..3 {
+        let (min, max) = (cell.min()[axis], cell.max()[axis]);
+        if max <= min {
+            continue;
+        }
+        for k in 1..BINS {
+            let plane = min + (max - min) * T::from_f64(k as f64 / BINS as f64);
+            let below = primitives
+                .iter()
+                .filter(|&&i| bounds[i].min()[axis] <= plane)
+                .count();
+            let above = primitives
+                .iter()
+                .filter(|&&i| bounds[i].max()[axis] > plane)
+                .count();
+            let (below_cell, above_cell) = split_cell(cell, axis, plane);
+            let cost = T::from_f64(TRAVERSAL_COST)
+                + (below_cell.surface_area() * T::from_f64(below as f64)
+                    + above_cell.surface_area() * T::from_f64(above as f64))
+                    / area;
+            if best.is_none_or(|(best_cost, ..)| cost < best_cost) {
+                best = Some((cost, axis, plane));
+            }
+        }
+    }
+    let (cost, axis, plane) = best?;
+    (cost < count).then_some((axis, plane))
+}
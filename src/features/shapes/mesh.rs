@@ -0,0 +1,214 @@
+use crate::{
+    features::{intersections::Intersection, rays::Ray},
+    Float, Point3, Vector3,
+};
+
+use super::{
+    accelerator::{Accelerator, AcceleratorKind},
+    bounds::BoundingBox,
+    bvh::Bvh,
+    triangle::{intersect_triangle, Triangle},
+    Shape, ShapeBase,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A triangle mesh stored as shared buffers: each face is three indices
+/// into the vertices, and optionally three indices into the normals.
+/// Shared vertices are stored once, instead of once per [`Triangle`].
+///
+/// The intersections name the mesh and record the face being hit.
+pub struct Mesh<T: Float> {
+    base: ShapeBase<T>,
+    vertices: Vec<Point3<T>>,
+    faces: Vec<[usize; 3]>,
+    normals: Vec<Vector3<T>>,
+    /// Indices into `normals` for each face, empty for a flat shaded mesh.
+    normal_faces: Vec<[usize; 3]>,
+    bounds: BoundingBox<T>,
+    /// The spatial structure over the faces, if built.
+    accelerator: Option<Accelerator<T>>,
+}
+
+impl<T: Float> Mesh<T> {
+    /// Returns a flat shaded mesh.
+    /// # Panics
+    ///
+    /// This function panics if a face refers to a vertex out of range.
+    pub fn new(vertices: Vec<Point3<T>>, faces: Vec<[usize; 3]>) -> Self {
+        assert!(
+            faces.iter().flatten().all(|&i| i < vertices.len()),
+            "A face of the mesh refers to a missing vertex."
+        );
+        let bounds = BoundingBox::from_points(vertices.iter().copied());
+        Self {
+            base: ShapeBase::default(),
+            vertices,
+            faces,
+            normals: Vec::new(),
+            normal_faces: Vec::new(),
+            bounds,
+            accelerator: None,
+        }
+    }
+
+    /// Returns this mesh with the normals at the vertices of every face,
+    /// which are interpolated over the faces like [`super::triangle::SmoothTriangle`].
+    /// # Panics
+    ///
+    /// This function panics if there is not one `normal_faces` per face, or
+    /// if they refer to a normal out of range.
+    pub fn with_normals(mut self, normals: Vec<Vector3<T>>, normal_faces: Vec<[usize; 3]>) -> Self {
+        assert_eq!(
+            normal_faces.len(),
+            self.faces.len(),
+            "Every face of the mesh needs its normals."
+        );
+        assert!(
+            normal_faces.iter().flatten().all(|&i| i < normals.len()),
+            "A face of the mesh refers to a missing normal."
+        );
+        self.normals = normals;
+        self.normal_faces = normal_faces;
+        self
+    }
+
+    pub fn vertices(&self) -> &[Point3<T>] {
+        &self.vertices
+    }
+
+    pub fn faces(&self) -> &[[usize; 3]] {
+        &self.faces
+    }
+
+    pub fn normals(&self) -> &[Vector3<T>] {
+        &self.normals
+    }
+
+    /// Returns the number of faces.
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Builds a bounding volume hierarchy over the faces with the surface
+    /// area heuristic, at most `leaf_size` faces per leaf, so that a ray
+    /// only tests the faces of the leaves it hits.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build_bvh(&mut self, leaf_size: usize) {
+        self.build_accelerator(AcceleratorKind::Bvh, leaf_size);
+    }
+
+    /// Returns the hierarchy built by [`Mesh::build_bvh`].
+    pub fn bvh(&self) -> Option<&Bvh<T>> {
+        match &self.accelerator {
+            Some(Accelerator::Bvh(bvh)) => Some(bvh),
+            _ => None,
+        }
+    }
+
+    /// Returns the spatial structure over the faces, if built.
+    pub fn accelerator(&self) -> Option<&Accelerator<T>> {
+        self.accelerator.as_ref()
+    }
+
+    /// Appends the intersection of the ray with the `face`, if any.
+    fn intersect_face<'a>(
+        &'a self,
+        face: usize,
+        local_ray: &Ray<T>,
+        xs: &mut Vec<Intersection<'a, T>>,
+    ) {
+        let [p1, p2, p3] = self.faces[face].map(|i| self.vertices[i]);
+        xs.extend(
+            intersect_triangle(p1, p2 - p1, p3 - p1, local_ray)
+                .map(|(t, u, v)| Intersection::with_face(t, self, face, u, v)),
+        );
+    }
+
+    /// Returns the standalone triangle of the `face`.
+    pub fn triangle(&self, face: usize) -> Triangle<T> {
+        let [p1, p2, p3] = self.faces[face].map(|i| self.vertices[i]);
+        Triangle::new(p1, p2, p3)
+    }
+
+    /// Returns the flat normal of the `face`.
+    fn face_normal(&self, face: usize) -> Vector3<T> {
+        let [p1, p2, p3] = self.faces[face].map(|i| self.vertices[i]);
+        (p3 - p1).cross(&(p2 - p1)).normalized()
+    }
+}
+
+impl<T: Float> Shape<T> for Mesh<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    /// Builds the hierarchy over the faces, at most `threshold` per leaf.
+    fn divide(&mut self, threshold: usize) {
+        self.build_bvh(threshold.max(1));
+    }
+
+    /// Builds the structure of the given `kind` over the faces.
+    fn build_accelerator(&mut self, kind: AcceleratorKind, leaf_size: usize) {
+        let bounds: Vec<BoundingBox<T>> = self
+            .faces
+            .iter()
+            .map(|indices| BoundingBox::from_points(indices.map(|i| self.vertices[i])))
+            .collect();
+        self.accelerator = Some(Accelerator::build(kind, &bounds, leaf_size));
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        if !self.bounds.intersects(local_ray) {
+            return;
+        }
+        match &self.accelerator {
+            Some(accelerator) => {
+                accelerator.visit(local_ray, |face| {
+                    self.intersect_face(face, local_ray, xs);
+                    false
+                });
+            }
+            None => {
+                for face in 0..self.faces.len() {
+                    self.intersect_face(face, local_ray, xs);
+                }
+            }
+        }
+    }
+
+    /// Without the face being hit, the normal is unknown.
+    /// # Panics
+    ///
+    /// The normal must be asked with the intersection, by
+    /// [`Shape::normal_at_hit`].
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        panic!("The normal of a mesh depends on the face being hit.")
+    }
+
+    fn local_normal_at_hit(&self, local_point: Point3<T>, hit: &Intersection<'_, T>) -> Vector3<T> {
+        let Some(face) = hit.face() else {
+            return self.local_normal_at(local_point);
+        };
+        match (self.normal_faces.get(face), hit.uv()) {
+            (Some(indices), Some((u, v))) => {
+                let [n1, n2, n3] = indices.map(|i| self.normals[i]);
+                n2 * u + n3 * v + n1 * (T::one() - u - v)
+            }
+            _ => self.face_normal(face),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+
+use crate::{
+    features::{intersections::Intersection, math::roots::solve_quadratic, rays::Ray},
+    Float, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+/// Number of marching steps per radius of the smallest ball.
+const STEPS_PER_RADIUS: usize = 16;
+/// Number of bisections refining a crossing of the isosurface.
+const BISECTIONS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A spherical field of influence: `weight * (1 - d^2 / radius^2)^2` at a
+/// distance `d < radius` from `center`, zero beyond.
+pub struct Metaball<T: Float> {
+    center: Point3<T>,
+    radius: T,
+    weight: T,
+}
+
+impl<T: Float> Metaball<T> {
+    pub fn new(center: Point3<T>, radius: T, weight: T) -> Self {
+        Self {
+            center,
+            radius,
+            weight,
+        }
+    }
+
+    pub fn center(&self) -> Point3<T> {
+        self.center
+    }
+
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    pub fn weight(&self) -> T {
+        self.weight
+    }
+
+    /// Returns the strength of the field at `point`.
+    fn field(&self, point: Point3<T>) -> T {
+        let d2 = (point - self.center).length2() / (self.radius * self.radius);
+        if d2 >= T::one() {
+            T::zero()
+        } else {
+            let falloff = T::one() - d2;
+            self.weight * falloff * falloff
+        }
+    }
+
+    /// Returns the gradient of the field at `point`.
+    fn gradient(&self, point: Point3<T>) -> Vector3<T> {
+        let r2 = self.radius * self.radius;
+        let offset = point - self.center;
+        let d2 = offset.length2() / r2;
+        if d2 >= T::one() {
+            Vector3::zero()
+        } else {
+            offset * (-T::four() * self.weight * (T::one() - d2) / r2)
+        }
+    }
+
+    /// Returns the range of `t` where the ray is within the influence of this ball.
+    fn span(&self, ray: &Ray<T>) -> Option<(T, T)> {
+        let to_ray = ray.origin() - self.center;
+        let direction = ray.direction();
+        let roots = solve_quadratic(
+            direction.dot(&direction),
+            T::two() * direction.dot(&to_ray),
+            to_ray.dot(&to_ray) - self.radius * self.radius,
+        );
+        match roots[..] {
+            [t0, t1] => Some((t0, t1)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A blobby isosurface: the points where the sum of the fields of the
+/// `balls` equals `threshold`. Balls close enough to each other merge
+/// smoothly into a single surface.
+///
+/// The surface has no closed form, so rays are marched through the
+/// influence of the balls and every crossing of the threshold is refined by
+/// bisection.
+pub struct Metaballs<T: Float> {
+    base: ShapeBase<T>,
+    balls: Vec<Metaball<T>>,
+    threshold: T,
+}
+
+impl<T: Float> Metaballs<T> {
+    /// # Panics
+    ///
+    /// This function panics if `threshold` is not positive, as the surface
+    /// would not be bounded by the balls.
+    pub fn new(balls: Vec<Metaball<T>>, threshold: T) -> Self {
+        assert!(
+            threshold > T::zero(),
+            "The threshold of metaballs must be positive."
+        );
+        Self {
+            base: ShapeBase::default(),
+            balls,
+            threshold,
+        }
+    }
+
+    pub fn balls(&self) -> &[Metaball<T>] {
+        &self.balls
+    }
+
+    pub fn threshold(&self) -> T {
+        self.threshold
+    }
+
+    /// Returns the sum of the fields at `point`, minus the threshold:
+    /// positive inside the surface, negative outside.
+    fn potential(&self, point: Point3<T>) -> T {
+        self.balls
+            .iter()
+            .fold(-self.threshold, |acc, ball| acc + ball.field(point))
+    }
+
+    /// Returns the disjoint ranges of `t` along the ray covered by the balls.
+    fn spans(&self, ray: &Ray<T>) -> Vec<(T, T)> {
+        let mut spans: Vec<(T, T)> = self.balls.iter().filter_map(|b| b.span(ray)).collect();
+        spans.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(spans.len());
+        for (t0, t1) in spans {
+            match merged.last_mut() {
+                Some(last) if t0 <= last.1 => last.1 = last.1.max(t1),
+                _ => merged.push((t0, t1)),
+            }
+        }
+        merged
+    }
+}
+
+impl<T: Float> Shape<T> for Metaballs<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    /// The union of the spheres of influence of the balls.
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::from_points(self.balls.iter().flat_map(|b| {
+            let r = Vector3::new(b.radius, b.radius, b.radius);
+            [b.center - r, b.center + r]
+        }))
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let Some(min_radius) = self.balls.iter().map(|b| b.radius).reduce(T::min) else {
+            return;
+        };
+        // The step is a distance in object space, converted into `t`.
+        let step =
+            min_radius / T::from_f64(STEPS_PER_RADIUS as f64) / local_ray.direction().magnitude();
+        let potential_at = |t: T| self.potential(local_ray.position(t));
+        for (start, end) in self.spans(local_ray) {
+            let (mut t0, mut p0) = (start, potential_at(start));
+            while t0 < end {
+                let t1 = (t0 + step).min(end);
+                let p1 = potential_at(t1);
+                if (p0 > T::zero()) != (p1 > T::zero()) {
+                    let (mut lo, mut hi) = (t0, t1);
+                    for _ in 0..BISECTIONS {
+                        let mid = (lo + hi) / T::two();
+                        if (potential_at(mid) > T::zero()) == (p0 > T::zero()) {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    xs.push(Intersection::new((lo + hi) / T::two(), self));
+                }
+                (t0, p0) = (t1, p1);
+            }
+        }
+    }
+
+    /// The normal points against the gradient of the field, which grows
+    /// towards the centers of the balls.
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        -self
+            .balls
+            .iter()
+            .map(|ball| ball.gradient(local_point))
+            .sum::<Vector3<T>>()
+    }
+}
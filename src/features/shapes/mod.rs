@@ -0,0 +1,441 @@
+//! Shapes that can be intersected by rays. Every shape implements
+//! [`Shape`] by working in its own object space, the conversion between
+//! world and object space is shared by all shapes.
+use std::fmt::Debug;
+
+use crate::{
+    features::{
+        intersections::{Intersection, Intersections},
+        materials::Material,
+        rays::{Ray, RayPacket},
+        scene::{unsupported, value::SceneValue, SceneError},
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+use self::{accelerator::AcceleratorKind, bounds::BoundingBox};
+
+pub mod accelerator;
+pub mod bounds;
+pub mod bvh;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+pub mod group;
+pub mod height_field;
+pub mod instance;
+pub mod kd_tree;
+pub mod mesh;
+pub mod metaballs;
+pub mod parametric;
+pub mod plane;
+pub mod sphere;
+pub mod test_shape;
+pub mod torus;
+pub mod triangle;
+pub mod volume;
+
+#[derive(Debug, Clone, PartialEq)]
+/// The properties shared by every shape.
+pub struct ShapeBase<T: Float> {
+    transform: Matrix4<T>,
+    /// Cached inverse of `transform`, applied to every ray and point.
+    inverse: Matrix4<T>,
+    /// The transformation of the enclosing groups, from the parent's object
+    /// space to world space. Identity for shapes not in a group.
+    parent_transform: Matrix4<T>,
+    /// Cached inverse of `parent_transform * transform`, from world space
+    /// to object space through the whole parent chain.
+    world_inverse: Matrix4<T>,
+    /// The transformation at the end of the shutter interval, the shape
+    /// moving from `transform` at time `0` to this one at time `1`.
+    end_transform: Option<Matrix4<T>>,
+    material: Material<T>,
+    /// Whether this shape blocks the light in the shadow test.
+    casts_shadow: bool,
+    /// Whether the points of this shape can be in the shadow of others.
+    receives_shadow: bool,
+}
+
+impl<T: Float> Default for ShapeBase<T> {
+    fn default() -> Self {
+        Self {
+            transform: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            parent_transform: Matrix4::identity(),
+            world_inverse: Matrix4::identity(),
+            end_transform: None,
+            material: Material::default(),
+            casts_shadow: true,
+            receives_shadow: true,
+        }
+    }
+}
+
+impl<T: Float> ShapeBase<T> {
+    /// Returns the transformation from object space to world space.
+    pub fn transform(&self) -> Matrix4<T> {
+        self.transform
+    }
+
+    /// Returns the transformation from world space to object space.
+    pub fn inverse(&self) -> Matrix4<T> {
+        self.inverse
+    }
+
+    /// Returns the transformation from object space to world space through
+    /// the whole parent chain.
+    pub fn world_transform(&self) -> Matrix4<T> {
+        self.parent_transform * self.transform
+    }
+
+    /// Returns the transformation from world space to object space through
+    /// the whole parent chain.
+    pub fn world_inverse(&self) -> Matrix4<T> {
+        self.world_inverse
+    }
+
+    /// Sets the transformation from object space to the parent's space.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    pub fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.inverse = transform
+            .inverse()
+            .expect("The transformation of a shape must be invertible.");
+        self.transform = transform;
+        self.update_world_inverse();
+    }
+
+    /// Returns the transformation at time `1` of a moving shape.
+    pub fn end_transform(&self) -> Option<Matrix4<T>> {
+        self.end_transform
+    }
+
+    /// Makes the shape move from its transformation at time `0` to
+    /// `end_transform` at time `1`, or stay still with `None`.
+    /// # Panics
+    ///
+    /// This function panics if `end_transform` is not invertible.
+    pub fn set_motion(&mut self, end_transform: Option<Matrix4<T>>) {
+        if let Some(end) = end_transform {
+            assert!(
+                end.is_invertible(),
+                "The transformation of a shape must be invertible."
+            );
+        }
+        self.end_transform = end_transform;
+    }
+
+    /// Returns the transformation from object space to the parent's space
+    /// at `time`, interpolated for moving shapes.
+    pub fn transform_at(&self, time: T) -> Matrix4<T> {
+        match self.end_transform {
+            Some(end) => Matrix4::interpolate(&self.transform, &end, time),
+            None => self.transform,
+        }
+    }
+
+    /// Returns the transformation from the parent's space to object space
+    /// at `time`.
+    pub fn inverse_at(&self, time: T) -> Matrix4<T> {
+        match self.end_transform {
+            Some(_) => self
+                .transform_at(time)
+                .inverse()
+                .expect("The transformation of a shape must be invertible."),
+            None => self.inverse,
+        }
+    }
+
+    /// Returns the transformation from world space to object space through
+    /// the whole parent chain at `time`. Only the motion of this shape is
+    /// followed, not the one of its enclosing groups.
+    pub fn world_inverse_at(&self, time: T) -> Matrix4<T> {
+        match self.end_transform {
+            Some(_) => (self.parent_transform * self.transform_at(time))
+                .inverse()
+                .expect("The transformation of a shape must be invertible."),
+            None => self.world_inverse,
+        }
+    }
+
+    /// Sets the transformation of the enclosing groups.
+    pub fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.parent_transform = parent_transform;
+        self.update_world_inverse();
+    }
+
+    /// Returns the material of the surface.
+    pub fn material(&self) -> &Material<T> {
+        &self.material
+    }
+
+    /// Sets the material of the surface.
+    pub fn set_material(&mut self, material: Material<T>) {
+        self.material = material;
+    }
+
+    /// Returns `true` if the shape blocks the light in the shadow test.
+    pub fn casts_shadow(&self) -> bool {
+        self.casts_shadow
+    }
+
+    /// Sets whether the shape blocks the light in the shadow test.
+    pub fn set_casts_shadow(&mut self, casts_shadow: bool) {
+        self.casts_shadow = casts_shadow;
+    }
+
+    /// Returns `true` if the points of the shape can be in shadow.
+    pub fn receives_shadow(&self) -> bool {
+        self.receives_shadow
+    }
+
+    /// Sets whether the points of the shape can be in shadow.
+    pub fn set_receives_shadow(&mut self, receives_shadow: bool) {
+        self.receives_shadow = receives_shadow;
+    }
+
+    fn update_world_inverse(&mut self) {
+        self.world_inverse = self
+            .world_transform()
+            .inverse()
+            .expect("The transformation of a shape must be invertible.");
+    }
+}
+
+/// A shape placed in the world by a transformation.
+///
+/// Implementors only deal with their object space in [`Shape::local_intersect`]
+/// and [`Shape::local_normal_at`]; [`Shape::intersect`] and [`Shape::normal_at`]
+/// take care of the conversion from and to world space.
+///
+/// A shape in a [`group::Group`] is transformed relative to the group: its
+/// intersections are computed from the ray in the group's object space, while
+/// its normals are converted through the whole parent chain.
+///
+/// A moving shape is intersected where it is at the time of the ray, see
+/// [`Shape::set_motion`].
+pub trait Shape<T: Float>: Debug + Send + Sync {
+    /// Returns the properties shared by every shape.
+    fn base(&self) -> &ShapeBase<T>;
+    /// Returns the mutable properties shared by every shape.
+    fn base_mut(&mut self) -> &mut ShapeBase<T>;
+    /// Returns the box enclosing this shape in object space.
+    fn bounds(&self) -> BoundingBox<T>;
+    /// Appends the intersections of a ray already transformed into object
+    /// space to `xs`, so that a single buffer collects the hits of every
+    /// shape a ray is tested against.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>);
+    /// Returns the intersections of a ray already transformed into object space.
+    fn local_intersect<'a>(&'a self, local_ray: &Ray<T>) -> Vec<Intersection<'a, T>> {
+        let mut xs = Vec::new();
+        self.local_intersect_into(local_ray, &mut xs);
+        xs
+    }
+    /// Returns the normal at a point in object space.
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T>;
+    /// Returns the intersections of each ray of a packet already
+    /// transformed into object space, one list per ray. Shapes tracing
+    /// the whole packet at once, such as groups culling it against their
+    /// bounds, override the default of one ray after the other.
+    fn local_intersect_packet<'a>(
+        &'a self,
+        local_packet: &RayPacket<T>,
+    ) -> Vec<Vec<Intersection<'a, T>>> {
+        local_packet
+            .rays()
+            .map(|ray| self.local_intersect(&ray))
+            .collect()
+    }
+    /// Returns the normal at a point in object space for the intersection `hit`.
+    /// Shapes interpolating their normals over the surface, such as
+    /// smooth triangles, use the `(u, v)` recorded in the hit.
+    fn local_normal_at_hit(
+        &self,
+        local_point: Point3<T>,
+        _hit: &Intersection<'_, T>,
+    ) -> Vector3<T> {
+        self.local_normal_at(local_point)
+    }
+
+    /// Returns the transformation of this shape.
+    fn transform(&self) -> Matrix4<T> {
+        self.base().transform()
+    }
+    /// Sets the transformation of this shape.
+    /// # Panics
+    ///
+    /// This function panics if `transform` is not invertible.
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base_mut().set_transform(transform)
+    }
+    /// Makes this shape move from its transformation at time `0` to
+    /// `end_transform` at time `1`, which a [`Camera`](crate::Camera) with
+    /// a shutter interval blurs. The patterns stay at their place at time
+    /// `0` on the surface.
+    /// # Panics
+    ///
+    /// This function panics if `end_transform` is not invertible.
+    fn set_motion(&mut self, end_transform: Option<Matrix4<T>>) {
+        self.base_mut().set_motion(end_transform)
+    }
+    /// Sets the transformation of the groups enclosing this shape.
+    /// Shapes holding other shapes pass their own world transformation down.
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base_mut().set_parent_transform(parent_transform)
+    }
+    /// Returns the material of this shape.
+    fn material(&self) -> &Material<T> {
+        self.base().material()
+    }
+    /// Sets the material of this shape.
+    /// Shapes made of internal primitives pass the material down to them.
+    fn set_material(&mut self, material: Material<T>) {
+        self.base_mut().set_material(material)
+    }
+    /// Returns `true` if this shape blocks the light in the shadow test.
+    fn casts_shadow(&self) -> bool {
+        self.base().casts_shadow()
+    }
+    /// Sets whether this shape blocks the light, helper geometry such as
+    /// a water surface may let the light through.
+    /// Shapes made of internal primitives pass the flag down to them.
+    fn set_casts_shadow(&mut self, casts_shadow: bool) {
+        self.base_mut().set_casts_shadow(casts_shadow)
+    }
+    /// Returns `true` if the points of this shape can be in shadow.
+    fn receives_shadow(&self) -> bool {
+        self.base().receives_shadow()
+    }
+    /// Sets whether the points of this shape can be in shadow, otherwise
+    /// they are lit by every light.
+    /// Shapes made of internal primitives pass the flag down to them.
+    fn set_receives_shadow(&mut self, receives_shadow: bool) {
+        self.base_mut().set_receives_shadow(receives_shadow)
+    }
+    /// Returns `true` for participating media such as [`volume::Volume`],
+    /// which scatter the light in every direction instead of off a surface.
+    fn is_medium(&self) -> bool {
+        false
+    }
+    /// Returns the box enclosing this shape in the space of its parent.
+    /// The box of a moving shape encloses it at both ends of its motion.
+    fn parent_space_bounds(&self) -> BoundingBox<T> {
+        let mut bounds = self.bounds().transform(&self.transform());
+        if let Some(end) = self.base().end_transform() {
+            bounds.merge(&self.bounds().transform(&end));
+        }
+        bounds
+    }
+    /// Subdivides the shapes held by this shape into a bounding volume
+    /// hierarchy, until there are less than `threshold` shapes per group.
+    /// This does nothing for primitives.
+    fn divide(&mut self, _threshold: usize) {}
+    /// Builds the spatial structures of the given `kind` over the
+    /// primitives of this shape and of the shapes inside it, with about
+    /// `leaf_size` primitives per leaf. Shapes made of a single primitive
+    /// have nothing to build.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    fn build_accelerator(&mut self, _kind: AcceleratorKind, _leaf_size: usize) {}
+    /// Returns the description of this shape in a scene file, without its
+    /// transformation and material, see
+    /// [`Scene::to_value`](crate::Scene::to_value).
+    /// # Errors
+    ///
+    /// The default returns an error, for the shapes no scene file describes.
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Err(unsupported::<Self>())
+    }
+    /// Returns `true` if `other` is this shape, or one of the shapes it holds.
+    fn includes(&self, other: &dyn Shape<T>) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+    /// Converts a point from world space to object space, through the
+    /// enclosing groups.
+    fn world_to_object(&self, world_point: Point3<T>) -> Point3<T> {
+        self.base().world_inverse() * world_point
+    }
+    /// Converts a normal from object space to world space, through the
+    /// enclosing groups.
+    fn normal_to_world(&self, local_normal: Vector3<T>) -> Vector3<T> {
+        (self.base().world_inverse().transpose() * local_normal).normalized()
+    }
+    /// Converts a point from world space to object space at `time`.
+    fn world_to_object_at(&self, world_point: Point3<T>, time: T) -> Point3<T> {
+        self.base().world_inverse_at(time) * world_point
+    }
+    /// Converts a normal from object space to world space at `time`.
+    fn normal_to_world_at(&self, local_normal: Vector3<T>, time: T) -> Vector3<T> {
+        (self.base().world_inverse_at(time).transpose() * local_normal).normalized()
+    }
+    /// Returns the intersections of a ray in world space with this shape,
+    /// where it is at the time of the ray.
+    fn intersect<'a>(&'a self, ray: &Ray<T>) -> Vec<Intersection<'a, T>> {
+        let mut xs = Vec::new();
+        self.append_intersections(ray, &mut xs);
+        xs
+    }
+    /// Replaces the content of `xs` by the intersections of a ray in world
+    /// space with this shape, sorted by `t`. The memory of `xs` is reused,
+    /// so a buffer kept across rays stops allocating once large enough.
+    fn intersect_into<'a>(&'a self, ray: &Ray<T>, xs: &mut Intersections<'a, T>) {
+        xs.refill(|xs| self.append_intersections(ray, xs));
+    }
+    /// Returns `true` if the ray in the parent's space hits a part of this
+    /// shape casting shadows at a `t` within `[0, max_t)`. Unlike
+    /// [`Shape::intersect`], the search may stop at the first such hit.
+    fn occludes(&self, ray: &Ray<T>, max_t: T) -> bool {
+        self.intersect(ray)
+            .iter()
+            .any(|x| x.t() >= T::zero() && x.t() < max_t && x.object().casts_shadow())
+    }
+    /// Appends the intersections of a ray in world space with this shape
+    /// to `xs`, without sorting them.
+    fn append_intersections<'a>(&'a self, ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        self.local_intersect_into(&ray.transform(&self.base().inverse_at(ray.time())), xs)
+    }
+    /// Returns the intersections of each ray of a packet in world space
+    /// with this shape, one list per ray. The packet is transformed at
+    /// once unless this shape moves and its rays are cast at different
+    /// times.
+    fn intersect_packet<'a>(&'a self, packet: &RayPacket<T>) -> Vec<Vec<Intersection<'a, T>>> {
+        let time = match (self.base().end_transform(), packet.common_time()) {
+            (None, _) => T::zero(),
+            (Some(_), Some(time)) => time,
+            (Some(_), None) => return packet.rays().map(|ray| self.intersect(&ray)).collect(),
+        };
+        self.local_intersect_packet(&packet.transform(&self.base().inverse_at(time)))
+    }
+    /// Returns the normal at the `world_point` on the surface, in world space.
+    fn normal_at(&self, world_point: Point3<T>) -> Vector3<T> {
+        let local_normal = self.local_normal_at(self.world_to_object(world_point));
+        self.normal_to_world(local_normal)
+    }
+    /// Returns the normal at the `world_point` of the intersection `hit`, in world space.
+    fn normal_at_hit(&self, world_point: Point3<T>, hit: &Intersection<'_, T>) -> Vector3<T> {
+        self.normal_at_hit_time(world_point, hit, T::zero())
+    }
+    /// Returns the normal at the `world_point` of the intersection `hit` of
+    /// a ray cast at `time`, in world space.
+    fn normal_at_hit_time(
+        &self,
+        world_point: Point3<T>,
+        hit: &Intersection<'_, T>,
+        time: T,
+    ) -> Vector3<T> {
+        let local_point = self.world_to_object_at(world_point, time);
+        let local_normal = self.local_normal_at_hit(local_point, hit);
+        self.normal_to_world_at(local_normal, time)
+    }
+}
+
+/// Shapes are compared by identity: two references are equal only if they
+/// point to the same object.
+impl<T: Float> PartialEq for dyn Shape<T> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+}
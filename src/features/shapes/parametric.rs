@@ -0,0 +1,181 @@
+use crate::{
+    features::{intersections::Intersection, materials::Material, rays::Ray},
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, triangle::SmoothTriangle, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A surface given by a parametric function `(u, v) -> Point3` over the
+/// unit square `[0, 1] x [0, 1]`, tessellated into smooth triangles.
+///
+/// The vertex normals are the area-weighted average of the normals of the
+/// faces sharing the vertex, so the function does not need to provide its
+/// derivatives. Intersections name the triangle being hit, whose parent space is the
+/// object space of the surface.
+pub struct ParametricSurface<T: Float> {
+    base: ShapeBase<T>,
+    resolution: (usize, usize),
+    triangles: Vec<SmoothTriangle<T>>,
+    bounds: BoundingBox<T>,
+}
+
+impl<T: Float> ParametricSurface<T> {
+    /// Samples `f` on a grid of `u_steps` by `v_steps` cells, each cell
+    /// split into two triangles. Degenerate triangles, such as the ones
+    /// collapsing at the poles of a sphere, are skipped.
+    /// # Panics
+    ///
+    /// This function panics if `u_steps` or `v_steps` is zero.
+    pub fn new(f: impl Fn(T, T) -> Point3<T>, u_steps: usize, v_steps: usize) -> Self {
+        assert!(
+            u_steps > 0 && v_steps > 0,
+            "The resolution of a parametric surface must be at least one step."
+        );
+        let to_param = |i: usize, steps: usize| T::from_f64(i as f64 / steps as f64);
+        let points: Vec<Point3<T>> = (0..=u_steps)
+            .flat_map(|i| (0..=v_steps).map(move |j| (i, j)))
+            .map(|(i, j)| f(to_param(i, u_steps), to_param(j, v_steps)))
+            .collect();
+        Self {
+            base: ShapeBase::default(),
+            resolution: (u_steps, v_steps),
+            triangles: tessellate(&points, u_steps, v_steps),
+            bounds: BoundingBox::from_points(points),
+        }
+    }
+
+    /// Returns the number of grid cells along `u` and `v`.
+    pub fn resolution(&self) -> (usize, usize) {
+        self.resolution
+    }
+
+    /// Returns the triangles tessellating this surface.
+    pub fn triangles(&self) -> &[SmoothTriangle<T>] {
+        &self.triangles
+    }
+
+    /// Passes the world transformation down to the triangles.
+    fn update_triangles(&mut self) {
+        let world_transform = self.base.world_transform();
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_parent_transform(world_transform);
+        }
+    }
+}
+
+/// Tessellates a grid of `(u_steps + 1) x (v_steps + 1)` points, stored with
+/// `v` varying fastest, into two smooth triangles per cell.
+///
+/// The vertex normals are the area-weighted average of the normals of the
+/// faces sharing the vertex. Degenerate triangles are skipped.
+pub(crate) fn tessellate<T: Float>(
+    points: &[Point3<T>],
+    u_steps: usize,
+    v_steps: usize,
+) -> Vec<SmoothTriangle<T>> {
+    let columns = v_steps + 1;
+    // Two triangles per cell, as indices into `points`.
+    let faces: Vec<[usize; 3]> = (0..u_steps)
+        .flat_map(|i| (0..v_steps).map(move |j| (i, j)))
+        .flat_map(|(i, j)| {
+            let p00 = i * columns + j;
+            let (p10, p01, p11) = (p00 + columns, p00 + 1, p00 + columns + 1);
+            [[p00, p10, p11], [p00, p11, p01]]
+        })
+        .filter(|&[a, b, c]| {
+            let area = (points[c] - points[a]).cross(&(points[b] - points[a]));
+            area.magnitude() > T::epsilon()
+        })
+        .collect();
+    // The unnormalized face normal is proportional to the area of the face.
+    let mut normals = vec![Vector3::zero(); points.len()];
+    for &[a, b, c] in faces.iter() {
+        let n = (points[c] - points[a]).cross(&(points[b] - points[a]));
+        for idx in [a, b, c] {
+            normals[idx] += n;
+        }
+    }
+    faces
+        .into_iter()
+        .map(|[a, b, c]| {
+            SmoothTriangle::new(
+                points[a],
+                points[b],
+                points[c],
+                normals[a].normalized(),
+                normals[b].normalized(),
+                normals[c].normalized(),
+            )
+        })
+        .collect()
+}
+
+impl<T: Float> Shape<T> for ParametricSurface<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.bounds
+    }
+
+    /// The triangles are in the object space of the surface, which is
+    /// their parent space.
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base.set_transform(transform);
+        self.update_triangles();
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base.set_parent_transform(parent_transform);
+        self.update_triangles();
+    }
+
+    /// The intersections name the triangles, which are shaded with the
+    /// material of the surface.
+    fn set_material(&mut self, material: Material<T>) {
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_material(material.clone());
+        }
+        self.base.set_material(material);
+    }
+
+    fn set_casts_shadow(&mut self, casts_shadow: bool) {
+        self.base.set_casts_shadow(casts_shadow);
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_casts_shadow(casts_shadow);
+        }
+    }
+
+    fn set_receives_shadow(&mut self, receives_shadow: bool) {
+        self.base.set_receives_shadow(receives_shadow);
+        for triangle in self.triangles.iter_mut() {
+            triangle.set_receives_shadow(receives_shadow);
+        }
+    }
+
+    fn includes(&self, other: &dyn Shape<T>) -> bool {
+        std::ptr::addr_eq(self, other) || self.triangles.iter().any(|t| std::ptr::addr_eq(t, other))
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        if !self.bounds.intersects(local_ray) {
+            return;
+        }
+        for triangle in &self.triangles {
+            triangle.local_intersect_into(local_ray, xs);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// The intersections name the triangles, the normal must be asked to them.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        panic!("The normal of a parametric surface is given by its triangles.")
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Matrix4, Point3, Vector3, EPSILON,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// An infinite plane, the xz plane in its object space.
+pub struct Plane<T: Float> {
+    base: ShapeBase<T>,
+}
+
+impl<T: Float> Default for Plane<T> {
+    fn default() -> Self {
+        Self {
+            base: ShapeBase::default(),
+        }
+    }
+}
+
+impl<T: Float> Plane<T> {
+    pub fn new(transform: Matrix4<T>) -> Self {
+        let mut plane = Self::default();
+        plane.set_transform(transform);
+        plane
+    }
+}
+
+impl<T: Float> Shape<T> for Plane<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::new(
+            Point3::new(T::neg_infinity(), T::zero(), T::neg_infinity()),
+            Point3::new(T::infinity(), T::zero(), T::infinity()),
+        )
+    }
+
+    /// A ray parallel to the plane, including a coplanar one, never intersects it.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let direction = local_ray.direction();
+        if direction.y.abs() < T::from_f64(EPSILON) {
+            return;
+        }
+        let t = -local_ray.origin().y / direction.y;
+        xs.push(Intersection::new(t, self));
+    }
+
+    /// The normal is the same everywhere on the plane.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        Vector3::unit_y()
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(SceneValue::object([("add", "plane".into())]))
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Matrix4, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A unit sphere centered at the origin of its object space.
+/// It is placed in the world by its transformation.
+pub struct Sphere<T: Float> {
+    base: ShapeBase<T>,
+}
+
+impl<T: Float> Default for Sphere<T> {
+    fn default() -> Self {
+        Self {
+            base: ShapeBase::default(),
+        }
+    }
+}
+
+impl<T: Float> Sphere<T> {
+    pub fn new(transform: Matrix4<T>) -> Self {
+        let mut sphere = Self::default();
+        sphere.set_transform(transform);
+        sphere
+    }
+}
+
+impl<T: Float> Shape<T> for Sphere<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::new(
+            Point3::new(-T::one(), -T::one(), -T::one()),
+            Point3::new(T::one(), T::one(), T::one()),
+        )
+    }
+
+    /// Returns both intersections of the ray with this sphere, sorted by `t`.
+    /// A tangent ray gives two identical intersections, a missing ray none.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let sphere_to_ray = local_ray.origin() - Point3::origin();
+        let a = local_ray.direction().dot(&local_ray.direction());
+        let b = T::two() * local_ray.direction().dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - T::one();
+        let discriminant = b * b - T::four() * a * c;
+        if discriminant < T::zero() {
+            return;
+        }
+        let sqrt_disc = discriminant.sqrt();
+        let t1 = (-b - sqrt_disc) / (T::two() * a);
+        let t2 = (-b + sqrt_disc) / (T::two() * a);
+        xs.extend([Intersection::new(t1, self), Intersection::new(t2, self)]);
+    }
+
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        local_point - Point3::origin()
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(SceneValue::object([("add", "sphere".into())]))
+    }
+}
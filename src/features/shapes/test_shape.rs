@@ -0,0 +1,52 @@
+use std::sync::Mutex;
+
+use crate::{
+    features::{intersections::Intersection, rays::Ray},
+    Float, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+/// A shape without geometry, recording the ray it was intersected with in
+/// object space. It is meant to verify the world/object space plumbing of
+/// the [`Shape`] trait, for example when implementing custom shapes.
+#[derive(Debug, Default)]
+pub struct TestShape<T: Float> {
+    base: ShapeBase<T>,
+    saved_ray: Mutex<Option<Ray<T>>>,
+}
+
+impl<T: Float> TestShape<T> {
+    /// Returns the ray in object space of the last intersection test.
+    pub fn saved_ray(&self) -> Option<Ray<T>> {
+        *self.saved_ray.lock().unwrap()
+    }
+}
+
+impl<T: Float> Shape<T> for TestShape<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    /// The bounds of a unit sphere, to test the transformation of bounds.
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::new(
+            Point3::new(-T::one(), -T::one(), -T::one()),
+            Point3::new(T::one(), T::one(), T::one()),
+        )
+    }
+
+    /// Records the ray and never reports any intersection.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, _xs: &mut Vec<Intersection<'a, T>>) {
+        *self.saved_ray.lock().unwrap() = Some(*local_ray);
+    }
+
+    /// The normal is the vector from the origin to the point.
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        local_point - Point3::origin()
+    }
+}
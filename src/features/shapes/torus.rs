@@ -0,0 +1,110 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        math::roots::solve_quartic,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Point3, Vector3,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A torus centered at the origin of its object space, lying in the xz
+/// plane around the y axis. The center of its tube is at a distance
+/// `major_radius` from the origin, and the tube has a radius of `minor_radius`.
+pub struct Torus<T: Float> {
+    base: ShapeBase<T>,
+    major_radius: T,
+    minor_radius: T,
+}
+
+impl<T: Float> Default for Torus<T> {
+    /// A torus with a major radius of 1 and a minor radius of 0.25.
+    fn default() -> Self {
+        Self::new(T::one(), T::one() / T::four())
+    }
+}
+
+impl<T: Float> Torus<T> {
+    pub fn new(major_radius: T, minor_radius: T) -> Self {
+        Self {
+            base: ShapeBase::default(),
+            major_radius,
+            minor_radius,
+        }
+    }
+
+    /// Returns the distance from the origin to the center of the tube.
+    pub fn major_radius(&self) -> T {
+        self.major_radius
+    }
+
+    /// Returns the radius of the tube.
+    pub fn minor_radius(&self) -> T {
+        self.minor_radius
+    }
+}
+
+impl<T: Float> Shape<T> for Torus<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        let outer = self.major_radius + self.minor_radius;
+        BoundingBox::new(
+            Point3::new(-outer, -self.minor_radius, -outer),
+            Point3::new(outer, self.minor_radius, outer),
+        )
+    }
+
+    /// Solves the quartic equation of the ray substituted into the implicit
+    /// torus equation `(x^2 + y^2 + z^2 + R^2 - r^2)^2 = 4R^2(x^2 + z^2)`.
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        let o = local_ray.origin().to_vec();
+        let d = local_ray.direction();
+        let r2 = self.major_radius * self.major_radius;
+        let four_r2 = T::four() * r2;
+        let sum_d_sqrd = d.dot(&d);
+        let e = o.dot(&o) - r2 - self.minor_radius * self.minor_radius;
+        let f = o.dot(&d);
+        let c4 = sum_d_sqrd * sum_d_sqrd;
+        let c3 = T::four() * sum_d_sqrd * f;
+        let c2 = T::two() * sum_d_sqrd * e + T::four() * f * f + four_r2 * d.y * d.y;
+        let c1 = T::four() * f * e + T::two() * four_r2 * o.y * d.y;
+        let c0 = e * e - four_r2 * (self.minor_radius * self.minor_radius - o.y * o.y);
+        xs.extend(
+            solve_quartic(c4, c3, c2, c1, c0)
+                .into_iter()
+                .map(|t| Intersection::new(t, self)),
+        );
+    }
+
+    /// The gradient of the implicit torus equation.
+    fn local_normal_at(&self, local_point: Point3<T>) -> Vector3<T> {
+        let p = local_point.to_vec();
+        let r2 = self.major_radius * self.major_radius;
+        let param_sqrd = r2 + self.minor_radius * self.minor_radius;
+        let sum_sqrd = p.dot(&p);
+        Vector3::new(
+            p.x * (sum_sqrd - param_sqrd),
+            p.y * (sum_sqrd - param_sqrd + T::two() * r2),
+            p.z * (sum_sqrd - param_sqrd),
+        )
+        .normalized()
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        Ok(SceneValue::object([
+            ("add", "torus".into()),
+            ("major-radius", SceneValue::number(self.major_radius)),
+            ("minor-radius", SceneValue::number(self.minor_radius)),
+        ]))
+    }
+}
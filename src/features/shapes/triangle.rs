@@ -0,0 +1,239 @@
+use crate::{
+    features::{
+        intersections::Intersection,
+        rays::Ray,
+        scene::{value::SceneValue, SceneError},
+    },
+    Float, Point3, Vector3, EPSILON,
+};
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A flat triangle defined by three points.
+pub struct Triangle<T: Float> {
+    base: ShapeBase<T>,
+    p1: Point3<T>,
+    p2: Point3<T>,
+    p3: Point3<T>,
+    /// Precomputed edges `p2 - p1` and `p3 - p1`.
+    e1: Vector3<T>,
+    e2: Vector3<T>,
+    /// Precomputed normal, constant over the triangle.
+    normal: Vector3<T>,
+}
+
+impl<T: Float> Triangle<T> {
+    pub fn new(p1: Point3<T>, p2: Point3<T>, p3: Point3<T>) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Self {
+            base: ShapeBase::default(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).normalized(),
+        }
+    }
+
+    /// Returns the vertices of this [`Triangle`].
+    pub fn vertices(&self) -> [Point3<T>; 3] {
+        [self.p1, self.p2, self.p3]
+    }
+
+    /// Returns the edges `p2 - p1` and `p3 - p1` of this [`Triangle`].
+    pub fn edges(&self) -> [Vector3<T>; 2] {
+        [self.e1, self.e2]
+    }
+
+    /// Returns the normal of this [`Triangle`].
+    pub fn normal(&self) -> Vector3<T> {
+        self.normal
+    }
+
+    /// Returns the intersection of the ray with this triangle as `(t, u, v)`.
+    fn intersect_uv(&self, ray: &Ray<T>) -> Option<(T, T, T)> {
+        intersect_triangle(self.p1, self.e1, self.e2, ray)
+    }
+}
+
+/// Möller–Trumbore ray-triangle intersection of the triangle at `p1` with
+/// the edges `e1` and `e2`, returning `(t, u, v)` where `u` and `v` are the
+/// barycentric coordinates of the intersection relative to `p2` and `p3`.
+pub(crate) fn intersect_triangle<T: Float>(
+    p1: Point3<T>,
+    e1: Vector3<T>,
+    e2: Vector3<T>,
+    ray: &Ray<T>,
+) -> Option<(T, T, T)> {
+    let dir_cross_e2 = ray.direction().cross(&e2);
+    let det = e1.dot(&dir_cross_e2);
+    // The ray is parallel to the triangle.
+    if det.abs() < T::from_f64(EPSILON) {
+        return None;
+    }
+    let f = T::one() / det;
+    let p1_to_origin = ray.origin() - p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if u < T::zero() || u > T::one() {
+        return None;
+    }
+    let origin_cross_e1 = p1_to_origin.cross(&e1);
+    let v = f * ray.direction().dot(&origin_cross_e1);
+    if v < T::zero() || u + v > T::one() {
+        return None;
+    }
+    Some((f * e2.dot(&origin_cross_e1), u, v))
+}
+
+impl<T: Float> Shape<T> for Triangle<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        BoundingBox::from_points(self.vertices())
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        xs.extend(
+            self.intersect_uv(local_ray)
+                .map(|(t, u, v)| Intersection::with_uv(t, self, u, v)),
+        );
+    }
+
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        self.normal
+    }
+
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        let [p1, p2, p3] = self
+            .vertices()
+            .map(|p| SceneValue::numbers([p.x, p.y, p.z]));
+        Ok(SceneValue::object([
+            ("add", "triangle".into()),
+            ("p1", p1),
+            ("p2", p2),
+            ("p3", p3),
+        ]))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A triangle with a normal at each vertex. The normal at an intersection
+/// is interpolated from the vertex normals, which smooths out the facets of
+/// a mesh.
+pub struct SmoothTriangle<T: Float> {
+    triangle: Triangle<T>,
+    n1: Vector3<T>,
+    n2: Vector3<T>,
+    n3: Vector3<T>,
+    /// Texture coordinates at the vertices, if any.
+    texture_coords: Option<[(T, T); 3]>,
+}
+
+impl<T: Float> SmoothTriangle<T> {
+    pub fn new(
+        p1: Point3<T>,
+        p2: Point3<T>,
+        p3: Point3<T>,
+        n1: Vector3<T>,
+        n2: Vector3<T>,
+        n3: Vector3<T>,
+    ) -> Self {
+        Self {
+            triangle: Triangle::new(p1, p2, p3),
+            n1,
+            n2,
+            n3,
+            texture_coords: None,
+        }
+    }
+
+    /// Returns this triangle with texture coordinates at its vertices.
+    pub fn with_texture_coords(mut self, texture_coords: [(T, T); 3]) -> Self {
+        self.texture_coords = Some(texture_coords);
+        self
+    }
+
+    /// Returns the vertices of this [`SmoothTriangle`].
+    pub fn vertices(&self) -> [Point3<T>; 3] {
+        self.triangle.vertices()
+    }
+
+    /// Returns the normals at the vertices of this [`SmoothTriangle`].
+    pub fn normals(&self) -> [Vector3<T>; 3] {
+        [self.n1, self.n2, self.n3]
+    }
+
+    /// Returns the texture coordinates at the vertices of this [`SmoothTriangle`].
+    pub fn texture_coords(&self) -> Option<[(T, T); 3]> {
+        self.texture_coords
+    }
+
+    /// Returns the texture coordinates at the intersection `hit`, interpolated
+    /// like the normals.
+    pub fn texture_coords_at(&self, hit: &Intersection<'_, T>) -> Option<(T, T)> {
+        let [(s1, t1), (s2, t2), (s3, t3)] = self.texture_coords?;
+        let (u, v) = hit.uv()?;
+        let w = T::one() - u - v;
+        Some((s2 * u + s3 * v + s1 * w, t2 * u + t3 * v + t1 * w))
+    }
+}
+
+impl<T: Float> Shape<T> for SmoothTriangle<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.triangle.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.triangle.base
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.triangle.bounds()
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        xs.extend(
+            self.triangle
+                .intersect_uv(local_ray)
+                .map(|(t, u, v)| Intersection::with_uv(t, self, u, v)),
+        );
+    }
+
+    /// Without the position on the surface, only the flat normal is known.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        self.triangle.normal
+    }
+
+    fn local_normal_at_hit(&self, local_point: Point3<T>, hit: &Intersection<'_, T>) -> Vector3<T> {
+        match hit.uv() {
+            Some((u, v)) => self.n2 * u + self.n3 * v + self.n1 * (T::one() - u - v),
+            None => self.local_normal_at(local_point),
+        }
+    }
+
+    /// The texture coordinates are not written.
+    fn scene_value(&self) -> Result<SceneValue, SceneError> {
+        let [p1, p2, p3] = self
+            .vertices()
+            .map(|p| SceneValue::numbers([p.x, p.y, p.z]));
+        let [n1, n2, n3] = self.normals().map(|n| SceneValue::numbers([n.x, n.y, n.z]));
+        Ok(SceneValue::object([
+            ("add", "smooth-triangle".into()),
+            ("p1", p1),
+            ("p2", p2),
+            ("p3", p3),
+            ("n1", n1),
+            ("n2", n2),
+            ("n3", n3),
+        ]))
+    }
+}
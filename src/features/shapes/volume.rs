@@ -0,0 +1,128 @@
+use crate::{
+    features::{intersections::Intersection, math::sampling::with_thread_rng, rays::Ray},
+    Float, Matrix4, Point3, Vector3,
+};
+
+use rand::Rng;
+
+use super::{bounds::BoundingBox, Shape, ShapeBase};
+
+#[derive(Debug)]
+/// A participating medium of constant `density` filling a `boundary`
+/// shape, such as smoke or a cloud. A ray crossing the medium scatters at
+/// a random distance, exponentially distributed with the density: the
+/// denser the medium, the sooner. Rays may also cross it without scattering.
+///
+/// The boundary is transformed relative to the volume, like the children
+/// of a [`super::group::Group`]. Only the first span of the ray inside the
+/// boundary is considered, which is exact for convex boundaries.
+///
+/// The random numbers are drawn from the generator of the current thread,
+/// see [`crate::features::math::sampling::seed_thread_rng`].
+pub struct Volume<T: Float> {
+    base: ShapeBase<T>,
+    boundary: Box<dyn Shape<T>>,
+    density: T,
+}
+
+impl<T: Float> Volume<T> {
+    /// # Panics
+    ///
+    /// This function panics if `density` is not positive.
+    pub fn new(boundary: impl Shape<T> + 'static, density: T) -> Self {
+        Self::from_boxed(Box::new(boundary), density)
+    }
+
+    /// Returns a volume filling a boundary already boxed.
+    /// # Panics
+    ///
+    /// This function panics if `density` is not positive.
+    pub fn from_boxed(boundary: Box<dyn Shape<T>>, density: T) -> Self {
+        assert!(
+            density > T::zero(),
+            "The density of a volume must be positive."
+        );
+        Self {
+            base: ShapeBase::default(),
+            boundary,
+            density,
+        }
+    }
+
+    pub fn boundary(&self) -> &dyn Shape<T> {
+        self.boundary.as_ref()
+    }
+
+    pub fn density(&self) -> T {
+        self.density
+    }
+}
+
+impl<T: Float> Shape<T> for Volume<T> {
+    fn base(&self) -> &ShapeBase<T> {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut ShapeBase<T> {
+        &mut self.base
+    }
+
+    fn set_transform(&mut self, transform: Matrix4<T>) {
+        self.base.set_transform(transform);
+        self.boundary
+            .set_parent_transform(self.base.world_transform());
+    }
+
+    fn set_parent_transform(&mut self, parent_transform: Matrix4<T>) {
+        self.base.set_parent_transform(parent_transform);
+        self.boundary
+            .set_parent_transform(self.base.world_transform());
+    }
+
+    fn bounds(&self) -> BoundingBox<T> {
+        self.boundary.parent_space_bounds()
+    }
+
+    fn is_medium(&self) -> bool {
+        true
+    }
+
+    fn local_intersect_into<'a>(&'a self, local_ray: &Ray<T>, xs: &mut Vec<Intersection<'a, T>>) {
+        // The hits of the boundary are gathered after the ones already in
+        // `xs`, then replaced by the hit of the medium.
+        let start = xs.len();
+        self.boundary.append_intersections(local_ray, xs);
+        xs[start..].sort_by(|a, b| {
+            a.t()
+                .partial_cmp(&b.t())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let bounds = match xs[start..] {
+            [enter, exit, ..] => Some((enter.t(), exit.t())),
+            _ => None,
+        };
+        xs.truncate(start);
+        let Some((enter, exit)) = bounds else {
+            return;
+        };
+        let enter = enter.max(T::zero());
+        if enter >= exit {
+            return;
+        }
+        let speed = local_ray.direction().magnitude();
+        let distance_inside = (exit - enter) * speed;
+        let u: f64 = with_thread_rng(|rng| rng.gen());
+        // `1 - u` lies in `(0, 1]`, its logarithm is finite.
+        let hit_distance = -T::from_f64((1.0 - u).ln()) / self.density;
+        if hit_distance > distance_inside {
+            return;
+        }
+        xs.push(Intersection::new(enter + hit_distance / speed, self));
+    }
+
+    /// A medium has no surface, the normal is arbitrary: media are shaded
+    /// without it.
+    fn local_normal_at(&self, _local_point: Point3<T>) -> Vector3<T> {
+        Vector3::new(T::one(), T::zero(), T::zero())
+    }
+}
@@ -0,0 +1,87 @@
+//! The split of the canvas into tiles, the units of work of a render. The
+//! pixels of a tile are close in the image and usually in the scene, so
+//! they reuse the same objects in the caches, and the tiles are rendered
+//! in an order chosen for the previews.
+use super::camera::PixelBounds;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The order in which the tiles of a canvas are rendered.
+pub enum TileOrder {
+    /// Row by row from the top-left corner, like the pixels of the book.
+    #[default]
+    Scanline,
+    /// From the center of the canvas outwards, ring by ring, so that the
+    /// subject of the image shows first.
+    Spiral,
+    /// Along a Hilbert curve, each tile being next to the previous one.
+    Hilbert,
+}
+
+/// Returns the tiles of at most `size` x `size` pixels covering `region`,
+/// in `order`.
+/// # Panics
+///
+/// This function panics if `size` is zero.
+pub fn tiles(region: &PixelBounds, size: usize, order: TileOrder) -> Vec<PixelBounds> {
+    assert!(size > 0, "The tiles cannot be empty.");
+    let columns = region.width().div_ceil(size);
+    let rows = region.height().div_ceil(size);
+    let mut cells: Vec<(usize, usize)> = (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column, row)))
+        .collect();
+    match order {
+        TileOrder::Scanline => {}
+        TileOrder::Spiral => {
+            // Twice the coordinates, so that the center of an even number
+            // of tiles lies on an integer.
+            let (cx, cy) = (columns as i64 - 1, rows as i64 - 1);
+            cells.sort_by(|&a, &b| {
+                let key = |(column, row): (usize, usize)| {
+                    let (dx, dy) = (2 * column as i64 - cx, 2 * row as i64 - cy);
+                    let angle = (dy as f64).atan2(dx as f64);
+                    (dx.abs().max(dy.abs()), angle)
+                };
+                let ((ring_a, angle_a), (ring_b, angle_b)) = (key(a), key(b));
+                ring_a.cmp(&ring_b).then(angle_a.total_cmp(&angle_b))
+            });
+        }
+        TileOrder::Hilbert => {
+            let n = columns.max(rows).next_power_of_two();
+            cells.sort_by_key(|&(column, row)| hilbert_index(n, column, row));
+        }
+    }
+    cells
+        .into_iter()
+        .map(|(column, row)| {
+            let (min_x, min_y) = (region.min_x + column * size, region.min_y + row * size);
+            PixelBounds::new(
+                min_x,
+                min_y,
+                (min_x + size).min(region.max_x),
+                (min_y + size).min(region.max_y),
+            )
+        })
+        .collect()
+}
+
+/// Returns the position of the cell `(x, y)` along the Hilbert curve
+/// filling a square of `n` x `n` cells, `n` being a power of two.
+fn hilbert_index(n: usize, mut x: usize, mut y: usize) -> usize {
+    let mut index = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = usize::from(x & s > 0);
+        let ry = usize::from(y & s > 0);
+        index += s * s * ((3 * rx) ^ ry);
+        // Rotate the quadrant so that the curve continues.
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - (x & (s - 1));
+                y = s - 1 - (y & (s - 1));
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    index
+}
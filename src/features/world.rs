@@ -0,0 +1,445 @@
+//! Implementation of `World`, the scene to render: the shapes and the
+//! lights illuminating them.
+use std::sync::Arc;
+
+use crate::{
+    features::{
+        checkpoint::fingerprint,
+        colors::Color,
+        environment::EnvironmentMap,
+        fog::Fog,
+        intersections::{Computations, Intersection, Intersections},
+        lights::{phong_terms, PointLight},
+        materials::Material,
+        patterns::Pattern,
+        progress::count_shadow_ray,
+        rays::{Ray, RayPacket},
+        settings::RenderSettings,
+        shapes::{
+            accelerator::{Accelerator, AcceleratorKind},
+            bounds::BoundingBox,
+            group::Children,
+            sphere::Sphere,
+            Shape,
+        },
+    },
+    Float, Matrix4, Point3, Vector3, EPSILON,
+};
+
+/// The number of bounces followed by default for the reflected rays, which
+/// stops the recursion between facing mirrors.
+pub const MAX_DEPTH: usize = 5;
+
+#[derive(Debug)]
+/// The collection of the shapes and lights of a scene.
+pub struct World<T: Float> {
+    objects: Children<T>,
+    lights: Vec<PointLight<T>>,
+    /// The offset of the points tested for shadows off the surfaces.
+    shadow_bias: T,
+    fog: Option<Fog<T>>,
+    /// The environment seen by the rays hitting nothing, looked up by
+    /// their direction.
+    skybox: Option<Arc<dyn Pattern<T>>>,
+    environment: Option<Arc<EnvironmentMap<T>>>,
+    /// The spatial structure over the objects, dropped when they change.
+    accelerator: Option<Accelerator<T>>,
+}
+
+impl<T: Float> Default for World<T> {
+    /// The canonical world of the book's tests: two concentric spheres,
+    /// the inner one half the size of the outer one, lit by a white light
+    /// at `(-10, 10, -10)`.
+    fn default() -> Self {
+        let mut world = Self::new();
+        let f = T::from_f64;
+        world.add_light(PointLight::new(
+            Point3::new(f(-10.0), f(10.0), f(-10.0)),
+            Color::new(T::one(), T::one(), T::one()),
+        ));
+        let mut outer = Sphere::default();
+        outer.set_material(
+            Material::default()
+                .with_color(Color::new(f(0.8), T::one(), f(0.6)))
+                .with_diffuse(f(0.7))
+                .with_specular(f(0.2)),
+        );
+        world.add_object(outer);
+        let mut inner = Sphere::default();
+        inner.set_transform(Matrix4::scaling(f(0.5), f(0.5), f(0.5)));
+        world.add_object(inner);
+        world
+    }
+}
+
+impl<T: Float> World<T> {
+    /// Returns a world with neither objects nor lights.
+    pub fn new() -> Self {
+        Self {
+            objects: Vec::new(),
+            lights: Vec::new(),
+            shadow_bias: T::from_f64(EPSILON),
+            fog: None,
+            skybox: None,
+            environment: None,
+            accelerator: None,
+        }
+    }
+
+    /// Adds a shape to this world.
+    pub fn add_object(&mut self, object: impl Shape<T> + 'static) {
+        self.add_boxed_object(Box::new(object));
+    }
+
+    /// Adds a shape already boxed to this world.
+    pub fn add_boxed_object(&mut self, object: Box<dyn Shape<T>>) {
+        self.objects.push(object);
+        self.accelerator = None;
+    }
+
+    /// Builds the spatial structures of the given `kind` over the objects
+    /// and inside them, with about `leaf_size` primitives per leaf. Adding
+    /// or modifying objects drops the structure over the objects; build it
+    /// again once the scene is complete.
+    /// # Panics
+    ///
+    /// This function panics if `leaf_size` is zero.
+    pub fn build_accelerator(&mut self, kind: AcceleratorKind, leaf_size: usize) {
+        for object in self.objects.iter_mut() {
+            object.build_accelerator(kind, leaf_size);
+        }
+        let bounds: Vec<BoundingBox<T>> = self
+            .objects
+            .iter()
+            .map(|object| object.parent_space_bounds())
+            .collect();
+        self.accelerator = Some(Accelerator::build(kind, &bounds, leaf_size));
+    }
+
+    /// Returns the spatial structure over the objects, if built.
+    pub fn accelerator(&self) -> Option<&Accelerator<T>> {
+        self.accelerator.as_ref()
+    }
+
+    /// Adds a light to this world.
+    pub fn add_light(&mut self, light: PointLight<T>) {
+        self.lights.push(light);
+    }
+
+    /// Returns the objects of this [`World`].
+    pub fn objects(&self) -> &[Box<dyn Shape<T>>] {
+        &self.objects
+    }
+
+    /// Returns the objects of this [`World`], to modify them in place.
+    pub fn objects_mut(&mut self) -> &mut [Box<dyn Shape<T>>] {
+        self.accelerator = None;
+        &mut self.objects
+    }
+
+    /// Returns the lights of this [`World`].
+    pub fn lights(&self) -> &[PointLight<T>] {
+        &self.lights
+    }
+
+    /// Returns the lights of this [`World`], to replace or modify them.
+    pub fn lights_mut(&mut self) -> &mut Vec<PointLight<T>> {
+        &mut self.lights
+    }
+
+    /// Returns the offset of the points tested for shadows, [`EPSILON`] by default.
+    pub fn shadow_bias(&self) -> T {
+        self.shadow_bias
+    }
+
+    /// Sets the offset of the points tested for shadows off the surfaces.
+    /// Too small, the surfaces shadow themselves with acne; too large, the
+    /// shadows detach from the objects casting them. The default suits
+    /// scenes of a few units, scale it along with the scene.
+    pub fn set_shadow_bias(&mut self, shadow_bias: T) {
+        self.shadow_bias = shadow_bias;
+    }
+
+    /// Takes the part of `settings` which belongs to the scene: the
+    /// `epsilon` becomes the shadow bias.
+    pub fn apply_settings(&mut self, settings: &RenderSettings) {
+        self.set_shadow_bias(T::from_f64(settings.epsilon));
+    }
+
+    /// Returns the fog filling this [`World`], if any.
+    pub fn fog(&self) -> Option<&Fog<T>> {
+        self.fog.as_ref()
+    }
+
+    /// Sets the fog filling this world, `None` for a clear scene.
+    pub fn set_fog(&mut self, fog: Option<Fog<T>>) {
+        self.fog = fog;
+    }
+
+    /// Returns the environment surrounding this [`World`], if any.
+    pub fn skybox(&self) -> Option<&dyn Pattern<T>> {
+        self.skybox.as_deref()
+    }
+
+    /// Sets the environment surrounding this world, `None` for a black
+    /// background. The pattern is looked up at the unit direction of the
+    /// rays as a point, e.g. a [`CubeMap`](crate::CubeMap) of six images or
+    /// a spherical [`TextureMap`](crate::TextureMap); its transformation
+    /// turns the whole environment.
+    pub fn set_skybox(&mut self, skybox: Option<Arc<dyn Pattern<T>>>) {
+        self.skybox = skybox;
+    }
+
+    /// Returns the environment map lighting this [`World`], if any.
+    pub fn environment(&self) -> Option<&EnvironmentMap<T>> {
+        self.environment.as_deref()
+    }
+
+    /// Sets the environment map lighting this world, `None` to light it by
+    /// its lights only. The path tracer samples it as a light source; it is
+    /// also the background of the rays hitting nothing, in place of the
+    /// skybox.
+    pub fn set_environment(&mut self, environment: Option<Arc<EnvironmentMap<T>>>) {
+        self.environment = environment;
+    }
+
+    /// Returns a hash of the shapes, lights and environment of this world,
+    /// see [`Checkpoint`](crate::Checkpoint).
+    pub(crate) fn fingerprint(&self) -> u64 {
+        fingerprint(&[
+            &self.objects,
+            &self.lights,
+            &self.shadow_bias,
+            &self.fog,
+            &self.skybox,
+            &self.environment,
+        ])
+    }
+
+    /// Returns the color of the environment in `direction`: the environment
+    /// map, or else the skybox, or else black.
+    pub fn background_at(&self, direction: Vector3<T>) -> Color<T> {
+        if let Some(environment) = &self.environment {
+            return environment.radiance(direction);
+        }
+        match &self.skybox {
+            Some(skybox) => {
+                let d = direction.normalized();
+                skybox.pattern_at(Point3::new(d.x, d.y, d.z))
+            }
+            None => Color::default(),
+        }
+    }
+
+    /// Returns the intersections of a ray with every object, sorted by `t`.
+    pub fn intersect(&self, ray: &Ray<T>) -> Intersections<'_, T> {
+        let mut xs = Intersections::default();
+        self.intersect_into(ray, &mut xs);
+        xs
+    }
+
+    /// Replaces the content of `xs` by the intersections of a ray with
+    /// every object, sorted by `t`. Render loops keep one buffer per thread
+    /// and reuse its memory for every ray.
+    pub fn intersect_into<'a>(&'a self, ray: &Ray<T>, xs: &mut Intersections<'a, T>) {
+        xs.refill(|xs| match &self.accelerator {
+            Some(accelerator) => {
+                accelerator.visit(ray, |i| {
+                    self.objects[i].append_intersections(ray, xs);
+                    false
+                });
+            }
+            None => {
+                for object in &self.objects {
+                    object.append_intersections(ray, xs);
+                }
+            }
+        });
+    }
+
+    /// Returns the intersections of each ray of `packet` with every object
+    /// of this world, one sorted collection per ray. With an accelerator,
+    /// each ray walks it on its own to find the objects it may hit, and
+    /// every object is traced with the smaller packet of the rays reaching
+    /// it.
+    pub fn intersect_packet(&self, packet: &RayPacket<T>) -> Vec<Intersections<'_, T>> {
+        let mut xs: Vec<Vec<Intersection<'_, T>>> = (0..packet.len()).map(|_| Vec::new()).collect();
+        match &self.accelerator {
+            Some(accelerator) => {
+                let mut masks = vec![vec![false; packet.len()]; self.objects.len()];
+                for (lane, ray) in packet.rays().enumerate() {
+                    accelerator.visit(&ray, |i| {
+                        masks[i][lane] = true;
+                        false
+                    });
+                }
+                for (object, mask) in self.objects.iter().zip(&masks) {
+                    let Some((packet, lanes)) = packet.select(mask) else {
+                        continue;
+                    };
+                    for (&lane, hits) in lanes.iter().zip(object.intersect_packet(&packet)) {
+                        xs[lane].extend(hits);
+                    }
+                }
+            }
+            None => {
+                for object in &self.objects {
+                    for (lane, hits) in xs.iter_mut().zip(object.intersect_packet(packet)) {
+                        lane.extend(hits);
+                    }
+                }
+            }
+        }
+        xs.into_iter().map(Intersections::new).collect()
+    }
+
+    /// Returns the color of the prepared intersection `comps`, lit by every
+    /// light of this world, plus the light it emits and the color it reflects.
+    /// `remaining` is the number of bounces still allowed to the reflected rays.
+    ///
+    /// The diffuse and specular terms of each light are dimmed by the
+    /// transparent objects in its way, see [`World::light_transmittance_at`].
+    ///
+    /// A medium scatters the light of every light it sees, whatever the
+    /// direction, and reflects nothing.
+    pub fn shade_hit(&self, comps: &Computations<'_, T>, remaining: usize) -> Color<T> {
+        let material = comps.object.material();
+        if comps.object.is_medium() {
+            return self.shade_medium(comps);
+        }
+        let surface = self.lights.iter().fold(Color::default(), |color, light| {
+            let terms = phong_terms(
+                material,
+                comps.object,
+                light,
+                comps.over_point,
+                comps.eyev,
+                comps.normalv,
+                false,
+            );
+            let transmittance = if comps.object.receives_shadow() {
+                self.light_transmittance_at(comps.over_point, light, comps.time)
+            } else {
+                T::one()
+            };
+            color + terms.ambient + (terms.diffuse + terms.specular) * transmittance
+        });
+        surface + material.emissive + self.reflected_color(comps, remaining)
+    }
+
+    /// Returns the color of the prepared scattering point `comps` in a
+    /// medium: like [`lighting_from`](crate::lighting_from), each light adds its ambient term and
+    /// its diffuse term with no regard to the direction, dimmed by the
+    /// objects in its way.
+    fn shade_medium(&self, comps: &Computations<'_, T>) -> Color<T> {
+        let material = comps.object.material();
+        let surface_color = material.color_at(comps.object, comps.point);
+        self.lights.iter().fold(material.emissive, |color, light| {
+            let effective_color = surface_color * light.intensity_at(comps.point);
+            let ambient = surface_color * light.intensity() * material.ambient;
+            let transmittance = self.light_transmittance_at(comps.point, light, comps.time);
+            color + ambient + effective_color * material.diffuse * transmittance
+        })
+    }
+
+    /// Returns the color seen along `ray`, the skybox (or black) if nothing is hit.
+    /// `remaining` is the number of bounces still allowed to the reflected
+    /// rays, usually [`MAX_DEPTH`].
+    ///
+    /// With a fog, the color fades by the distance travelled along the ray,
+    /// and the rays hitting nothing take the color of the fog.
+    pub fn color_at(&self, ray: &Ray<T>, remaining: usize) -> Color<T> {
+        let (color, distance) = match self.intersect(ray).hit() {
+            Some(hit) => {
+                let comps = hit.prepare_computations_with_bias(ray, self.shadow_bias);
+                let distance = hit.t() * ray.direction().magnitude();
+                (self.shade_hit(&comps, remaining), distance)
+            }
+            None => (self.background_at(ray.direction()), T::infinity()),
+        };
+        match &self.fog {
+            Some(fog) => fog.apply(color, distance),
+            None => color,
+        }
+    }
+
+    /// Returns the color seen in the reflection of the prepared intersection
+    /// `comps`, black for a non-reflective surface or once no bounce
+    /// `remaining`.
+    pub fn reflected_color(&self, comps: &Computations<'_, T>, remaining: usize) -> Color<T> {
+        let reflective = comps.object.material().reflective;
+        if remaining == 0 || reflective == T::zero() {
+            return Color::default();
+        }
+        let reflect_ray = Ray::new(comps.over_point, comps.reflectv).with_time(comps.time);
+        self.color_at(&reflect_ray, remaining - 1) * reflective
+    }
+
+    /// Returns `true` if an object casting shadows lies between `point` and
+    /// `light`.
+    pub fn is_shadowed(&self, point: Point3<T>, light: &PointLight<T>) -> bool {
+        self.is_shadowed_at(point, light, T::zero())
+    }
+
+    /// Returns the fraction of the light of `light` reaching `point` at
+    /// `time`: one if nothing casting shadows lies between them, and else
+    /// the product of the [`Material::transparency`] of every surface they
+    /// cross, so that an opaque object stops the light and a glass pane,
+    /// crossed twice, lets most of it through.
+    ///
+    /// [`Material::transparency`]: crate::Material::transparency
+    pub fn light_transmittance_at(&self, point: Point3<T>, light: &PointLight<T>, time: T) -> T {
+        let v = light.position() - point;
+        let distance = v.magnitude();
+        let ray = Ray::new(point, v.normalized()).with_time(time);
+        count_shadow_ray();
+        let mut transmittance = T::one();
+        // Returns `true` once the light is stopped.
+        let mut cross = |object: &dyn Shape<T>| {
+            for x in object.intersect(&ray).iter() {
+                if x.t() >= T::zero() && x.t() < distance && x.object().casts_shadow() {
+                    transmittance *= x.object().material().transparency;
+                }
+            }
+            transmittance <= T::zero()
+        };
+        match &self.accelerator {
+            Some(accelerator) => {
+                accelerator.visit(&ray, |i| cross(self.objects[i].as_ref()));
+            }
+            None => {
+                for object in &self.objects {
+                    if cross(object.as_ref()) {
+                        break;
+                    }
+                }
+            }
+        }
+        transmittance.max(T::zero())
+    }
+
+    /// Returns `true` if an object casting shadows lies between `point` and
+    /// `light` at `time`, the moving shapes being where they are then,
+    /// however transparent.
+    pub fn is_shadowed_at(&self, point: Point3<T>, light: &PointLight<T>, time: T) -> bool {
+        let v = light.position() - point;
+        let distance = v.magnitude();
+        let ray = Ray::new(point, v.normalized()).with_time(time);
+        self.is_occluded(&ray, distance)
+    }
+
+    /// Returns `true` if an object casting shadows is hit by `ray` at a `t`
+    /// within `[0, max_t)`. Shadow rays only need to know whether anything
+    /// blocks them, so the search stops at the first such object instead
+    /// of collecting and sorting every intersection.
+    pub fn is_occluded(&self, ray: &Ray<T>, max_t: T) -> bool {
+        count_shadow_ray();
+        match &self.accelerator {
+            Some(accelerator) => accelerator.visit(ray, |i| self.objects[i].occludes(ray, max_t)),
+            None => self
+                .objects
+                .iter()
+                .any(|object| object.occludes(ray, max_t)),
+        }
+    }
+}
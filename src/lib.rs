@@ -6,6 +6,9 @@ use std::{
 
 use bytemuck::Pod;
 use num_traits::Num;
+/// The tolerance of the comparisons of the geometry, and the default
+/// offset off the surfaces, [`RenderSettings::epsilon`], which the renders
+/// take from [`World::shadow_bias`].
 pub const EPSILON: f64 = 0.0001;
 
 extern crate approx;
@@ -16,11 +19,86 @@ pub mod features;
 mod test;
 
 pub use features::linalg::{
+    matrix::{Matrix, Matrix2, Matrix3, Matrix4},
     point::{Point, Point3},
+    quaternion::Quaternion,
+    transform::{Decomposition, Transform},
     vector::{Vector, Vector3},
 };
 
-pub use features::canvas::{ppm_canvas::PPMCanvas, RawCanvas};
+pub use features::animation::{
+    render_sequence, render_turntable, Animation, Easing, Keyframe, Track,
+};
+pub use features::camera::{AdaptiveSampling, Camera, PixelBounds, Projection, RenderLayers};
+#[cfg(feature = "exr")]
+pub use features::canvas::exr::ExrImage;
+pub use features::canvas::{
+    compositing::Overlay,
+    denoise::Denoiser,
+    export::{save_pgm, save_pixels, to_hdr, to_pfm, to_pgm, to_qoi, ExportError, ImageFormat},
+    ppm_canvas::{to_ppm, to_ppm_binary, PPMCanvas},
+    RawCanvas,
+};
+pub use features::checkpoint::{Checkpoint, CheckpointError};
+pub use features::debug::{DebugView, HitTrace, LightTrace, RayTrace};
+pub use features::environment::EnvironmentMap;
+pub use features::fog::{Fog, FogFalloff};
+pub use features::importers::{
+    image::ImageError,
+    mtl::{MtlLibrary, MtlMaterial},
+    obj::{ObjParser, ObjTriangle},
+    ply::PlyError,
+    stl::StlError,
+};
+pub use features::integrators::{Integrator, PathTracer, Whitted};
+pub use features::intersections::{Computations, Intersection, Intersections};
+pub use features::lights::{
+    lighting, lighting_from, phong_terms, Attenuation, PhongTerms, PointLight,
+};
+pub use features::materials::Material;
+pub use features::patterns::{
+    checker::CheckerPattern,
+    cube_map::CubeMap,
+    gradient::GradientPattern,
+    ring::RingPattern,
+    stripe::StripePattern,
+    test_pattern::TestPattern,
+    texture_map::{ImageTexture, TextureMap},
+    uv_mapping::{CubeFace, UvMapping},
+    uv_patterns::{TextureFilter, UvAlignCheck, UvChecker, UvImage, UvPattern},
+    Pattern,
+};
+pub use features::progress::{ProgressObserver, RayCounts, RenderProgress};
+pub use features::rays::{Ray, RayPacket, PACKET_SIZE};
+pub use features::samplers::{
+    HaltonSampler, RandomSampler, Sampler, SobolSampler, StratifiedSampler,
+};
+pub use features::scene::{builder::ShapeBuilder, value::SceneValue, Scene, SceneError};
+pub use features::settings::RenderSettings;
+pub use features::shapes::{
+    accelerator::{Accelerator, AcceleratorKind},
+    bounds::BoundingBox,
+    bvh::Bvh,
+    csg::{Csg, CsgOperation},
+    cylinder::Cylinder,
+    disk::Disk,
+    group::Group,
+    height_field::HeightField,
+    instance::Instance,
+    kd_tree::KdTree,
+    mesh::Mesh,
+    metaballs::{Metaball, Metaballs},
+    parametric::ParametricSurface,
+    plane::Plane,
+    sphere::Sphere,
+    test_shape::TestShape,
+    torus::Torus,
+    triangle::{SmoothTriangle, Triangle},
+    volume::Volume,
+    Shape,
+};
+pub use features::tiles::TileOrder;
+pub use features::world::{World, MAX_DEPTH};
 
 /// A scalar type in the context of this library, following `lina`.
 /// This is implemented for at least these types:
@@ -29,12 +107,22 @@ pub use features::canvas::{ppm_canvas::PPMCanvas, RawCanvas};
 /// - Signed integers: `i8`, `i16`, `i32`, `i64`, `i128`, `isize`
 /// - Unsigned integers: `u8`, `u16`, `u32`, `u64`, `u128`, `usize`
 pub trait Scalar:
-    Num + Clone + Copy + Debug + AddAssign + SubAssign + MulAssign + DivAssign + Pod
+    Num + Clone + Copy + Debug + AddAssign + SubAssign + MulAssign + DivAssign + Pod + Send + Sync
 {
 }
 
 impl<T> Scalar for T where
-    T: Num + Clone + Copy + Debug + AddAssign + SubAssign + MulAssign + DivAssign + Pod
+    T: Num
+        + Clone
+        + Copy
+        + Debug
+        + AddAssign
+        + SubAssign
+        + MulAssign
+        + DivAssign
+        + Pod
+        + Send
+        + Sync
 {
 }
 
@@ -54,6 +142,10 @@ pub trait Float: Scalar + num_traits::Float + num_traits::FloatConst {
     fn four() -> Self {
         Self::one() + Self::one() + Self::one() + Self::one()
     }
+    /// Converts a `f64` literal, such as [`EPSILON`], into this scalar type.
+    fn from_f64(value: f64) -> Self {
+        <Self as num_traits::NumCast>::from(value).unwrap()
+    }
 }
 
 impl<T> Float for T where T: Scalar + num_traits::Float + num_traits::FloatConst {}
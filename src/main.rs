@@ -1,53 +1,240 @@
-use std::fs;
+//! The command line renderer of scene files:
+//!
+//! ```text
+//! raytracer-rust render scene.json -o out.png --width 1920 --height 1080 --samples 16 --threads 8
+//! ```
+//!
+//! The scenes are JSON or RON files, or `.script` procedural scenes with the
+//! `scripting` feature, see [`Scene`]; the options override
+//! the size of their camera and the settings of the render. A progress bar
+//! is drawn on the standard error during the render, and a summary printed
+//! once it is done.
+use std::{
+    error::Error,
+    fs,
+    path::PathBuf,
+    process::ExitCode,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use raytracer_rust::{features::colors::Color, PPMCanvas, Point3, RawCanvas, Vector3};
+use clap::{Args, Parser, Subcommand};
+use raytracer_rust::{
+    Camera, ImageFormat, Overlay, RenderProgress, RenderSettings, Scene, UvImage,
+};
 
-fn main() {
-    draw_projectile();
+#[derive(Debug, Parser)]
+#[command(version, about = "Renders the scene files of the ray tracer.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 }
 
-struct Projectile {
-    pos: Point3<f64>,
-    velocity: Vector3<f64>,
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Renders a JSON or RON scene file, or a .script file with the
+    /// scripting feature.
+    Render(RenderArgs),
 }
-struct Environment {
-    gravity: Vector3<f64>,
-    wind: Vector3<f64>,
+
+/// The arguments of the `render` command.
+#[derive(Debug, Args)]
+struct RenderArgs {
+    /// The scene file to render.
+    scene: PathBuf,
+    /// The image to write, its format picked from the extension: ppm, qoi,
+    /// hdr, pfm, or png and exr with the features of the same names.
+    #[arg(short, long, value_name = "FILE", default_value = "out.ppm")]
+    output: PathBuf,
+    /// The format of the image whatever its extension: one of the
+    /// extensions, p3 or p6 for a plain or raw PPM.
+    #[arg(long, value_parser = parse_format)]
+    format: Option<ImageFormat>,
+    /// The width of the image [default: the camera's].
+    #[arg(long, value_name = "PIXELS", value_parser = parse_positive)]
+    width: Option<usize>,
+    /// The height of the image [default: the camera's].
+    #[arg(long, value_name = "PIXELS", value_parser = parse_positive)]
+    height: Option<usize>,
+    /// The rays averaged for each pixel, spread over its area when more
+    /// than one [default: 1].
+    #[arg(long, value_name = "N", value_parser = parse_positive)]
+    samples: Option<usize>,
+    /// The threads rendering [default: every core].
+    #[arg(long, value_name = "N", value_parser = parse_positive)]
+    threads: Option<usize>,
+    /// An image (ppm, pfm, or png with the png feature) to stamp onto the
+    /// render, such as a logo.
+    #[arg(long, value_name = "IMAGE")]
+    overlay: Option<PathBuf>,
+    /// The pixel of the top-left corner of the overlay.
+    #[arg(long, value_name = "X,Y", value_parser = parse_pixel, default_value = "0,0")]
+    overlay_at: (usize, usize),
+    /// From 0 to keep the render to 1 to replace it by the overlay.
+    #[arg(long, value_name = "A", value_parser = parse_opacity, default_value_t = 1.0)]
+    overlay_opacity: f64,
+}
+
+fn parse_format(value: &str) -> Result<ImageFormat, String> {
+    ImageFormat::from_name(value).map_err(|e| e.to_string())
 }
-fn tick(proj: Projectile, env: &Environment) -> Projectile {
-    let new_pos = proj.pos + proj.velocity;
-    let new_velocity = proj.velocity + env.gravity + env.wind;
-    Projectile {
-        pos: new_pos,
-        velocity: new_velocity,
+
+fn parse_positive(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err("expected a positive integer".to_string()),
+    }
+}
+
+fn parse_pixel(value: &str) -> Result<(usize, usize), String> {
+    value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+        .ok_or("expected the pixel `X,Y`".to_string())
+}
+
+fn parse_opacity(value: &str) -> Result<f64, String> {
+    match value.parse() {
+        Ok(a) if (0.0..=1.0).contains(&a) => Ok(a),
+        _ => Err("expected a number in [0, 1]".to_string()),
     }
 }
-fn draw_projectile() {
-    let start = Point3::new(0.0, 1.0, 0.0);
-    let velocity = Vector3::new(1.0, 1.8, 0.0).normalized() * 11.0;
-    let mut p = Projectile {
-        pos: start,
-        velocity,
+
+fn render(args: RenderArgs) -> Result<(), Box<dyn Error>> {
+    // Checked before the render rather than after it.
+    let format = match args.format {
+        Some(format) => format,
+        None => ImageFormat::from_path(&args.output)?,
+    };
+    // The overlay of the options replaces the one of the scene.
+    let overlay = match &args.overlay {
+        Some(path) => Some(Arc::new(
+            Overlay::new(UvImage::from_file(path)?)
+                .at(args.overlay_at.0, args.overlay_at.1)
+                .with_opacity(args.overlay_opacity),
+        )),
+        None => None,
     };
-    let gravity = Vector3::new(0.0, -0.1, 0.0);
-    let wind = Vector3::new(-0.01, 0.0, 0.0);
-    let e = Environment { gravity, wind };
-    const WIDTH: usize = 900;
-    const HEIGHT: usize = 550;
-    let mut canvas: RawCanvas<WIDTH, HEIGHT, f64> = RawCanvas::default();
-    let p_color = Color::new(1.0, 0.0, 0.0);
-    println!("{}", canvas.height());
-    canvas.write_pixel(0, canvas.height() - 1, p_color).unwrap();
-    while p.pos.y > 0.0 {
-        p = tick(p, &e);
-        if (p.pos.x as usize) < WIDTH && (p.pos.y as usize) < HEIGHT {
-            let cp_x = p.pos.x as usize;
-            let cp_y = (canvas.height() - 1) as f64 - p.pos.y;
-            if cp_y > 0.0 && (cp_y as usize) < canvas.height() {
-                canvas.write_pixel(cp_x, cp_y as usize, p_color).unwrap();
-            }
+    let Scene { mut world, camera } = Scene::<f64>::load(&args.scene)?;
+    let settings = RenderSettings {
+        samples_per_pixel: args.samples.unwrap_or(camera.settings().samples_per_pixel),
+        threads: args.threads.unwrap_or(camera.settings().threads),
+        overlay: overlay.or_else(|| camera.settings().overlay.clone()),
+        ..camera.settings().clone()
+    };
+    let (width, height) = (
+        args.width.unwrap_or(camera.hsize()),
+        args.height.unwrap_or(camera.vsize()),
+    );
+    let camera = Camera::new(width, height, camera.field_of_view())
+        .with_transform(camera.transform())
+        .with_settings(settings.clone());
+    world.apply_settings(&settings);
+    let start = Instant::now();
+    let mut last = None;
+    let pixels = camera.render_pixels(&world, &settings.whitted(), &mut |p: &RenderProgress| {
+        draw_progress(p);
+        last = Some(*p);
+    });
+    let elapsed = start.elapsed();
+    format.save(&args.output, width, height, &pixels)?;
+    println!(
+        "Rendered {width}x{height} pixels to {} in {}",
+        args.output.display(),
+        format_duration(elapsed)
+    );
+    if let Some(progress) = last {
+        println!("  Primary rays: {}", progress.rays.primary);
+        println!("  Shadow rays:  {}", progress.rays.shadow);
+        println!(
+            "  Rays/s:       {}",
+            format_rate(progress.rays_per_second())
+        );
+    }
+    if let Some(bytes) = peak_memory() {
+        println!("  Peak memory:  {}", format_bytes(bytes));
+    }
+    Ok(())
+}
+
+/// Redraws the progress bar on the standard error after a tile, with the
+/// estimated time left, the rays cast per second and the memory in use.
+fn draw_progress(progress: &RenderProgress) {
+    const WIDTH: usize = 30;
+    let fraction = progress.fraction();
+    let filled = ((fraction * WIDTH as f64) as usize).min(WIDTH);
+    let eta = progress
+        .remaining()
+        .map_or("--".to_string(), format_duration);
+    let memory =
+        resident_memory().map_or(String::new(), |bytes| format!("  {}", format_bytes(bytes)));
+    // Clears the rest of the line, longer when last drawn.
+    eprint!(
+        "\r[{}{}] {:5.1}%  ETA {eta}  {} rays/s{memory}\x1b[K",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        fraction * 100.0,
+        format_rate(progress.rays_per_second()),
+    );
+    if progress.is_done() {
+        eprintln!();
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{:.1}s", duration.as_secs_f64()),
+        60..=3599 => format!("{}m{:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h{:02}m", seconds / 3600, seconds / 60 % 60),
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    match rate {
+        r if r >= 1e9 => format!("{:.2}G", r / 1e9),
+        r if r >= 1e6 => format!("{:.2}M", r / 1e6),
+        r if r >= 1e3 => format!("{:.1}k", r / 1e3),
+        r => format!("{r:.0}"),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Returns the resident memory of the process, where `/proc` tells it.
+fn resident_memory() -> Option<u64> {
+    proc_status_kb("VmRSS:")
+}
+
+/// Returns the peak resident memory of the process, where `/proc` tells
+/// it.
+fn peak_memory() -> Option<u64> {
+    proc_status_kb("VmHWM:")
+}
+
+/// Returns the value in bytes of the `field` of `/proc/self/status`, given
+/// there in kB.
+fn proc_status_kb(field: &str) -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    let kb: u64 = line[field.len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+fn main() -> ExitCode {
+    let Command::Render(args) = Cli::parse().command;
+    match render(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
         }
     }
-    let ppm_canvas: PPMCanvas<WIDTH, HEIGHT> = canvas.into();
-    fs::write("chapter2_proj_draw.ppm", format!("{}", ppm_canvas)).unwrap();
 }
@@ -0,0 +1,156 @@
+/// Tests of the keyframed animations.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, render_sequence, render_turntable, to_ppm, Animation, Camera, Easing,
+    Plane, Point3, PointLight, RenderProgress, Sphere, Track, Transform, Vector3, World,
+};
+
+fn slide() -> Track<f64> {
+    Track::new()
+        .with_key(2.0, Transform::translation(4.0, 0.0, 0.0), Easing::Linear)
+        .with_key(0.0, Transform::identity(), Easing::Linear)
+}
+
+#[test]
+fn easings_blend_from_the_start_to_the_end() {
+    for easing in [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ] {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+    assert_eq!(Easing::Linear.apply(0.25), 0.25);
+    assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+    assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+}
+#[test]
+fn a_track_blends_its_keyframes() {
+    let track = slide();
+    assert_eq!(track.keyframes()[0].time, 0.0);
+    assert_eq!(Track::<f64>::new().transform_at(1.0), None);
+    // The ends hold before and after the keyframes.
+    assert_eq!(track.transform_at(-1.0), Some(Transform::identity()));
+    assert_eq!(
+        track.transform_at(3.0),
+        Some(Transform::translation(4.0, 0.0, 0.0))
+    );
+    assert_relative_eq!(
+        track.transform_at(0.5).unwrap(),
+        Transform::translation(1.0, 0.0, 0.0)
+    );
+    // The easing of a keyframe paces the blend to the next one.
+    let eased = track.with_key(0.0, Transform::identity(), Easing::EaseIn);
+    assert_eq!(eased.keyframes().len(), 2);
+    assert_relative_eq!(
+        eased.transform_at(1.0).unwrap(),
+        Transform::translation(1.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn rotations_are_blended_around_their_axis() {
+    let track = Track::new()
+        .with_key(0.0, Transform::rotation_y(0.0), Easing::Linear)
+        .with_key(1.0, Transform::rotation_y(PI / 2.0), Easing::Linear);
+    assert_relative_eq!(
+        track.transform_at(0.5).unwrap(),
+        Transform::rotation_y(PI / 4.0),
+        epsilon = 1e-9
+    );
+}
+#[test]
+fn an_animation_moves_the_objects_and_the_camera() {
+    let mut world = World::new();
+    world.add_object(Sphere::default());
+    world.add_object(Sphere::new(Transform::scaling(2.0, 2.0, 2.0)));
+    let mut camera = Camera::new(10, 10, PI / 2.0);
+    let view = |x| {
+        Transform::view_transform(
+            Point3::new(x, 0.0, -5.0),
+            Point3::new(x, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        )
+    };
+    let animation = Animation::new(2.0).with_object(0, slide()).with_camera(
+        Track::new()
+            .with_key(0.0, view(0.0), Easing::Linear)
+            .with_key(2.0, view(2.0), Easing::Linear),
+    );
+    animation.apply(&mut world, &mut camera, 1.0);
+    assert_relative_eq!(
+        world.objects()[0].transform(),
+        Transform::translation(2.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        world.objects()[1].transform(),
+        Transform::scaling(2.0, 2.0, 2.0)
+    );
+    assert_relative_eq!(camera.transform(), view(1.0), epsilon = 1e-9);
+    assert_eq!(animation.frame_times(5), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+    assert_eq!(animation.frame_times(1), vec![0.0]);
+}
+#[test]
+fn rendering_the_frames_of_an_animation() {
+    let dir = std::env::temp_dir().join("raytracer-rust-animation-test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut world = World::default();
+    let mut camera = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let animation = Animation::new(2.0).with_object(0, slide());
+    let paths = render_sequence(&mut world, &mut camera, &animation, 3, &dir).unwrap();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[2], dir.join("frame_0002.ppm"));
+    let frames: Vec<String> = paths
+        .iter()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect();
+    assert!(frames[0].starts_with("P3\n11 11\n255\n"));
+    assert_ne!(frames[0], frames[2]);
+    assert_eq!(
+        world.objects()[0].transform(),
+        Transform::translation(4.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn a_turntable_orbits_around_the_center_of_the_finite_objects() {
+    let dir = std::env::temp_dir().join("raytracer-rust-turntable-test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut world = World::new();
+    world.add_light(PointLight::new(
+        Point3::new(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    world.add_object(Sphere::new(Transform::translation(3.0, 0.0, 0.0)));
+    // Left out of the center.
+    world.add_object(Plane::new(Transform::translation(0.0, -1.0, 0.0)));
+    let from = |z| {
+        Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+            Point3::new(3.0, 0.0, z),
+            Point3::new(3.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        ))
+    };
+    let render = |camera: &Camera<f64>| {
+        let integrator = camera.settings().whitted();
+        let pixels = camera.render_pixels(&world, &integrator, &mut |_: &RenderProgress| {});
+        to_ppm(11, 11, &pixels)
+    };
+    let paths =
+        render_turntable(&world, &from(-5.0), 4, Vector3::new(0.0, 1.0, 0.0), &dir).unwrap();
+    assert_eq!(paths.len(), 4);
+    let frame = |i: usize| std::fs::read_to_string(&paths[i]).unwrap();
+    assert_eq!(frame(0), render(&from(-5.0)));
+    // Half a turn later, the camera sees the side away from the light.
+    assert_eq!(frame(2), render(&from(5.0)));
+    assert_ne!(frame(0), frame(2));
+}
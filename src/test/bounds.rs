@@ -0,0 +1,216 @@
+/// Tests of the bounding boxes and the bounding volume hierarchy.
+use std::f64::consts::{FRAC_PI_4, SQRT_2};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    BoundingBox, Csg, Cylinder, Group, Plane, Point3, Ray, Shape, Sphere, TestShape, Transform,
+    Triangle, Vector3, EPSILON,
+};
+
+#[test]
+fn creating_an_empty_bounding_box() {
+    let b = BoundingBox::<f64>::default();
+    assert!(b.is_empty());
+    assert_eq!(
+        b.min(),
+        Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY)
+    );
+    let r = Ray::new(Point3::origin(), Vector3::new(0.0, 0.0, 1.0));
+    assert!(!b.intersects(&r));
+}
+#[test]
+fn adding_points_to_a_bounding_box() {
+    let mut b = BoundingBox::default();
+    b.add_point(Point3::new(-5.0, 2.0, 0.0));
+    b.add_point(Point3::new(7.0, 0.0, -3.0));
+    assert_eq!(b.min(), Point3::new(-5.0, 0.0, -3.0));
+    assert_eq!(b.max(), Point3::new(7.0, 2.0, 0.0));
+}
+#[test]
+fn bounds_of_primitives() {
+    let s = Sphere::<f64>::default();
+    assert_eq!(s.bounds().min(), Point3::new(-1.0, -1.0, -1.0));
+    assert_eq!(s.bounds().max(), Point3::new(1.0, 1.0, 1.0));
+    let p = Plane::<f64>::default();
+    assert_eq!(
+        p.bounds().min(),
+        Point3::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY)
+    );
+    assert_eq!(
+        p.bounds().max(),
+        Point3::new(f64::INFINITY, 0.0, f64::INFINITY)
+    );
+    let c = Cylinder::truncated(-5.0, 3.0, true);
+    assert_eq!(c.bounds().min(), Point3::new(-1.0, -5.0, -1.0));
+    assert_eq!(c.bounds().max(), Point3::new(1.0, 3.0, 1.0));
+    let t = Triangle::new(
+        Point3::new(-3.0, 7.0, 2.0),
+        Point3::new(6.0, 2.0, -4.0),
+        Point3::new(2.0, -1.0, -1.0),
+    );
+    assert_eq!(t.bounds().min(), Point3::new(-3.0, -1.0, -4.0));
+    assert_eq!(t.bounds().max(), Point3::new(6.0, 7.0, 2.0));
+}
+#[test]
+fn merging_and_containing_bounding_boxes() {
+    let mut b1 = BoundingBox::new(Point3::new(-5.0, -2.0, 0.0), Point3::new(7.0, 4.0, 4.0));
+    let b2 = BoundingBox::new(Point3::new(8.0, -7.0, -2.0), Point3::new(14.0, 2.0, 8.0));
+    b1.merge(&b2);
+    assert_eq!(b1.min(), Point3::new(-5.0, -7.0, -2.0));
+    assert_eq!(b1.max(), Point3::new(14.0, 4.0, 8.0));
+    assert!(b1.contains_point(Point3::new(14.0, 4.0, 8.0)));
+    assert!(!b1.contains_point(Point3::new(15.0, 0.0, 0.0)));
+    assert!(b1.contains_box(&b2));
+    assert!(!b2.contains_box(&b1));
+}
+#[test]
+fn transforming_a_bounding_box() {
+    let b = BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let m = Transform::rotation_x(FRAC_PI_4) * Transform::rotation_y(FRAC_PI_4);
+    let b2 = b.transform(&m);
+    assert_relative_eq!(
+        b2.min(),
+        Point3::new(-SQRT_2, -1.7071, -1.7071),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        b2.max(),
+        Point3::new(SQRT_2, 1.7071, 1.7071),
+        epsilon = EPSILON
+    );
+    let mut s = TestShape::default();
+    s.set_transform(Transform::translation(1.0, -3.0, 5.0) * Transform::scaling(0.5, 2.0, 4.0));
+    let b = s.parent_space_bounds();
+    assert_eq!(b.min(), Point3::new(0.5, -5.0, 1.0));
+    assert_eq!(b.max(), Point3::new(1.5, -1.0, 9.0));
+}
+#[test]
+fn intersecting_a_ray_with_a_bounding_box() {
+    let b = BoundingBox::new(Point3::new(5.0, -2.0, 0.0), Point3::new(11.0, 4.0, 7.0));
+    let cases = [
+        (
+            Point3::new(15.0, 1.0, 2.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            true,
+        ),
+        (
+            Point3::new(-5.0, -1.0, 4.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            true,
+        ),
+        (
+            Point3::new(7.0, 6.0, 5.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            true,
+        ),
+        (
+            Point3::new(9.0, -5.0, 6.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            true,
+        ),
+        (
+            Point3::new(8.0, 2.0, 12.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            true,
+        ),
+        (
+            Point3::new(6.0, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            true,
+        ),
+        (
+            Point3::new(8.0, 1.0, 3.5),
+            Vector3::new(0.0, 0.0, 1.0),
+            true,
+        ),
+        (
+            Point3::new(9.0, -1.0, -8.0),
+            Vector3::new(2.0, 4.0, 6.0),
+            false,
+        ),
+        (
+            Point3::new(8.0, 3.0, -4.0),
+            Vector3::new(6.0, 2.0, 4.0),
+            false,
+        ),
+        (
+            Point3::new(9.0, -1.0, -2.0),
+            Vector3::new(4.0, 6.0, 2.0),
+            false,
+        ),
+        (
+            Point3::new(4.0, 0.0, 9.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            false,
+        ),
+        (
+            Point3::new(8.0, 6.0, -1.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            false,
+        ),
+        (
+            Point3::new(12.0, 5.0, 4.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            false,
+        ),
+    ];
+    for (origin, direction, result) in cases {
+        let r = Ray::new(origin, direction.normalized());
+        assert_eq!(b.intersects(&r), result, "{origin:?} {direction:?}");
+    }
+}
+#[test]
+fn bounds_of_groups_and_csg() {
+    let mut g = Group::default();
+    g.add_child(Sphere::new(
+        Transform::translation(2.0, 5.0, -3.0) * Transform::scaling(2.0, 2.0, 2.0),
+    ));
+    let mut c = Cylinder::truncated(-2.0, 2.0, false);
+    c.set_transform(Transform::translation(-4.0, -1.0, 4.0) * Transform::scaling(0.5, 1.0, 0.5));
+    g.add_child(c);
+    assert_eq!(g.bounds().min(), Point3::new(-4.5, -3.0, -5.0));
+    assert_eq!(g.bounds().max(), Point3::new(4.0, 7.0, 4.5));
+    let c = Csg::difference(
+        Sphere::default(),
+        Sphere::new(Transform::translation(2.0, 3.0, 4.0)),
+    );
+    assert_eq!(c.bounds().min(), Point3::new(-1.0, -1.0, -1.0));
+    assert_eq!(c.bounds().max(), Point3::new(3.0, 4.0, 5.0));
+}
+#[test]
+fn partitioning_the_children_of_a_group() {
+    let mut g = Group::default();
+    g.add_child(Sphere::new(Transform::translation(-2.0, 0.0, 0.0)));
+    g.add_child(Sphere::new(Transform::translation(2.0, 0.0, 0.0)));
+    g.add_child(Sphere::default());
+    let (left, right) = g.partition_children();
+    assert_eq!(g.children().len(), 1);
+    assert_eq!(left.len(), 1);
+    assert_eq!(right.len(), 1);
+    assert_eq!(left[0].transform(), Transform::translation(-2.0, 0.0, 0.0));
+    assert_eq!(right[0].transform(), Transform::translation(2.0, 0.0, 0.0));
+}
+#[test]
+fn subdividing_a_group() {
+    let mut g = Group::default();
+    g.add_child(Sphere::new(Transform::translation(-2.0, -2.0, 0.0)));
+    g.add_child(Sphere::new(Transform::translation(-2.0, 2.0, 0.0)));
+    g.add_child(Sphere::new(Transform::scaling(4.0, 4.0, 4.0)));
+    g.divide(1);
+    // The big sphere stays, the two small ones move into a subgroup which
+    // is itself split into two subgroups.
+    assert_eq!(g.children().len(), 2);
+    assert_eq!(
+        g.children()[0].transform(),
+        Transform::scaling(4.0, 4.0, 4.0)
+    );
+    let r = Ray::new(Point3::new(-2.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = g.intersect(&r);
+    assert_eq!(xs.len(), 4);
+    assert_relative_eq!(xs[1].t(), 4.0, epsilon = EPSILON);
+    assert_eq!(
+        xs[1].object().transform(),
+        Transform::translation(-2.0, 2.0, 0.0)
+    );
+}
@@ -0,0 +1,204 @@
+/// Tests of the bounding volume hierarchies built with the surface area
+/// heuristic.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::shapes::Shape, BoundingBox, Bvh, Group, Mesh, Plane, Point3, Ray, Sphere, Transform,
+    Vector3, World, EPSILON,
+};
+
+/// Returns the unit boxes centered on `(x, 0, 0)` for each `x`.
+fn boxes(xs: &[f64]) -> Vec<BoundingBox<f64>> {
+    xs.iter()
+        .map(|&x| {
+            BoundingBox::new(
+                Point3::new(x - 0.5, -0.5, -0.5),
+                Point3::new(x + 0.5, 0.5, 0.5),
+            )
+        })
+        .collect()
+}
+
+/// Returns a grid of `n` x `n` quads in the `xy` plane, two faces each.
+fn grid(n: usize) -> Mesh<f64> {
+    let mut vertices = Vec::new();
+    for j in 0..=n {
+        for i in 0..=n {
+            vertices.push(Point3::new(i as f64, j as f64, (i * j) as f64 * 0.01));
+        }
+    }
+    let index = |i: usize, j: usize| j * (n + 1) + i;
+    let mut faces = Vec::new();
+    for j in 0..n {
+        for i in 0..n {
+            faces.push([index(i, j), index(i + 1, j), index(i + 1, j + 1)]);
+            faces.push([index(i, j), index(i + 1, j + 1), index(i, j + 1)]);
+        }
+    }
+    Mesh::new(vertices, faces)
+}
+
+#[test]
+fn the_surface_area_and_centroid_of_a_box() {
+    let b = BoundingBox::new(Point3::new(-1.0, 0.0, 2.0), Point3::new(1.0, 3.0, 6.0));
+    assert_eq!(b.surface_area(), 2.0 * (2.0 * 3.0 + 3.0 * 4.0 + 4.0 * 2.0));
+    assert_eq!(b.centroid(), Point3::new(0.0, 1.5, 4.0));
+    assert_eq!(BoundingBox::<f64>::default().surface_area(), 0.0);
+}
+#[test]
+fn leaves_hold_at_most_leaf_size_primitives() {
+    let xs: Vec<f64> = (0..100).map(|i| (i * 37 % 100) as f64).collect();
+    for leaf_size in [1, 2, 4, 8] {
+        let bvh = Bvh::build(&boxes(&xs), leaf_size);
+        assert_eq!(bvh.len(), 100);
+        assert!(bvh
+            .leaves()
+            .all(|leaf| !leaf.is_empty() && leaf.len() <= leaf_size));
+        let mut all: Vec<usize> = bvh.leaves().flatten().copied().collect();
+        all.sort();
+        assert_eq!(all, (0..100).collect::<Vec<_>>());
+    }
+}
+#[test]
+fn the_split_separates_clusters() {
+    // Two clusters of boxes: the cheapest split lies between them, so no
+    // leaf mixes both.
+    let mut xs: Vec<f64> = (0..8).map(|i| i as f64 * 0.1).collect();
+    xs.extend((0..2).map(|i| 100.0 + i as f64 * 0.1));
+    let bvh = Bvh::build(&boxes(&xs), 8);
+    assert!(bvh
+        .leaves()
+        .all(|leaf| leaf.iter().all(|&i| i < 8) || leaf.iter().all(|&i| i >= 8)));
+}
+#[test]
+fn the_tree_does_not_depend_on_the_number_of_threads() {
+    // Enough primitives for both halves of the first splits to get threads.
+    let xs: Vec<f64> = (0..40_000).map(|i| (i * 7919 % 40_000) as f64).collect();
+    let bounds = boxes(&xs);
+    let sequential = Bvh::build_with_threads(&bounds, 4, 1);
+    assert_eq!(Bvh::build_with_threads(&bounds, 4, 4), sequential);
+    assert_eq!(Bvh::build_with_threads(&bounds, 4, 3), sequential);
+    assert_eq!(Bvh::build(&bounds, 4), sequential);
+    assert_eq!(sequential.len(), 40_000);
+}
+#[test]
+fn coinciding_primitives_stay_in_one_leaf() {
+    let bvh = Bvh::build(&boxes(&[1.0; 6]), 2);
+    assert_eq!(bvh.node_count(), 1);
+    assert_eq!(bvh.leaves().next().unwrap().len(), 6);
+}
+#[test]
+fn a_ray_visits_only_the_leaves_it_hits() {
+    let xs: Vec<f64> = (0..32).map(|i| i as f64 * 2.0).collect();
+    let bvh = Bvh::build(&boxes(&xs), 1);
+    let r = Ray::new(Point3::new(10.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let mut visited = Vec::new();
+    assert!(!bvh.visit(&r, |i| {
+        visited.push(i);
+        false
+    }));
+    assert_eq!(visited, [5]);
+    // The visit stops when asked to.
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+    let mut count = 0;
+    assert!(bvh.visit(&r, |_| {
+        count += 1;
+        count == 3
+    }));
+    assert_eq!(count, 3);
+}
+#[test]
+fn unbounded_and_empty_primitives() {
+    let mut bounds = boxes(&[0.0, 3.0]);
+    bounds.push(BoundingBox::infinite());
+    bounds.push(BoundingBox::default());
+    let bvh = Bvh::build(&bounds, 1);
+    assert_eq!(bvh.len(), 3);
+    let r = Ray::new(Point3::new(0.0, 5.0, 10.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut visited = Vec::new();
+    bvh.visit(&r, |i| {
+        visited.push(i);
+        false
+    });
+    assert_eq!(visited, [2]);
+}
+#[test]
+fn a_group_with_a_bvh_has_the_same_intersections() {
+    let mut g = Group::new(Transform::rotation_y(0.4));
+    for i in 0..50 {
+        let (x, z) = ((i % 7) as f64 * 1.5 - 4.0, (i / 7) as f64 * 1.5 - 4.0);
+        g.add_child(Sphere::new(
+            Transform::translation(x, (i % 3) as f64 - 1.0, z) * Transform::scaling(0.6, 0.6, 0.6),
+        ));
+    }
+    g.add_child(Plane::new(Transform::translation(0.0, -3.0, 0.0)));
+    let rays: Vec<Ray<f64>> = (0..40)
+        .map(|i| {
+            let a = i as f64 * 0.7;
+            Ray::new(
+                Point3::new(a.cos() * 20.0, 4.0, a.sin() * 20.0),
+                Vector3::new(-a.cos(), -0.25 + 0.01 * i as f64, -a.sin()),
+            )
+        })
+        .collect();
+    let expected: Vec<Vec<f64>> = rays
+        .iter()
+        .map(|r| g.intersect(r).iter().map(|x| x.t()).collect())
+        .collect();
+    g.build_bvh(2);
+    assert!(g.bvh().is_some());
+    for (r, expected) in rays.iter().zip(expected) {
+        let ts: Vec<f64> = g.intersect(r).iter().map(|x| x.t()).collect();
+        assert_eq!(ts.len(), expected.len());
+        for (t, e) in ts.iter().zip(&expected) {
+            assert_relative_eq!(t, e, epsilon = EPSILON);
+        }
+        assert_eq!(
+            g.occludes(r, f64::INFINITY),
+            !expected.iter().all(|&t| t < 0.0)
+        );
+    }
+    g.add_child(Sphere::default());
+    assert!(g.bvh().is_none());
+}
+#[test]
+fn a_mesh_with_a_bvh_has_the_same_intersections() {
+    let mut m = grid(12);
+    let rays: Vec<Ray<f64>> = (0..30)
+        .map(|i| {
+            let (x, y) = ((i * 7 % 30) as f64 * 0.43, (i * 11 % 30) as f64 * 0.41);
+            Ray::new(Point3::new(x, y, -5.0), Vector3::new(0.05, -0.02, 1.0))
+        })
+        .collect();
+    let expected: Vec<_> = rays
+        .iter()
+        .map(|r| {
+            m.intersect(r)
+                .iter()
+                .map(|x| (x.t(), x.face()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    m.divide(4);
+    assert!(m.bvh().unwrap().leaves().all(|leaf| leaf.len() <= 4));
+    for (r, expected) in rays.iter().zip(expected) {
+        let hits: Vec<_> = m.intersect(r).iter().map(|x| (x.t(), x.face())).collect();
+        assert_eq!(hits, expected);
+    }
+}
+#[test]
+fn rendering_a_world_with_bvhs() {
+    let mut w = World::<f64>::default();
+    let mut m = grid(4);
+    m.set_transform(Transform::translation(-2.0, -2.0, 3.0));
+    m.build_bvh(2);
+    w.add_object(m);
+    let r = Ray::new(Point3::new(0.3, 0.2, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = w.intersect(&r);
+    assert_eq!(xs.len(), 5);
+    assert_eq!(
+        xs[4].object().transform(),
+        Transform::translation(-2.0, -2.0, 3.0)
+    );
+    assert!(w.is_occluded(&r, f64::INFINITY));
+}
@@ -0,0 +1,266 @@
+/// Tests of the camera projections and lens.
+use std::{f64::consts::PI, sync::Arc};
+
+use approx::assert_relative_eq;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::{colors::Color, patterns::uv_mapping::spherical_map},
+    Camera, Matrix4, PixelBounds, Point3, Projection, RawCanvas, RenderProgress, StripePattern,
+    Transform, Vector3, Whitted, World, EPSILON,
+};
+
+fn direction_at(c: &Camera<f64>, x: f64, y: f64) -> Vector3<f64> {
+    c.ray_for_position(x, y).unwrap().direction()
+}
+
+#[test]
+fn perspective_is_the_default_projection() {
+    let c = Camera::new(100, 50, PI / 2.0);
+    assert_eq!(c.projection(), Projection::Perspective);
+}
+#[test]
+fn fisheye_maps_the_angle_linearly_to_the_distance() {
+    let c = Camera::new(100, 100, PI).with_projection(Projection::Fisheye);
+    let v = |x, y, z| Vector3::new(x, y, z);
+    assert_relative_eq!(
+        direction_at(&c, 50.0, 50.0),
+        v(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+    // The edges of the image circle are 90° off the view direction.
+    assert_relative_eq!(
+        direction_at(&c, 100.0, 50.0),
+        v(-1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 0.0, 50.0),
+        v(1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 50.0, 0.0),
+        v(0.0, 1.0, 0.0),
+        epsilon = EPSILON
+    );
+    // Half way to the edge, half the angle.
+    let h = (PI / 4.0).sin();
+    assert_relative_eq!(
+        direction_at(&c, 50.0, 75.0),
+        v(0.0, -h, -h),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn fisheye_of_360_degrees_sees_behind() {
+    let c = Camera::new(200, 100, 2.0 * PI).with_projection(Projection::Fisheye);
+    // The image circle fits the height of the canvas.
+    assert_relative_eq!(
+        direction_at(&c, 150.0, 50.0),
+        Vector3::new(0.0, 0.0, 1.0),
+        epsilon = EPSILON
+    );
+    assert!(c.ray_for_position(160.0, 50.0).is_none());
+}
+#[test]
+fn fisheye_is_transformed_with_the_camera() {
+    let c = Camera::new(100, 100, PI)
+        .with_projection(Projection::Fisheye)
+        .with_transform(Matrix4::translation(0.0, -2.0, 0.0));
+    let r = c.ray_for_pixel(50, 50).unwrap();
+    assert_relative_eq!(r.origin(), Point3::new(0.0, 2.0, 0.0), epsilon = EPSILON);
+}
+#[test]
+fn pixels_outside_the_image_circle_are_black() {
+    let white = Color::new(1.0, 1.0, 1.0);
+    let mut w = World::new();
+    w.set_skybox(Some(Arc::new(StripePattern::new(white, white))));
+    let c = Camera::new(10, 10, PI).with_projection(Projection::Fisheye);
+    assert!(c.ray_for_pixel(0, 0).is_none());
+    let image: RawCanvas<10, 10, f64> = c.render(&w);
+    assert_eq!(*image.pixel_at(0, 0).unwrap(), Color::new(0.0, 0.0, 0.0));
+    assert_eq!(*image.pixel_at(5, 5).unwrap(), white);
+}
+#[test]
+fn pixels_on_the_rim_of_the_image_circle_average_their_rays() {
+    // Some samples of this pixel fall outside the circle: the others
+    // still see the white sky, undimmed.
+    let white = Color::new(1.0, 1.0, 1.0);
+    let mut w = World::new();
+    w.set_skybox(Some(Arc::new(StripePattern::new(white, white))));
+    let c = Camera::new(10, 10, PI).with_projection(Projection::Fisheye);
+    let whitted = Whitted {
+        samples_per_pixel: 16,
+        ..Whitted::default()
+    };
+    let image: RawCanvas<10, 10, f64> = c.render_with(&w, &whitted);
+    assert_relative_eq!(*image.pixel_at(0, 5).unwrap(), white, epsilon = EPSILON);
+    assert_eq!(*image.pixel_at(0, 0).unwrap(), Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn panorama_covers_all_the_directions() {
+    let c = Camera::new(200, 100, PI / 2.0).with_projection(Projection::Equirectangular);
+    let v = |x, y, z| Vector3::new(x, y, z);
+    assert_relative_eq!(
+        direction_at(&c, 100.0, 50.0),
+        v(0.0, 0.0, 1.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 0.0, 50.0),
+        v(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 50.0, 50.0),
+        v(1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 150.0, 50.0),
+        v(-1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 30.0, 0.0),
+        v(0.0, 1.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        direction_at(&c, 30.0, 100.0),
+        v(0.0, -1.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn panorama_has_the_layout_of_an_environment_map() {
+    let c = Camera::new(64, 32, PI / 2.0).with_projection(Projection::Equirectangular);
+    for (px, py) in [(3, 5), (40, 12), (63, 31), (20, 16)] {
+        let d = c.ray_for_pixel(px, py).unwrap().direction();
+        let (u, v) = spherical_map(Point3::new(d.x, d.y, d.z));
+        assert_relative_eq!(u, (px as f64 + 0.5) / 64.0, epsilon = EPSILON);
+        assert_relative_eq!(v, 1.0 - (py as f64 + 0.5) / 32.0, epsilon = EPSILON);
+    }
+}
+#[test]
+fn camera_is_a_pinhole_by_default() {
+    let c = Camera::new(100, 50, PI / 2.0);
+    assert_eq!(c.aperture(), 0.0);
+    assert_eq!(c.focal_distance(), 1.0);
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(c.sample_ray(10.5, 20.5, &mut rng), c.ray_for_pixel(10, 20));
+}
+#[test]
+fn rays_through_the_lens_converge_at_the_focal_distance() {
+    let c = Camera::new(100, 50, PI / 2.0)
+        .with_aperture(0.5)
+        .with_focal_distance(4.0);
+    let pinhole = c.ray_for_pixel(70, 10).unwrap();
+    let t = -4.0 / pinhole.direction().z;
+    let focus = pinhole.position(t);
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut spread = 0.0f64;
+    for _ in 0..50 {
+        let r = c.sample_ray(70.5, 10.5, &mut rng).unwrap();
+        let lens = r.origin();
+        assert_eq!(lens.z, 0.0);
+        assert!((lens.x * lens.x + lens.y * lens.y).sqrt() <= 0.25);
+        spread = spread.max(lens.x.abs());
+        let t = (-4.0 - lens.z) / r.direction().z;
+        assert_relative_eq!(r.position(t), focus, epsilon = EPSILON);
+    }
+    assert!(spread > 0.1);
+}
+#[test]
+fn out_of_focus_objects_are_blurred() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    let whitted = Whitted {
+        samples_per_pixel: 32,
+        ..Whitted::default()
+    };
+    // Just outside of the silhouette of the sphere.
+    let sharp: RawCanvas<11, 11, f64> = c.render_with(&w, &whitted);
+    assert_eq!(*sharp.pixel_at(5, 3).unwrap(), Color::new(0.0, 0.0, 0.0));
+    let blurred: RawCanvas<11, 11, f64> = c
+        .with_aperture(2.0)
+        .with_focal_distance(2.0)
+        .render_with(&w, &whitted);
+    assert!(blurred.pixel_at(5, 3).unwrap().g > 0.0);
+}
+#[test]
+fn whole_canvas_is_rendered_by_default() {
+    let c = Camera::<f64>::new(160, 120, PI / 2.0);
+    assert_eq!(c.render_region(), PixelBounds::new(0, 0, 160, 120));
+    let c = c.with_render_region(PixelBounds::new(10, 20, 200, 100));
+    assert_eq!(c.render_region(), PixelBounds::new(10, 20, 160, 100));
+}
+#[test]
+fn crop_window_covers_the_pixels_it_overlaps() {
+    let c = Camera::new(160, 120, PI / 2.0).with_crop_window(0.25, 0.1, 0.5, 0.51);
+    assert_eq!(c.render_region(), PixelBounds::new(40, 12, 80, 62));
+    let c = Camera::new(160, 120, PI / 2.0).with_crop_window(-1.0, 0.0, 2.0, 1.0);
+    assert_eq!(c.render_region(), PixelBounds::new(0, 0, 160, 120));
+}
+#[test]
+fn only_the_render_region_is_rendered() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    let full: RawCanvas<11, 11, f64> = c.render(&w);
+    let region = PixelBounds::new(4, 4, 7, 6);
+    let cropped: RawCanvas<11, 11, f64> = c.with_render_region(region).render(&w);
+    for y in 0..11 {
+        for x in 0..11 {
+            let expected = if region.contains(x, y) {
+                *full.pixel_at(x, y).unwrap()
+            } else {
+                Color::new(0.0, 0.0, 0.0)
+            };
+            assert_eq!(*cropped.pixel_at(x, y).unwrap(), expected);
+        }
+    }
+}
+#[test]
+fn adaptive_sampling_refines_only_the_edges() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    let plain: RawCanvas<11, 11, f64> = c.render(&w);
+    let adaptive: RawCanvas<11, 11, f64> = c.with_adaptive_sampling(0.001, 16).render(&w);
+    // The background far from the sphere is left as it is.
+    assert_eq!(adaptive.pixel_at(0, 0), plain.pixel_at(0, 0));
+    assert_eq!(adaptive.pixel_at(10, 0), plain.pixel_at(10, 0));
+    // The sphere covers a corner of this pixel, but not its center.
+    assert_eq!(*plain.pixel_at(4, 4).unwrap(), Color::new(0.0, 0.0, 0.0));
+    assert!(adaptive.pixel_at(4, 4).unwrap().g > 0.0);
+}
+#[test]
+fn rendering_the_pixels_of_a_size_known_at_run_time() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 7, PI / 2.0)
+        .with_transform(view)
+        .with_adaptive_sampling(0.001, 4);
+    let canvas: RawCanvas<11, 7, f64> = c.render(&w);
+    let pixels = c.render_pixels(&w, &c.settings().whitted(), &mut |_: &RenderProgress| {});
+    assert_eq!(pixels, canvas.pixels());
+}
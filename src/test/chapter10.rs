@@ -0,0 +1,179 @@
+/// Tests in Chapter 10.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, CheckerPattern, GradientPattern, Material, Matrix4, Pattern,
+    Point3, PointLight, RingPattern, Shape, Sphere, StripePattern, TestPattern, Transform, Vector3,
+    EPSILON,
+};
+
+fn black() -> Color<f64> {
+    Color::new(0.0, 0.0, 0.0)
+}
+
+fn white() -> Color<f64> {
+    Color::new(1.0, 1.0, 1.0)
+}
+
+#[test]
+fn creating_stripe_pattern() {
+    let pattern = StripePattern::new(white(), black());
+    assert_eq!(pattern.a(), white());
+    assert_eq!(pattern.b(), black());
+}
+#[test]
+fn stripe_pattern_is_constant_in_y_and_z() {
+    let pattern = StripePattern::new(white(), black());
+    for p in [
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(0.0, 2.0, 0.0),
+        Point3::new(0.0, 0.0, 1.0),
+        Point3::new(0.0, 0.0, 2.0),
+    ] {
+        assert_eq!(pattern.pattern_at(p), white());
+    }
+}
+#[test]
+fn stripe_pattern_alternates_in_x() {
+    let pattern = StripePattern::new(white(), black());
+    let at = |x: f64| pattern.pattern_at(Point3::new(x, 0.0, 0.0));
+    assert_eq!(at(0.0), white());
+    assert_eq!(at(0.9), white());
+    assert_eq!(at(1.0), black());
+    assert_eq!(at(-0.1), black());
+    assert_eq!(at(-1.0), black());
+    assert_eq!(at(-1.1), white());
+}
+#[test]
+fn lighting_with_pattern_applied() {
+    let m = Material::default()
+        .with_pattern(StripePattern::new(white(), black()))
+        .with_ambient(1.0)
+        .with_diffuse(0.0)
+        .with_specular(0.0);
+    let object = Sphere::default();
+    let eyev = Vector3::new(0.0, 0.0, -1.0);
+    let normalv = Vector3::new(0.0, 0.0, -1.0);
+    let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), white());
+    let c1 = lighting(
+        &m,
+        &object,
+        &light,
+        Point3::new(0.9, 0.0, 0.0),
+        eyev,
+        normalv,
+        false,
+    );
+    let c2 = lighting(
+        &m,
+        &object,
+        &light,
+        Point3::new(1.1, 0.0, 0.0),
+        eyev,
+        normalv,
+        false,
+    );
+    assert_eq!(c1, white());
+    assert_eq!(c2, black());
+}
+#[test]
+fn stripes_with_object_transformation() {
+    let mut object = Sphere::default();
+    object.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let pattern = StripePattern::new(white(), black());
+    let c = pattern.pattern_at_shape(&object, Point3::new(1.5, 0.0, 0.0));
+    assert_eq!(c, white());
+}
+#[test]
+fn stripes_with_pattern_transformation() {
+    let object = Sphere::default();
+    let mut pattern = StripePattern::new(white(), black());
+    pattern.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let c = pattern.pattern_at_shape(&object, Point3::new(1.5, 0.0, 0.0));
+    assert_eq!(c, white());
+}
+#[test]
+fn stripes_with_object_and_pattern_transformation() {
+    let mut object = Sphere::default();
+    object.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let mut pattern = StripePattern::new(white(), black());
+    pattern.set_transform(Transform::translation(0.5, 0.0, 0.0));
+    let c = pattern.pattern_at_shape(&object, Point3::new(2.5, 0.0, 0.0));
+    assert_eq!(c, white());
+}
+#[test]
+fn default_pattern_transformation() {
+    let pattern = TestPattern::<f64>::default();
+    assert_eq!(pattern.transform(), Matrix4::identity());
+}
+#[test]
+fn assigning_transformation() {
+    let mut pattern = TestPattern::default();
+    pattern.set_transform(Transform::translation(1.0, 2.0, 3.0));
+    assert_eq!(pattern.transform(), Transform::translation(1.0, 2.0, 3.0));
+}
+#[test]
+fn pattern_with_object_transformation() {
+    let mut shape = Sphere::default();
+    shape.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let pattern = TestPattern::default();
+    let c = pattern.pattern_at_shape(&shape, Point3::new(2.0, 3.0, 4.0));
+    assert_relative_eq!(c, Color::new(1.0, 1.5, 2.0), epsilon = EPSILON);
+}
+#[test]
+fn pattern_with_pattern_transformation() {
+    let shape = Sphere::default();
+    let mut pattern = TestPattern::default();
+    pattern.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let c = pattern.pattern_at_shape(&shape, Point3::new(2.0, 3.0, 4.0));
+    assert_relative_eq!(c, Color::new(1.0, 1.5, 2.0), epsilon = EPSILON);
+}
+#[test]
+fn pattern_with_object_and_pattern_transformation() {
+    let mut shape = Sphere::default();
+    shape.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let mut pattern = TestPattern::default();
+    pattern.set_transform(Transform::translation(0.5, 1.0, 1.5));
+    let c = pattern.pattern_at_shape(&shape, Point3::new(2.5, 3.0, 3.5));
+    assert_relative_eq!(c, Color::new(0.75, 0.5, 0.25), epsilon = EPSILON);
+}
+#[test]
+fn gradient_linearly_interpolates_between_colors() {
+    let pattern = GradientPattern::new(white(), black());
+    let at = |x: f64| pattern.pattern_at(Point3::new(x, 0.0, 0.0));
+    assert_eq!(at(0.0), white());
+    assert_eq!(at(0.25), Color::new(0.75, 0.75, 0.75));
+    assert_eq!(at(0.5), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(at(0.75), Color::new(0.25, 0.25, 0.25));
+}
+#[test]
+fn ring_extends_in_both_x_and_z() {
+    let pattern = RingPattern::new(white(), black());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(1.0, 0.0, 0.0)), black());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 1.0)), black());
+    // 0.708 = just slightly more than sqrt(2)/2
+    assert_eq!(pattern.pattern_at(Point3::new(0.708, 0.0, 0.708)), black());
+}
+#[test]
+fn checkers_repeat_in_x() {
+    let pattern = CheckerPattern::new(white(), black());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(0.99, 0.0, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(1.01, 0.0, 0.0)), black());
+}
+#[test]
+fn checkers_repeat_in_y() {
+    let pattern = CheckerPattern::new(white(), black());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.99, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 1.01, 0.0)), black());
+}
+#[test]
+fn checkers_repeat_in_z() {
+    let pattern = CheckerPattern::new(white(), black());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 0.0)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 0.99)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(0.0, 0.0, 1.01)), black());
+}
@@ -0,0 +1,114 @@
+/// Tests in Chapter 11.
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, Intersection, Material, Plane, Point3, PointLight, Ray, Shape,
+    Transform, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+/// The default world with a reflective plane below the spheres.
+fn world_with_reflective_plane() -> World<f64> {
+    let mut w = World::default();
+    let mut shape = Plane::default();
+    shape.set_material(Material::default().with_reflective(0.5));
+    shape.set_transform(Transform::translation(0.0, -1.0, 0.0));
+    w.add_object(shape);
+    w
+}
+
+/// A ray hitting the reflective plane of `world_with_reflective_plane` at 45 degrees.
+fn ray_to_plane() -> Ray<f64> {
+    Ray::new(
+        Point3::new(0.0, 0.0, -3.0),
+        Vector3::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    )
+}
+
+#[test]
+fn reflectivity_for_default_material() {
+    let m = Material::<f64>::default();
+    assert_eq!(m.reflective, 0.0);
+}
+#[test]
+fn transparency_and_refractive_index_for_default_material() {
+    let m = Material::<f64>::default();
+    assert_eq!(m.transparency, 0.0);
+    assert_eq!(m.refractive_index, 1.0);
+}
+#[test]
+fn precomputing_reflection_vector() {
+    let shape = Plane::default();
+    let r = Ray::new(
+        Point3::new(0.0, 1.0, -1.0),
+        Vector3::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    );
+    let comps = Intersection::new(SQRT_2, &shape).prepare_computations(&r);
+    assert_relative_eq!(
+        comps.reflectv,
+        Vector3::new(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2)
+    );
+}
+#[test]
+fn reflected_color_for_nonreflective_material() {
+    let mut w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let m = w.objects()[1].material().clone().with_ambient(1.0);
+    w.objects_mut()[1].set_material(m);
+    let comps = Intersection::new(1.0, w.objects()[1].as_ref()).prepare_computations(&r);
+    assert_eq!(
+        w.reflected_color(&comps, MAX_DEPTH),
+        Color::new(0.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn reflected_color_for_reflective_material() {
+    let w = world_with_reflective_plane();
+    let comps =
+        Intersection::new(SQRT_2, w.objects()[2].as_ref()).prepare_computations(&ray_to_plane());
+    assert_relative_eq!(
+        w.reflected_color(&comps, MAX_DEPTH),
+        Color::new(0.19033, 0.23791, 0.14274),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn shade_hit_with_reflective_material() {
+    let w = world_with_reflective_plane();
+    let comps =
+        Intersection::new(SQRT_2, w.objects()[2].as_ref()).prepare_computations(&ray_to_plane());
+    assert_relative_eq!(
+        w.shade_hit(&comps, MAX_DEPTH),
+        Color::new(0.87676, 0.92434, 0.82917),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn color_at_with_mutually_reflective_surfaces() {
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    let mirror = Material::default().with_reflective(1.0);
+    let mut lower = Plane::default();
+    lower.set_material(mirror.clone());
+    lower.set_transform(Transform::translation(0.0, -1.0, 0.0));
+    w.add_object(lower);
+    let mut upper = Plane::default();
+    upper.set_material(mirror);
+    upper.set_transform(Transform::translation(0.0, 1.0, 0.0));
+    w.add_object(upper);
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    // Terminates, the bounces are bounded.
+    let color = w.color_at(&r, MAX_DEPTH);
+    assert!(color.r > 0.0);
+}
+#[test]
+fn reflected_color_at_maximum_recursive_depth() {
+    let w = world_with_reflective_plane();
+    let comps =
+        Intersection::new(SQRT_2, w.objects()[2].as_ref()).prepare_computations(&ray_to_plane());
+    assert_eq!(w.reflected_color(&comps, 0), Color::new(0.0, 0.0, 0.0));
+}
@@ -0,0 +1,109 @@
+/// Tests in Chapter 13.
+use approx::assert_relative_eq;
+
+use crate::{Cylinder, Point3, Ray, Shape, Vector3, EPSILON};
+
+#[test]
+fn ray_misses_cylinder() {
+    let cyl = Cylinder::default();
+    for (origin, direction) in [
+        (Point3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        (Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        (Point3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 1.0, 1.0)),
+    ] {
+        let r = Ray::new(origin, direction.normalized());
+        assert!(cyl.local_intersect(&r).is_empty());
+    }
+}
+#[test]
+fn ray_strikes_cylinder() {
+    let cyl = Cylinder::default();
+    for (origin, direction, t0, t1) in [
+        (
+            Point3::new(1.0, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            5.0,
+            5.0,
+        ),
+        (
+            Point3::new(0.0, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Point3::new(0.5, 0.0, -5.0),
+            Vector3::new(0.1, 1.0, 1.0),
+            6.80798,
+            7.08872,
+        ),
+    ] {
+        let r = Ray::new(origin, direction.normalized());
+        let xs = cyl.local_intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert_relative_eq!(xs[0].t(), t0, epsilon = EPSILON);
+        assert_relative_eq!(xs[1].t(), t1, epsilon = EPSILON);
+    }
+}
+#[test]
+fn cylinder_normals() {
+    let cyl = Cylinder::default();
+    for (point, normal) in [
+        (Point3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+        (Point3::new(0.0, 5.0, -1.0), Vector3::new(0.0, 0.0, -1.0)),
+        (Point3::new(0.0, -2.0, 1.0), Vector3::new(0.0, 0.0, 1.0)),
+        (Point3::new(-1.0, 1.0, 0.0), Vector3::new(-1.0, 0.0, 0.0)),
+    ] {
+        assert_eq!(cyl.local_normal_at(point), normal);
+    }
+}
+#[test]
+fn default_cylinder_is_infinite_and_open() {
+    let cyl: Cylinder<f64> = Cylinder::default();
+    assert_eq!(cyl.minimum(), f64::NEG_INFINITY);
+    assert_eq!(cyl.maximum(), f64::INFINITY);
+    assert!(!cyl.closed());
+}
+#[test]
+fn intersect_truncated_cylinder() {
+    let cyl = Cylinder::truncated(1.0, 2.0, false);
+    for (origin, direction, count) in [
+        (Point3::new(0.0, 1.5, 0.0), Vector3::new(0.1, 1.0, 0.0), 0),
+        (Point3::new(0.0, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+        (Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+        (Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+        (Point3::new(0.0, 1.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+        (Point3::new(0.0, 1.5, -2.0), Vector3::new(0.0, 0.0, 1.0), 2),
+    ] {
+        let r = Ray::new(origin, direction.normalized());
+        assert_eq!(cyl.local_intersect(&r).len(), count);
+    }
+}
+#[test]
+fn intersect_caps_of_closed_cylinder() {
+    let cyl = Cylinder::truncated(1.0, 2.0, true);
+    for (origin, direction, count) in [
+        (Point3::new(0.0, 3.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 2),
+        (Point3::new(0.0, 3.0, -2.0), Vector3::new(0.0, -1.0, 2.0), 2),
+        (Point3::new(0.0, 4.0, -2.0), Vector3::new(0.0, -1.0, 1.0), 2),
+        (Point3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 1.0, 2.0), 2),
+        (Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 1.0, 1.0), 2),
+    ] {
+        let r = Ray::new(origin, direction.normalized());
+        assert_eq!(cyl.local_intersect(&r).len(), count);
+    }
+}
+#[test]
+fn normals_on_end_caps() {
+    let cyl = Cylinder::truncated(1.0, 2.0, true);
+    for (point, normal) in [
+        (Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        (Point3::new(0.5, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        (Point3::new(0.0, 1.0, 0.5), Vector3::new(0.0, -1.0, 0.0)),
+        (Point3::new(0.0, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        (Point3::new(0.5, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        (Point3::new(0.0, 2.0, 0.5), Vector3::new(0.0, 1.0, 0.0)),
+    ] {
+        assert_eq!(cyl.local_normal_at(point), normal);
+    }
+}
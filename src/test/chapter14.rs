@@ -0,0 +1,109 @@
+/// Tests in Chapter 14.
+use std::f64::consts::FRAC_PI_2;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::shapes::Shape, Group, Matrix4, Point3, Ray, Sphere, Transform, Vector3, EPSILON,
+};
+
+#[test]
+fn creating_a_new_group() {
+    let g = Group::<f64>::default();
+    assert_eq!(g.transform(), Matrix4::identity());
+    assert!(g.children().is_empty());
+}
+#[test]
+fn adding_a_child_to_a_group() {
+    let mut g = Group::new(Transform::translation(1.0, 0.0, 0.0));
+    g.add_child(Sphere::new(Transform::scaling(2.0, 2.0, 2.0)));
+    assert_eq!(g.children().len(), 1);
+    let child = &g.children()[0];
+    assert_eq!(
+        child.base().world_transform(),
+        Transform::translation(1.0, 0.0, 0.0) * Transform::scaling(2.0, 2.0, 2.0)
+    );
+}
+#[test]
+fn intersecting_a_ray_with_an_empty_group() {
+    let g = Group::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(g.local_intersect(&r).is_empty());
+}
+#[test]
+fn intersecting_a_ray_with_a_nonempty_group() {
+    let mut g = Group::default();
+    g.add_child(Sphere::default());
+    g.add_child(Sphere::new(Transform::translation(0.0, 0.0, -3.0)));
+    g.add_child(Sphere::new(Transform::translation(5.0, 0.0, 0.0)));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = g.local_intersect(&r);
+    assert_eq!(xs.len(), 4);
+    let (s1, s2) = (&*g.children()[0], &*g.children()[1]);
+    assert!(xs[0].object() == s2);
+    assert!(xs[1].object() == s2);
+    assert!(xs[2].object() == s1);
+    assert!(xs[3].object() == s1);
+}
+#[test]
+fn intersecting_a_transformed_group() {
+    let mut g = Group::new(Transform::scaling(2.0, 2.0, 2.0));
+    g.add_child(Sphere::new(Transform::translation(5.0, 0.0, 0.0)));
+    let r = Ray::new(Point3::new(10.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(&r).len(), 2);
+}
+
+/// A sphere at `(5, 0, 0)`, in a group scaled by `scaling`, in a group
+/// rotated around y.
+fn nested_groups(scaling: Matrix4<f64>) -> Group<f64> {
+    let mut g2 = Group::new(scaling);
+    g2.add_child(Sphere::new(Transform::translation(5.0, 0.0, 0.0)));
+    let mut g1 = Group::default();
+    g1.add_child(g2);
+    // Setting the transformation after adding the children reaches them too.
+    g1.set_transform(Transform::rotation_y(FRAC_PI_2));
+    g1
+}
+#[test]
+fn converting_a_point_from_world_to_object_space() {
+    // The sphere has a radius of 2 and is centered at `(0, 0, -10)`.
+    let g1 = nested_groups(Transform::scaling(2.0, 2.0, 2.0));
+    let r = Ray::new(Point3::new(-5.0, 0.0, -10.0), Vector3::new(1.0, 0.0, 0.0));
+    let xs = g1.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 3.0, epsilon = EPSILON);
+    let s = xs[0].object();
+    assert_relative_eq!(
+        s.world_to_object(Point3::new(-2.0, 0.0, -10.0)),
+        Point3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn converting_a_normal_from_object_to_world_space() {
+    // The sphere is centered at `(0, 0, -5)`, stretched by 3 along x.
+    let g1 = nested_groups(Transform::scaling(1.0, 2.0, 3.0));
+    let r = Ray::new(Point3::new(-5.0, 0.0, -5.0), Vector3::new(1.0, 0.0, 0.0));
+    let xs = g1.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 2.0, epsilon = EPSILON);
+    let s = xs[0].object();
+    let k = 3_f64.sqrt() / 3.0;
+    assert_relative_eq!(
+        s.normal_to_world(Vector3::new(k, k, k)),
+        Vector3::new(0.2857, 0.4286, -0.8571),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn finding_the_normal_on_a_child_object() {
+    // The sphere is centered at `(0, 0, -5)`, stretched by 3 along x.
+    let g1 = nested_groups(Transform::scaling(1.0, 2.0, 3.0));
+    let r = Ray::new(Point3::new(-5.0, 0.0, -5.0), Vector3::new(1.0, 0.0, 0.0));
+    let xs = g1.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 2.0, epsilon = EPSILON);
+    let s = xs[0].object();
+    assert_relative_eq!(
+        s.normal_at(Point3::new(1.7321, 1.1547, -5.5774)),
+        Vector3::new(0.2857, 0.4286, -0.8571),
+        epsilon = EPSILON
+    );
+}
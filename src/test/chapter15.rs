@@ -0,0 +1,78 @@
+/// Tests in Chapter 15.
+use approx::assert_relative_eq;
+
+use crate::{Intersection, Point3, Ray, Shape, SmoothTriangle, Triangle, Vector3, EPSILON};
+
+fn triangle() -> Triangle<f64> {
+    Triangle::new(
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(-1.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+    )
+}
+fn smooth_triangle() -> SmoothTriangle<f64> {
+    SmoothTriangle::new(
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(-1.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(-1.0, 0.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+    )
+}
+#[test]
+fn construct_triangle() {
+    let t = triangle();
+    assert_eq!(
+        t.edges(),
+        [Vector3::new(-1.0, -1.0, 0.0), Vector3::new(1.0, -1.0, 0.0)]
+    );
+    assert_eq!(t.normal(), Vector3::new(0.0, 0.0, -1.0));
+    for p in [
+        Point3::new(0.0, 0.5, 0.0),
+        Point3::new(-0.5, 0.75, 0.0),
+        Point3::new(0.5, 0.25, 0.0),
+    ] {
+        assert_eq!(t.local_normal_at(p), t.normal());
+    }
+}
+#[test]
+fn ray_misses_triangle() {
+    let t = triangle();
+    for (origin, direction) in [
+        // Parallel to the triangle.
+        (Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 1.0, 0.0)),
+        // Missing the p1-p3 edge.
+        (Point3::new(1.0, 1.0, -2.0), Vector3::new(0.0, 0.0, 1.0)),
+        // Missing the p1-p2 edge.
+        (Point3::new(-1.0, 1.0, -2.0), Vector3::new(0.0, 0.0, 1.0)),
+        // Missing the p2-p3 edge.
+        (Point3::new(0.0, -1.0, -2.0), Vector3::new(0.0, 0.0, 1.0)),
+    ] {
+        assert!(t.local_intersect(&Ray::new(origin, direction)).is_empty());
+    }
+}
+#[test]
+fn ray_strikes_triangle() {
+    let t = triangle();
+    let r = Ray::new(Point3::new(0.0, 0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = t.local_intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t(), 2.0);
+}
+#[test]
+fn smooth_triangle_intersection_stores_uv() {
+    let tri = smooth_triangle();
+    let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = tri.local_intersect(&r);
+    let (u, v) = xs[0].uv().unwrap();
+    assert_relative_eq!(u, 0.45, epsilon = EPSILON);
+    assert_relative_eq!(v, 0.25, epsilon = EPSILON);
+}
+#[test]
+fn smooth_triangle_interpolates_normal() {
+    let tri = smooth_triangle();
+    let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+    let n = tri.normal_at_hit(Point3::new(0.0, 0.0, 0.0), &i);
+    assert_relative_eq!(n, Vector3::new(-0.5547, 0.83205, 0.0), epsilon = EPSILON);
+}
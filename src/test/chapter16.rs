@@ -0,0 +1,118 @@
+/// Tests in Chapter 16.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::shapes::Shape, Csg, CsgOperation, Group, Intersection, Point3, Ray, Sphere,
+    Transform, Vector3, EPSILON,
+};
+
+#[test]
+fn csg_is_created_with_an_operation_and_two_shapes() {
+    let c = Csg::union(Sphere::<f64>::default(), Sphere::default());
+    assert_eq!(c.operation(), CsgOperation::Union);
+    assert!(c.includes(c.left()));
+    assert!(c.includes(c.right()));
+    assert!(!c.left().includes(c.right()));
+}
+#[test]
+fn evaluating_the_rule_for_csg_operations() {
+    use CsgOperation::*;
+    // (operation, left_hit, in_left, in_right, result)
+    let cases = [
+        (Union, true, true, true, false),
+        (Union, true, true, false, true),
+        (Union, true, false, true, false),
+        (Union, true, false, false, true),
+        (Union, false, true, true, false),
+        (Union, false, true, false, false),
+        (Union, false, false, true, true),
+        (Union, false, false, false, true),
+        (Intersection, true, true, true, true),
+        (Intersection, true, true, false, false),
+        (Intersection, true, false, true, true),
+        (Intersection, true, false, false, false),
+        (Intersection, false, true, true, true),
+        (Intersection, false, true, false, true),
+        (Intersection, false, false, true, false),
+        (Intersection, false, false, false, false),
+        (Difference, true, true, true, false),
+        (Difference, true, true, false, true),
+        (Difference, true, false, true, false),
+        (Difference, true, false, false, true),
+        (Difference, false, true, true, true),
+        (Difference, false, true, false, true),
+        (Difference, false, false, true, false),
+        (Difference, false, false, false, false),
+    ];
+    for (op, lhit, inl, inr, result) in cases {
+        assert_eq!(op.intersection_allowed(lhit, inl, inr), result);
+    }
+}
+#[test]
+fn filtering_a_list_of_intersections() {
+    for (op, x0, x1) in [
+        (CsgOperation::Union, 0, 3),
+        (CsgOperation::Intersection, 1, 2),
+        (CsgOperation::Difference, 0, 1),
+    ] {
+        let c = Csg::new(op, Sphere::<f64>::default(), Sphere::default());
+        let (s1, s2) = (c.left(), c.right());
+        let xs = vec![
+            Intersection::new(1.0, s1),
+            Intersection::new(2.0, s2),
+            Intersection::new(3.0, s1),
+            Intersection::new(4.0, s2),
+        ];
+        let result = c.filter_intersections(xs.clone());
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], xs[x0]);
+        assert_eq!(result[1], xs[x1]);
+    }
+}
+#[test]
+fn filtering_intersections_with_a_nested_child() {
+    let mut g = Group::default();
+    g.add_child(Sphere::default());
+    let c = Csg::difference(g, Sphere::new(Transform::translation(0.0, 0.0, 0.5)));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = c.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 4.0, epsilon = EPSILON);
+    assert_relative_eq!(xs[1].t(), 4.5, epsilon = EPSILON);
+}
+#[test]
+fn a_ray_misses_a_csg_object() {
+    let c = Csg::union(Sphere::default(), Sphere::default());
+    let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(c.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_hits_a_csg_object() {
+    let c = Csg::union(
+        Sphere::default(),
+        Sphere::new(Transform::translation(0.0, 0.0, 0.5)),
+    );
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = c.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 4.0, epsilon = EPSILON);
+    assert!(xs[0].object() == c.left());
+    assert_relative_eq!(xs[1].t(), 6.5, epsilon = EPSILON);
+    assert!(xs[1].object() == c.right());
+}
+#[test]
+fn normals_of_a_transformed_csg_object() {
+    let mut c = Csg::intersection(
+        Sphere::default(),
+        Sphere::new(Transform::translation(0.0, 0.0, 0.5)),
+    );
+    c.set_transform(Transform::translation(0.0, 3.0, 0.0));
+    let r = Ray::new(Point3::new(0.0, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = c.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 4.5, epsilon = EPSILON);
+    assert_relative_eq!(
+        xs[0].object().normal_at(r.position(xs[0].t())),
+        Vector3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+}
@@ -20,7 +20,7 @@ mod canvas_test {
             },
             colors::Color,
         },
-        Point3, Vector3,
+        to_ppm, Point3, Vector3,
     };
     use approx::assert_relative_eq;
     use std::fs;
@@ -60,6 +60,15 @@ mod canvas_test {
         }
     }
     #[test]
+    fn ppm_of_a_size_known_at_run_time() {
+        let mut canvas: RawCanvas<10, 2, f64> = RawCanvas::default();
+        canvas.write_pixel(3, 1, Color::new(1.0, 0.8, 0.6)).unwrap();
+        let text = to_ppm(10, 2, canvas.pixels());
+        let ppm_canvas: PPMCanvas<10, 2> = canvas.into();
+        assert_eq!(text, ppm_canvas.to_string());
+        assert!(text.starts_with("P3\n10 2\n255\n"));
+    }
+    #[test]
     fn split_long_lines() {
         let mut canvas: RawCanvas<10, 2, f64> = RawCanvas::default();
         for x in 0..10 {
@@ -133,3 +142,78 @@ mod canvas_test {
             .expect("error executing 'rm chapter2_proj_draw.ppm'");
     }
 }
+#[cfg(test)]
+mod compositing_test {
+    use approx::assert_relative_eq;
+
+    use crate::{features::colors::Color, ImageError, Overlay, RawCanvas, UvImage};
+
+    #[test]
+    fn overlay_with_opacity() {
+        let mut canvas: RawCanvas<4, 4, f64> = RawCanvas::default();
+        let mut logo: RawCanvas<2, 2, f64> = RawCanvas::default();
+        for x in 0..2 {
+            for y in 0..2 {
+                logo.write_pixel(x, y, Color::new(1.0, 1.0, 1.0)).unwrap();
+            }
+        }
+        canvas.overlay(&logo, 1, 1, 0.5);
+        assert_relative_eq!(canvas.pixel_at(1, 1).unwrap(), &Color::new(0.5, 0.5, 0.5));
+        assert_relative_eq!(canvas.pixel_at(2, 2).unwrap(), &Color::new(0.5, 0.5, 0.5));
+        assert_relative_eq!(canvas.pixel_at(0, 0).unwrap(), &Color::default());
+        assert_relative_eq!(canvas.pixel_at(3, 3).unwrap(), &Color::default());
+    }
+    #[test]
+    fn overlay_is_clipped_and_masked() {
+        let mut canvas: RawCanvas<3, 3, f64> = RawCanvas::default();
+        let mut logo: RawCanvas<2, 2, f64> = RawCanvas::default();
+        logo.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0)).unwrap();
+        logo.write_pixel(1, 1, Color::new(0.0, 1.0, 0.0)).unwrap();
+        // Only the top-left pixel of the logo is opaque.
+        canvas.overlay_masked(&logo, &[1.0, 0.0, 0.0, 0.0], 2, 2);
+        assert_relative_eq!(canvas.pixel_at(2, 2).unwrap(), &Color::new(1.0, 0.0, 0.0));
+    }
+    #[test]
+    fn overlay_of_an_image_file() {
+        let mut logo: RawCanvas<2, 2, f64> = RawCanvas::default();
+        logo.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0)).unwrap();
+        logo.write_pixel(1, 1, Color::new(0.0, 1.0, 0.0)).unwrap();
+        let dir = std::env::temp_dir().join("raytracer-rust-overlay-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        logo.save(dir.join("logo.pfm")).unwrap();
+        let overlay = Overlay::new(UvImage::from_file(dir.join("logo.pfm")).unwrap())
+            .at(2, 1)
+            .with_opacity(0.5);
+        // The same as stamping the canvas, clipped to the pixels.
+        let mut pixels = vec![Color::new(0.0, 0.0, 1.0); 3 * 3];
+        overlay.apply(&mut pixels, 3);
+        let mut canvas: RawCanvas<3, 3, f64> = RawCanvas::default();
+        for pixel in canvas.pixels_mut() {
+            *pixel = Color::new(0.0, 0.0, 1.0);
+        }
+        canvas.overlay(&logo, 2, 1, 0.5);
+        assert_eq!(pixels, canvas.pixels());
+        assert_relative_eq!(pixels[3 + 2], Color::new(0.5, 0.0, 0.5));
+        assert!(matches!(
+            UvImage::<f64>::from_file(dir.join("logo.bmp")),
+            Err(ImageError::UnsupportedFormat(_))
+        ));
+    }
+    #[test]
+    fn overlay_far_off_the_canvas_is_clipped() {
+        let logo: RawCanvas<2, 2, f64> = RawCanvas::default();
+        let mut canvas: RawCanvas<3, 3, f64> = RawCanvas::default();
+        for pixel in canvas.pixels_mut() {
+            *pixel = Color::new(0.0, 0.0, 1.0);
+        }
+        let before = canvas.clone();
+        canvas.overlay(&logo, usize::MAX, 0, 1.0);
+        canvas.overlay(&logo, 0, usize::MAX - 1, 1.0);
+        assert_eq!(canvas, before);
+        let mut pixels = vec![Color::new(0.0, 0.0, 1.0); 3 * 3];
+        Overlay::new(UvImage::<f64>::new(2, 2, vec![Color::default(); 4]))
+            .at(usize::MAX, usize::MAX)
+            .apply(&mut pixels, 3);
+        assert_eq!(pixels, before.pixels());
+    }
+}
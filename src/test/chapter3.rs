@@ -0,0 +1,211 @@
+/// Tests in Chapter 3.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::linalg::tuple::Tuple, Matrix, Matrix2, Matrix3, Matrix4, Point3, Vector3, EPSILON,
+};
+
+#[test]
+fn construct_matrix4() {
+    let m = Matrix4::new([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.5, 6.5, 7.5, 8.5],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.5, 14.5, 15.5, 16.5],
+    ]);
+    assert_eq!(m[(0, 0)], 1.0);
+    assert_eq!(m[(0, 3)], 4.0);
+    assert_eq!(m[(1, 0)], 5.5);
+    assert_eq!(m[(1, 2)], 7.5);
+    assert_eq!(m[(2, 2)], 11.0);
+    assert_eq!(m[(3, 0)], 13.5);
+    assert_eq!(m[(3, 2)], 15.5);
+    assert_eq!(m.row(1), Tuple([5.5, 6.5, 7.5, 8.5]));
+    assert_eq!(m.col(2), Tuple([3.0, 7.5, 11.0, 15.5]));
+}
+#[test]
+fn construct_other_sizes() {
+    let m2: Matrix<i32, 2, 2> = Matrix::new([[-3, 5], [1, -2]]);
+    assert_eq!(m2[(0, 0)], -3);
+    assert_eq!(m2[(1, 1)], -2);
+    let m3: Matrix<i32, 3, 3> = Matrix::from([[-3, 5, 0], [1, -2, -7], [0, 1, 1]]);
+    assert_eq!(m3[(1, 2)], -7);
+    assert_eq!(m3[(2, 2)], 1);
+}
+#[test]
+fn matrix_equality() {
+    let a = Matrix4::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 8, 7, 6], [5, 4, 3, 2]]);
+    let b = Matrix4::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 8, 7, 6], [5, 4, 3, 2]]);
+    let c = Matrix4::new([[2, 3, 4, 5], [6, 7, 8, 9], [8, 7, 6, 5], [4, 3, 2, 1]]);
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+#[test]
+fn multiply_matrices() {
+    let a = Matrix4::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 8, 7, 6], [5, 4, 3, 2]]);
+    let b = Matrix4::new([[-2, 1, 2, 3], [3, 2, 1, -1], [4, 3, 6, 5], [1, 2, 7, 8]]);
+    assert_eq!(
+        a * b,
+        Matrix4::new([
+            [20, 22, 50, 48],
+            [44, 54, 114, 108],
+            [40, 58, 110, 102],
+            [16, 26, 46, 42]
+        ])
+    );
+}
+#[test]
+fn multiply_matrix_by_tuple() {
+    let a = Matrix4::new([[1, 2, 3, 4], [2, 4, 4, 2], [8, 6, 4, 1], [0, 0, 0, 1]]);
+    let b = Tuple([1, 2, 3, 1]);
+    assert_eq!(a * b, Tuple([18, 24, 33, 1]));
+}
+#[test]
+fn multiply_by_identity() {
+    let a = Matrix4::new([[0, 1, 2, 4], [1, 2, 4, 8], [2, 4, 8, 16], [4, 8, 16, 32]]);
+    assert_eq!(a * Matrix4::identity(), a);
+    let t = Tuple([1, 2, 3, 4]);
+    assert_eq!(Matrix4::identity() * t, t);
+}
+#[test]
+fn transpose_matrix() {
+    let a = Matrix4::new([[0, 9, 3, 0], [9, 8, 0, 8], [1, 8, 5, 3], [0, 0, 5, 8]]);
+    assert_eq!(
+        a.transpose(),
+        Matrix4::new([[0, 9, 1, 0], [9, 8, 8, 0], [3, 0, 5, 5], [0, 8, 3, 8]])
+    );
+    let i: Matrix4<f64> = Matrix4::identity();
+    assert_eq!(i.transpose(), i);
+    let r: Matrix<i32, 2, 3> = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(r.transpose(), Matrix::new([[1, 4], [2, 5], [3, 6]]));
+}
+#[test]
+fn determinant_2x2() {
+    let a: Matrix<i32, 2, 2> = Matrix::new([[1, 5], [-3, 2]]);
+    assert_eq!(a.determinant(), 17);
+}
+#[test]
+fn submatrices() {
+    let a: Matrix<i32, 3, 3> = Matrix::new([[1, 5, 0], [-3, 2, 7], [0, 6, -3]]);
+    assert_eq!(a.submatrix(0, 2), Matrix::new([[-3, 2], [0, 6]]));
+    let b = Matrix4::new([[-6, 1, 1, 6], [-8, 5, 8, 6], [-1, 0, 8, 2], [-7, 1, -1, 1]]);
+    assert_eq!(
+        b.submatrix(2, 1),
+        Matrix::new([[-6, 1, 6], [-8, 8, 6], [-7, -1, 1]])
+    );
+}
+#[test]
+fn minors_and_cofactors() {
+    let a: Matrix<i32, 3, 3> = Matrix::new([[3, 5, 0], [2, -1, -7], [6, -1, 5]]);
+    assert_eq!(a.submatrix(1, 0).determinant(), 25);
+    assert_eq!(a.minor(1, 0), 25);
+    assert_eq!(a.minor(0, 0), -12);
+    assert_eq!(a.cofactor(0, 0), -12);
+    assert_eq!(a.minor(1, 0), 25);
+    assert_eq!(a.cofactor(1, 0), -25);
+}
+#[test]
+fn determinant_of_larger_matrices() {
+    let a: Matrix<i32, 3, 3> = Matrix::new([[1, 2, 6], [-5, 8, -4], [2, 6, 4]]);
+    assert_eq!(a.cofactor(0, 0), 56);
+    assert_eq!(a.cofactor(0, 1), 12);
+    assert_eq!(a.cofactor(0, 2), -46);
+    assert_eq!(a.determinant(), -196);
+    let b = Matrix4::new([[-2, -8, 3, 5], [-3, 1, 7, 3], [1, 2, -9, 6], [-6, 7, 7, -9]]);
+    assert_eq!(b.cofactor(0, 0), 690);
+    assert_eq!(b.cofactor(0, 1), 447);
+    assert_eq!(b.cofactor(0, 2), 210);
+    assert_eq!(b.cofactor(0, 3), 51);
+    assert_eq!(b.determinant(), -4071);
+}
+#[test]
+fn invertibility() {
+    let a = Matrix4::new([[6, 4, 4, 4], [5, 5, 7, 6], [4, -9, 3, -7], [9, 1, 7, -6]]);
+    assert_eq!(a.determinant(), -2120);
+    assert!(a.is_invertible());
+    let b: Matrix4<f64> = Matrix4::new([
+        [-4.0, 2.0, -2.0, -3.0],
+        [9.0, 6.0, 2.0, 6.0],
+        [0.0, -5.0, 1.0, -5.0],
+        [0.0, 0.0, 0.0, 0.0],
+    ]);
+    assert!(!b.is_invertible());
+    assert_eq!(b.inverse(), None);
+}
+#[test]
+fn inverse_matrix() {
+    let a: Matrix4<f64> = Matrix4::new([
+        [-5.0, 2.0, 6.0, -8.0],
+        [1.0, -5.0, 1.0, 8.0],
+        [7.0, 7.0, -6.0, -7.0],
+        [1.0, -3.0, 7.0, 4.0],
+    ]);
+    let b = a.inverse().unwrap();
+    assert_eq!(a.determinant(), 532.0);
+    assert_eq!(a.cofactor(2, 3), -160.0);
+    assert_eq!(b[(3, 2)], -160.0 / 532.0);
+    assert_eq!(a.cofactor(3, 2), 105.0);
+    assert_eq!(b[(2, 3)], 105.0 / 532.0);
+    assert_relative_eq!(
+        b,
+        Matrix4::new([
+            [0.21805, 0.45113, 0.24060, -0.04511],
+            [-0.80827, -1.45677, -0.44361, 0.52068],
+            [-0.07895, -0.22368, -0.05263, 0.19737],
+            [-0.52256, -0.81391, -0.30075, 0.30639],
+        ]),
+        epsilon = EPSILON
+    );
+    let c: Matrix4<f64> = Matrix4::new([
+        [8.0, -5.0, 9.0, 2.0],
+        [7.0, 5.0, 6.0, 1.0],
+        [-6.0, 0.0, 9.0, 6.0],
+        [-3.0, 0.0, -9.0, -4.0],
+    ]);
+    assert_relative_eq!(
+        c.inverse().unwrap(),
+        Matrix4::new([
+            [-0.15385, -0.15385, -0.28205, -0.53846],
+            [-0.07692, 0.12308, 0.02564, 0.03077],
+            [0.35897, 0.35897, 0.43590, 0.92308],
+            [-0.69231, -0.69231, -0.76923, -1.92308],
+        ]),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn multiply_product_by_inverse() {
+    let a: Matrix4<f64> = Matrix4::new([
+        [3.0, -9.0, 7.0, 3.0],
+        [3.0, -8.0, 2.0, -9.0],
+        [-4.0, 4.0, 4.0, 1.0],
+        [-6.0, 5.0, -1.0, 1.0],
+    ]);
+    let b: Matrix4<f64> = Matrix4::new([
+        [8.0, 2.0, 2.0, 2.0],
+        [3.0, -1.0, 7.0, 0.0],
+        [7.0, 0.0, 5.0, 4.0],
+        [6.0, -2.0, 0.0, 5.0],
+    ]);
+    let c = a * b;
+    assert_relative_eq!(c * b.inverse().unwrap(), a, epsilon = EPSILON);
+}
+#[test]
+fn multiply_rectangular_matrices() {
+    let a: Matrix<i32, 2, 3> = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    let b: Matrix<i32, 3, 2> = Matrix::new([[7, 8], [9, 10], [11, 12]]);
+    assert_eq!(a * b, Matrix2::new([[58, 64], [139, 154]]));
+    assert_eq!(
+        b * a,
+        Matrix3::new([[39, 54, 69], [49, 68, 87], [59, 82, 105]])
+    );
+    assert_eq!(a * Tuple([1, 0, -1]), Tuple([-2, -2]));
+}
+#[test]
+fn multiply_matrix_by_point_and_vector() {
+    let a = Matrix4::new([[1, 2, 3, 4], [2, 4, 4, 2], [8, 6, 4, 1], [0, 0, 0, 1]]);
+    // Same as multiplying the tuple (1, 2, 3, 1).
+    assert_eq!(a * Point3::new(1, 2, 3), Point3::new(18, 24, 33));
+    // The translation column does not affect a vector, and `w` stays zero.
+    assert_eq!(a * Vector3::new(1, 2, 3), Vector3::new(14, 22, 32));
+}
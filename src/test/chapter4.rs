@@ -0,0 +1,217 @@
+/// Tests in Chapter 4.
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+use approx::assert_relative_eq;
+
+use crate::{Point3, Quaternion, Transform, Vector3, EPSILON};
+
+#[test]
+fn translation() {
+    let transform = Transform::translation(5, -3, 2);
+    let p = Point3::new(-3, 4, 5);
+    assert_eq!(transform * p, Point3::new(2, 1, 7));
+}
+#[test]
+fn inverse_translation() {
+    let transform = Transform::translation(5.0, -3.0, 2.0);
+    let inv = transform.inverse().unwrap();
+    let p = Point3::new(-3.0, 4.0, 5.0);
+    assert_eq!(inv * p, Point3::new(-8.0, 7.0, 3.0));
+}
+#[test]
+fn translation_does_not_affect_vectors() {
+    let transform = Transform::translation(5, -3, 2);
+    let v = Vector3::new(-3, 4, 5);
+    assert_eq!(transform * v, v);
+}
+#[test]
+fn scaling() {
+    let transform = Transform::scaling(2, 3, 4);
+    assert_eq!(transform * Point3::new(-4, 6, 8), Point3::new(-8, 18, 32));
+    assert_eq!(transform * Vector3::new(-4, 6, 8), Vector3::new(-8, 18, 32));
+}
+#[test]
+fn inverse_scaling() {
+    let transform = Transform::scaling(2.0, 3.0, 4.0);
+    let inv = transform.inverse().unwrap();
+    let v = Vector3::new(-4.0, 6.0, 8.0);
+    assert_eq!(inv * v, Vector3::new(-2.0, 2.0, 2.0));
+}
+#[test]
+fn reflection() {
+    let transform = Transform::scaling(-1, 1, 1);
+    assert_eq!(transform * Point3::new(2, 3, 4), Point3::new(-2, 3, 4));
+    let inv = Transform::scaling(-1.0, 1.0, 1.0).inverse().unwrap();
+    assert_eq!(
+        inv * Point3::new(-2.0, 3.0, 4.0),
+        Point3::new(2.0, 3.0, 4.0)
+    );
+}
+#[test]
+fn rotation_around_x() {
+    let p = Point3::new(0.0, 1.0, 0.0);
+    let half_quarter = Transform::rotation_x(FRAC_PI_4);
+    let full_quarter = Transform::rotation_x(FRAC_PI_2);
+    assert_relative_eq!(
+        half_quarter * p,
+        Point3::new(0.0, SQRT_2 / 2.0, SQRT_2 / 2.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        full_quarter * p,
+        Point3::new(0.0, 0.0, 1.0),
+        epsilon = EPSILON
+    );
+    // The inverse rotates in the opposite direction.
+    assert_relative_eq!(
+        half_quarter.inverse().unwrap() * p,
+        Point3::new(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rotation_around_y() {
+    let p = Point3::new(0.0, 0.0, 1.0);
+    let half_quarter = Transform::rotation_y(FRAC_PI_4);
+    let full_quarter = Transform::rotation_y(FRAC_PI_2);
+    assert_relative_eq!(
+        half_quarter * p,
+        Point3::new(SQRT_2 / 2.0, 0.0, SQRT_2 / 2.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        full_quarter * p,
+        Point3::new(1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rotation_around_z() {
+    let p = Point3::new(0.0, 1.0, 0.0);
+    let half_quarter = Transform::rotation_z(FRAC_PI_4);
+    let full_quarter = Transform::rotation_z(FRAC_PI_2);
+    assert_relative_eq!(
+        half_quarter * p,
+        Point3::new(-SQRT_2 / 2.0, SQRT_2 / 2.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        full_quarter * p,
+        Point3::new(-1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn chained_transformations() {
+    let p = Point3::new(1.0, 0.0, 1.0);
+    let a = Transform::rotation_x(FRAC_PI_2);
+    let b = Transform::scaling(5.0, 5.0, 5.0);
+    let c = Transform::translation(10.0, 5.0, 7.0);
+    // Applied in reverse order: rotate, scale, then translate.
+    assert_relative_eq!(
+        c * b * a * p,
+        Point3::new(15.0, 0.0, 7.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rotation_around_arbitrary_axis() {
+    // The principal axes give back the dedicated rotations.
+    assert_relative_eq!(
+        Transform::rotation_around_axis(Vector3::unit_x(), FRAC_PI_4),
+        Transform::rotation_x(FRAC_PI_4),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        Transform::rotation_around_axis(Vector3::new(0.0, 2.0, 0.0), FRAC_PI_2),
+        Transform::rotation_y(FRAC_PI_2),
+        epsilon = EPSILON
+    );
+    // A third of a turn around the diagonal cycles the axes.
+    let diagonal = Vector3::new(1.0, 1.0, 1.0);
+    let r = Transform::rotation_around_axis(diagonal, 2.0 * std::f64::consts::FRAC_PI_3);
+    assert_relative_eq!(
+        r * Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(r * diagonal, diagonal, epsilon = EPSILON);
+    assert_relative_eq!(
+        r,
+        Quaternion::from_axis_angle(diagonal, 2.0 * std::f64::consts::FRAC_PI_3).to_matrix(),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn decompose_transformation() {
+    let t = Transform::translation(1.0, -2.0, 3.0)
+        * Transform::rotation_around_axis(Vector3::new(1.0, 2.0, 3.0), 1.2)
+        * Transform::scaling(2.0, 0.5, 4.0);
+    let d = t.decompose().unwrap();
+    assert_relative_eq!(
+        d.translation,
+        Vector3::new(1.0, -2.0, 3.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(d.scale, Vector3::new(2.0, 0.5, 4.0), epsilon = EPSILON);
+    assert_relative_eq!(
+        d.rotation.to_matrix(),
+        Transform::rotation_around_axis(Vector3::new(1.0, 2.0, 3.0), 1.2),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(d.to_matrix(), t, epsilon = EPSILON);
+}
+#[test]
+fn decompose_reflection_and_degenerate() {
+    let t = Transform::rotation_z(0.5) * Transform::scaling(-1.0, 2.0, 2.0);
+    let d = t.decompose().unwrap();
+    assert_relative_eq!(d.scale, Vector3::new(-1.0, 2.0, 2.0), epsilon = EPSILON);
+    assert_relative_eq!(d.to_matrix(), t, epsilon = EPSILON);
+    assert!(Transform::scaling(1.0, 0.0, 1.0).decompose().is_none());
+    let mut projective = Transform::identity();
+    projective[(3, 2)] = 1.0;
+    assert!(projective.decompose().is_none());
+}
+#[test]
+fn interpolate_transformations() {
+    let a = Transform::translation(1.0, 0.0, 0.0) * Transform::scaling(1.0, 1.0, 1.0);
+    let b = Transform::translation(3.0, 2.0, 0.0)
+        * Transform::rotation_y(FRAC_PI_2)
+        * Transform::scaling(3.0, 3.0, 3.0);
+    assert_relative_eq!(Transform::interpolate(&a, &b, 0.0), a, epsilon = EPSILON);
+    assert_relative_eq!(Transform::interpolate(&a, &b, 1.0), b, epsilon = EPSILON);
+    // Halfway: translated and scaled halfway, rotated by half the angle.
+    assert_relative_eq!(
+        Transform::interpolate(&a, &b, 0.5),
+        Transform::translation(2.0, 1.0, 0.0)
+            * Transform::rotation_y(FRAC_PI_4)
+            * Transform::scaling(2.0, 2.0, 2.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn interpolate_projective_transformations() {
+    // Not affine: blended element by element.
+    let mut a = Transform::identity();
+    a[(3, 2)] = 1.0;
+    let m = Transform::interpolate(&a, &Transform::scaling(3.0, 1.0, 1.0), 0.5);
+    assert_relative_eq!(m[(0, 0)], 2.0);
+    assert_relative_eq!(m[(3, 2)], 0.5);
+    assert_relative_eq!(m[(3, 3)], 1.0);
+}
+#[test]
+fn transform_normals() {
+    // Squashed along y: the normal tilts towards y instead of following the scaling.
+    let n = Transform::scaling(1.0, 0.5, 1.0)
+        .transform_normal(Vector3::new(0.0, SQRT_2 / 2.0, -SQRT_2 / 2.0))
+        .unwrap();
+    assert_relative_eq!(n, Vector3::new(0.0, 0.89443, -0.44721), epsilon = EPSILON);
+    // Translations do not affect normals.
+    let n = Transform::translation(0.0, 1.0, 0.0)
+        .transform_normal(Vector3::new(0.0, 2.0, 0.0))
+        .unwrap();
+    assert_relative_eq!(n, Vector3::new(0.0, 1.0, 0.0));
+    assert!(Transform::scaling(0.0, 1.0, 1.0)
+        .transform_normal(Vector3::unit_y())
+        .is_none());
+}
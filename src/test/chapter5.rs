@@ -0,0 +1,152 @@
+/// Tests in Chapter 5.
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    intersections, Intersection, Intersections, Point3, Ray, Shape, Sphere, Transform, Vector3,
+    EPSILON,
+};
+
+#[test]
+fn create_ray() {
+    let origin = Point3::new(1, 2, 3);
+    let direction = Vector3::new(4, 5, 6);
+    let r = Ray::new(origin, direction);
+    assert_eq!(r.origin(), origin);
+    assert_eq!(r.direction(), direction);
+}
+#[test]
+fn point_from_distance() {
+    let r = Ray::new(Point3::new(2.0, 3.0, 4.0), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(r.position(0.0), Point3::new(2.0, 3.0, 4.0));
+    assert_eq!(r.position(1.0), Point3::new(3.0, 3.0, 4.0));
+    assert_eq!(r.position(-1.0), Point3::new(1.0, 3.0, 4.0));
+    assert_eq!(r.position(2.5), Point3::new(4.5, 3.0, 4.0));
+}
+#[test]
+fn ray_intersects_sphere() {
+    let s = Sphere::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = s.intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t(), 4.0);
+    assert_eq!(xs[1].t(), 6.0);
+    assert_eq!(xs[0].object(), &s as &dyn Shape<f64>);
+}
+#[test]
+fn ray_intersects_sphere_at_tangent() {
+    let s = Sphere::default();
+    let r = Ray::new(Point3::new(0.0, 1.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = s.intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t(), 5.0);
+    assert_eq!(xs[1].t(), 5.0);
+}
+#[test]
+fn ray_misses_sphere() {
+    let s = Sphere::default();
+    let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(s.intersect(&r).is_empty());
+}
+#[test]
+fn ray_originates_inside_or_behind_sphere() {
+    let s = Sphere::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = s.intersect(&r);
+    assert_eq!((xs[0].t(), xs[1].t()), (-1.0, 1.0));
+    let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = s.intersect(&r);
+    assert_eq!((xs[0].t(), xs[1].t()), (-6.0, -4.0));
+}
+#[test]
+fn transform_ray() {
+    let r = Ray::new(Point3::new(1, 2, 3), Vector3::new(0, 1, 0));
+    let r2 = r.transform(&Transform::translation(3, 4, 5));
+    assert_eq!(r2.origin(), Point3::new(4, 6, 8));
+    assert_eq!(r2.direction(), Vector3::new(0, 1, 0));
+    let r3 = r.transform(&Transform::scaling(2, 3, 4));
+    assert_eq!(r3.origin(), Point3::new(2, 6, 12));
+    assert_eq!(r3.direction(), Vector3::new(0, 3, 0));
+}
+#[test]
+fn intersect_transformed_sphere() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut s = Sphere::default();
+    assert_eq!(s.transform(), Transform::identity());
+    s.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let xs = s.intersect(&r);
+    assert_eq!((xs[0].t(), xs[1].t()), (3.0, 7.0));
+    let s = Sphere::new(Transform::translation(5.0, 0.0, 0.0));
+    assert!(s.intersect(&r).is_empty());
+}
+#[test]
+fn sphere_normals() {
+    let s = Sphere::default();
+    assert_eq!(
+        s.normal_at(Point3::new(1.0, 0.0, 0.0)),
+        Vector3::new(1.0, 0.0, 0.0)
+    );
+    let k = 3_f64.sqrt() / 3.0;
+    let n = s.normal_at(Point3::new(k, k, k));
+    assert_relative_eq!(n, Vector3::new(k, k, k), epsilon = EPSILON);
+    assert_relative_eq!(n, n.normalized(), epsilon = EPSILON);
+}
+#[test]
+fn transformed_sphere_normals() {
+    let s = Sphere::new(Transform::translation(0.0, 1.0, 0.0));
+    let n = s.normal_at(Point3::new(0.0, 1.70711, -FRAC_1_SQRT_2));
+    assert_relative_eq!(
+        n,
+        Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        epsilon = EPSILON
+    );
+    let s = Sphere::new(Transform::scaling(1.0, 0.5, 1.0) * Transform::rotation_z(PI / 5.0));
+    let n = s.normal_at(Point3::new(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0));
+    assert_relative_eq!(n, Vector3::new(0.0, 0.97014, -0.24254), epsilon = EPSILON);
+}
+#[test]
+fn aggregate_intersections() {
+    let s = Sphere::default();
+    let i1 = Intersection::new(1.0, &s);
+    let i2 = Intersection::new(2.0, &s);
+    let xs = intersections![i2, i1];
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t(), 1.0);
+    assert_eq!(xs[1].t(), 2.0);
+    let xs: Intersections<f64> = s
+        .intersect(&Ray::new(
+            Point3::new(0.0, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ))
+        .into();
+    assert_eq!(xs[0].object(), &s as &dyn Shape<f64>);
+}
+#[test]
+fn hit_with_positive_and_negative_t() {
+    let s = Sphere::default();
+    let i1 = Intersection::new(1.0, &s);
+    let i2 = Intersection::new(2.0, &s);
+    assert_eq!(intersections![i2, i1].hit(), Some(&i1));
+    let i1 = Intersection::new(-1.0, &s);
+    let i2 = Intersection::new(1.0, &s);
+    assert_eq!(intersections![i2, i1].hit(), Some(&i2));
+    let i1 = Intersection::new(-2.0, &s);
+    let i2 = Intersection::new(-1.0, &s);
+    assert_eq!(intersections![i2, i1].hit(), None);
+}
+#[test]
+fn hit_is_lowest_nonnegative() {
+    let s = Sphere::default();
+    let i1 = Intersection::new(5.0, &s);
+    let i2 = Intersection::new(7.0, &s);
+    let i3 = Intersection::new(-3.0, &s);
+    let i4 = Intersection::new(2.0, &s);
+    let mut xs = intersections![i1, i2, i3];
+    xs.push(i4);
+    assert_eq!(xs.hit(), Some(&i4));
+    assert_eq!(
+        xs.iter().map(|i| i.t()).collect::<Vec<f64>>(),
+        vec![-3.0, 2.0, 5.0, 7.0]
+    );
+}
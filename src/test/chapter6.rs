@@ -0,0 +1,135 @@
+/// Tests in Chapter 6.
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, HeightField, Material, Point3, PointLight, Ray, Shape,
+    Sphere, Vector3, EPSILON,
+};
+
+#[test]
+fn reflect_vector_at_45_degrees() {
+    let v = Vector3::new(1.0, -1.0, 0.0);
+    let n = Vector3::new(0.0, 1.0, 0.0);
+    assert_relative_eq!(v.reflect(&n), Vector3::new(1.0, 1.0, 0.0));
+}
+#[test]
+fn reflect_vector_off_slanted_surface() {
+    let v = Vector3::new(0.0, -1.0, 0.0);
+    let n = Vector3::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0);
+    assert_relative_eq!(
+        v.reflect(&n),
+        Vector3::new(1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn point_light_has_position_and_intensity() {
+    let intensity = Color::new(1.0, 1.0, 1.0);
+    let position = Point3::new(0.0, 0.0, 0.0);
+    let light = PointLight::new(position, intensity);
+    assert_eq!(light.position(), position);
+    assert_eq!(light.intensity(), intensity);
+}
+#[test]
+fn default_material() {
+    let m = Material::<f64>::default();
+    assert_eq!(m.color, Color::new(1.0, 1.0, 1.0));
+    assert_eq!(m.ambient, 0.1);
+    assert_eq!(m.diffuse, 0.9);
+    assert_eq!(m.specular, 0.9);
+    assert_eq!(m.shininess, 200.0);
+}
+#[test]
+fn material_builder() {
+    let m = Material::default()
+        .with_color(Color::new(1.0, 0.2, 1.0))
+        .with_ambient(1.0)
+        .with_diffuse(0.7)
+        .with_specular(0.3)
+        .with_shininess(50.0);
+    assert_eq!(m.color, Color::new(1.0, 0.2, 1.0));
+    assert_eq!(m.ambient, 1.0);
+    assert_eq!(m.diffuse, 0.7);
+    assert_eq!(m.specular, 0.3);
+    assert_eq!(m.shininess, 50.0);
+}
+#[test]
+fn sphere_has_default_material() {
+    let s = Sphere::<f64>::default();
+    assert_eq!(s.material(), &Material::default());
+}
+#[test]
+fn sphere_may_be_assigned_material() {
+    let mut s = Sphere::default();
+    let m = Material::default().with_ambient(1.0);
+    s.set_material(m.clone());
+    assert_eq!(s.material(), &m);
+}
+#[test]
+fn tessellated_shape_shades_hits_with_its_material() {
+    let mut field = HeightField::from_heights(&[0.0, 0.0, 0.0, 0.0], 2, 2);
+    let m = Material::default().with_color(Color::new(0.0, 1.0, 0.0));
+    field.set_material(m.clone());
+    let r = Ray::new(Point3::new(0.3, 1.0, 0.6), Vector3::new(0.0, -1.0, 0.0));
+    let xs = field.intersect(&r);
+    assert_eq!(xs[0].object().material(), &m);
+}
+
+/// Lights the origin on a default material facing `-z`, seen along `eyev`,
+/// by a white light at `light_position`.
+fn light_at(eyev: Vector3<f64>, light_position: Point3<f64>) -> Color<f64> {
+    let m = Material::default();
+    let light = PointLight::new(light_position, Color::new(1.0, 1.0, 1.0));
+    let normalv = Vector3::new(0.0, 0.0, -1.0);
+    lighting(
+        &m,
+        &Sphere::default(),
+        &light,
+        Point3::new(0.0, 0.0, 0.0),
+        eyev,
+        normalv,
+        false,
+    )
+}
+
+#[test]
+fn lighting_with_eye_between_light_and_surface() {
+    let result = light_at(Vector3::new(0.0, 0.0, -1.0), Point3::new(0.0, 0.0, -10.0));
+    assert_relative_eq!(result, Color::new(1.9, 1.9, 1.9), epsilon = EPSILON);
+}
+#[test]
+fn lighting_with_eye_offset_45_degrees() {
+    let result = light_at(
+        Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        Point3::new(0.0, 0.0, -10.0),
+    );
+    assert_relative_eq!(result, Color::new(1.0, 1.0, 1.0), epsilon = EPSILON);
+}
+#[test]
+fn lighting_with_light_offset_45_degrees() {
+    let result = light_at(Vector3::new(0.0, 0.0, -1.0), Point3::new(0.0, 10.0, -10.0));
+    assert_relative_eq!(
+        result,
+        Color::new(0.7364, 0.7364, 0.7364),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn lighting_with_eye_in_path_of_reflection() {
+    let result = light_at(
+        Vector3::new(0.0, -FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        Point3::new(0.0, 10.0, -10.0),
+    );
+    assert_relative_eq!(
+        result,
+        Color::new(1.6364, 1.6364, 1.6364),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn lighting_with_light_behind_surface() {
+    let result = light_at(Vector3::new(0.0, 0.0, -1.0), Point3::new(0.0, 0.0, 10.0));
+    assert_relative_eq!(result, Color::new(0.1, 0.1, 0.1), epsilon = EPSILON);
+}
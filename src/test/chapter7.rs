@@ -0,0 +1,244 @@
+/// Tests in Chapter 7.
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, Camera, Intersection, Material, Matrix4, Point3, PointLight,
+    RawCanvas, Ray, Shape, Sphere, Transform, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+#[test]
+fn creating_world() {
+    let w = World::<f64>::new();
+    assert!(w.objects().is_empty());
+    assert!(w.lights().is_empty());
+}
+#[test]
+fn default_world() {
+    let light = PointLight::new(Point3::new(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+    let mut s1 = Sphere::default();
+    s1.set_material(
+        Material::default()
+            .with_color(Color::new(0.8, 1.0, 0.6))
+            .with_diffuse(0.7)
+            .with_specular(0.2),
+    );
+    let mut s2 = Sphere::default();
+    s2.set_transform(Transform::scaling(0.5, 0.5, 0.5));
+    let w = World::default();
+    assert_eq!(w.lights(), [light]);
+    assert_eq!(w.objects().len(), 2);
+    assert_eq!(w.objects()[0].material(), s1.material());
+    assert_eq!(w.objects()[0].transform(), s1.transform());
+    assert_eq!(w.objects()[1].material(), s2.material());
+    assert_eq!(w.objects()[1].transform(), s2.transform());
+}
+#[test]
+fn intersect_world_with_ray() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = w.intersect(&r);
+    assert_eq!(xs.len(), 4);
+    assert_eq!(xs[0].t(), 4.0);
+    assert_eq!(xs[1].t(), 4.5);
+    assert_eq!(xs[2].t(), 5.5);
+    assert_eq!(xs[3].t(), 6.0);
+}
+#[test]
+fn precomputing_state_of_intersection() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = Sphere::default();
+    let i = Intersection::new(4.0, &shape);
+    let comps = i.prepare_computations(&r);
+    assert_eq!(comps.t, i.t());
+    assert_eq!(comps.object, i.object());
+    assert_eq!(comps.point, Point3::new(0.0, 0.0, -1.0));
+    assert_eq!(comps.eyev, Vector3::new(0.0, 0.0, -1.0));
+    assert_eq!(comps.normalv, Vector3::new(0.0, 0.0, -1.0));
+}
+#[test]
+fn hit_on_the_outside() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = Sphere::default();
+    let comps = Intersection::new(4.0, &shape).prepare_computations(&r);
+    assert!(!comps.inside);
+}
+#[test]
+fn hit_on_the_inside() {
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = Sphere::default();
+    let comps = Intersection::new(1.0, &shape).prepare_computations(&r);
+    assert_eq!(comps.point, Point3::new(0.0, 0.0, 1.0));
+    assert_eq!(comps.eyev, Vector3::new(0.0, 0.0, -1.0));
+    assert!(comps.inside);
+    // The normal is inverted to face the eye.
+    assert_eq!(comps.normalv, Vector3::new(0.0, 0.0, -1.0));
+}
+#[test]
+fn shading_intersection() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = w.objects()[0].as_ref();
+    let comps = Intersection::new(4.0, shape).prepare_computations(&r);
+    assert_relative_eq!(
+        w.shade_hit(&comps, MAX_DEPTH),
+        Color::new(0.38066, 0.47583, 0.2855),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn shading_intersection_from_inside() {
+    let mut w = World::default();
+    w.lights_mut()[0] = PointLight::new(Point3::new(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0));
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = w.objects()[1].as_ref();
+    let comps = Intersection::new(0.5, shape).prepare_computations(&r);
+    assert_relative_eq!(
+        w.shade_hit(&comps, MAX_DEPTH),
+        Color::new(0.90498, 0.90498, 0.90498),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn color_when_ray_misses() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(w.color_at(&r, MAX_DEPTH), Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn color_when_ray_hits() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert_relative_eq!(
+        w.color_at(&r, MAX_DEPTH),
+        Color::new(0.38066, 0.47583, 0.2855),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn color_with_intersection_behind_ray() {
+    let mut w = World::default();
+    for object in w.objects_mut() {
+        let m = object.material().clone().with_ambient(1.0);
+        object.set_material(m);
+    }
+    let inner_color = w.objects()[1].material().color;
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.75), Vector3::new(0.0, 0.0, -1.0));
+    assert_eq!(w.color_at(&r, MAX_DEPTH), inner_color);
+}
+#[test]
+fn default_orientation() {
+    let from = Point3::new(0.0, 0.0, 0.0);
+    let to = Point3::new(0.0, 0.0, -1.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    assert_relative_eq!(Transform::view_transform(from, to, up), Matrix4::identity());
+}
+#[test]
+fn looking_in_positive_z() {
+    let from = Point3::new(0.0, 0.0, 0.0);
+    let to = Point3::new(0.0, 0.0, 1.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    assert_relative_eq!(
+        Transform::view_transform(from, to, up),
+        Transform::scaling(-1.0, 1.0, -1.0)
+    );
+}
+#[test]
+fn view_transform_moves_the_world() {
+    let from = Point3::new(0.0, 0.0, 8.0);
+    let to = Point3::new(0.0, 0.0, 0.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    assert_relative_eq!(
+        Transform::view_transform(from, to, up),
+        Transform::translation(0.0, 0.0, -8.0)
+    );
+}
+#[test]
+fn arbitrary_view_transform() {
+    let from = Point3::new(1.0, 3.0, 2.0);
+    let to = Point3::new(4.0, -2.0, 8.0);
+    let up = Vector3::new(1.0, 1.0, 0.0);
+    assert_relative_eq!(
+        Transform::view_transform(from, to, up),
+        Matrix4::new([
+            [-0.50709, 0.50709, 0.67612, -2.36643],
+            [0.76772, 0.60609, 0.12122, -2.82843],
+            [-0.35857, 0.59761, -0.71714, 0.00000],
+            [0.00000, 0.00000, 0.00000, 1.00000],
+        ]),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn constructing_a_camera() {
+    let c = Camera::new(160, 120, PI / 2.0);
+    assert_eq!(c.hsize(), 160);
+    assert_eq!(c.vsize(), 120);
+    assert_eq!(c.field_of_view(), PI / 2.0);
+    assert_eq!(c.transform(), Matrix4::identity());
+}
+#[test]
+fn pixel_size_for_a_horizontal_canvas() {
+    let c = Camera::new(200, 125, PI / 2.0);
+    assert_relative_eq!(c.pixel_size(), 0.01, epsilon = EPSILON);
+}
+#[test]
+fn pixel_size_for_a_vertical_canvas() {
+    let c = Camera::new(125, 200, PI / 2.0);
+    assert_relative_eq!(c.pixel_size(), 0.01, epsilon = EPSILON);
+}
+#[test]
+fn ray_through_the_center_of_the_canvas() {
+    let c = Camera::new(201, 101, PI / 2.0);
+    let r = c.ray_for_pixel(100, 50).unwrap();
+    assert_relative_eq!(r.origin(), Point3::new(0.0, 0.0, 0.0), epsilon = EPSILON);
+    assert_relative_eq!(
+        r.direction(),
+        Vector3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn ray_through_a_corner_of_the_canvas() {
+    let c = Camera::new(201, 101, PI / 2.0);
+    let r = c.ray_for_pixel(0, 0).unwrap();
+    assert_relative_eq!(r.origin(), Point3::new(0.0, 0.0, 0.0), epsilon = EPSILON);
+    assert_relative_eq!(
+        r.direction(),
+        Vector3::new(0.66519, 0.33259, -0.66851),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn ray_when_the_camera_is_transformed() {
+    let c = Camera::new(201, 101, PI / 2.0)
+        .with_transform(Matrix4::rotation_y(PI / 4.0) * Matrix4::translation(0.0, -2.0, 5.0));
+    let r = c.ray_for_pixel(100, 50).unwrap();
+    assert_relative_eq!(r.origin(), Point3::new(0.0, 2.0, -5.0), epsilon = EPSILON);
+    assert_relative_eq!(
+        r.direction(),
+        Vector3::new(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rendering_a_world_with_a_camera() {
+    let w = World::default();
+    let from = Point3::new(0.0, 0.0, -5.0);
+    let to = Point3::new(0.0, 0.0, 0.0);
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(from, to, up));
+    let image: RawCanvas<11, 11, f64> = c.render(&w);
+    assert_relative_eq!(
+        *image.pixel_at(5, 5).unwrap(),
+        Color::new(0.38066, 0.47583, 0.2855),
+        epsilon = EPSILON
+    );
+}
+#[test]
+#[should_panic]
+fn rendering_requires_a_canvas_of_the_camera_size() {
+    let c = Camera::new(11, 11, PI / 2.0);
+    let _: RawCanvas<10, 11, f64> = c.render(&World::default());
+}
@@ -0,0 +1,151 @@
+/// Tests in Chapter 8.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, Intersection, Material, Point3, PointLight, Ray, Shape,
+    Sphere, Transform, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+#[test]
+fn lighting_with_surface_in_shadow() {
+    let m = Material::default();
+    let position = Point3::new(0.0, 0.0, 0.0);
+    let eyev = Vector3::new(0.0, 0.0, -1.0);
+    let normalv = Vector3::new(0.0, 0.0, -1.0);
+    let light = PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
+    let in_shadow = true;
+    let result = lighting(
+        &m,
+        &Sphere::default(),
+        &light,
+        position,
+        eyev,
+        normalv,
+        in_shadow,
+    );
+    assert_relative_eq!(result, Color::new(0.1, 0.1, 0.1), epsilon = EPSILON);
+}
+#[test]
+fn no_shadow_when_nothing_is_collinear_with_point_and_light() {
+    let w = World::default();
+    let p = Point3::new(0.0, 10.0, 0.0);
+    assert!(!w.is_shadowed(p, &w.lights()[0]));
+}
+#[test]
+fn shadow_when_object_is_between_point_and_light() {
+    let w = World::default();
+    let p = Point3::new(10.0, -10.0, 10.0);
+    assert!(w.is_shadowed(p, &w.lights()[0]));
+}
+#[test]
+fn no_shadow_when_object_is_behind_light() {
+    let w = World::default();
+    let p = Point3::new(-20.0, 20.0, -20.0);
+    assert!(!w.is_shadowed(p, &w.lights()[0]));
+}
+#[test]
+fn no_shadow_when_object_is_behind_point() {
+    let w = World::default();
+    let p = Point3::new(-2.0, 2.0, -2.0);
+    assert!(!w.is_shadowed(p, &w.lights()[0]));
+}
+
+/// A sphere hiding another one from the light.
+fn blocked_sphere_world() -> World<f64> {
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(0.0, 0.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    w.add_object(Sphere::default());
+    let mut s2 = Sphere::default();
+    s2.set_transform(Transform::translation(0.0, 0.0, 10.0));
+    w.add_object(s2);
+    w
+}
+
+#[test]
+fn shade_hit_given_intersection_in_shadow() {
+    let w = blocked_sphere_world();
+    let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+    let comps = Intersection::new(4.0, w.objects()[1].as_ref()).prepare_computations(&r);
+    assert_relative_eq!(
+        w.shade_hit(&comps, MAX_DEPTH),
+        Color::new(0.1, 0.1, 0.1),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn hit_should_offset_point() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut shape = Sphere::default();
+    shape.set_transform(Transform::translation(0.0, 0.0, 1.0));
+    let comps = Intersection::new(5.0, &shape).prepare_computations(&r);
+    assert!(comps.over_point.z < -EPSILON / 2.0);
+    assert!(comps.point.z > comps.over_point.z);
+}
+#[test]
+fn shadow_bias_is_configurable() {
+    let mut w = World::<f64>::default();
+    assert_eq!(w.shadow_bias(), EPSILON);
+    w.set_shadow_bias(0.01);
+    assert_eq!(w.shadow_bias(), 0.01);
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let shape = Sphere::default();
+    let comps = Intersection::new(4.0, &shape).prepare_computations_with_bias(&r, 0.01);
+    assert_relative_eq!(comps.over_point, Point3::new(0.0, 0.0, -1.01));
+}
+#[test]
+fn shadow_bias_scaled_with_scene_removes_acne() {
+    // A sphere of radius 100 in `f32`: the default bias is lost in the
+    // rounding error of the hit points, the surface shadows itself.
+    let scale = 100.0_f32;
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(-1000.0, 1000.0, -1000.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    let mut s = Sphere::default();
+    s.set_transform(Transform::scaling(scale, scale, scale));
+    w.add_object(s);
+    let shadowed_hits = |w: &World<f32>| {
+        (0..100)
+            .filter(|&i| {
+                let (x, y) = (i as f32 * 0.37, i as f32 * 0.41);
+                let r = Ray::new(Point3::new(x, y, -500.0), Vector3::new(0.0, 0.0, 1.0));
+                w.color_at(&r, MAX_DEPTH).r < 0.1 + EPSILON as f32
+            })
+            .count()
+    };
+    assert!(shadowed_hits(&w) > 0);
+    w.set_shadow_bias(EPSILON as f32 * scale);
+    assert_eq!(shadowed_hits(&w), 0);
+}
+
+#[test]
+fn shapes_cast_and_receive_shadows_by_default() {
+    let s = Sphere::<f64>::default();
+    assert!(s.casts_shadow());
+    assert!(s.receives_shadow());
+}
+#[test]
+fn no_shadow_from_object_not_casting_shadows() {
+    let mut w = World::default();
+    let p = Point3::new(10.0, -10.0, 10.0);
+    for object in w.objects_mut() {
+        object.set_casts_shadow(false);
+    }
+    assert!(!w.is_shadowed(p, &w.lights()[0]));
+}
+#[test]
+fn object_not_receiving_shadows_is_lit() {
+    let mut w = blocked_sphere_world();
+    w.objects_mut()[1].set_receives_shadow(false);
+    let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+    let comps = Intersection::new(4.0, w.objects()[1].as_ref()).prepare_computations(&r);
+    assert_relative_eq!(
+        w.shade_hit(&comps, MAX_DEPTH),
+        Color::new(1.9, 1.9, 1.9),
+        epsilon = EPSILON
+    );
+}
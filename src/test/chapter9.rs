@@ -0,0 +1,98 @@
+/// Tests in Chapter 9.
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use approx::assert_relative_eq;
+
+use crate::{Matrix4, Plane, Point3, Ray, Shape, TestShape, Transform, Vector3, EPSILON};
+
+#[test]
+fn default_transformation() {
+    let s: TestShape<f64> = TestShape::default();
+    assert_eq!(s.transform(), Matrix4::identity());
+    let mut s = TestShape::default();
+    s.set_transform(Transform::translation(2.0, 3.0, 4.0));
+    assert_eq!(s.transform(), Transform::translation(2.0, 3.0, 4.0));
+}
+#[test]
+fn intersect_scaled_shape() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut s = TestShape::default();
+    s.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    assert!(s.intersect(&r).is_empty());
+    let saved = s.saved_ray().unwrap();
+    assert_eq!(saved.origin(), Point3::new(0.0, 0.0, -2.5));
+    assert_eq!(saved.direction(), Vector3::new(0.0, 0.0, 0.5));
+}
+#[test]
+fn intersect_translated_shape() {
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut s = TestShape::default();
+    s.set_transform(Transform::translation(5.0, 0.0, 0.0));
+    s.intersect(&r);
+    let saved = s.saved_ray().unwrap();
+    assert_eq!(saved.origin(), Point3::new(-5.0, 0.0, -5.0));
+    assert_eq!(saved.direction(), Vector3::new(0.0, 0.0, 1.0));
+}
+#[test]
+fn normal_on_translated_shape() {
+    let mut s = TestShape::default();
+    s.set_transform(Transform::translation(0.0, 1.0, 0.0));
+    let n = s.normal_at(Point3::new(0.0, 1.70711, -FRAC_1_SQRT_2));
+    assert_relative_eq!(
+        n,
+        Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn normal_on_transformed_shape() {
+    let mut s = TestShape::default();
+    s.set_transform(Transform::scaling(1.0, 0.5, 1.0) * Transform::rotation_z(PI / 5.0));
+    let n = s.normal_at(Point3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    assert_relative_eq!(n, Vector3::new(0.0, 0.97014, -0.24254), epsilon = EPSILON);
+}
+#[test]
+fn plane_normal_is_constant() {
+    let p = Plane::default();
+    assert_eq!(
+        p.local_normal_at(Point3::new(0.0, 0.0, 0.0)),
+        Vector3::unit_y()
+    );
+    assert_eq!(
+        p.local_normal_at(Point3::new(10.0, 0.0, -10.0)),
+        Vector3::unit_y()
+    );
+    assert_eq!(
+        p.local_normal_at(Point3::new(-5.0, 0.0, 150.0)),
+        Vector3::unit_y()
+    );
+}
+#[test]
+fn intersect_parallel_or_coplanar_ray() {
+    let p = Plane::default();
+    let r = Ray::new(Point3::new(0.0, 10.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(p.local_intersect(&r).is_empty());
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(p.local_intersect(&r).is_empty());
+}
+#[test]
+fn intersect_plane_from_above_and_below() {
+    let p = Plane::default();
+    let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let xs = p.local_intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t(), 1.0);
+    assert_eq!(xs[0].object(), &p as &dyn Shape<f64>);
+    let r = Ray::new(Point3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    let xs = p.local_intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t(), 1.0);
+}
+#[test]
+fn intersect_transformed_plane() {
+    // A wall facing -z, placed at z = 3.
+    let p = Plane::new(Transform::translation(0.0, 0.0, 3.0) * Transform::rotation_x(PI / 2.0));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = p.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 8.0, epsilon = EPSILON);
+}
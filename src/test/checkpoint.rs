@@ -0,0 +1,160 @@
+/// Tests of the checkpoints of the renders.
+use std::{f64::consts::PI, time::Duration};
+
+use crate::{
+    features::colors::Color, Camera, Checkpoint, CheckpointError, PixelBounds, Point3, PointLight,
+    RawCanvas, RenderProgress, RenderSettings, Transform, Vector3, Whitted, World,
+};
+
+fn camera() -> Camera<f64> {
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    Camera::new(11, 11, PI / 2.0)
+        .with_transform(view)
+        .with_tile_size(4)
+}
+
+#[test]
+fn checkpoint_goes_through_its_file_format() {
+    let c = camera();
+    let mut checkpoint = Checkpoint::new(11, 11, c.tiles()).with_scene(7, 42);
+    assert_eq!(checkpoint.tiles_done(), 0);
+    checkpoint.complete_tile(4, |x, y| Color::new(x as f64, y as f64, 0.5));
+    assert!(checkpoint.is_done(4) && !checkpoint.is_done(3));
+    assert_eq!(checkpoint.pixel_at(5, 6), Color::new(5.0, 6.0, 0.5));
+    let bytes = checkpoint.to_bytes();
+    assert_eq!(Checkpoint::from_bytes(&bytes).unwrap(), checkpoint);
+    assert!(matches!(
+        Checkpoint::<f64>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(CheckpointError::InvalidData)
+    ));
+    assert!(matches!(
+        Checkpoint::<f64>::from_bytes(b"P3\n1 1\n255\n0 0 0\n"),
+        Err(CheckpointError::InvalidData)
+    ));
+}
+#[test]
+fn render_resumes_from_the_checkpoint() {
+    let dir = std::env::temp_dir().join("raytracer-rust-checkpoint-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("resume.ckpt");
+    let w = World::default();
+    let c = camera();
+    // A render stopped after its first tile, marked in red.
+    let red = Color::new(1.0, 0.0, 0.0);
+    let mut checkpoint = c.checkpoint(&w, &Whitted::default());
+    checkpoint.complete_tile(0, |_, _| red);
+    checkpoint.save(&path).unwrap();
+    let image: RawCanvas<11, 11, f64> = c
+        .render_with_checkpoint(
+            &w,
+            &Whitted::default(),
+            &mut |_: &RenderProgress| {},
+            &path,
+            Duration::ZERO,
+        )
+        .unwrap();
+    let expected: RawCanvas<11, 11, f64> = c.render(&w);
+    let first = c.tiles()[0];
+    for (x, y) in PixelBounds::new(0, 0, 11, 11).pixels() {
+        if first.contains(x, y) {
+            assert_eq!(*image.pixel_at(x, y).unwrap(), red);
+        } else {
+            assert_eq!(image.pixel_at(x, y), expected.pixel_at(x, y));
+        }
+    }
+    // The checkpoint of a complete render is removed.
+    assert!(!path.exists());
+}
+#[test]
+fn checkpoint_of_another_render_is_rejected() {
+    let dir = std::env::temp_dir().join("raytracer-rust-checkpoint-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mismatch.ckpt");
+    let w = World::default();
+    let c = camera();
+    let seeded = c.clone().with_settings(RenderSettings {
+        seed: 1,
+        ..c.settings().clone()
+    });
+    let mut moved = World::default();
+    moved.add_light(PointLight::new(
+        Point3::new(0.0, 10.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    let others = [
+        c.clone()
+            .with_tile_size(8)
+            .checkpoint(&w, &Whitted::default()),
+        seeded.checkpoint(&w, &Whitted::default()),
+        c.checkpoint(&moved, &Whitted::default()),
+        c.checkpoint(
+            &w,
+            &Whitted {
+                samples_per_pixel: 4,
+                ..Whitted::default()
+            },
+        ),
+    ];
+    for other in others {
+        other.save(&path).unwrap();
+        let result: Result<RawCanvas<11, 11, f64>, _> = c.render_with_checkpoint(
+            &w,
+            &Whitted::default(),
+            &mut |_: &RenderProgress| {},
+            &path,
+            Duration::ZERO,
+        );
+        assert!(matches!(result, Err(CheckpointError::Mismatch)));
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+#[test]
+fn checkpoint_ignores_the_threads() {
+    let c = camera();
+    let w = World::default();
+    let checkpoint = c.checkpoint(&w, &Whitted::default());
+    let threaded = c.clone().with_settings(RenderSettings {
+        threads: 3,
+        ..c.settings().clone()
+    });
+    assert!(checkpoint.is_of_same_render(&threaded.checkpoint(&w, &Whitted::default())));
+    assert_eq!(checkpoint.seed(), c.settings().seed);
+}
+#[test]
+fn checkpointed_render_uses_the_threads_and_reports_progress() {
+    let dir = std::env::temp_dir().join("raytracer-rust-checkpoint-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("threads.ckpt");
+    let w = World::default();
+    let c = camera().with_settings(RenderSettings {
+        threads: 4,
+        ..RenderSettings::default()
+    });
+    let mut checkpoint = c.checkpoint(&w, &Whitted::default());
+    checkpoint.complete_tile(0, |x, y| *c.render::<11, 11>(&w).pixel_at(x, y).unwrap());
+    checkpoint.save(&path).unwrap();
+    let mut reports = Vec::new();
+    let image: RawCanvas<11, 11, f64> = c
+        .render_with_checkpoint(
+            &w,
+            &Whitted::default(),
+            &mut |p: &RenderProgress| reports.push((p.tiles_done, p.tiles_total)),
+            &path,
+            Duration::ZERO,
+        )
+        .unwrap();
+    // Only the tiles left are rendered and reported, in order.
+    let remaining = c.tiles().len() - 1;
+    assert_eq!(
+        reports,
+        (1..=remaining)
+            .map(|done| (done, remaining))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(image, c.render(&w));
+    assert!(!path.exists());
+}
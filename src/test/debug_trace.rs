@@ -0,0 +1,117 @@
+/// Tests of the traces of single pixels.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, Camera, Material, Plane, Point3, PointLight, RawCanvas,
+    Shape, Sphere, Transform, Vector3, World, EPSILON,
+};
+
+/// The default world with a reflective plane below the spheres.
+fn world_with_reflective_plane() -> World<f64> {
+    let mut w = World::default();
+    let mut shape = Plane::default();
+    shape.set_material(Material::default().with_reflective(0.5));
+    shape.set_transform(Transform::translation(0.0, -1.0, 0.0));
+    w.add_object(shape);
+    w
+}
+
+/// A camera looking at the origin from above the plane.
+fn camera() -> Camera<f64> {
+    let view = Transform::view_transform(
+        Point3::new(0.0, 1.5, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    Camera::new(11, 11, PI / 2.0).with_transform(view)
+}
+
+#[test]
+fn the_trace_of_a_pixel_has_the_rendered_color() {
+    let w = world_with_reflective_plane();
+    let c = camera();
+    let image: RawCanvas<11, 11, f64> = c.render(&w);
+    for (x, y) in [(5, 5), (5, 10), (0, 10), (0, 0)] {
+        let trace = w.debug_trace(&c, x, y).unwrap();
+        assert_relative_eq!(
+            trace.color,
+            *image.pixel_at(x, y).unwrap(),
+            epsilon = EPSILON
+        );
+    }
+}
+#[test]
+fn a_pixel_missing_everything_has_no_hit() {
+    let w = World::default();
+    let trace = w.debug_trace(&camera(), 0, 0).unwrap();
+    assert!(trace.intersections.is_empty());
+    assert!(trace.hit.is_none());
+    assert_eq!(trace.color, Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn the_trace_records_every_intersection_and_light() {
+    let w = World::default();
+    let trace = w.debug_trace(&camera(), 5, 5).unwrap();
+    assert_eq!(trace.intersections.len(), 4);
+    let hit = trace.hit.as_ref().unwrap();
+    assert_eq!(hit.comps.t, trace.intersections[0].t());
+    assert_eq!(hit.lights.len(), 1);
+    let light = hit.lights[0];
+    assert!(!light.in_shadow);
+    let expected = lighting(
+        hit.comps.object.material(),
+        hit.comps.object,
+        &light.light,
+        hit.comps.over_point,
+        hit.comps.eyev,
+        hit.comps.normalv,
+        false,
+    );
+    assert_relative_eq!(light.terms.total(), expected, epsilon = EPSILON);
+    assert!(light.terms.diffuse != Color::default());
+    assert!(hit.reflection.is_none());
+}
+#[test]
+fn the_trace_follows_the_reflections() {
+    let w = world_with_reflective_plane();
+    let trace = w.debug_trace(&camera(), 5, 10).unwrap();
+    let hit = trace.hit.as_ref().unwrap();
+    let reflection = hit.reflection.as_ref().unwrap();
+    assert_eq!(reflection.remaining, trace.remaining - 1);
+    assert_eq!(reflection.ray.origin(), hit.comps.over_point);
+    let lit = hit.lights[0].terms.total();
+    assert_relative_eq!(hit.color, lit + reflection.color * 0.5, epsilon = EPSILON);
+    let printed = trace.to_string();
+    assert!(printed.contains("reflection:"));
+    assert!(printed.contains("light 0"));
+}
+#[test]
+fn the_trace_tells_which_lights_are_shadowed() {
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(0.0, 10.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    w.add_light(PointLight::new(
+        Point3::new(0.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    w.add_object(Sphere::new(Transform::translation(0.0, 2.0, 0.0)));
+    let mut floor = Plane::default();
+    floor.set_transform(Transform::translation(0.0, -1.0, 0.0));
+    w.add_object(floor);
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+        Point3::new(0.0, 1.0, -5.0),
+        Point3::new(0.0, -1.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let trace = w.debug_trace(&c, 5, 5).unwrap();
+    let hit = trace.hit.unwrap();
+    assert_relative_eq!(hit.comps.point.y, -1.0, epsilon = EPSILON);
+    // The sphere hides the light right above, not the one in front.
+    assert!(hit.lights[0].in_shadow);
+    assert_eq!(hit.lights[0].terms.diffuse, Color::default());
+    assert!(!hit.lights[1].in_shadow);
+}
@@ -0,0 +1,78 @@
+/// Tests of the renders showing the geometry instead of its shading.
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::colors::Color, AcceleratorKind, BoundingBox, DebugView, Integrator, KdTree, Point3,
+    Ray, Vector3, World, MAX_DEPTH,
+};
+
+/// A ray hitting the spheres of the default world head-on.
+fn ray() -> Ray<f64> {
+    Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0))
+}
+
+fn radiance(view: DebugView, w: &World<f64>, r: &Ray<f64>) -> Color<f64> {
+    view.radiance(w, r, &mut SmallRng::seed_from_u64(0))
+}
+
+#[test]
+fn normals_are_mapped_to_colors() {
+    let w = World::default();
+    let color = radiance(DebugView::Normals, &w, &ray());
+    assert_eq!(color, Color::new(0.5, 0.5, 0.0));
+    let miss = Ray::new(Point3::new(0.0, 5.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(
+        radiance(DebugView::Normals, &w, &miss),
+        Color::new(0.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn the_depth_is_a_heat_map() {
+    let w = World::default();
+    // The hit is at a distance of 4.
+    let depth = |max_distance| radiance(DebugView::Depth { max_distance }, &w, &ray());
+    assert_eq!(depth(8.0), Color::new(0.0, 1.0, 0.0));
+    assert_eq!(depth(4.0), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(depth(2.0), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(depth(16.0), Color::new(0.0, 1.0, 1.0));
+}
+#[test]
+fn the_intersections_are_counted() {
+    let w = World::default();
+    let count = |max| radiance(DebugView::IntersectionCount { max }, &w, &ray());
+    // Both spheres are crossed: 4 intersections.
+    assert_eq!(count(4), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(count(7), Color::new(0.0, 1.0, 0.0));
+    assert_eq!(count(1), Color::new(1.0, 0.0, 0.0));
+}
+#[test]
+fn the_edges_of_the_bounds_are_drawn_over_the_shading() {
+    let mut w = World::default();
+    let view = DebugView::Bounds { line_width: 0.01 };
+    // Through the corner (1, 1, -1) of the box of the outer sphere.
+    let corner = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 1.0, 4.0));
+    assert_eq!(radiance(view, &w, &corner), Color::new(0.0, 1.0, 0.0));
+    assert_eq!(radiance(view, &w, &ray()), w.color_at(&ray(), MAX_DEPTH));
+    w.build_accelerator(AcceleratorKind::Bvh, 1);
+    assert_eq!(radiance(view, &w, &corner), Color::new(0.0, 1.0, 0.0));
+    w.build_accelerator(AcceleratorKind::KdTree, 1);
+    assert_eq!(radiance(view, &w, &corner), Color::new(0.0, 1.0, 0.0));
+}
+#[test]
+fn the_cells_of_a_kd_tree_fill_its_bounds() {
+    let bounds: Vec<_> = (0..20)
+        .map(|i| {
+            let x = (i * 7 % 20) as f64;
+            BoundingBox::new(Point3::new(x, -0.5, -0.5), Point3::new(x + 0.5, 0.5, 0.5))
+        })
+        .collect();
+    let tree = KdTree::build(&bounds, 2);
+    let cells = tree.cells();
+    assert_eq!(cells.len(), tree.leaves().count());
+    let volume = |b: &BoundingBox<f64>| {
+        let d = b.max() - b.min();
+        d.x * d.y * d.z
+    };
+    let total: f64 = cells.iter().map(volume).sum();
+    assert!((total - volume(&tree.bounds())).abs() < 1e-9);
+}
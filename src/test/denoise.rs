@@ -0,0 +1,67 @@
+/// Tests of the denoising filter.
+use crate::{features::colors::Color, Denoiser, RawCanvas};
+
+fn gray(v: f64) -> Color<f64> {
+    Color::new(v, v, v)
+}
+
+/// Returns a canvas of the color given by `f` for each pixel.
+fn canvas(f: impl Fn(usize, usize) -> Color<f64>) -> RawCanvas<16, 16, f64> {
+    let mut c = RawCanvas::default();
+    for y in 0..16 {
+        for x in 0..16 {
+            c.write_pixel(x, y, f(x, y)).unwrap();
+        }
+    }
+    c
+}
+
+/// Returns the mean and the variance of the red channel.
+fn statistics(c: &RawCanvas<16, 16, f64>) -> (f64, f64) {
+    let n = c.pixels().len() as f64;
+    let mean = c.pixels().iter().map(|p| p.r).sum::<f64>() / n;
+    let variance = c.pixels().iter().map(|p| (p.r - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance)
+}
+
+#[test]
+fn the_noise_of_a_flat_surface_is_smoothed() {
+    // A deterministic noise of +/- 0.2 around 0.5.
+    let mut image = canvas(|x, y| gray(0.5 + 0.2 * (((x * 7 + y * 13) % 5) as f64 - 2.0) / 2.0));
+    let normal = canvas(|_, _| Color::new(0.0, 0.0, -1.0));
+    let albedo = canvas(|_, _| gray(0.8));
+    let (mean, variance) = statistics(&image);
+    image.denoise(&normal, &albedo, &Denoiser::default());
+    let (denoised_mean, denoised_variance) = statistics(&image);
+    assert!((denoised_mean - mean).abs() < 0.01);
+    assert!(denoised_variance < variance / 10.0);
+}
+#[test]
+fn the_edges_of_the_guides_are_kept() {
+    let split =
+        |left: Color<f64>, right: Color<f64>| canvas(move |x, _| if x < 8 { left } else { right });
+    let mut image = split(gray(0.2), gray(0.8));
+    let original = image.clone();
+    let normal = split(Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0));
+    let albedo = canvas(|_, _| gray(0.8));
+    image.denoise(&normal, &albedo, &Denoiser::default());
+    assert_eq!(image, original);
+    // Without the normals, only the difference of color holds the edge,
+    // and it weakens at every pass.
+    let flat = canvas(|_, _| Color::new(0.0, 0.0, -1.0));
+    let mut blurred = original.clone();
+    blurred.denoise(&flat, &albedo, &Denoiser::default());
+    assert!(blurred.pixel_at(7, 0).unwrap().r > 0.2);
+}
+#[test]
+fn no_iteration_leaves_the_canvas_unchanged() {
+    let mut image = canvas(|x, y| gray(((x + y) % 2) as f64));
+    let original = image.clone();
+    let guide = canvas(|_, _| gray(0.0));
+    let denoiser = Denoiser {
+        iterations: 0,
+        ..Denoiser::default()
+    };
+    image.denoise(&guide, &guide, &denoiser);
+    assert_eq!(image, original);
+}
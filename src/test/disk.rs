@@ -0,0 +1,62 @@
+/// Tests of the disk and annulus primitives.
+use approx::assert_relative_eq;
+
+use crate::{Disk, Point3, Ray, Shape, Transform, Vector3, EPSILON};
+
+#[test]
+fn ray_intersects_disk() {
+    let disk = Disk::new(2.0);
+    for (origin, direction, t) in [
+        (
+            Point3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            1.0,
+        ),
+        (
+            Point3::new(1.5, -2.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            2.0,
+        ),
+        (
+            Point3::new(0.0, 1.0, -1.0),
+            Vector3::new(0.0, -1.0, 1.0),
+            1.0,
+        ),
+    ] {
+        let r = Ray::new(origin, direction);
+        let xs = disk.local_intersect(&r);
+        assert_eq!(xs.len(), 1);
+        assert_relative_eq!(xs[0].t(), t, epsilon = EPSILON);
+    }
+}
+#[test]
+fn ray_misses_disk() {
+    let disk = Disk::new(2.0);
+    for (origin, direction) in [
+        // Outside of the radius
+        (Point3::new(2.5, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+        // Parallel to the disk
+        (Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+    ] {
+        let r = Ray::new(origin, direction);
+        assert!(disk.local_intersect(&r).is_empty());
+    }
+}
+#[test]
+fn ray_through_annulus_hole() {
+    let annulus = Disk::annulus(0.5, 1.0);
+    let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    assert!(annulus.local_intersect(&r).is_empty());
+    let r = Ray::new(Point3::new(0.0, 1.0, 0.75), Vector3::new(0.0, -1.0, 0.0));
+    assert_eq!(annulus.local_intersect(&r).len(), 1);
+}
+#[test]
+fn normal_of_transformed_disk() {
+    let mut disk = Disk::default();
+    disk.set_transform(Transform::rotation_z(std::f64::consts::FRAC_PI_2));
+    assert_relative_eq!(
+        disk.normal_at(Point3::new(0.0, 0.5, 0.0)),
+        Vector3::new(-1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+}
@@ -0,0 +1,118 @@
+/// Tests of the image-based lighting.
+use std::{f64::consts::PI, sync::Arc};
+
+use approx::assert_relative_eq;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::{colors::Color, math::sampling::random_unit_vector},
+    EnvironmentMap, Integrator, Material, PathTracer, Point3, Ray, Shape, Sphere, UvImage, Vector3,
+    World, EPSILON, MAX_DEPTH,
+};
+
+fn gray(v: f64) -> Color<f64> {
+    Color::new(v, v, v)
+}
+
+#[test]
+fn environment_radiance_by_direction() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+    let map = EnvironmentMap::new(UvImage::new(2, 1, vec![red, blue]));
+    assert_eq!(map.radiance(Vector3::new(0.0, 0.0, -1.0)), red);
+    assert_eq!(map.radiance(Vector3::new(0.0, 0.0, 3.0)), blue);
+    let map = map.with_intensity(2.0);
+    assert_eq!(map.radiance(Vector3::new(0.0, 0.0, -1.0)), red * 2.0);
+}
+#[test]
+fn environment_samples_follow_the_bright_pixels() {
+    let mut pixels = vec![gray(0.0); 8];
+    pixels[1] = gray(4.0);
+    let map = EnvironmentMap::new(UvImage::new(4, 2, pixels));
+    let mut rng = SmallRng::seed_from_u64(1);
+    for _ in 0..100 {
+        let (direction, radiance, pdf) = map.sample(&mut rng);
+        assert_relative_eq!(direction.magnitude(), 1.0, epsilon = EPSILON);
+        assert_eq!(radiance, gray(4.0));
+        assert_eq!(map.radiance(direction), gray(4.0));
+        assert_relative_eq!(pdf, map.pdf(direction), max_relative = 1e-6);
+    }
+}
+#[test]
+fn environment_pdf_integrates_to_one() {
+    let pixels = (0..32).map(|i| gray((i % 5) as f64 + 0.5)).collect();
+    let map = EnvironmentMap::new(UvImage::new(8, 4, pixels));
+    let mut rng = SmallRng::seed_from_u64(7);
+    let n = 20000;
+    // Monte Carlo integration over uniform directions, of density 1 / 4π.
+    let sum: f64 = (0..n)
+        .map(|_| map.pdf(random_unit_vector(&mut rng)) * 4.0 * PI)
+        .sum();
+    assert_relative_eq!(sum / n as f64, 1.0, epsilon = 0.03);
+}
+#[test]
+fn rays_missing_everything_see_the_environment() {
+    let mut w = World::<f64>::new();
+    w.set_environment(Some(Arc::new(EnvironmentMap::new(UvImage::new(
+        1,
+        1,
+        vec![gray(0.5)],
+    )))));
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(w.color_at(&r, MAX_DEPTH), gray(0.5));
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(PathTracer::new(4).estimate(&w, &r, &mut rng), gray(0.5));
+}
+#[test]
+fn white_sphere_in_a_uniform_environment_has_its_color() {
+    // A convex white surface reflects all of the uniform light it receives.
+    let mut w = World::new();
+    let mut sphere = Sphere::default();
+    sphere.set_material(Material::default().with_diffuse(1.0));
+    w.add_object(sphere);
+    w.set_environment(Some(Arc::new(
+        EnvironmentMap::new(UvImage::new(4, 2, vec![gray(1.0); 8])).with_intensity(0.5),
+    )));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut rng = SmallRng::seed_from_u64(3);
+    let color = PathTracer::new(4000).estimate(&w, &r, &mut rng);
+    assert_relative_eq!(color, gray(0.5), epsilon = 0.02);
+}
+#[test]
+fn bright_spot_of_the_environment_lights_the_facing_side() {
+    // A sun straight above: the top of the sphere is lit, its bottom is not.
+    let mut pixels = vec![gray(0.0); 16];
+    pixels[1] = gray(100.0);
+    let mut w = World::new();
+    w.add_object(Sphere::default());
+    w.set_environment(Some(Arc::new(EnvironmentMap::new(UvImage::new(
+        4, 4, pixels,
+    )))));
+    let tracer = PathTracer::new(64);
+    let mut rng = SmallRng::seed_from_u64(5);
+    let top = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let bottom = Ray::new(Point3::new(0.0, -5.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    let lit = tracer.estimate(&w, &top, &mut rng);
+    assert!(lit.r > 0.1);
+    assert_eq!(tracer.estimate(&w, &bottom, &mut rng), gray(0.0));
+}
+#[test]
+fn white_sphere_passes_the_furnace_test() {
+    // Under a uniform light of radiance 1, a white diffuse surface has
+    // radiance 1 whatever the number of bounces it sees of itself.
+    let mut w = World::new();
+    let mut sphere = Sphere::default();
+    sphere.set_material(Material::default().with_diffuse(1.0));
+    w.add_object(sphere);
+    w.set_environment(Some(Arc::new(EnvironmentMap::new(UvImage::new(
+        4,
+        2,
+        vec![gray(1.0); 8],
+    )))));
+    let tracer = PathTracer::new(4000);
+    let mut rng = SmallRng::seed_from_u64(11);
+    for origin in [Point3::new(0.0, 0.0, -5.0), Point3::new(3.0, 4.0, 0.0)] {
+        let r = Ray::new(origin, (Point3::new(0.0, 0.0, 0.0) - origin).normalized());
+        assert_relative_eq!(tracer.estimate(&w, &r, &mut rng), gray(1.0), epsilon = 0.02);
+    }
+}
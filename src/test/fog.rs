@@ -0,0 +1,58 @@
+/// Tests of the fog.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, Fog, FogFalloff, Point3, Ray, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+fn gray() -> Color<f64> {
+    Color::new(0.5, 0.5, 0.5)
+}
+
+#[test]
+fn linear_fog_visibility() {
+    let fog = Fog::linear(gray(), 10.0, 20.0);
+    assert_eq!(fog.visibility(5.0), 1.0);
+    assert_relative_eq!(fog.visibility(15.0), 0.5);
+    assert_eq!(fog.visibility(25.0), 0.0);
+}
+#[test]
+fn exponential_fog_visibility() {
+    let fog = Fog::exponential(gray(), 0.1);
+    assert_eq!(fog.visibility(0.0), 1.0);
+    assert_relative_eq!(fog.visibility(10.0), (-1.0_f64).exp());
+    assert_eq!(fog.visibility(f64::INFINITY), 0.0);
+    let squared = Fog {
+        color: gray(),
+        falloff: FogFalloff::ExponentialSquared { density: 0.1 },
+    };
+    assert_relative_eq!(squared.visibility(20.0), (-4.0_f64).exp());
+}
+#[test]
+fn fog_blends_color_by_visibility() {
+    let fog = Fog::linear(gray(), 0.0, 2.0);
+    assert_relative_eq!(
+        fog.apply(Color::new(1.0, 0.0, 0.0), 1.0),
+        Color::new(0.75, 0.25, 0.25)
+    );
+}
+#[test]
+fn fog_fades_hits_by_distance() {
+    let mut w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let clear = w.color_at(&r, MAX_DEPTH);
+    // The hit is 4 units away, half way through the fog.
+    w.set_fog(Some(Fog::linear(gray(), 0.0, 8.0)));
+    assert_relative_eq!(
+        w.color_at(&r, MAX_DEPTH),
+        clear * 0.5 + gray() * 0.5,
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rays_missing_everything_take_fog_color() {
+    let mut w = World::default();
+    w.set_fog(Some(Fog::exponential(gray(), 0.01)));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(w.color_at(&r, MAX_DEPTH), gray());
+}
@@ -0,0 +1,448 @@
+/// Tests of the export of the rendered pixels to image files.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, save_pgm, save_pixels, to_hdr, to_pfm, to_pgm, to_ppm, to_ppm_binary,
+    to_qoi, Camera, ExportError, ImageFormat, PPMCanvas, Point3, RawCanvas, RenderLayers,
+    Transform, UvImage, Vector3, Whitted, World,
+};
+
+fn pixels() -> Vec<Color<f64>> {
+    vec![
+        Color::new(1.0, 0.0, 0.2),
+        Color::new(0.0, 1.5, 0.0),
+        Color::new(0.0, 0.0, 0.0),
+        Color::new(0.5, 0.5, 1.0),
+    ]
+}
+
+#[test]
+fn picking_the_format_from_the_extension() {
+    assert_eq!(ImageFormat::from_path("out.ppm").unwrap(), ImageFormat::Ppm);
+    assert_eq!(ImageFormat::from_path("OUT.PPM").unwrap(), ImageFormat::Ppm);
+    assert!(matches!(
+        ImageFormat::from_path("out.bmp"),
+        Err(ExportError::UnsupportedFormat(e)) if e == "bmp"
+    ));
+    assert!(matches!(
+        ImageFormat::from_path("out"),
+        Err(ExportError::UnsupportedFormat(_))
+    ));
+}
+#[test]
+fn saving_a_canvas() {
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut canvas = RawCanvas::<2, 2, f64>::default();
+    for (i, pixel) in pixels().into_iter().enumerate() {
+        canvas.write_pixel(i % 2, i / 2, pixel).unwrap();
+    }
+    canvas.save(dir.join("canvas.ppm")).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("canvas.ppm")).unwrap(),
+        to_ppm(2, 2, &pixels())
+    );
+    assert!(matches!(
+        canvas.save(dir.join("canvas.bmp")),
+        Err(ExportError::UnsupportedFormat(_))
+    ));
+}
+#[cfg(not(feature = "png"))]
+#[test]
+fn png_needs_the_png_feature() {
+    assert!(matches!(
+        ImageFormat::from_path("out.png"),
+        Err(ExportError::UnsupportedFormat(_))
+    ));
+}
+#[cfg(feature = "png")]
+#[test]
+fn saving_a_png() {
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("pixels.png");
+    save_pixels(&path, 2, 2, &pixels()).unwrap();
+    let data = std::fs::read(&path).unwrap();
+    assert!(data.starts_with(b"\x89PNG"));
+    let image = UvImage::<f64>::from_png(&data).unwrap();
+    assert_eq!((image.width(), image.height()), (2, 2));
+    // Clamped and quantized to 8 bits as in a PPM.
+    assert_eq!(image.pixel_at(0, 0), Color::new(1.0, 0.0, 51.0 / 255.0));
+    assert_eq!(image.pixel_at(1, 0), Color::new(0.0, 1.0, 0.0));
+    assert_eq!(
+        image.pixel_at(1, 1),
+        Color::new(127.0 / 255.0, 127.0 / 255.0, 1.0)
+    );
+}
+#[test]
+fn saving_pixels_of_the_wrong_size_panics() {
+    let result = std::panic::catch_unwind(|| save_pixels("never.ppm", 3, 2, &pixels()));
+    assert!(result.is_err());
+}
+#[test]
+fn picking_the_format_by_name() {
+    assert_eq!(
+        ImageFormat::from_name("P6").unwrap(),
+        ImageFormat::PpmBinary
+    );
+    assert_eq!(ImageFormat::from_name("p3").unwrap(), ImageFormat::Ppm);
+    assert_eq!(ImageFormat::from_name("ppm").unwrap(), ImageFormat::Ppm);
+    assert!(matches!(
+        ImageFormat::from_name("p5"),
+        Err(ExportError::UnsupportedFormat(e)) if e == "p5"
+    ));
+}
+#[test]
+fn writing_a_binary_ppm() {
+    let data = to_ppm_binary(2, 2, &pixels());
+    assert!(data.starts_with(b"P6\n2 2\n255\n"));
+    assert_eq!(data.len(), 11 + 2 * 2 * 3);
+    assert_eq!(&data[11..14], &[255, 0, 51]);
+    let image = UvImage::<f64>::from_ppm(&data).unwrap();
+    assert_eq!(image.pixel_at(1, 0), Color::new(0.0, 1.0, 0.0));
+    // The same pixels as the plain PPM.
+    let plain = UvImage::<f64>::from_ppm(to_ppm(2, 2, &pixels()).as_bytes()).unwrap();
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        assert_eq!(image.pixel_at(x, y), plain.pixel_at(x, y));
+    }
+}
+#[test]
+fn saving_a_canvas_as_a_binary_ppm() {
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut canvas = RawCanvas::<2, 1, f64>::default();
+    canvas.write_pixel(0, 0, Color::new(1.0, 0.5, 0.0)).unwrap();
+    let path = dir.join("canvas-p6.ppm");
+    canvas.save_as(&path, ImageFormat::PpmBinary).unwrap();
+    let ppm = PPMCanvas::<2, 1>::from(canvas);
+    assert_eq!(std::fs::read(&path).unwrap(), ppm.to_binary());
+    assert_eq!(ppm.to_binary(), b"P6\n2 1\n255\n\xff\x7f\x00\x00\x00\x00");
+}
+#[test]
+fn writing_a_grayscale_pgm() {
+    let data = to_pgm(3, 1, &[0.0, 2.0, 8.0], 4.0);
+    assert!(data.starts_with(b"P5\n3 1\n65535\n"));
+    // Big-endian 16-bit samples, clamped to the white level.
+    assert_eq!(&data[13..], &[0x00, 0x00, 0x80, 0x00, 0xff, 0xff]);
+}
+#[test]
+fn saving_the_depth_layer() {
+    let w = World::default();
+    let camera = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let layers: RenderLayers<11, 11, f64> = camera.render_layers(&w, &Whitted::default());
+    let depth = layers.depth.channel(0);
+    assert_eq!(depth.len(), 11 * 11);
+    let max = depth.iter().cloned().fold(0.0, f64::max);
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    save_pgm(dir.join("depth.pgm"), 11, 11, &depth, max).unwrap();
+    let data = std::fs::read(dir.join("depth.pgm")).unwrap();
+    let header = b"P5\n11 11\n65535\n".len();
+    assert_eq!(data.len(), header + 11 * 11 * 2);
+    let sample = |x: usize, y: usize| {
+        let i = header + (y * 11 + x) * 2;
+        u16::from_be_bytes([data[i], data[i + 1]])
+    };
+    // The center sees the front of the outer sphere at a distance of 4,
+    // nearer than the rim of the sphere; the corners see nothing.
+    assert_eq!(sample(5, 5), (4.0 / max * 65535.0_f64).round() as u16);
+    assert!(sample(5, 5) < sample(5, 4));
+    assert_eq!(sample(0, 0), 0);
+}
+#[test]
+fn writing_a_pfm() {
+    let data = to_pfm(2, 2, &pixels());
+    assert!(data.starts_with(b"PF\n2 2\n-1.0\n"));
+    // The highlights above 1 are kept.
+    let image = UvImage::<f64>::from_pfm(&data).unwrap();
+    for (i, pixel) in pixels().into_iter().enumerate() {
+        let read = image.pixel_at(i % 2, i / 2);
+        assert_relative_eq!(read.r, pixel.r, epsilon = 1e-6);
+        assert_relative_eq!(read.g, pixel.g, epsilon = 1e-6);
+        assert_relative_eq!(read.b, pixel.b, epsilon = 1e-6);
+    }
+}
+/// Returns the colors of the flat RGBE pixels of `data`.
+fn from_rgbe(data: &[u8]) -> Vec<Color<f64>> {
+    data.chunks(4)
+        .map(|p| {
+            let scale = 2f64.powi(p[3] as i32 - 128 - 8);
+            Color::new(
+                p[0] as f64 * scale,
+                p[1] as f64 * scale,
+                p[2] as f64 * scale,
+            )
+        })
+        .collect()
+}
+#[test]
+fn writing_a_radiance_hdr() {
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 2\n";
+    let data = to_hdr(2, 2, &pixels());
+    assert!(data.starts_with(header));
+    let read = from_rgbe(&data[header.len()..]);
+    assert_eq!(read.len(), 4);
+    assert_eq!(&data[header.len()..header.len() + 4], &[128, 0, 25, 129]);
+    // Black is all zeros; each color is kept within a mantissa step.
+    assert_eq!(read[2], Color::new(0.0, 0.0, 0.0));
+    for (read, pixel) in read.iter().zip(pixels()) {
+        assert_relative_eq!(read.g, pixel.g, epsilon = 1.5 / 128.0);
+        assert_relative_eq!(read.b, pixel.b, epsilon = 1.0 / 128.0);
+    }
+}
+#[test]
+fn the_scanlines_of_wide_hdr_images_are_run_length_encoded() {
+    let row: Vec<Color<f64>> = (0..10).map(|i| Color::new(i as f64, 0.0, 0.0)).collect();
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 10\n";
+    let data = to_hdr(10, 1, &row);
+    let scanline = &data[header.len()..];
+    assert_eq!(&scanline[..4], &[2, 2, 0, 10]);
+    // Four runs of ten literal bytes, one for each component.
+    assert_eq!(scanline.len(), 4 + 4 * 11);
+    let component = |c: usize| &scanline[4 + c * 11 + 1..4 + (c + 1) * 11];
+    let flat: Vec<u8> = (0..10)
+        .flat_map(|i| [0, 1, 2, 3].map(|c| component(c)[i]))
+        .collect();
+    for (read, pixel) in from_rgbe(&flat).iter().zip(&row) {
+        assert_relative_eq!(read.r, pixel.r, epsilon = pixel.r / 128.0);
+    }
+}
+#[test]
+fn saving_a_render_in_high_dynamic_range() {
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(ImageFormat::from_path("out.HDR").unwrap(), ImageFormat::Hdr);
+    save_pixels(dir.join("pixels.pfm"), 2, 2, &pixels()).unwrap();
+    assert_eq!(
+        std::fs::read(dir.join("pixels.pfm")).unwrap(),
+        to_pfm(2, 2, &pixels())
+    );
+    save_pixels(dir.join("pixels.hdr"), 2, 2, &pixels()).unwrap();
+    assert_eq!(
+        std::fs::read(dir.join("pixels.hdr")).unwrap(),
+        to_hdr(2, 2, &pixels())
+    );
+}
+#[cfg(not(feature = "exr"))]
+#[test]
+fn exr_needs_the_exr_feature() {
+    assert!(matches!(
+        ImageFormat::from_path("out.exr"),
+        Err(ExportError::UnsupportedFormat(_))
+    ));
+}
+/// The size and the channels of an OpenEXR file read by the exr crate,
+/// each channel as its name and its row-major samples.
+#[cfg(feature = "exr")]
+type ExrFile = ((usize, usize), Vec<(String, Vec<f32>)>);
+/// Reads the OpenEXR `data` of 32-bit float channels back through the exr
+/// crate.
+#[cfg(feature = "exr")]
+fn read_exr(data: &[u8]) -> ExrFile {
+    use exr::prelude::{read, FlatSamples, ReadChannels, ReadLayers};
+
+    let image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(std::io::Cursor::new(data))
+        .unwrap();
+    let layer = image.layer_data;
+    let channels = layer
+        .channel_data
+        .list
+        .into_iter()
+        .map(|channel| match channel.sample_data {
+            FlatSamples::F32(samples) => (channel.name.to_string(), samples),
+            _ => panic!("expected 32-bit floats"),
+        })
+        .collect();
+    ((layer.size.0, layer.size.1), channels)
+}
+#[cfg(feature = "exr")]
+#[test]
+fn writing_an_exr_image() {
+    use crate::ExrImage;
+
+    let image = ExrImage::new(2, 2)
+        .with_layer("", &pixels())
+        .with_channel("Z", &[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(image.channel_names(), ["B", "G", "R", "Z"]);
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    image.save(dir.join("channels.exr")).unwrap();
+    let (size, channels) = read_exr(&std::fs::read(dir.join("channels.exr")).unwrap());
+    assert_eq!(size, (2, 2));
+    // The highlights above 1 are kept.
+    assert_eq!(
+        channels,
+        [
+            ("B".to_string(), vec![0.2, 0.0, 0.0, 1.0]),
+            ("G".to_string(), vec![0.0, 1.5, 0.0, 0.5]),
+            ("R".to_string(), vec![1.0, 0.0, 0.0, 0.5]),
+            ("Z".to_string(), vec![1.0, 2.0, 3.0, 4.0]),
+        ]
+    );
+    assert!(matches!(
+        ExrImage::new(2, 2).to_bytes(),
+        Err(ExportError::Exr(_))
+    ));
+}
+#[cfg(feature = "exr")]
+#[test]
+fn exporting_the_render_layers_to_exr() {
+    let camera = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let layers: RenderLayers<11, 11, f64> =
+        camera.render_layers(&World::default(), &Whitted::default());
+    let image = layers.to_exr();
+    assert_eq!(
+        image.channel_names(),
+        [
+            "B",
+            "G",
+            "R",
+            "Z",
+            "albedo.B",
+            "albedo.G",
+            "albedo.R",
+            "normal.B",
+            "normal.G",
+            "normal.R",
+            "object_id"
+        ]
+    );
+    let (size, channels) = read_exr(&image.to_bytes().unwrap());
+    assert_eq!(size, (11, 11));
+    // The center sees the outer sphere, the first object, at a distance
+    // of 4.
+    let center = 5 * 11 + 5;
+    assert_relative_eq!(channels[3].1[center], 4.0, epsilon = 1e-5);
+    assert_eq!(channels[10].1[center], 1.0);
+    assert_eq!(ImageFormat::from_path("out.exr").unwrap(), ImageFormat::Exr);
+    let beauty = ImageFormat::Exr
+        .encode(11, 11, layers.beauty.pixels())
+        .unwrap();
+    let (_, beauty_channels) = read_exr(&beauty);
+    assert_eq!(beauty_channels, channels[..3]);
+}
+/// Returns the size and the RGB samples of the QOI `data`, decoded as by
+/// the reference decoder.
+fn read_qoi(data: &[u8]) -> (u32, u32, Vec<[u8; 3]>) {
+    assert_eq!(&data[..4], b"qoif");
+    let width = u32::from_be_bytes(data[4..8].try_into().unwrap());
+    let height = u32::from_be_bytes(data[8..12].try_into().unwrap());
+    assert_eq!(&data[12..14], &[3, 0]);
+    assert_eq!(&data[data.len() - 8..], &[0, 0, 0, 0, 0, 0, 0, 1]);
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut pixels = Vec::new();
+    let mut pos = 14;
+    while pixels.len() < (width * height) as usize {
+        let op = data[pos];
+        pos += 1;
+        match op {
+            0xfe => {
+                px[..3].copy_from_slice(&data[pos..pos + 3]);
+                pos += 3;
+            }
+            _ => match op >> 6 {
+                0 => px = index[op as usize],
+                1 => {
+                    px[0] = px[0].wrapping_add((op >> 4 & 3).wrapping_sub(2));
+                    px[1] = px[1].wrapping_add((op >> 2 & 3).wrapping_sub(2));
+                    px[2] = px[2].wrapping_add((op & 3).wrapping_sub(2));
+                }
+                2 => {
+                    let dg = (op & 0x3f).wrapping_sub(32);
+                    let next = data[pos];
+                    pos += 1;
+                    px[0] = px[0].wrapping_add(dg.wrapping_add((next >> 4).wrapping_sub(8)));
+                    px[1] = px[1].wrapping_add(dg);
+                    px[2] = px[2].wrapping_add(dg.wrapping_add((next & 0xf).wrapping_sub(8)));
+                }
+                _ => {
+                    for _ in 0..(op & 0x3f) {
+                        pixels.push([px[0], px[1], px[2]]);
+                    }
+                }
+            },
+        }
+        let [r, g, b, a] = px.map(|c| c as usize);
+        index[(r * 3 + g * 5 + b * 7 + a * 11) % 64] = px;
+        pixels.push([px[0], px[1], px[2]]);
+    }
+    assert_eq!(pos, data.len() - 8);
+    (width, height, pixels)
+}
+#[test]
+fn writing_a_qoi_image() {
+    // A full color, a run of it and a small difference to it.
+    let pixels = [
+        Color::new(1.0, 0.0, 0.2),
+        Color::new(1.0, 0.0, 0.2),
+        Color::new(1.0, 0.0, 0.2),
+        Color::new(1.0, 1.0 / 255.0, 0.2),
+    ];
+    let data = to_qoi(2, 2, &pixels);
+    assert_eq!(
+        &data[14..data.len() - 8],
+        &[0xfe, 255, 0, 51, 0xc1, 0x40 | 2 << 4 | 3 << 2 | 2]
+    );
+    let (width, height, read) = read_qoi(&data);
+    assert_eq!((width, height), (2, 2));
+    assert_eq!(
+        read,
+        [[255, 0, 51], [255, 0, 51], [255, 0, 51], [255, 1, 51]]
+    );
+}
+#[test]
+fn qoi_images_are_lossless() {
+    // Long runs, returns to earlier colors, gradients and noise.
+    let mut pixels = vec![Color::new(0.0, 0.0, 0.0); 100];
+    for i in 0..400 {
+        let f = i as f64;
+        pixels.push(match i % 4 {
+            0 => Color::new(f / 400.0, 0.5, 1.0 - f / 400.0),
+            1 => Color::new((f * 0.37).sin().abs(), (f * 1.3).cos().abs(), 0.25),
+            2 => Color::new(0.0, 0.0, 0.0),
+            _ => Color::new(1.0, 1.0, 1.0),
+        });
+    }
+    let data = to_qoi(50, 10, &pixels);
+    let expected: Vec<[u8; 3]> = pixels
+        .iter()
+        .map(|&c| {
+            let c: Color<u8> = c.into();
+            [c.r, c.g, c.b]
+        })
+        .collect();
+    assert_eq!(read_qoi(&data).2, expected);
+}
+#[test]
+fn saving_a_render_as_qoi() {
+    let camera = Camera::new(11, 11, PI / 2.0).with_transform(Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let image: RawCanvas<11, 11, f64> = camera.render(&World::default());
+    let dir = std::env::temp_dir().join("raytracer-rust-image-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    image.save(dir.join("render.qoi")).unwrap();
+    let data = std::fs::read(dir.join("render.qoi")).unwrap();
+    assert_eq!(data, to_qoi(11, 11, image.pixels()));
+    assert_eq!(read_qoi(&data).2.len(), 11 * 11);
+    assert!(data.len() < to_ppm_binary(11, 11, image.pixels()).len());
+}
@@ -0,0 +1,188 @@
+/// Tests of the image textures.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, ImageError, ImageTexture, Pattern, Point3, TextureFilter, UvImage,
+    UvMapping, UvPattern, EPSILON,
+};
+
+fn assert_color(actual: Color<f64>, expected: Color<f64>) {
+    assert_relative_eq!(actual.r, expected.r, epsilon = EPSILON);
+    assert_relative_eq!(actual.g, expected.g, epsilon = EPSILON);
+    assert_relative_eq!(actual.b, expected.b, epsilon = EPSILON);
+}
+
+#[test]
+fn reading_a_file_with_the_wrong_magic_number() {
+    let ppm = b"P32\n1 1\n255\n0 0 0\n";
+    assert!(matches!(
+        UvImage::<f64>::from_ppm(ppm),
+        Err(ImageError::UnsupportedFormat(_))
+    ));
+}
+#[test]
+fn reading_a_ppm_returns_an_image_of_the_right_size() {
+    let ppm = b"P3\n10 2\n255\n\
+        0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n\
+        0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0  0 0 0\n";
+    let image = UvImage::<f64>::from_ppm(ppm).unwrap();
+    assert_eq!(image.width(), 10);
+    assert_eq!(image.height(), 2);
+}
+#[test]
+fn reading_pixel_data_from_a_ppm_file() {
+    let ppm = b"P3\n4 3\n255\n\
+        255 127 0  0 127 255  127 255 0  255 255 255\n\
+        0 0 0  255 0 0  0 255 0  0 0 255\n\
+        255 255 0  0 255 255  255 0 255  127 127 127\n";
+    let image = UvImage::<f64>::from_ppm(ppm).unwrap();
+    let f = |v: f64| v / 255.0;
+    for (x, y, color) in [
+        (0, 0, Color::new(1.0, f(127.0), 0.0)),
+        (1, 0, Color::new(0.0, f(127.0), 1.0)),
+        (2, 0, Color::new(f(127.0), 1.0, 0.0)),
+        (3, 0, Color::new(1.0, 1.0, 1.0)),
+        (0, 1, Color::new(0.0, 0.0, 0.0)),
+        (1, 1, Color::new(1.0, 0.0, 0.0)),
+        (2, 1, Color::new(0.0, 1.0, 0.0)),
+        (3, 1, Color::new(0.0, 0.0, 1.0)),
+        (0, 2, Color::new(1.0, 1.0, 0.0)),
+        (1, 2, Color::new(0.0, 1.0, 1.0)),
+        (2, 2, Color::new(1.0, 0.0, 1.0)),
+        (3, 2, Color::new(f(127.0), f(127.0), f(127.0))),
+    ] {
+        assert_color(image.pixel_at(x, y), color);
+    }
+}
+#[test]
+fn ppm_parsing_ignores_comment_lines() {
+    let ppm = b"P3\n# this is a comment\n2 1\n# this, too\n255\n# another comment\n\
+        255 255 255\n# oh, no, comments in the pixel data!\n255 0 255\n";
+    let image = UvImage::<f64>::from_ppm(ppm).unwrap();
+    assert_color(image.pixel_at(0, 0), Color::new(1.0, 1.0, 1.0));
+    assert_color(image.pixel_at(1, 0), Color::new(1.0, 0.0, 1.0));
+}
+#[test]
+fn ppm_parsing_allows_an_rgb_triple_to_span_lines() {
+    let ppm = b"P3\n1 1\n255\n51\n153\n\n204\n";
+    let image = UvImage::<f64>::from_ppm(ppm).unwrap();
+    assert_color(image.pixel_at(0, 0), Color::new(0.2, 0.6, 0.8));
+}
+#[test]
+fn ppm_parsing_respects_the_scale_setting() {
+    let ppm = b"P3\n2 2\n100\n100 100 100  50 50 50\n75 50 25  0 0 0\n";
+    let image = UvImage::<f64>::from_ppm(ppm).unwrap();
+    assert_color(image.pixel_at(0, 1), Color::new(0.75, 0.5, 0.25));
+}
+#[test]
+fn reading_a_raw_ppm() {
+    let mut ppm = b"P6\n2 1\n255\n".to_vec();
+    ppm.extend_from_slice(&[255, 0, 51, 0, 255, 0]);
+    let image = UvImage::<f64>::from_ppm(&ppm).unwrap();
+    assert_color(image.pixel_at(0, 0), Color::new(1.0, 0.0, 0.2));
+    assert_color(image.pixel_at(1, 0), Color::new(0.0, 1.0, 0.0));
+    // Two bytes per sample above 255.
+    let mut ppm = b"P6 1 1 65535 ".to_vec();
+    ppm.extend_from_slice(&[0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+    let image = UvImage::<f64>::from_ppm(&ppm).unwrap();
+    assert_color(
+        image.pixel_at(0, 0),
+        Color::new(1.0, 32768.0 / 65535.0, 0.0),
+    );
+}
+#[test]
+fn reading_a_truncated_ppm() {
+    assert!(matches!(
+        UvImage::<f64>::from_ppm(b"P3\n2 1\n255\n0 0 0 0\n"),
+        Err(ImageError::InvalidData)
+    ));
+    assert!(matches!(
+        UvImage::<f64>::from_ppm(b"P6\n2 1\n255\n\x00\x00"),
+        Err(ImageError::InvalidData)
+    ));
+    assert!(matches!(
+        UvImage::<f64>::from_ppm(b"P3\n2\n"),
+        Err(ImageError::InvalidHeader)
+    ));
+}
+#[test]
+fn checker_pattern_in_2d_from_a_ppm_image() {
+    let mut ppm = String::from("P3\n10 10\n10\n");
+    for y in 0..10 {
+        for x in 0..10 {
+            let v = (x + y) % 10;
+            ppm.push_str(&format!("{v} {v} {v}  "));
+        }
+        ppm.push('\n');
+    }
+    let image = UvImage::<f64>::from_ppm(ppm.as_bytes()).unwrap();
+    let gray = |v: f64| Color::new(v, v, v);
+    assert_color(image.uv_pattern_at(0.0, 0.0), gray(0.9));
+    assert_color(image.uv_pattern_at(0.3, 0.0), gray(0.2));
+    assert_color(image.uv_pattern_at(0.6, 0.3), gray(0.1));
+    assert_color(image.uv_pattern_at(1.0, 1.0), gray(0.9));
+}
+#[test]
+fn bilinear_filtering_blends_the_closest_pixels() {
+    let black = Color::new(0.0, 0.0, 0.0);
+    let white = Color::new(1.0, 1.0, 1.0);
+    // Black on the left column, white on the right one.
+    let image = UvImage::new(2, 2, vec![black, white, black, white]);
+    assert_eq!(image.filter(), TextureFilter::Nearest);
+    assert_color(image.uv_pattern_at(0.25, 0.5), black);
+    let image = image.with_filter(TextureFilter::Bilinear);
+    assert_color(image.uv_pattern_at(0.25, 0.5), Color::new(0.25, 0.25, 0.25));
+    assert_color(image.uv_pattern_at(0.75, 0.9), Color::new(0.75, 0.75, 0.75));
+    assert_color(image.uv_pattern_at(1.0, 0.0), white);
+}
+#[test]
+fn image_texture_on_a_plane() {
+    let ppm = b"P3\n2 2\n1\n1 0 0  0 1 0\n0 0 1  1 1 1\n";
+    let texture = ImageTexture::new(UvImage::<f64>::from_ppm(ppm).unwrap(), UvMapping::Planar);
+    // `v` grows with `z`, the top row is at `v = 1`.
+    assert_color(
+        texture.pattern_at(Point3::new(0.1, 0.0, 0.1)),
+        Color::new(0.0, 0.0, 1.0),
+    );
+    assert_color(
+        texture.pattern_at(Point3::new(0.9, 0.0, 0.9)),
+        Color::new(0.0, 1.0, 0.0),
+    );
+}
+#[cfg(feature = "png")]
+#[test]
+fn reading_a_png() {
+    let mut data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut data, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[255, 0, 51, 255, 0, 255, 0, 128])
+            .unwrap();
+    }
+    let image = UvImage::<f64>::from_png(&data).unwrap();
+    assert_eq!((image.width(), image.height()), (2, 1));
+    assert_color(image.pixel_at(0, 0), Color::new(1.0, 0.0, 0.2));
+    assert_color(image.pixel_at(1, 0), Color::new(0.0, 1.0, 0.0));
+}
+#[test]
+fn reading_an_hdr_pfm() {
+    let mut pfm = b"PF\n1 2\n-1.0\n".to_vec();
+    // The bottom row comes first.
+    for v in [1.5f32, 0.0, 0.0, 0.0, 2.5, 0.0] {
+        pfm.extend_from_slice(&v.to_le_bytes());
+    }
+    let image = UvImage::<f64>::from_pfm(&pfm).unwrap();
+    assert_eq!(image.pixel_at(0, 0), Color::new(0.0, 2.5, 0.0));
+    assert_eq!(image.pixel_at(0, 1), Color::new(1.5, 0.0, 0.0));
+    let mut pfm = b"Pf 1 1 1.0 ".to_vec();
+    pfm.extend_from_slice(&3.0f32.to_be_bytes());
+    let image = UvImage::<f64>::from_pfm(&pfm).unwrap();
+    assert_eq!(image.pixel_at(0, 0), Color::new(3.0, 3.0, 3.0));
+    assert!(matches!(
+        UvImage::<f64>::from_pfm(b"PF\n1 1\n-1.0\n\x00\x00"),
+        Err(ImageError::InvalidData)
+    ));
+}
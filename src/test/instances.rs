@@ -0,0 +1,115 @@
+/// Tests of the instances sharing their geometry.
+use std::sync::Arc;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::{colors::Color, shapes::Shape},
+    Group, Instance, Material, Mesh, Point3, Ray, Sphere, Transform, Vector3, World, EPSILON,
+};
+
+/// Returns a square of two faces in the `xy` plane, from `(-1, -1)` to `(1, 1)`.
+fn square() -> Mesh<f64> {
+    Mesh::new(
+        vec![
+            Point3::new(-1.0, -1.0, 0.0),
+            Point3::new(1.0, -1.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(-1.0, 1.0, 0.0),
+        ],
+        vec![[0, 1, 2], [0, 2, 3]],
+    )
+}
+
+#[test]
+fn instances_share_their_geometry() {
+    let geometry: Arc<dyn Shape<f64>> = Arc::new(square());
+    let instances: Vec<_> = (0..10)
+        .map(|i| Instance::new(geometry.clone(), Transform::translation(i as f64, 0.0, 0.0)))
+        .collect();
+    assert_eq!(Arc::strong_count(&geometry), 11);
+    assert!(Arc::ptr_eq(instances[3].geometry(), &geometry));
+}
+#[test]
+fn the_intersections_name_the_instance_and_the_shape_hit() {
+    let geometry: Arc<dyn Shape<f64>> = Arc::new(Sphere::default());
+    let instance = Instance::new(geometry.clone(), Transform::translation(0.0, 0.0, 5.0));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = instance.intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 9.0);
+    assert_relative_eq!(xs[1].t(), 11.0);
+    assert!(xs[0].object() == &instance as &dyn Shape<f64>);
+    assert!(xs[0].inner().unwrap() == geometry.as_ref());
+    let miss = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(instance.intersect(&miss).is_empty());
+}
+#[test]
+fn an_instance_is_placed_like_the_transformed_geometry() {
+    let transform = Transform::translation(1.0, 2.0, 3.0) * Transform::scaling(1.0, 2.0, 3.0);
+    let inner = Transform::rotation_y(0.5);
+    let geometry: Arc<dyn Shape<f64>> = Arc::new(Sphere::new(inner));
+    let instance = Instance::new(geometry, transform);
+    let sphere = Sphere::new(transform * inner);
+    assert_eq!(instance.parent_space_bounds(), sphere.parent_space_bounds());
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.3, 0.5, 1.0));
+    let (xs, expected) = (instance.intersect(&r), sphere.intersect(&r));
+    assert_eq!(xs.len(), expected.len());
+    for (x, e) in xs.iter().zip(&expected) {
+        assert_relative_eq!(x.t(), e.t(), epsilon = EPSILON);
+        let point = r.position(x.t());
+        let normal = instance.normal_at_hit(point, x);
+        let expected_normal = sphere.normal_at_hit(point, e);
+        assert_relative_eq!(normal, expected_normal, epsilon = EPSILON);
+    }
+}
+#[test]
+fn the_normal_is_given_by_the_face_of_a_mesh_or_the_child_of_a_group() {
+    let mut group = Group::new(Transform::scaling(1.0, 1.0, 2.0));
+    group.add_child(Sphere::new(Transform::translation(0.0, 0.0, 1.0)));
+    let mut tilted = square();
+    tilted.set_transform(Transform::rotation_y(std::f64::consts::FRAC_PI_4));
+    let r = Ray::new(Point3::new(0.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 1.0));
+    for geometry in [Arc::new(group) as Arc<dyn Shape<f64>>, Arc::new(tilted)] {
+        let instance = Instance::new(geometry.clone(), Transform::translation(0.0, 0.0, 1.0));
+        let hit = instance.intersect(&r)[0];
+        // Moving the ray instead of the geometry gives the same normal.
+        let moved = Ray::new(Point3::new(0.0, 0.0, -11.0), Vector3::new(0.0, 0.0, 1.0));
+        let expected = geometry.intersect(&moved)[0];
+        assert_relative_eq!(hit.t(), expected.t(), epsilon = EPSILON);
+        let normal = instance.normal_at_hit(r.position(hit.t()), &hit);
+        let expected_normal = expected
+            .object()
+            .normal_at_hit(moved.position(expected.t()), &expected);
+        assert_relative_eq!(normal, expected_normal, epsilon = EPSILON);
+    }
+}
+#[test]
+fn each_instance_has_its_own_material() {
+    let mut sphere = Sphere::default();
+    sphere.set_material(Material::matte(Color::new(0.0, 0.0, 1.0)));
+    let geometry: Arc<dyn Shape<f64>> = Arc::new(sphere);
+    let blue = Instance::new(geometry.clone(), Transform::translation(-2.0, 0.0, 0.0));
+    let mut red = Instance::new(geometry, Transform::translation(2.0, 0.0, 0.0));
+    red.set_material(Material::matte(Color::new(1.0, 0.0, 0.0)));
+    assert_eq!(blue.material().color, Color::new(0.0, 0.0, 1.0));
+    let mut w = World::default();
+    w.objects_mut()[0].set_transform(Transform::translation(0.0, 100.0, 0.0));
+    w.objects_mut()[1].set_transform(Transform::translation(0.0, 100.0, 0.0));
+    w.add_object(blue);
+    w.add_object(red);
+    let toward = |x: f64| Ray::new(Point3::new(x, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let (left, right) = (w.color_at(&toward(-2.0), 5), w.color_at(&toward(2.0), 5));
+    assert!(left.b > 0.0 && left.r == 0.0);
+    assert!(right.r > 0.0 && right.b == 0.0);
+}
+#[test]
+fn an_instance_occludes_unless_it_casts_no_shadow() {
+    let geometry: Arc<dyn Shape<f64>> = Arc::new(Sphere::default());
+    let mut instance = Instance::new(geometry, Transform::translation(0.0, 0.0, 5.0));
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(instance.occludes(&r, 10.0));
+    assert!(!instance.occludes(&r, 9.0));
+    instance.set_casts_shadow(false);
+    assert!(!instance.occludes(&r, 10.0));
+}
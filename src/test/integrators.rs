@@ -0,0 +1,102 @@
+/// Tests of the integrators.
+use approx::assert_relative_eq;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::colors::Color, Integrator, Material, PathTracer, Plane, Point3, PointLight, Ray,
+    Shape, Sphere, Vector3, Whitted, World, EPSILON, MAX_DEPTH,
+};
+
+#[test]
+fn whitted_integrator_is_color_at() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(
+        Whitted::default().estimate(&w, &r, &mut rng),
+        w.color_at(&r, MAX_DEPTH)
+    );
+}
+#[test]
+fn path_missing_everything_is_black() {
+    let w = World::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(
+        PathTracer::new(4).estimate(&w, &r, &mut rng),
+        Color::new(0.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn path_hitting_black_emitter_sees_its_emission() {
+    let mut w = World::new();
+    let mut lamp = Sphere::default();
+    let emissive = Color::new(1.0, 0.5, 0.25);
+    lamp.set_material(
+        Material::default()
+            .with_color(Color::new(0.0, 0.0, 0.0))
+            .with_emissive(emissive),
+    );
+    w.add_object(lamp);
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_relative_eq!(
+        PathTracer::new(8).estimate(&w, &r, &mut rng),
+        emissive,
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn point_lights_are_sampled_directly() {
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(0.0, 10.0, 0.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    w.add_object(Plane::default());
+    let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    // The Lambertian term only: the bounces off a lone plane escape.
+    let expected = 0.9 / std::f64::consts::PI;
+    assert_relative_eq!(
+        PathTracer::new(8).estimate(&w, &r, &mut rng),
+        Color::new(expected, expected, expected),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn furnace_converges_to_geometric_series() {
+    // Inside a sphere emitting 1 and reflecting half of the light, every
+    // bounce adds half of the previous one: 1 + 1/2 + 1/4 + ... = 2.
+    let mut w = World::new();
+    let mut furnace = Sphere::default();
+    furnace.set_material(
+        Material::default()
+            .with_diffuse(0.5)
+            .with_emissive(Color::new(1.0, 1.0, 1.0)),
+    );
+    w.add_object(furnace);
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut rng = SmallRng::seed_from_u64(42);
+    let color = PathTracer::new(4000).estimate(&w, &r, &mut rng);
+    assert_relative_eq!(color, Color::new(2.0, 2.0, 2.0), epsilon = 0.05);
+}
+#[test]
+fn bright_paths_are_clamped() {
+    let mut w = World::new();
+    let mut lamp = Sphere::default();
+    lamp.set_material(
+        Material::default()
+            .with_color(Color::new(0.0, 0.0, 0.0))
+            .with_emissive(Color::new(100.0, 50.0, 0.0)),
+    );
+    w.add_object(lamp);
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    let unclamped = PathTracer::new(1).estimate(&w, &r, &mut rng);
+    assert_relative_eq!(unclamped, Color::new(100.0, 50.0, 0.0));
+    let clamped = PathTracer::new(1)
+        .with_max_radiance(10.0)
+        .estimate(&w, &r, &mut rng);
+    assert_relative_eq!(clamped, Color::new(10.0, 5.0, 0.0));
+}
@@ -0,0 +1,89 @@
+/// Tests of the intersections appended to a buffer shared by the shapes.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::shapes::Shape, Csg, Group, Intersection, Intersections, Point3, Ray, Sphere,
+    Transform, Vector3, Volume, World, EPSILON,
+};
+
+fn ray() -> Ray<f64> {
+    Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0))
+}
+
+#[test]
+fn intersections_are_appended_after_the_ones_in_the_buffer() {
+    let (s1, s2) = (
+        Sphere::default(),
+        Sphere::new(Transform::translation(0.0, 0.0, 3.0)),
+    );
+    let mut xs = vec![Intersection::new(100.0, &s1 as &dyn Shape<f64>)];
+    s2.append_intersections(&ray(), &mut xs);
+    s1.append_intersections(&ray(), &mut xs);
+    let ts: Vec<f64> = xs.iter().map(Intersection::t).collect();
+    assert_eq!(ts, [100.0, 7.0, 9.0, 4.0, 6.0]);
+}
+#[test]
+fn a_group_sorts_only_its_own_intersections() {
+    let mut g = Group::default();
+    g.add_child(Sphere::new(Transform::translation(0.0, 0.0, 3.0)));
+    g.add_child(Sphere::default());
+    let s = Sphere::default();
+    let mut xs = vec![Intersection::new(100.0, &s as &dyn Shape<f64>)];
+    g.append_intersections(&ray(), &mut xs);
+    let ts: Vec<f64> = xs.iter().map(Intersection::t).collect();
+    assert_eq!(ts, [100.0, 4.0, 6.0, 7.0, 9.0]);
+    assert_eq!(g.intersect(&ray()), xs[1..]);
+}
+#[test]
+fn a_csg_shape_filters_only_its_own_intersections() {
+    let c = Csg::difference(
+        Sphere::default(),
+        Sphere::new(Transform::translation(0.0, 0.0, 0.5)),
+    );
+    let s = Sphere::default();
+    let mut xs = vec![Intersection::new(100.0, &s as &dyn Shape<f64>)];
+    c.append_intersections(&ray(), &mut xs);
+    assert_eq!(xs.len(), 3);
+    assert_eq!(xs[0].t(), 100.0);
+    assert_relative_eq!(xs[1].t(), 4.0, epsilon = EPSILON);
+    assert_relative_eq!(xs[2].t(), 4.5, epsilon = EPSILON);
+}
+#[test]
+fn a_volume_leaves_no_intersection_of_its_boundary() {
+    let v = Volume::new(Sphere::default(), 1e9);
+    let s = Sphere::default();
+    let mut xs = vec![Intersection::new(100.0, &s as &dyn Shape<f64>)];
+    v.append_intersections(&ray(), &mut xs);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t(), 100.0);
+    assert!(std::ptr::addr_eq(xs[1].object(), &v));
+    assert_relative_eq!(xs[1].t(), 4.0, epsilon = EPSILON);
+}
+#[test]
+fn intersecting_a_shape_into_a_buffer_replaces_its_content() {
+    let mut g = Group::default();
+    g.add_child(Sphere::new(Transform::translation(0.0, 0.0, 3.0)));
+    g.add_child(Sphere::default());
+    let mut xs = Intersections::default();
+    g.intersect_into(&ray(), &mut xs);
+    let ts: Vec<f64> = xs.iter().map(Intersection::t).collect();
+    assert_eq!(ts, [4.0, 6.0, 7.0, 9.0]);
+    let miss = Ray::new(Point3::new(0.0, 5.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    g.intersect_into(&miss, &mut xs);
+    assert!(xs.is_empty());
+}
+#[test]
+fn a_buffer_is_reused_across_the_rays_of_a_world() {
+    let w = World::<f64>::default();
+    let mut xs = Intersections::default();
+    for i in 0..5 {
+        let r = Ray::new(
+            Point3::new(i as f64 * 0.3, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        w.intersect_into(&r, &mut xs);
+        assert_eq!(xs, w.intersect(&r));
+    }
+    xs.clear();
+    assert!(xs.hit().is_none());
+}
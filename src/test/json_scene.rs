@@ -0,0 +1,266 @@
+/// Tests of the JSON scene files.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::{colors::Color, scene::json},
+    Point3, Scene, SceneError, SceneValue, Transform, Vector3,
+};
+
+/// The first scene of the book's bonus chapter on scene files.
+pub(super) const SCENE: &str = r#"[
+    {
+        "add": "camera",
+        "width": 100,
+        "height": 50,
+        "field-of-view": 0.785,
+        "from": [-6, 6, -10],
+        "to": [6, 0, 6],
+        "up": [-0.45, 1, 0]
+    },
+    { "add": "light", "at": [50, 100, -50], "intensity": [1, 1, 1] },
+    {
+        "define": "white-material",
+        "value": { "color": [1, 1, 1], "diffuse": 0.7, "ambient": 0.1, "specular": 0.0 }
+    },
+    {
+        "define": "blue-material",
+        "extend": "white-material",
+        "value": { "color": [0.537, 0.831, 0.914] }
+    },
+    {
+        "define": "standard-transform",
+        "value": [["translate", 1, -1, 1], ["scale", 0.5, 0.5, 0.5]]
+    },
+    {
+        "define": "large-object",
+        "value": ["standard-transform", ["scale", 3.5, 3.5, 3.5]]
+    },
+    {
+        "add": "plane",
+        "material": { "color": [1, 1, 1], "ambient": 1, "diffuse": 0, "specular": 0 },
+        "transform": [["rotate-x", 1.5707963267948966], ["translate", 0, 0, 500]]
+    },
+    { "add": "sphere", "material": "blue-material", "transform": ["large-object"], "shadow": false }
+]"#;
+
+fn scene(src: &str) -> Scene<f64> {
+    Scene::from_json(src).unwrap()
+}
+
+fn error(src: &str) -> SceneError {
+    Scene::<f64>::from_json(src).unwrap_err()
+}
+
+#[test]
+fn parsing_json_values() {
+    let value = json::parse(r#" {"a": [1, -2.5e1, true, null], "b": "x\"é😀\n"} "#).unwrap();
+    assert_eq!(
+        value.get("a"),
+        Some(&SceneValue::Array(vec![
+            SceneValue::Number(1.0),
+            SceneValue::Number(-25.0),
+            SceneValue::Bool(true),
+            SceneValue::Null,
+        ]))
+    );
+    assert_eq!(value.get("b").unwrap().as_str(), Some("x\"é😀\n"));
+    assert_eq!(json::parse("[]").unwrap(), SceneValue::Array(Vec::new()));
+}
+#[test]
+fn syntax_errors_give_their_line() {
+    for (src, line) in [
+        ("[1,\n2,\n]", 3),
+        ("{\"a\" 1}", 1),
+        ("[1] 2", 1),
+        ("\n\"open", 2),
+        ("nul", 1),
+    ] {
+        assert!(
+            matches!(json::parse(src), Err(SceneError::Syntax { line: l }) if l == line),
+            "{src}"
+        );
+    }
+}
+#[test]
+fn loading_the_scene_of_the_book() {
+    let s = scene(SCENE);
+    assert_eq!((s.camera.hsize(), s.camera.vsize()), (100, 50));
+    assert_relative_eq!(s.camera.field_of_view(), 0.785);
+    assert_eq!(
+        s.camera.transform(),
+        Transform::view_transform(
+            Point3::new(-6.0, 6.0, -10.0),
+            Point3::new(6.0, 0.0, 6.0),
+            Vector3::new(-0.45, 1.0, 0.0)
+        )
+    );
+    assert_eq!(s.world.lights().len(), 1);
+    assert_eq!(
+        s.world.lights()[0].position(),
+        Point3::new(50.0, 100.0, -50.0)
+    );
+    let [plane, sphere] = s.world.objects() else {
+        panic!("expected two objects");
+    };
+    assert_eq!(plane.material().ambient, 1.0);
+    assert_eq!(
+        plane.transform(),
+        Transform::translation(0.0, 0.0, 500.0) * Transform::rotation_x(PI / 2.0)
+    );
+    // The blue material extends the white one.
+    let m = sphere.material();
+    assert_eq!(m.color, Color::new(0.537, 0.831, 0.914));
+    assert_eq!((m.diffuse, m.ambient, m.specular), (0.7, 0.1, 0.0));
+    assert_eq!(m.shininess, 200.0);
+    assert_eq!(
+        sphere.transform(),
+        Transform::scaling(3.5, 3.5, 3.5)
+            * Transform::scaling(0.5, 0.5, 0.5)
+            * Transform::translation(1.0, -1.0, 1.0)
+    );
+    assert!(!sphere.casts_shadow());
+}
+#[test]
+fn defined_shapes_are_reused() {
+    let s = scene(
+        r#"[
+        { "add": "camera", "width": 10, "height": 10, "field-of-view": 1,
+          "from": [0, 0, -5], "to": [0, 0, 0], "up": [0, 1, 0] },
+        { "define": "ball", "value": { "add": "sphere", "transform": [["scale", 2, 2, 2]],
+          "material": { "reflective": 0.5 } } },
+        { "define": "sphere", "value": { "add": "sphere", "material": { "diffuse": 0.2 } } },
+        { "add": "ball", "transform": [["translate", 0, 1, 0]] },
+        { "add": "group", "transform": [["translate", 5, 0, 0]],
+          "children": ["ball", { "add": "sphere" }] },
+        { "add": "csg", "operation": "difference",
+          "left": { "add": "cylinder", "min": 0, "max": 1, "closed": true },
+          "right": "ball" }
+    ]"#,
+    );
+    let objects = s.world.objects();
+    assert_eq!(objects.len(), 3);
+    assert_eq!(objects[0].material().reflective, 0.5);
+    assert_eq!(
+        objects[0].transform(),
+        Transform::translation(0.0, 1.0, 0.0) * Transform::scaling(2.0, 2.0, 2.0)
+    );
+    // The children of the group are placed by it.
+    assert_eq!(
+        objects[1].parent_space_bounds().max(),
+        Point3::new(7.0, 2.0, 2.0)
+    );
+    assert_eq!(objects[2].bounds().min(), Point3::new(-2.0, -2.0, -2.0));
+}
+#[test]
+fn invalid_scenes_are_reported() {
+    let camera = r#"{ "add": "camera", "width": 10, "height": 10, "field-of-view": 1,
+        "from": [0, 0, -5], "to": [0, 0, 0], "up": [0, 1, 0] }"#;
+    let invalid = |commands: &str| {
+        let src = format!("[{camera}, {commands}]");
+        matches!(Scene::<f64>::from_json(&src), Err(SceneError::Invalid(_)))
+    };
+    assert!(matches!(error("[]"), SceneError::Invalid(_)));
+    assert!(matches!(error("{}"), SceneError::Invalid(_)));
+    assert!(invalid(r#"{ "add": "cube" }"#));
+    assert!(invalid(
+        r#"{ "add": "sphere", "transform": [["scale", 0, 1, 1]] }"#
+    ));
+    assert!(invalid(
+        r#"{ "add": "sphere", "transform": [["spin", 1]] }"#
+    ));
+    assert!(invalid(r#"{ "add": "sphere", "material": "missing" }"#));
+    assert!(invalid(r#"{ "add": "light", "at": [0, 0] }"#));
+    assert!(invalid(r#"{ "define": "a", "extend": "b", "value": {} }"#));
+    assert!(!invalid(r#"{ "add": "sphere", "unknown-key": 1 }"#));
+}
+#[test]
+fn definitions_containing_themselves_are_reported() {
+    let camera = r#"{ "add": "camera", "width": 10, "height": 10, "field-of-view": 1,
+        "from": [0, 0, -5], "to": [0, 0, 0], "up": [0, 1, 0] }"#;
+    let message = |commands: &str| match error(&format!("[{camera}, {commands}]")) {
+        SceneError::Invalid(message) => message,
+        e => panic!("unexpected error {e}"),
+    };
+    assert_eq!(
+        message(
+            r#"{ "define": "spin", "value": [["rotate-y", 1], "spin"] },
+            { "add": "sphere", "transform": "spin" }"#
+        ),
+        "the definition `spin` contains itself"
+    );
+    assert_eq!(
+        message(
+            r#"{ "define": "a", "value": { "add": "group", "children": ["b"] } },
+            { "define": "b", "value": { "add": "group", "children": ["a"] } },
+            { "add": "a" }"#
+        ),
+        "the definition `b` contains itself"
+    );
+    assert_eq!(
+        message(
+            r#"{ "define": "c", "value": { "add": "csg", "operation": "union",
+                "left": "c", "right": { "add": "sphere" } } },
+            { "add": "group", "children": ["c"] }"#
+        ),
+        "the definition `c` contains itself"
+    );
+}
+#[test]
+fn reading_a_scene_file_with_an_overlay() {
+    let dir = std::env::temp_dir().join("raytracer-rust-json-scene-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("logo.ppm"), "P3\n1 1\n255\n255 0 0\n").unwrap();
+    let src = SCENE.replace(
+        r#"{ "add": "sphere","#,
+        r#"{ "add": "overlay", "file": "logo.ppm", "at": [3, 4], "opacity": 0.5 },
+        { "add": "sphere","#,
+    );
+    std::fs::write(dir.join("overlay.json"), src).unwrap();
+    let s = Scene::<f64>::from_json_file(dir.join("overlay.json")).unwrap();
+    let overlay = s.camera.settings().overlay.as_ref().unwrap();
+    assert_eq!((overlay.x, overlay.y, overlay.opacity), (3, 4, 0.5));
+    assert_eq!(overlay.image.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+    for commands in [
+        r#"{ "add": "overlay" }"#,
+        r#"{ "add": "overlay", "file": "logo.ppm", "at": [-1, 0] }"#,
+        r#"{ "add": "overlay", "file": "logo.ppm", "opacity": 2 }"#,
+        r#"{ "add": "overlay", "file": "missing.ppm" }"#,
+    ] {
+        let src = SCENE.replace(
+            r#"{ "add": "sphere","#,
+            &format!("{commands}, {{ \"add\": \"sphere\","),
+        );
+        std::fs::write(dir.join("invalid.json"), src).unwrap();
+        assert!(matches!(
+            Scene::<f64>::from_json_file(dir.join("invalid.json")),
+            Err(SceneError::Invalid(_))
+        ));
+    }
+}
+#[test]
+fn reading_a_scene_file_with_a_model() {
+    let dir = std::env::temp_dir().join("raytracer-rust-json-scene-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("triangle.obj"),
+        "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3",
+    )
+    .unwrap();
+    let src = SCENE.replace(
+        r#"{ "add": "sphere","#,
+        r#"{ "add": "obj", "file": "triangle.obj" }, { "add": "sphere","#,
+    );
+    std::fs::write(dir.join("scene.json"), src).unwrap();
+    let s = Scene::<f64>::from_json_file(dir.join("scene.json")).unwrap();
+    assert_eq!(s.world.objects().len(), 3);
+    assert_eq!(
+        s.world.objects()[1].bounds().max(),
+        Point3::new(1.0, 1.0, 0.0)
+    );
+    assert!(matches!(
+        Scene::<f64>::from_json_file(dir.join("missing.json")),
+        Err(SceneError::Io(_))
+    ));
+}
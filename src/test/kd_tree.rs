@@ -0,0 +1,212 @@
+/// Tests of the kd-trees and of the choice of the spatial structures.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::shapes::Shape, Accelerator, AcceleratorKind, BoundingBox, Camera, Group, KdTree,
+    Mesh, Plane, Point3, RawCanvas, Ray, Sphere, Transform, Vector3, World, EPSILON,
+};
+
+/// Returns the boxes of `n` unit cubes along `x`, 2 apart, plus a long
+/// box crossing all of them.
+fn boxes(n: usize) -> Vec<BoundingBox<f64>> {
+    let mut boxes: Vec<BoundingBox<f64>> = (0..n)
+        .map(|i| {
+            let x = 2.0 * i as f64;
+            BoundingBox::new(Point3::new(x, 0.0, 0.0), Point3::new(x + 1.0, 1.0, 1.0))
+        })
+        .collect();
+    boxes.push(BoundingBox::new(
+        Point3::new(0.0, 0.25, 0.25),
+        Point3::new(2.0 * n as f64, 0.75, 0.75),
+    ));
+    boxes
+}
+
+/// Returns a cloud of spheres and a plane.
+fn scene() -> Group<f64> {
+    let mut g = Group::default();
+    for i in 0..60 {
+        let (x, y, z) = (
+            (i * 7 % 11) as f64 - 5.0,
+            (i * 3 % 5) as f64 - 2.0,
+            (i * 5 % 13) as f64 - 6.0,
+        );
+        let scale = 0.3 + (i % 4) as f64 * 0.2;
+        g.add_child(Sphere::new(
+            Transform::translation(x, y, z) * Transform::scaling(scale, scale, scale),
+        ));
+    }
+    g.add_child(Plane::new(Transform::translation(0.0, -4.0, 0.0)));
+    g
+}
+
+fn rays() -> Vec<Ray<f64>> {
+    (0..50)
+        .map(|i| {
+            let a = i as f64 * 0.37;
+            let origin = Point3::new(a.cos() * 15.0, (i % 5) as f64 - 2.0, a.sin() * 15.0);
+            Ray::new(
+                origin,
+                Vector3::new(-a.cos(), 0.02 * (i % 7) as f64 - 0.06, -a.sin()),
+            )
+        })
+        .collect()
+}
+
+fn ts(xs: &[crate::Intersection<'_, f64>]) -> Vec<f64> {
+    xs.iter().map(|x| x.t()).collect()
+}
+
+#[test]
+fn a_kd_tree_splits_the_primitives_into_small_cells() {
+    let tree = KdTree::build(&boxes(16), 2);
+    assert_eq!(tree.len(), 17);
+    assert!(tree.node_count() > 1);
+    // The long box is referenced by several cells.
+    assert!(tree.leaves().filter(|leaf| leaf.contains(&16)).count() > 1);
+    let mut all: Vec<usize> = tree.leaves().flatten().collect();
+    all.sort();
+    all.dedup();
+    assert_eq!(all, (0..17).collect::<Vec<_>>());
+}
+#[test]
+fn a_kd_tree_gives_each_primitive_once() {
+    let tree = KdTree::build(&boxes(16), 1);
+    let r = Ray::new(Point3::new(-1.0, 0.5, 0.5), Vector3::new(1.0, 0.0, 0.0));
+    let mut visited = Vec::new();
+    assert!(!tree.visit(&r, |i| {
+        visited.push(i);
+        false
+    }));
+    visited.sort();
+    assert_eq!(visited, (0..17).collect::<Vec<_>>());
+    // The same holds when the ray goes the other way, or starts inside.
+    for origin in [40.0, 9.5] {
+        let r = Ray::new(Point3::new(origin, 0.5, 0.5), Vector3::new(-1.0, 0.0, 0.0));
+        let mut visited = Vec::new();
+        tree.visit(&r, |i| {
+            visited.push(i);
+            false
+        });
+        visited.sort();
+        assert_eq!(visited, (0..17).collect::<Vec<_>>());
+    }
+}
+#[test]
+fn a_kd_tree_skips_the_cells_a_ray_misses() {
+    let tree = KdTree::build(&boxes(16), 1);
+    let r = Ray::new(Point3::new(4.5, 5.0, 0.1), Vector3::new(0.0, -1.0, 0.0));
+    let mut visited = Vec::new();
+    tree.visit(&r, |i| {
+        visited.push(i);
+        false
+    });
+    // The cell of the third cube also holds the long box.
+    visited.sort();
+    assert_eq!(visited, [2, 16]);
+}
+#[test]
+fn the_kind_of_an_accelerator() {
+    let bounds = boxes(4);
+    assert_eq!(
+        Accelerator::build(AcceleratorKind::Bvh, &bounds, 2).kind(),
+        AcceleratorKind::Bvh
+    );
+    let kd = Accelerator::build(AcceleratorKind::KdTree, &bounds, 2);
+    assert_eq!(kd.kind(), AcceleratorKind::KdTree);
+    assert_eq!(kd.len(), 5);
+    assert_eq!(AcceleratorKind::default(), AcceleratorKind::Bvh);
+}
+#[test]
+fn groups_have_the_same_intersections_with_either_structure() {
+    let mut g = scene();
+    let expected: Vec<Vec<f64>> = rays().iter().map(|r| ts(&g.intersect(r))).collect();
+    for kind in [AcceleratorKind::Bvh, AcceleratorKind::KdTree] {
+        g.build_accelerator(kind, 2);
+        assert_eq!(g.accelerator().unwrap().kind(), kind);
+        for (r, expected) in rays().iter().zip(&expected) {
+            let ts = ts(&g.intersect(r));
+            assert_eq!(ts.len(), expected.len());
+            for (t, e) in ts.iter().zip(expected) {
+                assert_relative_eq!(t, e, epsilon = EPSILON);
+            }
+            let visible = expected.iter().any(|&t| t >= 0.0);
+            assert_eq!(g.occludes(r, f64::INFINITY), visible);
+        }
+    }
+}
+#[test]
+fn meshes_have_the_same_intersections_with_a_kd_tree() {
+    let n = 10;
+    let vertices: Vec<Point3<f64>> = (0..=n)
+        .flat_map(|j| {
+            (0..=n).map(move |i| Point3::new(i as f64, j as f64, ((i + j) % 3) as f64 * 0.2))
+        })
+        .collect();
+    let index = |i: usize, j: usize| j * (n + 1) + i;
+    let faces: Vec<[usize; 3]> = (0..n)
+        .flat_map(|j| {
+            (0..n).flat_map(move |i| {
+                [
+                    [index(i, j), index(i + 1, j), index(i + 1, j + 1)],
+                    [index(i, j), index(i + 1, j + 1), index(i, j + 1)],
+                ]
+            })
+        })
+        .collect();
+    let mut m = Mesh::new(vertices, faces);
+    let rays: Vec<Ray<f64>> = (0..40)
+        .map(|i| {
+            Ray::new(
+                Point3::new(
+                    (i * 7 % 40) as f64 * 0.23 + 0.05,
+                    (i * 13 % 40) as f64 * 0.21 + 0.05,
+                    -3.0,
+                ),
+                Vector3::new(0.03, 0.01, 1.0),
+            )
+        })
+        .collect();
+    let expected: Vec<_> = rays
+        .iter()
+        .map(|r| {
+            m.intersect(r)
+                .iter()
+                .map(|x| (x.t(), x.face()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    m.build_accelerator(AcceleratorKind::KdTree, 2);
+    for (r, expected) in rays.iter().zip(expected) {
+        let hits: Vec<_> = m.intersect(r).iter().map(|x| (x.t(), x.face())).collect();
+        assert_eq!(hits, expected);
+    }
+}
+#[test]
+fn a_world_renders_the_same_with_either_structure() {
+    let mut w = World::default();
+    w.add_object(scene());
+    let mut c = Camera::new(20, 15, 1.0);
+    c.set_transform(Transform::view_transform(
+        Point3::new(0.0, 3.0, -18.0),
+        Point3::origin(),
+        Vector3::new(0.0, 1.0, 0.0),
+    ));
+    let expected: RawCanvas<20, 15, f64> = c.render(&w);
+    for kind in [AcceleratorKind::Bvh, AcceleratorKind::KdTree] {
+        w.build_accelerator(kind, 2);
+        assert_eq!(w.accelerator().unwrap().kind(), kind);
+        let image: RawCanvas<20, 15, f64> = c.render(&w);
+        for y in 0..15 {
+            for x in 0..20 {
+                assert_relative_eq!(
+                    *image.pixel_at(x, y).unwrap(),
+                    *expected.pixel_at(x, y).unwrap(),
+                    epsilon = EPSILON
+                );
+            }
+        }
+    }
+    w.objects_mut();
+    assert!(w.accelerator().is_none());
+}
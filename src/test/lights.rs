@@ -0,0 +1,119 @@
+/// Tests of light sources.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, lighting_from, Attenuation, Material, Point3, PointLight,
+    Sphere, Vector3, EPSILON,
+};
+
+/// A white light in front of a surface at the origin facing `-z`, seen
+/// from the light.
+fn front_light() -> PointLight<f64> {
+    PointLight::new(Point3::new(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0))
+}
+
+#[test]
+fn lights_contributions_are_summed() {
+    let m = Material::default();
+    let key = front_light();
+    let fill = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::new(0.5, 0.5, 0.5));
+    let (point, eyev, normalv) = (
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, 0.0, -1.0),
+    );
+    let result = lighting_from(
+        &m,
+        &Sphere::default(),
+        &[key, fill],
+        point,
+        eyev,
+        normalv,
+        |_| false,
+    );
+    let expected = lighting(&m, &Sphere::default(), &key, point, eyev, normalv, false)
+        + lighting(&m, &Sphere::default(), &fill, point, eyev, normalv, false);
+    assert_relative_eq!(result, expected, epsilon = EPSILON);
+    // 1.9 from the key light, half of 0.7364 from the fill light.
+    assert_relative_eq!(
+        result,
+        Color::new(2.2682, 2.2682, 2.2682),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn each_light_has_its_own_shadow_test() {
+    let m = Material::default();
+    let key = front_light();
+    let fill = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+    let result = lighting_from(
+        &m,
+        &Sphere::default(),
+        &[key, fill],
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        |light| light == &fill,
+    );
+    // The fill light only adds its ambient term.
+    assert_relative_eq!(result, Color::new(2.0, 2.0, 2.0), epsilon = EPSILON);
+}
+#[test]
+fn no_lights_is_black() {
+    let result = lighting_from(
+        &Material::default(),
+        &Sphere::default(),
+        &[],
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        |_| false,
+    );
+    assert_eq!(result, Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn attenuation_factors() {
+    assert_eq!(Attenuation::None.factor(10.0), 1.0);
+    assert_relative_eq!(Attenuation::Linear(0.5).factor(2.0), 0.5);
+    assert_relative_eq!(Attenuation::Quadratic(1.0).factor(3.0), 0.1);
+    let custom = Attenuation::Custom {
+        constant: 2.0,
+        linear: 1.0,
+        quadratic: 0.5,
+    };
+    assert_relative_eq!(custom.factor(2.0), 1.0 / 6.0);
+}
+#[test]
+fn default_light_is_not_attenuated() {
+    let light = front_light();
+    assert_eq!(light.attenuation(), Attenuation::None);
+    assert_eq!(
+        light.intensity_at(Point3::new(0.0, 0.0, 100.0)),
+        light.intensity()
+    );
+}
+#[test]
+fn attenuated_intensity_at_point() {
+    let light = front_light().with_attenuation(Attenuation::Linear(0.1));
+    assert_relative_eq!(
+        light.intensity_at(Point3::new(0.0, 0.0, 0.0)),
+        Color::new(0.5, 0.5, 0.5),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn attenuation_dims_diffuse_and_specular_only() {
+    let m = Material::default();
+    let light = front_light().with_attenuation(Attenuation::Linear(0.1));
+    let result = lighting(
+        &m,
+        &Sphere::default(),
+        &light,
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        Vector3::new(0.0, 0.0, -1.0),
+        false,
+    );
+    // The ambient 0.1, plus half of the diffuse 0.9 and specular 0.9.
+    assert_relative_eq!(result, Color::new(1.0, 1.0, 1.0), epsilon = EPSILON);
+}
@@ -0,0 +1,108 @@
+/// Tests of the material presets.
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, lighting, Intersection, Material, Plane, Point3, PointLight, Ray,
+    Shape, Sphere, Transform, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+#[test]
+fn glass_preset() {
+    let m = Material::<f64>::glass();
+    assert_eq!(m.transparency, 0.9);
+    assert_eq!(m.refractive_index, 1.5);
+    assert_eq!(m.reflective, 0.9);
+}
+#[test]
+fn matte_preset_has_no_highlight() {
+    let color = Color::new(0.2, 0.4, 0.6);
+    let m = Material::matte(color);
+    assert_eq!(m.color, color);
+    assert_eq!(m.specular, 0.0);
+    assert_eq!(m.reflective, 0.0);
+    // The eye in the path of the reflection of the light.
+    let light = PointLight::new(Point3::new(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
+    let result = lighting(
+        &m,
+        &Sphere::default(),
+        &light,
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, -FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+        Vector3::new(0.0, 0.0, -1.0),
+        false,
+    );
+    assert_relative_eq!(
+        result,
+        color * (0.1 + 0.9 * FRAC_1_SQRT_2),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn metal_preset_keeps_its_color() {
+    let color = Color::new(0.9, 0.6, 0.2);
+    let m = Material::metal(color);
+    assert_eq!(m.color, color);
+    assert!(m.reflective > 0.0 && m.reflective < 1.0);
+}
+#[test]
+fn mirror_shows_only_its_reflection() {
+    let mut w = World::default();
+    let mut floor = Plane::default();
+    floor.set_material(Material::mirror());
+    floor.set_transform(Transform::translation(0.0, -1.0, 0.0));
+    w.add_object(floor);
+    let r = Ray::new(
+        Point3::new(0.0, 0.0, -3.0),
+        Vector3::new(0.0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    );
+    let comps = Intersection::new(SQRT_2, w.objects()[2].as_ref()).prepare_computations(&r);
+    let reflected = w.reflected_color(&comps, MAX_DEPTH);
+    assert!(reflected.g > 0.0);
+    // Only the highlight would add to the reflection, the eye is not in it.
+    assert_relative_eq!(w.shade_hit(&comps, MAX_DEPTH), reflected, epsilon = EPSILON);
+}
+#[test]
+fn glass_lets_the_light_through() {
+    let pane = |material: Material<f64>| {
+        let mut pane = Plane::new(Transform::translation(0.0, 1.0, 0.0));
+        pane.set_material(material);
+        pane
+    };
+    let light = PointLight::new(Point3::new(0.0, 10.0, 0.0), Color::new(1.0, 1.0, 1.0));
+    let world = |pane: Option<Plane<f64>>| {
+        let mut w = World::new();
+        w.add_light(light);
+        w.add_object(Plane::default());
+        if let Some(pane) = pane {
+            w.add_object(pane);
+        }
+        w
+    };
+    let point = Point3::new(0.0, EPSILON, 0.0);
+    let glass = world(Some(pane(Material::glass())));
+    assert_relative_eq!(glass.light_transmittance_at(point, &light, 0.0), 0.9);
+    assert!(glass.is_shadowed(point, &light));
+    let opaque = world(Some(pane(Material::default())));
+    assert_eq!(opaque.light_transmittance_at(point, &light, 0.0), 0.0);
+    let mut clear = pane(Material::default());
+    clear.set_casts_shadow(false);
+    assert_eq!(
+        world(Some(clear)).light_transmittance_at(point, &light, 0.0),
+        1.0
+    );
+    // The floor under the pane is lit at 90%, past its ambient term.
+    let r = Ray::new(
+        Point3::new(0.0, 0.5, -5.0),
+        Vector3::new(0.0, -0.1, 1.0).normalized(),
+    );
+    let lit = world(None).color_at(&r, MAX_DEPTH);
+    let ambient = opaque.color_at(&r, MAX_DEPTH);
+    assert_relative_eq!(
+        glass.color_at(&r, MAX_DEPTH),
+        ambient + (lit - ambient) * 0.9,
+        epsilon = EPSILON
+    );
+    assert!(ambient.r < lit.r);
+}
@@ -0,0 +1,79 @@
+/// Tests of the indexed triangle meshes.
+use approx::assert_relative_eq;
+
+use crate::{Mesh, Point3, Ray, Shape, SmoothTriangle, Transform, Vector3, EPSILON};
+
+/// A unit square in the xy plane, split along its diagonal.
+fn square() -> Mesh<f64> {
+    Mesh::new(
+        vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ],
+        vec![[0, 1, 2], [0, 2, 3]],
+    )
+}
+
+#[test]
+fn constructing_a_mesh() {
+    let m = square();
+    assert_eq!(m.len(), 2);
+    assert_eq!(m.vertices().len(), 4);
+    assert_eq!(m.triangle(1).vertices()[2], Point3::new(0.0, 1.0, 0.0));
+    assert_eq!(m.bounds().max(), Point3::new(1.0, 1.0, 0.0));
+}
+#[test]
+#[should_panic]
+fn a_face_must_refer_to_existing_vertices() {
+    Mesh::new(vec![Point3::<f64>::origin()], vec![[0, 1, 2]]);
+}
+#[test]
+fn intersecting_a_mesh_records_the_face() {
+    let m = square();
+    let r = Ray::new(Point3::new(0.25, 0.75, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = m.local_intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_relative_eq!(xs[0].t(), 2.0, epsilon = EPSILON);
+    assert_eq!(xs[0].face(), Some(1));
+    let r = Ray::new(Point3::new(2.0, 0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(m.local_intersect(&r).is_empty());
+}
+#[test]
+fn normal_of_a_flat_mesh() {
+    let mut m = square();
+    m.set_transform(Transform::scaling(1.0, 1.0, 2.0));
+    let r = Ray::new(Point3::new(0.75, 0.25, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = m.intersect(&r);
+    assert_relative_eq!(
+        m.normal_at_hit(r.position(xs[0].t()), &xs[0]),
+        Vector3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn mesh_normals_match_smooth_triangles() {
+    let (p1, p2, p3) = (
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(-1.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+    );
+    let (n1, n2, n3) = (
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(-1.0, 0.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+    );
+    let m = Mesh::new(vec![p1, p2, p3], vec![[0, 1, 2]])
+        .with_normals(vec![n1, n2, n3], vec![[0, 1, 2]]);
+    let tri = SmoothTriangle::new(p1, p2, p3, n1, n2, n3);
+    let r = Ray::new(Point3::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    let (xs, expected) = (m.intersect(&r), tri.intersect(&r));
+    assert_eq!(xs[0].uv(), expected[0].uv());
+    let p = r.position(xs[0].t());
+    assert_relative_eq!(
+        m.normal_at_hit(p, &xs[0]),
+        tri.normal_at_hit(p, &expected[0]),
+        epsilon = EPSILON
+    );
+}
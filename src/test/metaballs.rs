@@ -0,0 +1,72 @@
+/// Tests of the metaballs.
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use approx::assert_relative_eq;
+
+use crate::{Metaball, Metaballs, Point3, Ray, Shape, Transform, Vector3, EPSILON};
+
+#[test]
+fn single_metaball_is_a_sphere() {
+    // (1 - d^2)^2 = 0.25 at d = sqrt(0.5)
+    let blob = Metaballs::new(vec![Metaball::new(Point3::origin(), 1.0, 1.0)], 0.25);
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = blob.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 5.0 - FRAC_1_SQRT_2, epsilon = EPSILON);
+    assert_relative_eq!(xs[1].t(), 5.0 + FRAC_1_SQRT_2, epsilon = EPSILON);
+    let r = Ray::new(Point3::new(0.0, 0.8, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(blob.local_intersect(&r).is_empty());
+}
+#[test]
+fn close_metaballs_merge() {
+    let blob = Metaballs::new(
+        vec![
+            Metaball::new(Point3::new(-0.5, 0.0, 0.0), 1.0, 1.0),
+            Metaball::new(Point3::new(0.5, 0.0, 0.0), 1.0, 1.0),
+        ],
+        0.5,
+    );
+    // 2 * (1 - (0.25 + y^2))^2 = 0.5 at y = 0.5, between the two centers.
+    let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let xs = blob.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 4.5, epsilon = EPSILON);
+    assert_relative_eq!(xs[1].t(), 5.5, epsilon = EPSILON);
+}
+#[test]
+fn distant_metaballs_stay_apart() {
+    let blob = Metaballs::new(
+        vec![
+            Metaball::new(Point3::new(-2.0, 0.0, 0.0), 1.0, 1.0),
+            Metaball::new(Point3::new(2.0, 0.0, 0.0), 1.0, 1.0),
+        ],
+        0.25,
+    );
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+    let ts: Vec<f64> = blob.local_intersect(&r).iter().map(|i| i.t()).collect();
+    assert_eq!(ts.len(), 4);
+    for (t, expected) in ts.iter().zip([
+        3.0 - FRAC_1_SQRT_2,
+        3.0 + FRAC_1_SQRT_2,
+        7.0 - FRAC_1_SQRT_2,
+        7.0 + FRAC_1_SQRT_2,
+    ]) {
+        assert_relative_eq!(*t, expected, epsilon = EPSILON);
+    }
+}
+#[test]
+fn metaball_normals() {
+    let mut blob = Metaballs::new(vec![Metaball::new(Point3::origin(), 1.0, 1.0)], 0.25);
+    assert_relative_eq!(
+        blob.local_normal_at(Point3::new(FRAC_1_SQRT_2, 0.0, 0.0))
+            .normalized(),
+        Vector3::new(1.0, 0.0, 0.0),
+        epsilon = EPSILON
+    );
+    blob.set_transform(Transform::translation(0.0, 3.0, 0.0));
+    assert_relative_eq!(
+        blob.normal_at(Point3::new(0.0, 3.0 - FRAC_1_SQRT_2, 0.0)),
+        Vector3::new(0.0, -1.0, 0.0),
+        epsilon = EPSILON
+    );
+}
@@ -1,2 +1,60 @@
+mod animation;
+mod bounds;
+mod bvh;
+mod camera;
 mod chapter1;
+mod chapter10;
+mod chapter11;
+mod chapter13;
+mod chapter14;
+mod chapter15;
+mod chapter16;
 mod chapter2;
+mod chapter3;
+mod chapter4;
+mod chapter5;
+mod chapter6;
+mod chapter7;
+mod chapter8;
+mod chapter9;
+mod checkpoint;
+mod debug_trace;
+mod debug_views;
+mod denoise;
+mod disk;
+mod environment;
+mod fog;
+mod image_export;
+mod image_textures;
+mod instances;
+mod integrators;
+mod intersection_buffers;
+mod json_scene;
+mod kd_tree;
+mod lights;
+mod materials;
+mod mesh;
+mod metaballs;
+mod motion_blur;
+mod obj;
+mod occlusion;
+mod parametric;
+mod ply;
+mod progress;
+mod quaternion;
+mod ray_packets;
+mod render_layers;
+mod render_settings;
+mod ron_scene;
+mod samplers;
+mod sampling;
+mod scene_builder;
+mod scene_export;
+#[cfg(feature = "scripting")]
+mod scene_script;
+mod simd;
+mod stl;
+mod texture_mapping;
+mod tiles;
+mod torus;
+mod volume;
@@ -0,0 +1,121 @@
+/// Tests of the motion blur: rays cast at a time, moving shapes and the
+/// shutter of the camera.
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use approx::assert_relative_eq;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::colors::Color, Camera, Matrix4, Point3, PointLight, RawCanvas, Ray, Shape, Sphere,
+    Transform, Vector3, Whitted, World, EPSILON,
+};
+
+fn moving_sphere() -> Sphere<f64> {
+    let mut s = Sphere::default();
+    s.set_motion(Some(Matrix4::translation(2.0, 0.0, 0.0)));
+    s
+}
+
+#[test]
+fn rays_are_cast_at_time_zero_by_default() {
+    let r = Ray::new(Point3::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(r.time(), 0.0);
+    let r = r.with_time(0.5);
+    assert_eq!(r.time(), 0.5);
+    assert_eq!(
+        r.transform(&Matrix4::translation(3.0, 4.0, 5.0)).time(),
+        0.5
+    );
+}
+#[test]
+fn moving_shape_is_intersected_where_it_is_at_the_time_of_the_ray() {
+    let s = moving_sphere();
+    let r = Ray::new(Point3::new(1.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    // Half way, the sphere is centered on (1, 0, 0).
+    let xs = s.intersect(&r.with_time(0.5));
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t(), 4.0, epsilon = EPSILON);
+    assert_relative_eq!(xs[1].t(), 6.0, epsilon = EPSILON);
+    // At the start, the ray grazes it.
+    let xs = s.intersect(&r);
+    assert_relative_eq!(xs[0].t(), 5.0, epsilon = EPSILON);
+    assert!(s
+        .intersect(&Ray::new(
+            Point3::new(3.5, 0.0, -5.0),
+            Vector3::new(0.0, 0.0, 1.0)
+        ))
+        .is_empty());
+}
+#[test]
+fn normal_of_a_moving_shape_follows_its_motion() {
+    let s = moving_sphere();
+    let r = Ray::new(Point3::new(1.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0)).with_time(0.5);
+    let xs = s.intersect(&r);
+    let comps = xs[0].prepare_computations(&r);
+    assert_eq!(comps.time, 0.5);
+    assert_relative_eq!(
+        comps.normalv,
+        Vector3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+    let still = s.normal_at_hit(Point3::new(1.0, 0.0, -1.0), &xs[0]);
+    assert_relative_eq!(
+        still,
+        Vector3::new(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rotation_is_interpolated_spherically() {
+    let mut s = Sphere::default();
+    s.set_motion(Some(Matrix4::rotation_y(PI / 2.0)));
+    let m = s.base().transform_at(0.5);
+    assert_relative_eq!(m, Matrix4::rotation_y(PI / 4.0), epsilon = EPSILON);
+    assert_relative_eq!(s.base().transform_at(0.0), Matrix4::identity());
+}
+#[test]
+fn bounds_of_a_moving_shape_cover_its_motion() {
+    let b = moving_sphere().parent_space_bounds();
+    assert_relative_eq!(b.min(), Point3::new(-1.0, -1.0, -1.0), epsilon = EPSILON);
+    assert_relative_eq!(b.max(), Point3::new(3.0, 1.0, 1.0), epsilon = EPSILON);
+}
+#[test]
+fn camera_samples_the_times_within_the_shutter() {
+    let c = Camera::new(11, 11, PI / 2.0);
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(c.shutter(), (0.0, 0.0));
+    assert_eq!(c.sample_ray(5.5, 5.5, &mut rng).unwrap().time(), 0.0);
+    let c = c.with_shutter(0.25, 0.75);
+    assert_eq!(c.ray_for_pixel(5, 5).unwrap().time(), 0.25);
+    let times: Vec<f64> = (0..100)
+        .map(|_| c.sample_ray(5.5, 5.5, &mut rng).unwrap().time())
+        .collect();
+    assert!(times.iter().all(|t| (0.25..=0.75).contains(t)));
+    assert!(times.iter().any(|&t| t < 0.4) && times.iter().any(|&t| t > 0.6));
+}
+#[test]
+fn moving_objects_are_blurred() {
+    let mut w = World::new();
+    w.add_light(PointLight::new(
+        Point3::new(-10.0, 10.0, -10.0),
+        Color::new(1.0, 1.0, 1.0),
+    ));
+    // From the left of the view to its right.
+    let mut s = Sphere::new(Matrix4::translation(-2.0, 0.0, 0.0));
+    s.set_motion(Some(Matrix4::translation(2.0, 0.0, 0.0)));
+    w.add_object(s);
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    let whitted = Whitted {
+        samples_per_pixel: 32,
+        ..Whitted::default()
+    };
+    let still: RawCanvas<11, 11, f64> = c.render_with(&w, &whitted);
+    assert_eq!(*still.pixel_at(5, 5).unwrap(), Color::new(0.0, 0.0, 0.0));
+    let blurred: RawCanvas<11, 11, f64> = c.with_shutter(0.0, 1.0).render_with(&w, &whitted);
+    assert!(blurred.pixel_at(5, 5).unwrap().r > 0.0);
+}
@@ -0,0 +1,313 @@
+/// Tests of the OBJ importer, following Chapter 15, and of the MTL importer.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, Material, MtlLibrary, ObjParser, ObjTriangle, Point3, Ray, Shape,
+    Vector3, EPSILON,
+};
+
+#[test]
+fn ignoring_unrecognized_lines() {
+    let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+    let parser = ObjParser::<f64>::parse(gibberish);
+    assert_eq!(parser.ignored(), 5);
+}
+#[test]
+fn vertex_records() {
+    let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+    let parser = ObjParser::<f64>::parse(file);
+    assert_eq!(parser.ignored(), 0);
+    assert_eq!(
+        parser.vertices(),
+        [
+            Point3::new(-1.0, 1.0, 0.0),
+            Point3::new(-1.0, 0.5, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ]
+    );
+}
+#[test]
+fn parsing_triangle_faces() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+# Two triangles
+f 1 2 3
+f 1 3 4";
+    let parser = ObjParser::<f64>::parse(file);
+    assert_eq!(parser.ignored(), 0);
+    let v = parser.vertices();
+    let g = parser.default_group();
+    assert_eq!(g.len(), 2);
+    assert_eq!(g[0].vertices(), [v[0], v[1], v[2]]);
+    assert_eq!(g[1].vertices(), [v[0], v[2], v[3]]);
+}
+#[test]
+fn triangulating_polygons() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+    let parser = ObjParser::<f64>::parse(file);
+    let v = parser.vertices();
+    let g = parser.default_group();
+    assert_eq!(g.len(), 3);
+    assert_eq!(g[0].vertices(), [v[0], v[1], v[2]]);
+    assert_eq!(g[1].vertices(), [v[0], v[2], v[3]]);
+    assert_eq!(g[2].vertices(), [v[0], v[3], v[4]]);
+}
+#[test]
+fn triangles_in_groups() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+    let parser = ObjParser::<f64>::parse(file);
+    let v = parser.vertices();
+    assert!(parser.default_group().is_empty());
+    assert_eq!(
+        parser.group_names().collect::<Vec<_>>(),
+        ["FirstGroup", "SecondGroup"]
+    );
+    let g1 = parser.group("FirstGroup").unwrap();
+    let g2 = parser.group("SecondGroup").unwrap();
+    assert_eq!(g1[0].vertices(), [v[0], v[1], v[2]]);
+    assert_eq!(g2[0].vertices(), [v[0], v[2], v[3]]);
+}
+#[test]
+fn face_elements_with_slashes_and_relative_indices() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+vt 1 0
+vt 1 1
+vn 0 0 1
+vn 0 0 1
+f 1/1/1 2//2 3/3
+f -3 -2 -1
+f 1 2 4";
+    let parser = ObjParser::<f64>::parse(file);
+    // The face referring to a missing vertex is ignored.
+    assert_eq!(parser.ignored(), 1);
+    let g = parser.default_group();
+    assert_eq!(g.len(), 2);
+    assert_eq!(g[0].vertices(), g[1].vertices());
+}
+#[test]
+fn converting_an_obj_file_to_a_group() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+f 1 2 3
+g FirstGroup
+f 1 3 4";
+    let g = ObjParser::<f64>::parse(file).into_group();
+    assert_eq!(g.children().len(), 2);
+    let r = Ray::new(Point3::new(0.5, 0.8, -2.0), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(&r).len(), 1);
+}
+#[test]
+fn reading_a_missing_file() {
+    assert!(ObjParser::<f64>::from_file("does/not/exist.obj").is_err());
+}
+#[test]
+fn vertex_normal_and_texture_records() {
+    let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3
+vt 0.5 0.25
+vt 1";
+    let parser = ObjParser::<f64>::parse(file);
+    assert_eq!(parser.ignored(), 0);
+    assert_eq!(
+        parser.normals(),
+        [
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.707, 0.0, -0.707),
+            Vector3::new(1.0, 2.0, 3.0),
+        ]
+    );
+    assert_eq!(parser.texture_coords(), [(0.5, 0.25), (1.0, 0.0)]);
+}
+#[test]
+fn faces_with_normals() {
+    let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/0/3 2/102/1 3/14/2
+f 1/1/3 2/1/1 3/1/2";
+    let parser = ObjParser::<f64>::parse(file);
+    // Texture coordinates out of range
+    assert_eq!(parser.ignored(), 2);
+    let g = parser.default_group();
+    let ObjTriangle::Smooth(t) = &g[0] else {
+        panic!("A face with normals must be smooth.")
+    };
+    let v = parser.vertices();
+    let n = parser.normals();
+    assert_eq!(t.vertices(), [v[0], v[1], v[2]]);
+    assert_eq!(t.normals(), [n[2], n[0], n[1]]);
+    assert_eq!(t.texture_coords(), None);
+}
+#[test]
+fn faces_with_texture_coordinates() {
+    let file = "v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+f 1/1 2/2 3/3
+f 1 2 3";
+    let parser = ObjParser::<f64>::parse(file);
+    let g = parser.default_group();
+    let ObjTriangle::Smooth(t) = &g[0] else {
+        panic!("A face with texture coordinates must be smooth.")
+    };
+    assert!(matches!(g[1], ObjTriangle::Flat(_)));
+    // The flat normal at every vertex.
+    assert_eq!(t.normals(), [Vector3::new(0.0, 0.0, -1.0); 3]);
+    let r = Ray::new(Point3::new(0.25, 0.5, -1.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = t.intersect(&r);
+    let (s, tc) = t.texture_coords_at(&xs[0]).unwrap();
+    assert_relative_eq!(s, 0.25, epsilon = EPSILON);
+    assert_relative_eq!(tc, 0.5, epsilon = EPSILON);
+}
+
+const MTL: &str = "# Two materials
+newmtl red
+Kd 0.9 0.1 0.1
+Ks 0.5
+Ns 200
+illum 2
+newmtl glass
+d 0.25
+map_Kd -s 1 1 1 textures/glass.png";
+
+#[test]
+fn parsing_material_libraries() {
+    let library = MtlLibrary::<f64>::parse(MTL);
+    // `illum` is not supported.
+    assert_eq!(library.ignored(), 1);
+    assert_eq!(library.materials().len(), 2);
+    let red = library.get("red").unwrap();
+    assert_eq!(red.diffuse, Color::new(0.9, 0.1, 0.1));
+    assert_eq!(red.specular, Color::new(0.5, 0.5, 0.5));
+    assert_eq!(red.shininess, 200.0);
+    assert_eq!(red.dissolve, 1.0);
+    let glass = library.get("glass").unwrap();
+    assert_eq!(glass.diffuse, Color::new(0.8, 0.8, 0.8));
+    assert_eq!(glass.dissolve, 0.25);
+    assert_eq!(glass.diffuse_map.as_deref(), Some("textures/glass.png"));
+    assert!(library.get("missing").is_none());
+}
+#[test]
+fn statements_before_any_material_are_ignored() {
+    let library = MtlLibrary::<f64>::parse("Kd 1 1 1\nnewmtl a");
+    assert_eq!(library.ignored(), 1);
+    assert_eq!(library.materials()[0].name, "a");
+}
+#[test]
+fn faces_use_materials() {
+    let file = "mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 3
+usemtl red
+f 1 2 3
+g Window
+usemtl glass
+f 1 2 3
+usemtl unknown
+f 1 2 3";
+    let mut parser = ObjParser::<f64>::parse(file);
+    assert_eq!(parser.ignored(), 0);
+    assert_eq!(parser.material_libraries(), ["scene.mtl"]);
+    // The materials are not loaded yet.
+    assert!(parser.material_of(None, 1).is_none());
+    parser.load_materials(MtlLibrary::parse(MTL));
+    assert!(parser.material_of(None, 0).is_none());
+    assert_eq!(parser.material_of(None, 1).unwrap().name, "red");
+    assert_eq!(parser.material_of(Some("Window"), 0).unwrap().name, "glass");
+    assert!(parser.material_of(Some("Window"), 1).is_none());
+}
+#[test]
+fn reading_an_obj_file_with_its_materials() {
+    let dir = std::env::temp_dir().join("raytracer-rust-obj-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("scene.mtl"), MTL).unwrap();
+    std::fs::write(
+        dir.join("scene.obj"),
+        "mtllib scene.mtl missing.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3",
+    )
+    .unwrap();
+    let parser = ObjParser::<f64>::from_file(dir.join("scene.obj")).unwrap();
+    assert_eq!(parser.materials().materials().len(), 2);
+    assert_eq!(parser.material_of(None, 0).unwrap().name, "red");
+}
+#[test]
+fn triangles_get_the_materials_of_their_faces() {
+    let dir = std::env::temp_dir().join("raytracer-rust-obj-material-test");
+    std::fs::create_dir_all(dir.join("textures")).unwrap();
+    std::fs::write(dir.join("scene.mtl"), MTL.replace("glass.png", "glass.ppm")).unwrap();
+    std::fs::write(dir.join("textures/glass.ppm"), "P3\n1 1\n255\n0 0 255\n").unwrap();
+    std::fs::write(
+        dir.join("scene.obj"),
+        "mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 3
+usemtl red
+f 1 2 3
+usemtl glass
+f 1 2 3",
+    )
+    .unwrap();
+    let group = ObjParser::<f64>::from_file(dir.join("scene.obj"))
+        .unwrap()
+        .into_group();
+    let children = group.children();
+    assert_eq!(*children[0].material(), Material::default());
+    let red = children[1].material();
+    assert_eq!(red.color, Color::new(0.9, 0.1, 0.1));
+    assert_eq!(red.diffuse, 1.0);
+    assert_relative_eq!(red.specular, 0.5, epsilon = EPSILON);
+    assert_eq!(red.shininess, 200.0);
+    assert_eq!(red.transparency, 0.0);
+    assert!(red.pattern.is_none());
+    let glass = children[2].material();
+    assert_eq!(glass.transparency, 0.75);
+    let texture = glass.pattern.as_ref().unwrap();
+    assert_eq!(
+        texture.pattern_at_shape(children[2].as_ref(), Point3::new(0.5, 0.0, 0.5)),
+        Color::new(0.0, 0.0, 1.0)
+    );
+}
@@ -0,0 +1,63 @@
+/// Tests of the any-hit queries of the shadow rays.
+use crate::{features::shapes::Shape, Group, Point3, Ray, Sphere, Transform, Vector3, World};
+
+fn ray() -> Ray<f64> {
+    Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0))
+}
+
+#[test]
+fn a_ray_is_occluded_by_a_hit_before_max_t() {
+    let w = World::<f64>::default();
+    assert!(w.is_occluded(&ray(), 5.0));
+    assert!(w.is_occluded(&ray(), f64::INFINITY));
+    // The outer sphere is hit at t = 4.
+    assert!(!w.is_occluded(&ray(), 4.0));
+    assert!(!w.is_occluded(&ray(), 3.0));
+}
+#[test]
+fn hits_behind_the_origin_do_not_occlude() {
+    let w = World::<f64>::default();
+    let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(!w.is_occluded(&r, f64::INFINITY));
+}
+#[test]
+fn objects_casting_no_shadow_do_not_occlude() {
+    let mut w = World::new();
+    let mut s = Sphere::<f64>::default();
+    s.set_casts_shadow(false);
+    w.add_object(s);
+    assert!(!w.is_occluded(&ray(), f64::INFINITY));
+}
+#[test]
+fn a_group_is_occluding_through_its_children() {
+    let mut inner = Group::new(Transform::scaling(2.0, 2.0, 2.0));
+    let mut hidden = Sphere::new(Transform::translation(0.0, 0.0, -1.0));
+    hidden.set_casts_shadow(false);
+    inner.add_child(hidden);
+    inner.add_child(Sphere::new(Transform::translation(0.0, 0.0, 1.0)));
+    let mut g = Group::new(Transform::translation(0.0, 0.0, 1.0));
+    g.add_child(inner);
+    // The shadow casting sphere spans t in [6, 10], the other one [2, 6].
+    assert!(!g.occludes(&ray(), 6.0));
+    assert!(g.occludes(&ray(), 6.5));
+    let miss = Ray::new(Point3::new(0.0, 5.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(!g.occludes(&miss, f64::INFINITY));
+}
+#[test]
+fn occlusion_agrees_with_the_shadows_of_the_world() {
+    let w = World::<f64>::default();
+    let light = w.lights()[0];
+    for point in [
+        Point3::new(0.0, 10.0, 0.0),
+        Point3::new(10.0, -10.0, 10.0),
+        Point3::new(-20.0, 20.0, -20.0),
+        Point3::new(-2.0, 2.0, -2.0),
+    ] {
+        let v = light.position() - point;
+        let r = Ray::new(point, v.normalized());
+        assert_eq!(
+            w.is_occluded(&r, v.magnitude()),
+            w.is_shadowed(point, &light)
+        );
+    }
+}
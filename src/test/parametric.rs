@@ -0,0 +1,87 @@
+/// Tests of the tessellated surfaces: parametric surfaces and height fields.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, HeightField, ParametricSurface, Point3, RawCanvas, Ray, Shape,
+    Transform, Vector3, EPSILON,
+};
+
+fn unit_sphere(u: f64, v: f64) -> Point3<f64> {
+    let (theta, phi) = (u * 2.0 * PI, v * PI);
+    Point3::new(phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin())
+}
+
+#[test]
+fn tessellating_a_flat_patch() {
+    let patch = ParametricSurface::new(|u, v| Point3::new(u, 0.0, v), 4, 2);
+    assert_eq!(patch.resolution(), (4, 2));
+    assert_eq!(patch.triangles().len(), 16);
+    let r = Ray::new(Point3::new(0.3, 1.0, 0.8), Vector3::new(0.0, -1.0, 0.0));
+    let xs = patch.intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_relative_eq!(xs[0].t(), 1.0, epsilon = EPSILON);
+    let p = r.position(xs[0].t());
+    assert_relative_eq!(
+        xs[0].object().normal_at_hit(p, &xs[0]),
+        Vector3::new(0.0, 1.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn degenerate_triangles_are_skipped() {
+    // Each pole collapses one triangle of every cell touching it.
+    let sphere = ParametricSurface::new(unit_sphere, 8, 4);
+    assert_eq!(sphere.triangles().len(), 2 * 8 * 4 - 2 * 8);
+}
+#[test]
+fn tessellated_sphere_approximates_the_sphere() {
+    let sphere = ParametricSurface::new(unit_sphere, 64, 32);
+    let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = sphere.intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_relative_eq!(xs[0].t().min(xs[1].t()), 4.0, epsilon = 0.01);
+    assert_relative_eq!(xs[0].t().max(xs[1].t()), 6.0, epsilon = 0.01);
+}
+#[test]
+fn transformed_surface_normals() {
+    let mut patch = ParametricSurface::new(|u, v| Point3::new(u, 0.0, v), 2, 2);
+    patch.set_transform(Transform::translation(0.0, 2.0, 0.0) * Transform::rotation_z(PI));
+    let r = Ray::new(Point3::new(-0.3, 0.0, 0.6), Vector3::new(0.0, 1.0, 0.0));
+    let xs = patch.intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_relative_eq!(xs[0].t(), 2.0, epsilon = EPSILON);
+    let p = r.position(xs[0].t());
+    assert_relative_eq!(
+        xs[0].object().normal_at_hit(p, &xs[0]),
+        Vector3::new(0.0, -1.0, 0.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn height_field_from_canvas() {
+    let mut canvas = RawCanvas::<3, 2, f64>::default();
+    for x in 0..3 {
+        canvas.write_pixel(x, 1, Color::new(0.5, 0.5, 0.5)).unwrap();
+    }
+    let field = HeightField::from_canvas(&canvas);
+    assert_eq!(field.size(), (3, 2));
+    assert_eq!(field.triangles().len(), 4);
+    // The ramp rises from 0 at z = 0 to 0.5 at z = 1.
+    let r = Ray::new(Point3::new(0.3, 2.0, 0.4), Vector3::new(0.0, -1.0, 0.0));
+    let xs = field.intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_relative_eq!(xs[0].t(), 1.8, epsilon = EPSILON);
+    let p = r.position(xs[0].t());
+    assert_relative_eq!(
+        xs[0].object().normal_at_hit(p, &xs[0]),
+        Vector3::new(0.0, 2.0, -1.0).normalized(),
+        epsilon = EPSILON
+    );
+}
+#[test]
+#[should_panic]
+fn height_field_needs_a_grid() {
+    HeightField::from_heights(&[0.0, 1.0, 2.0], 3, 1);
+}
@@ -0,0 +1,98 @@
+/// Tests of the PLY importer.
+use approx::assert_relative_eq;
+
+use crate::{Mesh, PlyError, Point3, Ray, Shape, Vector3, EPSILON};
+
+/// A square in the xy plane as a single quad, the normals leaning outwards.
+const ASCII: &str = "ply
+format ascii 1.0
+comment made by hand
+element vertex 4
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 -1 0 -1
+1 0 0 1 0 -1
+1 1 0 1 0 -1
+0 1 0 -1 0 -1
+4 0 1 2 3
+";
+
+#[test]
+fn reading_an_ascii_ply() {
+    let mesh = Mesh::<f64>::from_ply(ASCII.as_bytes()).unwrap();
+    assert_eq!(mesh.vertices().len(), 4);
+    assert_eq!(mesh.faces(), [[0, 1, 2], [0, 2, 3]]);
+    assert_eq!(mesh.normals().len(), 4);
+    // The normal is interpolated between the vertices.
+    let r = Ray::new(Point3::new(0.5, 0.8, -1.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = mesh.intersect(&r);
+    assert_relative_eq!(
+        mesh.normal_at_hit(r.position(xs[0].t()), &xs[0]),
+        Vector3::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn reading_a_binary_ply() {
+    let mut bytes = b"ply
+format binary_little_endian 1.0
+element vertex 3
+property double x
+property double y
+property double z
+property uchar red
+element face 1
+property list uchar uint vertex_index
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+"
+    .to_vec();
+    for p in [[0.0_f64, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+        bytes.extend(p.iter().flat_map(|f| f.to_le_bytes()));
+        bytes.push(255);
+    }
+    bytes.push(3);
+    bytes.extend([0_u32, 1, 2].iter().flat_map(|i| i.to_le_bytes()));
+    bytes.extend([0_i32, 1].iter().flat_map(|i| i.to_le_bytes()));
+    let mesh = Mesh::<f64>::from_ply(&bytes).unwrap();
+    assert_eq!(
+        mesh.vertices(),
+        [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ]
+    );
+    assert_eq!(mesh.faces(), [[0, 1, 2]]);
+    assert!(mesh.normals().is_empty());
+}
+#[test]
+fn invalid_ply_content() {
+    assert!(matches!(
+        Mesh::<f64>::from_ply(b"plx\nend_header\n"),
+        Err(PlyError::InvalidHeader { line: 1 })
+    ));
+    assert!(matches!(
+        Mesh::<f64>::from_ply(b"ply\nformat binary_big_endian 1.0\nend_header\n"),
+        Err(PlyError::UnsupportedFormat(_))
+    ));
+    let truncated = ASCII.replace("4 0 1 2 3\n", "");
+    assert!(matches!(
+        Mesh::<f64>::from_ply(truncated.as_bytes()),
+        Err(PlyError::InvalidData)
+    ));
+    let out_of_range = ASCII.replace("4 0 1 2 3", "3 0 1 7");
+    assert!(matches!(
+        Mesh::<f64>::from_ply(out_of_range.as_bytes()),
+        Err(PlyError::InvalidIndex(7))
+    ));
+}
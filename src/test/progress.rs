@@ -0,0 +1,101 @@
+/// Tests of the progress reports of a render.
+use std::{f64::consts::PI, time::Duration};
+
+use crate::{
+    Camera, PixelBounds, Point3, ProgressObserver, RawCanvas, RayCounts, RenderProgress,
+    RenderSettings, Transform, Vector3, Whitted, World,
+};
+
+fn camera() -> Camera<f64> {
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    Camera::new(11, 11, PI / 2.0).with_transform(view)
+}
+
+#[test]
+fn progress_is_reported_after_each_tile() {
+    let w = World::default();
+    let c = camera().with_tile_size(4);
+    let mut reports = Vec::new();
+    let image: RawCanvas<11, 11, f64> =
+        c.render_with_progress(&w, &Whitted::default(), &mut |p: &RenderProgress| {
+            reports.push(*p)
+        });
+    assert_eq!(reports.len(), 9);
+    assert_eq!(reports[0].tiles_done, 1);
+    assert_eq!(reports[0].pixels_done, 16);
+    let last = reports.last().unwrap();
+    assert!(last.is_done());
+    assert_eq!(last.pixels_done, 121);
+    assert_eq!(last.pixels_total, 121);
+    assert_eq!(last.fraction(), 1.0);
+    assert!(reports.windows(2).all(|p| p[0].elapsed <= p[1].elapsed));
+    let expected: RawCanvas<11, 11, f64> = c.render(&w);
+    assert_eq!(image.pixels(), expected.pixels());
+}
+#[test]
+fn progress_counts_the_rays_cast() {
+    let w = World::default();
+    let rays = |settings: RenderSettings| {
+        let c = camera().with_tile_size(4).with_settings(settings.clone());
+        let mut last = None;
+        let _: RawCanvas<11, 11, f64> =
+            c.render_with_progress(&w, &settings.whitted(), &mut |p: &RenderProgress| {
+                last = Some(*p)
+            });
+        last.unwrap().rays
+    };
+    let single = rays(RenderSettings::single_threaded());
+    assert_eq!(single.primary, 121);
+    // One shadow ray for each pixel seeing a sphere, lit by one light.
+    assert!(single.shadow > 0 && single.shadow < 121);
+    let sampled = |threads| RenderSettings {
+        threads,
+        samples_per_pixel: 2,
+        ..RenderSettings::default()
+    };
+    let threaded = rays(sampled(4));
+    assert_eq!(threaded.primary, 242);
+    assert_eq!(threaded, rays(sampled(1)));
+}
+#[test]
+fn progress_covers_the_render_region_only() {
+    struct Count(usize, usize);
+    impl ProgressObserver for Count {
+        fn on_progress(&mut self, progress: &RenderProgress) {
+            self.0 += 1;
+            self.1 = progress.pixels_total;
+        }
+    }
+    let mut count = Count(0, 0);
+    let c = camera().with_render_region(PixelBounds::new(2, 2, 5, 4));
+    let _: RawCanvas<11, 11, f64> =
+        c.render_with_progress(&World::default(), &Whitted::default(), &mut count);
+    assert_eq!((count.0, count.1), (1, 6));
+}
+#[test]
+fn remaining_time_is_extrapolated_from_the_elapsed_time() {
+    let mut p = RenderProgress {
+        tiles_done: 0,
+        tiles_total: 4,
+        pixels_done: 0,
+        pixels_total: 400,
+        elapsed: Duration::ZERO,
+        rays: RayCounts::default(),
+    };
+    assert_eq!(p.remaining(), None);
+    p.tiles_done = 1;
+    p.pixels_done = 100;
+    p.elapsed = Duration::from_secs(2);
+    assert_eq!(p.fraction(), 0.25);
+    assert_eq!(p.remaining(), Some(Duration::from_secs(6)));
+    assert!(!p.is_done());
+    p.rays = RayCounts {
+        primary: 100,
+        shadow: 50,
+    };
+    assert_eq!(p.rays_per_second(), 75.0);
+}
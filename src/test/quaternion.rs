@@ -0,0 +1,83 @@
+/// Tests of the quaternion rotations.
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+use approx::assert_relative_eq;
+
+use crate::{Point3, Quaternion, Transform, Vector3, EPSILON};
+
+#[test]
+fn normalize_quaternion() {
+    let q = Quaternion::new(1.0, 2.0, 2.0, 4.0);
+    assert_eq!(q.magnitude(), 5.0);
+    assert_relative_eq!(q.normalized(), Quaternion::new(0.2, 0.4, 0.4, 0.8));
+    assert_relative_eq!(q.normalized().magnitude(), 1.0);
+}
+#[test]
+fn multiply_quaternions() {
+    let i = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+    let j = Quaternion::new(0.0, 1.0, 0.0, 0.0);
+    let k = Quaternion::new(0.0, 0.0, 1.0, 0.0);
+    assert_eq!(i * j, k);
+    assert_eq!(j * i, -k);
+    assert_eq!(i * i, Quaternion::new(0.0, 0.0, 0.0, -1.0));
+    assert_eq!(i * Quaternion::identity(), i);
+}
+#[test]
+fn quaternion_to_rotation_matrix() {
+    let p = Point3::new(0.0, 1.0, 0.0);
+    let qx = Quaternion::from_axis_angle(Vector3::unit_x(), FRAC_PI_4);
+    assert_relative_eq!(
+        qx.to_matrix(),
+        Transform::rotation_x(FRAC_PI_4),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        qx.to_matrix() * p,
+        Point3::new(0.0, SQRT_2 / 2.0, SQRT_2 / 2.0),
+        epsilon = EPSILON
+    );
+    let qy = Quaternion::from_axis_angle(Vector3::unit_y(), FRAC_PI_2);
+    assert_relative_eq!(
+        Transform::from(qy),
+        Transform::rotation_y(FRAC_PI_2),
+        epsilon = EPSILON
+    );
+    // The product composes the rotations like the matrices do.
+    assert_relative_eq!(
+        (qy * qx).to_matrix(),
+        Transform::rotation_y(FRAC_PI_2) * Transform::rotation_x(FRAC_PI_4),
+        epsilon = EPSILON
+    );
+}
+#[test]
+fn rotation_matrix_to_quaternion() {
+    for m in [
+        Transform::identity(),
+        Transform::rotation_x(2.5),
+        Transform::rotation_y(-1.0),
+        Transform::rotation_z(3.0),
+        Transform::rotation_x(0.3) * Transform::rotation_z(2.9),
+    ] {
+        let q = Quaternion::from_matrix(&m);
+        assert_relative_eq!(q.magnitude(), 1.0, epsilon = EPSILON);
+        assert_relative_eq!(q.to_matrix(), m, epsilon = EPSILON);
+    }
+}
+#[test]
+fn slerp_quaternions() {
+    let a = Quaternion::identity();
+    let b = Quaternion::from_axis_angle(Vector3::unit_z(), FRAC_PI_2);
+    assert_relative_eq!(a.slerp(&b, 0.0), a, epsilon = EPSILON);
+    assert_relative_eq!(a.slerp(&b, 1.0), b, epsilon = EPSILON);
+    assert_relative_eq!(
+        a.slerp(&b, 0.5),
+        Quaternion::from_axis_angle(Vector3::unit_z(), FRAC_PI_4),
+        epsilon = EPSILON
+    );
+    // Interpolating to `-b` takes the same (shortest) path.
+    assert_relative_eq!(
+        a.slerp(&-b, 0.5).to_matrix(),
+        Transform::rotation_z(FRAC_PI_4),
+        epsilon = EPSILON
+    );
+}
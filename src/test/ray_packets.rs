@@ -0,0 +1,149 @@
+/// Tests of the packets of rays traced together.
+use approx::assert_relative_eq;
+
+use crate::{
+    AcceleratorKind, BoundingBox, Group, Matrix4, Point3, Ray, RayPacket, Shape, Sphere, Transform,
+    Vector3, World, EPSILON, PACKET_SIZE,
+};
+
+/// Returns `n` parallel rays along `z` spread along `x`, from -3 to 3.
+fn rays(n: usize) -> Vec<Ray<f64>> {
+    (0..n)
+        .map(|i| {
+            let x = -3.0 + 6.0 * i as f64 / (n - 1) as f64;
+            Ray::new(Point3::new(x, 0.5, -5.0), Vector3::new(0.0, 0.0, 1.0))
+        })
+        .collect()
+}
+
+/// Asserts that the intersections of the packet of `rays` are the ones of
+/// each ray traced alone.
+fn assert_same_hits(shape: &dyn Shape<f64>, rays: &[Ray<f64>]) {
+    let xs = shape.intersect_packet(&RayPacket::new(rays));
+    assert_eq!(xs.len(), rays.len());
+    for (ray, hits) in rays.iter().zip(xs) {
+        let expected = shape.intersect(ray);
+        assert_eq!(hits.len(), expected.len());
+        for (hit, expected) in hits.iter().zip(&expected) {
+            assert_relative_eq!(hit.t(), expected.t(), epsilon = EPSILON);
+            assert!(std::ptr::addr_eq(hit.object(), expected.object()));
+        }
+    }
+}
+
+#[test]
+fn a_packet_holds_its_rays() {
+    let rays = rays(4);
+    let packet = RayPacket::new(&rays);
+    assert_eq!(packet.len(), 4);
+    assert_eq!(packet.rays().collect::<Vec<_>>(), rays);
+    assert_eq!(packet.origins(0), &[-3.0, -1.0, 1.0, 3.0]);
+    assert_eq!(packet.directions(2), &[1.0; 4]);
+    assert_eq!(packet.common_time(), Some(0.0));
+}
+#[test]
+#[should_panic]
+fn a_packet_holds_at_most_packet_size_rays() {
+    RayPacket::new(&rays(PACKET_SIZE + 1));
+}
+#[test]
+fn transforming_a_packet_transforms_each_ray() {
+    let rays = rays(8);
+    let m = Transform::translation(1.0, 2.0, 3.0)
+        * Transform::rotation_y(0.5)
+        * Transform::scaling(2.0, 1.0, 0.5);
+    let packet = RayPacket::new(&rays).transform(&m);
+    for (ray, transformed) in rays.iter().zip(packet.rays()) {
+        let expected = ray.transform(&m);
+        assert_relative_eq!(transformed.origin(), expected.origin(), epsilon = EPSILON);
+        assert_relative_eq!(
+            transformed.direction(),
+            expected.direction(),
+            epsilon = EPSILON
+        );
+    }
+}
+#[test]
+fn a_box_is_tested_against_every_ray_of_a_packet() {
+    let b = BoundingBox::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let rays = rays(7);
+    let hits = b.intersects_packet(&RayPacket::new(&rays));
+    for (lane, ray) in rays.iter().enumerate() {
+        assert_eq!(hits[lane], b.intersects(ray));
+    }
+    assert_eq!(&hits[..7], &[false, false, true, true, true, false, false]);
+    assert!(!hits[7]);
+}
+#[test]
+fn a_packet_intersects_a_shape_like_its_rays() {
+    let s = Sphere::new(Transform::translation(1.0, 0.0, 0.0) * Transform::scaling(2.0, 2.0, 2.0));
+    assert_same_hits(&s, &rays(8));
+    assert_same_hits(&s, &rays(4));
+}
+#[test]
+fn a_packet_intersects_a_subdivided_group_like_its_rays() {
+    let mut g = Group::new(Transform::rotation_z(0.3));
+    for i in 0..6 {
+        g.add_child(Sphere::new(Transform::translation(
+            i as f64 - 2.5,
+            0.0,
+            i as f64,
+        )));
+    }
+    g.divide(1);
+    assert_same_hits(&g, &rays(8));
+}
+#[test]
+fn rays_cast_at_different_times_see_a_moving_shape_where_it_is() {
+    let mut s = Sphere::default();
+    s.set_motion(Some(Matrix4::translation(2.0, 0.0, 0.0)));
+    let rays: Vec<Ray<f64>> = [0.0, 0.5, 1.0]
+        .iter()
+        .map(|&time| {
+            Ray::new(Point3::new(2.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0)).with_time(time)
+        })
+        .collect();
+    let packet = RayPacket::new(&rays);
+    assert_eq!(packet.common_time(), None);
+    let xs = s.intersect_packet(&packet);
+    assert!(xs[0].is_empty());
+    assert_eq!(xs[1].len(), 2);
+    assert_eq!(xs[2].len(), 2);
+    assert_same_hits(&s, &rays);
+}
+#[test]
+fn a_packet_intersects_the_world_like_its_rays() {
+    let w = World::<f64>::default();
+    let rays = rays(8);
+    let xs = w.intersect_packet(&RayPacket::new(&rays));
+    for (ray, hits) in rays.iter().zip(&xs) {
+        let expected = w.intersect(ray);
+        assert_eq!(hits.len(), expected.len());
+        for (hit, expected) in hits.iter().zip(expected.iter()) {
+            assert_relative_eq!(hit.t(), expected.t(), epsilon = EPSILON);
+        }
+    }
+}
+#[test]
+fn a_packet_walks_the_accelerator_of_the_world() {
+    for kind in [AcceleratorKind::Bvh, AcceleratorKind::KdTree] {
+        let mut w = World::<f64>::default();
+        for i in 0..6 {
+            let x = -2.5 + i as f64;
+            w.add_object(Sphere::new(
+                Transform::translation(x, 0.5, 3.0) * Transform::scaling(0.4, 0.4, 0.4),
+            ));
+        }
+        w.build_accelerator(kind, 1);
+        let rays = rays(8);
+        let xs = w.intersect_packet(&RayPacket::new(&rays));
+        for (ray, hits) in rays.iter().zip(&xs) {
+            let expected = w.intersect(ray);
+            assert_eq!(hits.len(), expected.len());
+            for (hit, expected) in hits.iter().zip(expected.iter()) {
+                assert_relative_eq!(hit.t(), expected.t(), epsilon = EPSILON);
+                assert!(std::ptr::addr_eq(hit.object(), expected.object()));
+            }
+        }
+    }
+}
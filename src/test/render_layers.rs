@@ -0,0 +1,77 @@
+/// Tests of the renders in several layers.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::colors::Color, Camera, Group, Point3, RawCanvas, RenderLayers, Sphere, Transform,
+    Vector3, Whitted, World, EPSILON,
+};
+
+fn camera() -> Camera<f64> {
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    Camera::new(11, 11, PI / 2.0).with_transform(view)
+}
+
+#[test]
+fn the_beauty_is_the_ordinary_render() {
+    let w = World::default();
+    let layers: RenderLayers<11, 11, f64> = camera().render_layers(&w, &Whitted::default());
+    let image: RawCanvas<11, 11, f64> = camera().render(&w);
+    assert_eq!(layers.beauty, image);
+}
+#[test]
+fn the_layers_describe_the_surface_seen() {
+    let w = World::default();
+    let layers: RenderLayers<11, 11, f64> = camera().render_layers(&w, &Whitted::default());
+    let at = |canvas: &RawCanvas<11, 11, f64>, x, y| *canvas.pixel_at(x, y).unwrap();
+    // The center sees the front of the outer sphere, at a distance of 4.
+    assert_relative_eq!(
+        at(&layers.albedo, 5, 5),
+        Color::new(0.8, 1.0, 0.6),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        at(&layers.normal, 5, 5),
+        Color::new(0.0, 0.0, -1.0),
+        epsilon = EPSILON
+    );
+    assert_relative_eq!(
+        at(&layers.depth, 5, 5),
+        Color::new(4.0, 4.0, 4.0),
+        epsilon = EPSILON
+    );
+    assert_eq!(at(&layers.object_id, 5, 5), Color::new(1.0, 1.0, 1.0));
+    // The corner sees nothing.
+    for canvas in [
+        &layers.albedo,
+        &layers.normal,
+        &layers.depth,
+        &layers.object_id,
+    ] {
+        assert_eq!(at(canvas, 0, 0), Color::new(0.0, 0.0, 0.0));
+    }
+}
+#[test]
+fn the_object_id_is_the_one_of_the_top_level_object() {
+    let mut w = World::new();
+    w.add_light(World::<f64>::default().lights()[0]);
+    w.add_object(Sphere::new(Transform::translation(0.0, 0.0, 10.0)));
+    let mut g = Group::new(Transform::translation(0.0, 0.0, 1.0));
+    g.add_child(Sphere::default());
+    w.add_object(g);
+    let layers: RenderLayers<11, 11, f64> = camera().render_layers(&w, &Whitted::default());
+    assert_eq!(
+        *layers.object_id.pixel_at(5, 5).unwrap(),
+        Color::new(2.0, 2.0, 2.0)
+    );
+    assert_relative_eq!(
+        *layers.depth.pixel_at(5, 5).unwrap(),
+        Color::new(5.0, 5.0, 5.0),
+        epsilon = EPSILON
+    );
+}
@@ -0,0 +1,196 @@
+/// Tests of the settings of a render.
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    features::{colors::Color, world::MAX_DEPTH},
+    Camera, Overlay, Point3, PointLight, RawCanvas, RenderLayers, RenderProgress, RenderSettings,
+    Sphere, Transform, UvImage, Vector3, Volume, Whitted, World, EPSILON,
+};
+
+fn camera() -> Camera<f64> {
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    Camera::new(11, 11, PI / 2.0).with_transform(view)
+}
+
+/// Returns the settings averaging 4 jittered rays per pixel on `threads`.
+fn sampled(threads: usize) -> RenderSettings {
+    RenderSettings {
+        samples_per_pixel: 4,
+        threads,
+        ..RenderSettings::default()
+    }
+}
+
+#[test]
+fn the_default_settings_are_the_ones_of_the_book() {
+    let settings = RenderSettings::default();
+    assert_eq!(settings.max_depth, MAX_DEPTH);
+    assert_eq!(settings.samples_per_pixel, 1);
+    assert!(settings.threads >= 1);
+    assert_eq!(settings.epsilon, EPSILON);
+    assert_eq!(settings.clamp, None);
+    assert_eq!(settings.seed, 0);
+    assert_eq!(RenderSettings::single_threaded().threads, 1);
+    assert_eq!(camera().settings(), &settings);
+}
+#[test]
+fn the_render_follows_the_settings() {
+    let w = World::default();
+    let c = camera().with_settings(sampled(1));
+    let image: RawCanvas<11, 11, f64> = c.render(&w);
+    let expected: RawCanvas<11, 11, f64> = c.render_with(&w, &sampled(1).whitted());
+    assert_eq!(image, expected);
+}
+#[test]
+fn the_image_does_not_depend_on_the_number_of_threads() {
+    let w = World::default();
+    let single: RawCanvas<11, 11, f64> = camera()
+        .with_tile_size(4)
+        .with_settings(sampled(1))
+        .render(&w);
+    let threaded: RawCanvas<11, 11, f64> = camera()
+        .with_tile_size(4)
+        .with_settings(sampled(4))
+        .render(&w);
+    assert_eq!(single, threaded);
+}
+#[test]
+fn several_samples_smooth_the_edges() {
+    let w = World::default();
+    let render = |samples_per_pixel| -> RawCanvas<11, 11, f64> {
+        camera()
+            .with_settings(RenderSettings {
+                samples_per_pixel,
+                ..RenderSettings::default()
+            })
+            .render(&w)
+    };
+    let (one, several) = (render(1), render(16));
+    // A pixel whose center misses the spheres, but not its whole area.
+    let black = Color::default();
+    let edge = (0..11)
+        .flat_map(|y| (0..11).map(move |x| (x, y)))
+        .find(|&(x, y)| {
+            *one.pixel_at(x, y).unwrap() == black && *several.pixel_at(x, y).unwrap() != black
+        });
+    assert!(edge.is_some());
+}
+#[test]
+fn the_seed_changes_the_samples() {
+    let w = World::default();
+    let render = |seed| -> RawCanvas<11, 11, f64> {
+        // The lens is sampled at random.
+        camera()
+            .with_aperture(0.5)
+            .with_focal_distance(4.0)
+            .with_settings(RenderSettings { seed, ..sampled(2) })
+            .render(&w)
+    };
+    assert_eq!(render(7), render(7));
+    assert_ne!(render(7), render(8));
+}
+#[test]
+fn the_media_do_not_depend_on_the_number_of_threads() {
+    let mut w = World::default();
+    w.add_object(Volume::new(
+        Sphere::new(Transform::translation(0.0, 0.0, -2.0)),
+        0.5,
+    ));
+    let render = |threads| -> RawCanvas<11, 11, f64> {
+        let settings = RenderSettings {
+            max_depth: 4,
+            seed: 3,
+            ..sampled(threads)
+        };
+        camera()
+            .with_tile_size(4)
+            .with_settings(settings.clone())
+            .render_with(&w, &settings.path_tracer())
+    };
+    let single = render(1);
+    assert_eq!(single, render(4));
+    assert_eq!(single, render(1));
+    let layers = |threads| -> RenderLayers<11, 11, f64> {
+        camera()
+            .with_tile_size(4)
+            .with_settings(sampled(threads))
+            .render_layers(&w, &sampled(threads).path_tracer())
+    };
+    assert_eq!(layers(1), layers(4));
+}
+#[test]
+fn the_overlay_is_stamped_on_every_render() {
+    let w = World::default();
+    let red = Color::new(1.0, 0.0, 0.0);
+    let overlay = Overlay::new(UvImage::new(2, 1, vec![red; 2]))
+        .at(9, 10)
+        .with_opacity(0.5);
+    let plain = camera();
+    let stamped = camera().with_settings(RenderSettings {
+        overlay: Some(Arc::new(overlay.clone())),
+        ..RenderSettings::default()
+    });
+    let mut expected: RawCanvas<11, 11, f64> = plain.render(&w);
+    overlay.apply(expected.pixels_mut(), 11);
+    assert_eq!(stamped.render::<11, 11>(&w), expected);
+    let layers: RenderLayers<11, 11, f64> = stamped.render_layers(&w, &Whitted::default());
+    assert_eq!(layers.beauty, expected);
+    assert_eq!(
+        stamped.render_pixels(&w, &Whitted::default(), &mut |_: &RenderProgress| {}),
+        expected.pixels()
+    );
+}
+#[test]
+fn the_clamp_limits_the_brightest_component() {
+    let mut w = World::default();
+    w.lights_mut()[0] = PointLight::new(
+        Point3::new(-10.0, 10.0, -10.0),
+        Color::new(10.0, 10.0, 10.0),
+    );
+    let c = camera().with_settings(RenderSettings {
+        clamp: Some(1.0),
+        ..RenderSettings::single_threaded()
+    });
+    let image: RawCanvas<11, 11, f64> = c.render(&w);
+    let brightest = image
+        .pixels()
+        .iter()
+        .map(|p| p.r.max(p.g).max(p.b))
+        .fold(0.0, f64::max);
+    assert!(brightest <= 1.0 + EPSILON);
+    let unclamped: RawCanvas<11, 11, f64> = camera().render(&w);
+    assert!(unclamped.pixels().iter().any(|p| p.r > 1.0));
+}
+#[test]
+fn the_world_takes_the_epsilon() {
+    let mut w = World::<f64>::default();
+    w.apply_settings(&RenderSettings {
+        epsilon: 0.01,
+        ..RenderSettings::default()
+    });
+    assert_eq!(w.shadow_bias(), 0.01);
+}
+#[test]
+fn threaded_tiles_are_reported_in_order() {
+    let w = World::default();
+    let c = camera().with_tile_size(4).with_settings(sampled(4));
+    let mut reports = Vec::new();
+    let _: RawCanvas<11, 11, f64> =
+        c.render_with_progress(&w, &sampled(4).whitted(), &mut |p: &RenderProgress| {
+            reports.push(*p)
+        });
+    let done: Vec<_> = reports.iter().map(|p| p.pixels_done).collect();
+    let expected: Vec<_> = c
+        .tiles()
+        .iter()
+        .scan(0, |done, tile| {
+            *done += tile.area();
+            Some(*done)
+        })
+        .collect();
+    assert_eq!(done, expected);
+}
@@ -0,0 +1,146 @@
+/// Tests of the RON scene files.
+use crate::{
+    features::{
+        colors::Color,
+        scene::{json, ron},
+    },
+    Point3, Scene, SceneError, SceneValue, Transform,
+};
+
+use super::json_scene::SCENE;
+
+fn number(v: f64) -> SceneValue {
+    SceneValue::Number(v)
+}
+
+fn string(s: &str) -> SceneValue {
+    SceneValue::String(s.to_string())
+}
+
+#[test]
+fn parsing_ron_values() {
+    let value = ron::parse(
+        r##"#![enable(implicit_some)]
+        // A comment
+        SolidCube(
+            size: Some(2.5), /* an inline comment */
+            tint: None,
+            corners: [(1, -2), (+3, 4_000,)],
+            operation: Difference,
+            step: RotateX(1e-1),
+            label: r#"raw "text""#,
+            escaped: "a\tb\u{e9}",
+            letter: 'é',
+            map: { "key with spaces": true },
+            empty: (),
+        )"##,
+    )
+    .unwrap();
+    let entries = [
+        ("type", string("solid-cube")),
+        ("size", number(2.5)),
+        ("tint", SceneValue::Null),
+        (
+            "corners",
+            SceneValue::Array(vec![
+                SceneValue::Array(vec![number(1.0), number(-2.0)]),
+                SceneValue::Array(vec![number(3.0), number(4000.0)]),
+            ]),
+        ),
+        ("operation", string("difference")),
+        (
+            "step",
+            SceneValue::Array(vec![string("rotate-x"), number(0.1)]),
+        ),
+        ("label", string("raw \"text\"")),
+        ("escaped", string("a\tbé")),
+        ("letter", string("é")),
+        (
+            "map",
+            SceneValue::Object(vec![(
+                "key with spaces".to_string(),
+                SceneValue::Bool(true),
+            )]),
+        ),
+        ("empty", SceneValue::Null),
+    ];
+    assert_eq!(
+        value,
+        SceneValue::Object(
+            entries
+                .into_iter()
+                .map(|(key, v)| (key.to_string(), v))
+                .collect()
+        )
+    );
+}
+#[test]
+fn syntax_errors_give_their_line() {
+    for (src, line) in [
+        ("[\n1,\n2 3]", 3),
+        ("Sphere(a: )", 1),
+        ("(a: 1", 1),
+        ("/* open\n", 2),
+        ("'ab'", 1),
+    ] {
+        assert!(
+            matches!(ron::parse(src), Err(SceneError::Syntax { line: l }) if l == line),
+            "{src}"
+        );
+    }
+}
+#[test]
+fn loading_a_ron_scene() {
+    let s = Scene::<f64>::from_ron(
+        r#"[
+            Camera(width: 20, height: 10, field_of_view: 1.0,
+                   from: (0, 1.5, -5), to: (0, 1, 0), up: (0, 1, 0)),
+            Light(at: (-10, 10, -10), intensity: (1, 1, 1)),
+            Define(name: "glass", value: (transparency: 0.9, refractive_index: 1.5)),
+            Define(name: "half", value: [Scale(0.5, 0.5, 0.5)]),
+            Sphere(material: "glass", transform: ["half", Translate(0, 1, 0)]),
+            Cylinder(min: 0, max: 1, closed: true, material: (
+                pattern: Stripes(colors: [(1, 0, 0), (0, 0, 1)], transform: [RotateY(1.5)]),
+            )),
+            Csg(operation: Difference, left: Sphere(), right: Cylinder()),
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!((s.camera.hsize(), s.camera.vsize()), (20, 10));
+    assert_eq!(s.world.lights().len(), 1);
+    let objects = s.world.objects();
+    assert_eq!(objects.len(), 3);
+    assert_eq!(objects[0].material().refractive_index, 1.5);
+    assert_eq!(
+        objects[0].transform(),
+        Transform::translation(0.0, 1.0, 0.0) * Transform::scaling(0.5, 0.5, 0.5)
+    );
+    let pattern = objects[1].material().pattern.clone().unwrap();
+    assert_eq!(pattern.transform(), Transform::rotation_y(1.5));
+    assert_eq!(
+        pattern.pattern_at_shape(objects[1].as_ref(), Point3::new(0.0, 0.5, 0.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn scenes_are_saved_to_ron() {
+    let value = json::parse(SCENE).unwrap();
+    let text = ron::to_string(&value);
+    assert!(text.contains("Camera("));
+    assert!(text.contains("Define(\n        name: \"blue-material\","));
+    assert!(text.contains("RotateX(1.5707963267948966)"));
+    // Writing what was read gives the same text.
+    assert_eq!(ron::to_string(&ron::parse(&text).unwrap()), text);
+    let from_json = Scene::<f64>::from_json(SCENE).unwrap();
+    let from_ron = Scene::<f64>::from_ron(&text).unwrap();
+    assert_eq!(from_ron.camera.transform(), from_json.camera.transform());
+    assert_eq!(from_ron.world.lights(), from_json.world.lights());
+    let objects = |s: &Scene<f64>| -> Vec<_> {
+        s.world
+            .objects()
+            .iter()
+            .map(|o| (o.transform(), o.material().clone(), o.casts_shadow()))
+            .collect()
+    };
+    assert_eq!(objects(&from_ron), objects(&from_json));
+}
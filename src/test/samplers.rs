@@ -0,0 +1,183 @@
+/// Tests of the samplers and of the renders drawing from them.
+use std::{f64::consts::PI, sync::Arc};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::{colors::Color, samplers::radical_inverse},
+    Camera, HaltonSampler, Point3, Projection, RandomSampler, RawCanvas, Sampler, SobolSampler,
+    StratifiedSampler, StripePattern, Transform, Vector3, Whitted, World, EPSILON,
+};
+
+/// Returns the first two dimensions of every sample of the pixel `(x, y)`.
+fn pixel_samples(sampler: &mut dyn Sampler, x: usize, y: usize) -> Vec<(f64, f64)> {
+    (0..sampler.samples_per_pixel())
+        .map(|i| {
+            sampler.start_pixel_sample((x, y), i);
+            sampler.next_2d()
+        })
+        .collect()
+}
+
+#[test]
+fn samples_depend_only_on_the_pixel_and_the_index() {
+    let samplers: [Box<dyn Sampler>; 4] = [
+        Box::new(RandomSampler::new(8)),
+        Box::new(StratifiedSampler::jittered(2, 4)),
+        Box::new(HaltonSampler::new(8)),
+        Box::new(SobolSampler::new(8)),
+    ];
+    for mut sampler in samplers {
+        let first = pixel_samples(sampler.as_mut(), 3, 5);
+        pixel_samples(sampler.as_mut(), 7, 1);
+        assert_eq!(pixel_samples(sampler.as_mut(), 3, 5), first);
+        assert_ne!(pixel_samples(sampler.as_mut(), 7, 1), first);
+        for (u, v) in first {
+            assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+        }
+    }
+}
+#[test]
+fn seed_changes_the_samples() {
+    let mut a = RandomSampler::new(4);
+    let mut b = RandomSampler::new(4).with_seed(1);
+    assert_ne!(pixel_samples(&mut a, 0, 0), pixel_samples(&mut b, 0, 0));
+}
+#[test]
+fn stratified_samples_cover_every_cell_once() {
+    for mut sampler in [
+        StratifiedSampler::new(4, 2),
+        StratifiedSampler::jittered(4, 2),
+    ] {
+        assert_eq!(sampler.samples_per_pixel(), 8);
+        let mut cells: Vec<(usize, usize)> = pixel_samples(&mut sampler, 2, 3)
+            .into_iter()
+            .map(|(u, v)| ((u * 4.0) as usize, (v * 2.0) as usize))
+            .collect();
+        cells.sort();
+        let all: Vec<(usize, usize)> = (0..4).flat_map(|x| (0..2).map(move |y| (x, y))).collect();
+        assert_eq!(cells, all);
+    }
+}
+#[test]
+fn stratified_samples_are_centered_unless_jittered() {
+    let mut sampler = StratifiedSampler::new(2, 2);
+    for (u, v) in pixel_samples(&mut sampler, 0, 0) {
+        assert!(u == 0.25 || u == 0.75);
+        assert!(v == 0.25 || v == 0.75);
+    }
+    let mut sampler = StratifiedSampler::jittered(2, 2);
+    assert!(pixel_samples(&mut sampler, 0, 0)
+        .iter()
+        .any(|&(u, _)| u != 0.25 && u != 0.75));
+}
+#[test]
+fn every_dimension_is_stratified() {
+    let mut sampler = StratifiedSampler::jittered(3, 3);
+    let mut strata: Vec<usize> = (0..9)
+        .map(|i| {
+            sampler.start_pixel_sample((1, 1), i);
+            sampler.next_2d();
+            (sampler.next_1d() * 9.0) as usize
+        })
+        .collect();
+    strata.sort();
+    assert_eq!(strata, (0..9).collect::<Vec<_>>());
+}
+#[test]
+fn render_with_a_sampler_spreads_the_rays_over_the_pixels() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    let image: RawCanvas<11, 11, f64> =
+        c.render_with_sampler(&w, &Whitted::default(), &StratifiedSampler::jittered(4, 4));
+    // The sphere covers a corner of this pixel, but not its center.
+    assert!(image.pixel_at(4, 4).unwrap().g > 0.0);
+    assert_eq!(*image.pixel_at(0, 0).unwrap(), Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn render_with_a_sampler_averages_the_samples_with_a_ray() {
+    // The rim of the image circle: only some samples of the pixel see the
+    // white sky, which is not dimmed by the others.
+    let white = Color::new(1.0, 1.0, 1.0);
+    let mut w = World::new();
+    w.set_skybox(Some(Arc::new(StripePattern::new(white, white))));
+    let c = Camera::new(10, 10, PI).with_projection(Projection::Fisheye);
+    let image: RawCanvas<10, 10, f64> =
+        c.render_with_sampler(&w, &Whitted::default(), &StratifiedSampler::jittered(4, 4));
+    assert_relative_eq!(*image.pixel_at(0, 5).unwrap(), white, epsilon = EPSILON);
+    assert_eq!(*image.pixel_at(0, 0).unwrap(), Color::new(0.0, 0.0, 0.0));
+}
+#[test]
+fn radical_inverse_mirrors_the_digits() {
+    assert_eq!(radical_inverse(2, 0), 0.0);
+    assert_eq!(radical_inverse(2, 1), 0.5);
+    assert_eq!(radical_inverse(2, 6), 0.375);
+    assert_relative_eq!(radical_inverse(3, 5), 7.0 / 9.0);
+}
+#[test]
+fn halton_samples_fill_each_dimension_evenly() {
+    let mut sampler = HaltonSampler::new(9);
+    let samples = pixel_samples(&mut sampler, 4, 2);
+    // The bases 2 and 3: 8 samples span the 8 eighths of the first
+    // dimension and 9 samples the 9 ninths of the second.
+    let mut eighths: Vec<usize> = samples[..8]
+        .iter()
+        .map(|&(u, _)| (u * 8.0) as usize)
+        .collect();
+    eighths.sort();
+    assert_eq!(eighths, (0..8).collect::<Vec<_>>());
+    let mut ninths: Vec<usize> = samples.iter().map(|&(_, v)| (v * 9.0) as usize).collect();
+    ninths.sort();
+    assert_eq!(ninths, (0..9).collect::<Vec<_>>());
+}
+#[test]
+fn sobol_samples_hit_every_cell_of_the_elementary_grids() {
+    let mut sampler = SobolSampler::new(12);
+    assert_eq!(sampler.samples_per_pixel(), 16);
+    for (x, y) in [(0, 0), (5, 3)] {
+        for dimension in 0..3 {
+            let samples: Vec<(f64, f64)> = (0..16)
+                .map(|i| {
+                    sampler.start_pixel_sample((x, y), i);
+                    for _ in 0..dimension {
+                        sampler.next_2d();
+                    }
+                    sampler.next_2d()
+                })
+                .collect();
+            for (nx, ny) in [(16.0, 1.0), (8.0, 2.0), (4.0, 4.0), (2.0, 8.0), (1.0, 16.0)] {
+                let mut cells: Vec<(usize, usize)> = samples
+                    .iter()
+                    .map(|&(u, v)| ((u * nx) as usize, (v * ny) as usize))
+                    .collect();
+                cells.sort();
+                cells.dedup();
+                assert_eq!(cells.len(), 16);
+            }
+        }
+    }
+}
+#[test]
+fn low_discrepancy_samplers_converge_faster_than_random_ones() {
+    // The error of the integral of `u * v` over the unit square, `1 / 4`,
+    // estimated from the samples of many pixels.
+    let error = |sampler: &mut dyn Sampler| {
+        let mut total = 0.0;
+        for pixel in 0..32 {
+            let sum: f64 = pixel_samples(sampler, pixel, 0)
+                .iter()
+                .map(|&(u, v)| u * v)
+                .sum();
+            total += (sum / sampler.samples_per_pixel() as f64 - 0.25).abs();
+        }
+        total
+    };
+    let random = error(&mut RandomSampler::new(64));
+    assert!(error(&mut HaltonSampler::new(64)) < random / 3.0);
+    assert!(error(&mut SobolSampler::new(64)) < random / 3.0);
+}
@@ -0,0 +1,76 @@
+/// Tests of the random sampling helpers.
+use approx::assert_relative_eq;
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    features::math::sampling::{
+        random_cosine_direction, random_in_hemisphere, random_in_unit_disk, random_unit_vector,
+    },
+    Vector3, EPSILON,
+};
+
+const N: usize = 20000;
+
+#[test]
+fn random_unit_vectors_cover_the_sphere() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let mut sum = Vector3::zero();
+    for _ in 0..N {
+        let v: Vector3<f64> = random_unit_vector(&mut rng);
+        assert_relative_eq!(v.magnitude(), 1.0, epsilon = EPSILON);
+        sum += v;
+    }
+    assert_relative_eq!(sum / N as f64, Vector3::zero(), epsilon = 0.02);
+}
+#[test]
+fn hemisphere_samples_face_the_normal() {
+    let mut rng = SmallRng::seed_from_u64(2);
+    let normal = Vector3::new(0.0, 1.0, 0.0);
+    let mut mean_cos = 0.0;
+    for _ in 0..N {
+        let v = random_in_hemisphere(normal, &mut rng);
+        assert!(v.dot(&normal) >= 0.0);
+        mean_cos += v.dot(&normal) / N as f64;
+    }
+    // The mean cosine over the uniform hemisphere is 1/2.
+    assert_relative_eq!(mean_cos, 0.5, epsilon = 0.01);
+}
+#[test]
+fn cosine_samples_lean_to_the_normal() {
+    let mut rng = SmallRng::seed_from_u64(3);
+    let normal = Vector3::new(0.0, 0.0, -1.0);
+    let mut mean_cos = 0.0;
+    for _ in 0..N {
+        let v = random_cosine_direction(normal, &mut rng);
+        assert_relative_eq!(v.magnitude(), 1.0, epsilon = EPSILON);
+        assert!(v.dot(&normal) >= 0.0);
+        mean_cos += v.dot(&normal) / N as f64;
+    }
+    // The mean cosine under a cosine density is 2/3.
+    assert_relative_eq!(mean_cos, 2.0 / 3.0, epsilon = 0.01);
+}
+#[test]
+fn disk_samples_cover_the_disk_uniformly() {
+    let mut rng = SmallRng::seed_from_u64(4);
+    let mut inner = 0;
+    for _ in 0..N {
+        let (x, y): (f64, f64) = random_in_unit_disk(&mut rng);
+        let r2 = x * x + y * y;
+        assert!(r2 <= 1.0);
+        if r2 < 0.25 {
+            inner += 1;
+        }
+    }
+    // The inner disk of radius 1/2 holds a quarter of the area.
+    assert_relative_eq!(inner as f64 / N as f64, 0.25, epsilon = 0.02);
+}
+#[test]
+fn same_seed_gives_same_directions() {
+    let mut a = SmallRng::seed_from_u64(7);
+    let mut b = SmallRng::seed_from_u64(7);
+    for _ in 0..10 {
+        let va: Vector3<f64> = random_unit_vector(&mut a);
+        let vb: Vector3<f64> = random_unit_vector(&mut b);
+        assert_eq!(va, vb);
+    }
+}
@@ -0,0 +1,91 @@
+/// Tests of the builder functions of scenes.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::{colors::Color, scene::builder::*},
+    Camera, Material, Point3, Scene, Sphere, Transform, Vector3,
+};
+
+#[test]
+fn a_new_scene_is_empty_with_the_camera_of_the_book() {
+    let scene = Scene::<f64>::new();
+    assert!(scene.world.objects().is_empty());
+    assert!(scene.world.lights().is_empty());
+    assert_eq!((scene.camera.hsize(), scene.camera.vsize()), (400, 200));
+    assert_relative_eq!(scene.camera.field_of_view(), PI / 3.0);
+    assert_eq!(
+        scene.camera.transform(),
+        Transform::view_transform(
+            Point3::new(0.0, 1.5, -5.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0)
+        )
+    );
+}
+#[test]
+fn the_steps_of_a_shape_apply_in_order() {
+    let scene = Scene::new()
+        .light(point_light(
+            Point3::new(-10.0, 10.0, -10.0),
+            Color::new(1.0, 1.0, 1.0),
+        ))
+        .add(sphere().radius(2.0).at(0.0, 1.0, 0.0).material(glass()))
+        .add(
+            plane()
+                .rotate_x(PI / 2.0)
+                .at(0.0, 0.0, 10.0)
+                .color(Color::new(1.0, 0.0, 0.0))
+                .no_shadow(),
+        )
+        .add(Sphere::default());
+    assert_eq!(scene.world.lights().len(), 1);
+    let [ball, wall, plain] = scene.world.objects() else {
+        panic!("expected three objects");
+    };
+    assert_eq!(
+        ball.transform(),
+        Transform::translation(0.0, 1.0, 0.0) * Transform::scaling(2.0, 2.0, 2.0)
+    );
+    assert_eq!(ball.material(), &Material::glass());
+    assert_eq!(
+        wall.transform(),
+        Transform::translation(0.0, 0.0, 10.0) * Transform::rotation_x(PI / 2.0)
+    );
+    assert_eq!(wall.material().color, Color::new(1.0, 0.0, 0.0));
+    assert!(!wall.casts_shadow());
+    assert_eq!(plain.transform(), Transform::identity());
+}
+#[test]
+fn groups_and_csg_place_their_children() {
+    let scene = Scene::new()
+        .add(group([sphere(), sphere().at(3.0, 0.0, 0.0)]).at(0.0, 2.0, 0.0))
+        .add(difference(cylinder(0.0, 1.0, true), sphere().radius(0.5)).at(5.0, 0.0, 0.0));
+    let [group, csg] = scene.world.objects() else {
+        panic!("expected two objects");
+    };
+    assert_eq!(
+        group.parent_space_bounds().max(),
+        Point3::new(4.0, 3.0, 1.0)
+    );
+    assert_eq!(
+        csg.parent_space_bounds().min(),
+        Point3::new(4.0, -0.5, -1.0)
+    );
+}
+#[test]
+fn the_camera_of_a_scene_can_be_replaced() {
+    let scene = Scene::<f64>::new()
+        .camera(Camera::new(10, 20, 1.0))
+        .look(Point3::new(0.0, 0.0, -5.0), Point3::new(0.0, 0.0, 0.0));
+    assert_eq!((scene.camera.hsize(), scene.camera.vsize()), (10, 20));
+    assert_eq!(
+        scene.camera.transform(),
+        Transform::view_transform(
+            Point3::new(0.0, 0.0, -5.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0)
+        )
+    );
+}
@@ -0,0 +1,118 @@
+/// Tests of the writing of scenes to scene files.
+use crate::{
+    features::scene::{json, ron},
+    Camera, Metaballs, Scene, SceneError, SceneValue, Sphere, Transform, World,
+};
+
+use super::json_scene::SCENE;
+
+/// The scene of the book with one shape of each kind the files describe.
+fn scene() -> Scene<f64> {
+    let shapes = r#"{ "add": "cylinder", "min": 0, "max": 2, "closed": true,
+          "material": { "pattern": { "type": "checkers", "colors": [[1, 1, 1], [0, 0, 0]],
+            "transform": [["scale", 0.25, 0.25, 0.25]] } } },
+        { "add": "cylinder" },
+        { "add": "disk", "radius": 2, "inner-radius": 0.5, "material": { "emissive": [4, 4, 4] } },
+        { "add": "torus", "major-radius": 2, "minor-radius": 0.3,
+          "transform": [["rotate-y", 0.3], ["shear", 1, 0, 0, 0, 0, 0]] },
+        { "add": "smooth-triangle", "p1": [0, 1, 0], "p2": [-1, 0, 0], "p3": [1, 0, 0],
+          "n1": [0, 1, 0], "n2": [-1, 0, 0], "n3": [1, 0, 0] },
+        { "add": "group", "transform": [["translate", 0, 2, 0]], "children": [
+            { "add": "triangle", "p1": [0, 0, 0], "p2": [1, 0, 0], "p3": [0, 1, 0] },
+            { "add": "csg", "operation": "difference",
+              "left": { "add": "sphere", "material": { "transparency": 0.9 } },
+              "right": { "add": "sphere", "transform": [["scale", 0.5, 0.5, 0.5]] } }
+        ] },
+        { "add": "sphere","#;
+    Scene::from_json(&SCENE.replace(r#"{ "add": "sphere","#, shapes)).unwrap()
+}
+
+#[test]
+fn a_scene_is_written_as_the_commands_building_it() {
+    let value = scene().to_value().unwrap();
+    let commands = value.as_array().unwrap();
+    assert_eq!(commands.len(), 10);
+    assert_eq!(commands[0].get("add").unwrap().as_str(), Some("camera"));
+    assert_eq!(commands[1].get("add").unwrap().as_str(), Some("light"));
+    // A default cylinder is written without its infinite bounds.
+    assert_eq!(
+        commands[4],
+        SceneValue::object([("add", "cylinder".into())])
+    );
+    // A scaling followed by a translation is kept as such.
+    let sphere = &commands[9];
+    assert_eq!(
+        sphere.get("transform"),
+        Some(&SceneValue::Array(vec![
+            SceneValue::Array(vec![
+                "scale".into(),
+                SceneValue::Number(1.75),
+                SceneValue::Number(1.75),
+                SceneValue::Number(1.75),
+            ]),
+            SceneValue::Array(vec![
+                "translate".into(),
+                SceneValue::Number(1.75),
+                SceneValue::Number(-1.75),
+                SceneValue::Number(1.75),
+            ]),
+        ]))
+    );
+    assert_eq!(sphere.get("shadow"), Some(&false.into()));
+    // A rotation is kept as its matrix.
+    let plane_transform = commands[2].get("transform").unwrap().as_array().unwrap();
+    assert_eq!(plane_transform.len(), 1);
+    assert_eq!(plane_transform[0].as_array().unwrap().len(), 17);
+}
+#[test]
+fn written_scenes_read_back_identically() {
+    let original = scene();
+    let value = original.to_value().unwrap();
+    for read in [
+        Scene::<f64>::from_json(&json::to_string(&value)).unwrap(),
+        Scene::<f64>::from_ron(&ron::to_string(&value)).unwrap(),
+    ] {
+        assert_eq!(read.to_value().unwrap(), value);
+        assert_eq!(read.camera.transform(), original.camera.transform());
+        for (a, b) in read.world.objects().iter().zip(original.world.objects()) {
+            assert_eq!(a.transform(), b.transform());
+            assert_eq!(a.material().color, b.material().color);
+            assert_eq!(a.material().transparency, b.material().transparency);
+            assert_eq!(a.bounds().max(), b.bounds().max());
+        }
+    }
+}
+#[test]
+fn saving_and_loading_a_scene_file() {
+    let dir = std::env::temp_dir().join("raytracer-rust-scene-export-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let original = scene();
+    let value = original.to_value().unwrap();
+    for name in ["scene.json", "scene.ron"] {
+        original.save(dir.join(name)).unwrap();
+        let read = Scene::<f64>::load(dir.join(name)).unwrap();
+        assert_eq!(read.to_value().unwrap(), value);
+    }
+    assert!(std::fs::read_to_string(dir.join("scene.json"))
+        .unwrap()
+        .starts_with("[\n    {"));
+    assert!(matches!(
+        original.save(dir.join("scene.yaml")),
+        Err(SceneError::Unsupported(_))
+    ));
+    assert!(matches!(
+        Scene::<f64>::load(dir.join("scene.yaml")),
+        Err(SceneError::Unsupported(_))
+    ));
+}
+#[test]
+fn shapes_without_a_description_cannot_be_written() {
+    let mut world = World::new();
+    world.add_object(Sphere::new(Transform::translation(0.0, 1.0, 0.0)));
+    world.add_object(Metaballs::new(Vec::new(), 1.0));
+    let scene = Scene {
+        world,
+        camera: Camera::new(10, 10, 1.0),
+    };
+    assert!(matches!(scene.to_value(), Err(SceneError::Unsupported(_))));
+}
@@ -0,0 +1,130 @@
+/// Tests of the scripts building scenes.
+use std::f64::consts::PI;
+
+use approx::assert_relative_eq;
+
+use crate::{features::scene::builder::*, Material, Point3, Scene, SceneError, Transform};
+
+const SPHERES: &str = "
+// Random spheres on a floor.
+seed(7);
+light(point_light(point(-10, 10, -10), color(1, 1, 1)));
+add(plane().material(matte(color(0.5, 0.5, 0.5))));
+for i in 0..50 {
+    let r = rand(0.1, 0.3);
+    add(sphere().radius(r).at(rand(-10, 10), r, rand(0, 20)).color(color(rand(), rand(), rand())));
+}
+";
+
+#[test]
+fn a_script_places_random_spheres() {
+    let scene = Scene::<f64>::from_script(SPHERES).unwrap();
+    assert_eq!(scene.world.lights().len(), 1);
+    assert_eq!(scene.world.objects().len(), 51);
+    for sphere in &scene.world.objects()[1..] {
+        // Resting on the floor.
+        let bounds = sphere.parent_space_bounds();
+        assert_relative_eq!(bounds.min().y, 0.0, epsilon = 1e-9);
+        assert!((0.2..=0.6).contains(&bounds.max().y));
+    }
+    // The same seed draws the same spheres, another one others.
+    let again = Scene::<f64>::from_script(SPHERES).unwrap();
+    let other = Scene::<f64>::from_script(&SPHERES.replace("seed(7)", "seed(8)")).unwrap();
+    assert_eq!(
+        scene.world.objects()[5].transform(),
+        again.world.objects()[5].transform()
+    );
+    assert_ne!(
+        scene.world.objects()[5].transform(),
+        other.world.objects()[5].transform()
+    );
+}
+#[test]
+fn scripts_have_variables_loops_and_conditions() {
+    let scene = Scene::<f64>::from_script(
+        "
+        let count = 0;
+        for i in 0..10 {
+            if i % 2 == 0 && i > 2 {
+                add(sphere().at(i, 0, 0));
+                count = count + 1;
+            } else if i == 1 {
+                add(plane());
+            }
+        }
+        if count != 3 { add(sphere()); }
+        ",
+    )
+    .unwrap();
+    // The spheres at 4, 6 and 8, and the plane.
+    assert_eq!(scene.world.objects().len(), 4);
+    assert_eq!(
+        scene.world.objects()[3].transform(),
+        Transform::translation(8.0, 0.0, 0.0)
+    );
+}
+#[test]
+fn scripts_use_the_scene_builder() {
+    let scene = Scene::<f64>::from_script(
+        "
+        camera(100, 50, PI / 2);
+        look(point(0, 0, -5), point(0, 0, 0));
+        let ball = sphere().radius(2).at(0, 1, 0).material(glass().reflective(0.9));
+        add(group([ball, difference(sphere(), cylinder(-1, 1, true).scale(0.5, 1, 0.5))]));
+        add(torus(1, 0.25).rotate_x(-PI / 2).no_shadow());
+        ",
+    )
+    .unwrap();
+    assert_eq!((scene.camera.hsize(), scene.camera.vsize()), (100, 50));
+    assert_relative_eq!(scene.camera.field_of_view(), PI / 2.0);
+    let expected = Scene::new()
+        .look(Point3::new(0.0, 0.0, -5.0), Point3::new(0.0, 0.0, 0.0))
+        .add(group([sphere().radius(2.0).at(0.0, 1.0, 0.0).material(
+            Material {
+                reflective: 0.9,
+                ..glass()
+            },
+        )]));
+    assert_eq!(scene.camera.transform(), expected.camera.transform());
+    assert_eq!(
+        scene.world.objects()[0].parent_space_bounds(),
+        expected.world.objects()[0].parent_space_bounds()
+    );
+    assert!(!scene.world.objects()[1].casts_shadow());
+}
+#[test]
+fn script_errors_give_their_line() {
+    let error = |src: &str| Scene::<f64>::from_script(src).unwrap_err().to_string();
+    assert!(matches!(
+        Scene::<f64>::from_script("add(sphere();\n"),
+        Err(SceneError::Syntax { line: 1 })
+    ));
+    assert!(matches!(
+        Scene::<f64>::from_script("let x = 1;\nlet = 2;"),
+        Err(SceneError::Syntax { line: 2 })
+    ));
+    assert_eq!(
+        error("for i in 0..2 {\n    add(spher());\n}"),
+        "Invalid scene: line 2: Function not found: spher ()."
+    );
+    assert_eq!(
+        error("let s = sphere();\nadd(s);\nadd(s);"),
+        "Invalid scene: line 3: the shape was already used."
+    );
+    assert_eq!(
+        error("add(sphere().radius(true));"),
+        "Invalid scene: line 1: expected a number, found bool."
+    );
+    assert_eq!(
+        error("let x = y + 1;"),
+        "Invalid scene: line 1: Variable not found: y."
+    );
+}
+#[test]
+fn loading_a_script_file() {
+    let dir = std::env::temp_dir().join("raytracer-rust-scene-script-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("spheres.script"), SPHERES).unwrap();
+    let scene = Scene::<f64>::load(dir.join("spheres.script")).unwrap();
+    assert_eq!(scene.world.objects().len(), 51);
+}
@@ -0,0 +1,79 @@
+/// Tests of the SIMD kernels of the vectors of `f32` and `f64`, against
+/// the products computed component by component.
+use approx::assert_relative_eq;
+
+use crate::{Point3, Vector3};
+
+const VECTORS: [[f64; 3]; 5] = [
+    [1.0, 2.0, 3.0],
+    [-4.5, 0.25, 7.0],
+    [0.0, -1.0, 0.0],
+    [1e3, -2e-3, 12.5],
+    [-0.3, 0.7, -0.1],
+];
+
+#[test]
+fn the_products_of_f64_vectors_match_the_scalar_ones() {
+    for a in VECTORS {
+        for b in VECTORS {
+            let (u, v) = (
+                Vector3::new(a[0], a[1], a[2]),
+                Vector3::new(b[0], b[1], b[2]),
+            );
+            assert_relative_eq!(u.dot(&v), a[0] * b[0] + a[1] * b[1] + a[2] * b[2]);
+            assert_relative_eq!(u.length2(), a[0] * a[0] + a[1] * a[1] + a[2] * a[2]);
+            let cross = Vector3::new(
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            );
+            assert_relative_eq!(u.cross(&v), cross);
+            assert_relative_eq!(u + v, Vector3::new(a[0] + b[0], a[1] + b[1], a[2] + b[2]));
+            assert_relative_eq!(u - v, Vector3::new(a[0] - b[0], a[1] - b[1], a[2] - b[2]));
+            assert_relative_eq!(
+                u * b[1],
+                Vector3::new(a[0] * b[1], a[1] * b[1], a[2] * b[1])
+            );
+        }
+    }
+}
+#[test]
+fn the_products_of_f32_vectors_match_the_scalar_ones() {
+    for a in VECTORS.map(|a| a.map(|c| c as f32)) {
+        for b in VECTORS.map(|b| b.map(|c| c as f32)) {
+            let (u, v) = (
+                Vector3::new(a[0], a[1], a[2]),
+                Vector3::new(b[0], b[1], b[2]),
+            );
+            let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+            assert_relative_eq!(u.dot(&v), dot, epsilon = 1e-3, max_relative = 1e-5);
+            let cross = Vector3::new(
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            );
+            assert_relative_eq!(u.cross(&v), cross);
+            assert_eq!(u.cross(&v).w, 0.0);
+            assert_relative_eq!(u + v, Vector3::new(a[0] + b[0], a[1] + b[1], a[2] + b[2]));
+            assert_relative_eq!(
+                u * b[1],
+                Vector3::new(a[0] * b[1], a[1] * b[1], a[2] * b[1])
+            );
+        }
+    }
+}
+#[test]
+fn points_keep_their_w_component() {
+    let p = Point3::new(1.0f32, 2.0, 3.0);
+    let q = p + Vector3::new(0.5, -1.0, 2.0);
+    assert_eq!(q, Point3::new(1.5, 1.0, 5.0));
+    assert_eq!(q.w, 1.0);
+    assert_eq!(q - p, Vector3::new(0.5, -1.0, 2.0));
+    assert_eq!((q - p).w, 0.0);
+}
+#[test]
+fn normalized_f32_vectors_have_unit_length() {
+    let mut v = Vector3::new(1.0f32, -2.0, 3.0);
+    v.normalize();
+    assert_relative_eq!(v.magnitude(), 1.0, epsilon = 1e-6);
+}
@@ -0,0 +1,77 @@
+/// Tests of the STL importer.
+use crate::{Mesh, Point3, Ray, Shape, StlError, Vector3};
+
+/// A square in the xy plane, made of two facets sharing an edge.
+const ASCII: &str = "solid square
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid square";
+
+fn binary(triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+    // Binary files may start with "solid" too.
+    let mut bytes = b"solid binary".to_vec();
+    bytes.resize(80, 0);
+    bytes.extend((triangles.len() as u32).to_le_bytes());
+    for triangle in triangles {
+        bytes.extend([0_f32; 3].iter().flat_map(|f| f.to_le_bytes()));
+        bytes.extend(triangle.iter().flatten().flat_map(|f| f.to_le_bytes()));
+        bytes.extend(0_u16.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn reading_an_ascii_stl() {
+    let mesh = Mesh::<f64>::from_stl(ASCII.as_bytes()).unwrap();
+    assert_eq!(mesh.len(), 2);
+    // The shared vertices are stored once.
+    assert_eq!(mesh.vertices().len(), 4);
+    assert_eq!(mesh.faces(), [[0, 1, 2], [0, 2, 3]]);
+    let r = Ray::new(Point3::new(0.2, 0.7, -1.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = mesh.intersect(&r);
+    assert_eq!(xs[0].face(), Some(1));
+}
+#[test]
+fn reading_a_binary_stl() {
+    let bytes = binary(&[
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]],
+        [[0.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+    ]);
+    let ascii = Mesh::<f64>::from_stl(ASCII.as_bytes()).unwrap();
+    let mesh = Mesh::<f64>::from_stl(&bytes).unwrap();
+    assert_eq!(mesh.vertices(), ascii.vertices());
+    assert_eq!(mesh.faces(), ascii.faces());
+}
+#[test]
+fn invalid_stl_content() {
+    let mut bytes = binary(&[[[0.0; 3]; 3]]);
+    bytes.pop();
+    assert!(matches!(
+        Mesh::<f64>::from_stl(&bytes[12..]),
+        Err(StlError::UnknownFormat)
+    ));
+    assert!(matches!(
+        Mesh::<f64>::from_stl(b"solid a\nvertex 0 0\n"),
+        Err(StlError::InvalidVertex { line: 2 })
+    ));
+    assert!(matches!(
+        Mesh::<f64>::from_stl(b"solid a\nvertex 0 0 0\n"),
+        Err(StlError::IncompleteFacet)
+    ));
+    assert!(matches!(
+        Mesh::<f64>::from_stl_file("does/not/exist.stl"),
+        Err(StlError::Io(_))
+    ));
+}
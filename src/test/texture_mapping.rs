@@ -0,0 +1,271 @@
+/// Tests of the texture mapping.
+use std::{f64::consts::FRAC_1_SQRT_2, sync::Arc};
+
+use approx::assert_relative_eq;
+
+use crate::{
+    features::{
+        colors::Color,
+        patterns::uv_mapping::{cube_map, cylindrical_map, planar_map, spherical_map},
+    },
+    CubeFace, CubeMap, Pattern, Point3, RawCanvas, Ray, TextureMap, UvAlignCheck, UvChecker,
+    UvImage, UvMapping, UvPattern, Vector3, World, EPSILON, MAX_DEPTH,
+};
+
+fn black() -> Color<f64> {
+    Color::new(0.0, 0.0, 0.0)
+}
+
+fn white() -> Color<f64> {
+    Color::new(1.0, 1.0, 1.0)
+}
+
+fn assert_uv((u, v): (f64, f64), expected: (f64, f64)) {
+    assert_relative_eq!(u, expected.0, epsilon = EPSILON);
+    assert_relative_eq!(v, expected.1, epsilon = EPSILON);
+}
+
+#[test]
+fn checker_pattern_in_2d() {
+    let checkers = UvChecker::new(2.0, 2.0, black(), white());
+    assert_eq!(checkers.uv_pattern_at(0.0, 0.0), black());
+    assert_eq!(checkers.uv_pattern_at(0.5, 0.0), white());
+    assert_eq!(checkers.uv_pattern_at(0.0, 0.5), white());
+    assert_eq!(checkers.uv_pattern_at(0.5, 0.5), black());
+    assert_eq!(checkers.uv_pattern_at(1.0, 1.0), black());
+}
+#[test]
+fn spherical_mapping_on_a_3d_point() {
+    let h = FRAC_1_SQRT_2;
+    for (p, uv) in [
+        (Point3::new(0.0, 0.0, -1.0), (0.0, 0.5)),
+        (Point3::new(1.0, 0.0, 0.0), (0.25, 0.5)),
+        (Point3::new(0.0, 0.0, 1.0), (0.5, 0.5)),
+        (Point3::new(-1.0, 0.0, 0.0), (0.75, 0.5)),
+        (Point3::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+        (Point3::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+        (Point3::new(h, h, 0.0), (0.25, 0.75)),
+    ] {
+        assert_uv(spherical_map(p), uv);
+    }
+}
+#[test]
+fn texture_map_pattern_with_spherical_map() {
+    let pattern = TextureMap::new(
+        UvChecker::new(16.0, 8.0, black(), white()),
+        UvMapping::Spherical,
+    );
+    for (p, color) in [
+        (Point3::new(0.4315, 0.4670, 0.7719), white()),
+        (Point3::new(-0.9654, 0.2552, -0.0534), black()),
+        (Point3::new(0.1039, 0.7090, 0.6975), white()),
+        (Point3::new(-0.4986, -0.7856, -0.3663), black()),
+        (Point3::new(-0.0317, -0.9395, 0.3411), black()),
+        (Point3::new(0.4809, -0.7721, 0.4154), black()),
+        (Point3::new(0.0285, -0.9612, -0.2745), black()),
+        (Point3::new(-0.5734, -0.2162, -0.7903), white()),
+        (Point3::new(0.7688, -0.1470, 0.6223), black()),
+        (Point3::new(-0.7652, 0.2175, 0.6060), black()),
+    ] {
+        assert_eq!(pattern.pattern_at(p), color);
+    }
+}
+#[test]
+fn planar_mapping_on_a_3d_point() {
+    for (p, uv) in [
+        (Point3::new(0.25, 0.0, 0.5), (0.25, 0.5)),
+        (Point3::new(0.25, 0.0, -0.25), (0.25, 0.75)),
+        (Point3::new(0.25, 0.5, -0.25), (0.25, 0.75)),
+        (Point3::new(1.25, 0.0, 0.5), (0.25, 0.5)),
+        (Point3::new(0.25, 0.0, -1.75), (0.25, 0.25)),
+        (Point3::new(1.0, 0.0, -1.0), (0.0, 0.0)),
+        (Point3::new(0.0, 0.0, 0.0), (0.0, 0.0)),
+    ] {
+        assert_uv(planar_map(p), uv);
+    }
+}
+#[test]
+fn cylindrical_mapping_on_a_3d_point() {
+    let h = FRAC_1_SQRT_2;
+    for (p, uv) in [
+        (Point3::new(0.0, 0.0, -1.0), (0.0, 0.0)),
+        (Point3::new(0.0, 0.5, -1.0), (0.0, 0.5)),
+        (Point3::new(0.0, 1.0, -1.0), (0.0, 0.0)),
+        (Point3::new(h, 0.5, -h), (0.125, 0.5)),
+        (Point3::new(1.0, 0.5, 0.0), (0.25, 0.5)),
+        (Point3::new(h, 0.5, h), (0.375, 0.5)),
+        (Point3::new(0.0, -0.25, 1.0), (0.5, 0.75)),
+        (Point3::new(-h, 0.5, h), (0.625, 0.5)),
+        (Point3::new(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        (Point3::new(-h, 0.5, -h), (0.875, 0.5)),
+    ] {
+        assert_uv(cylindrical_map(p), uv);
+    }
+}
+#[test]
+fn identifying_the_face_of_a_cube_from_a_point() {
+    for (p, face) in [
+        (Point3::new(-1.0, 0.5, -0.25), CubeFace::Left),
+        (Point3::new(1.1, -0.75, 0.8), CubeFace::Right),
+        (Point3::new(0.1, 0.6, 0.9), CubeFace::Front),
+        (Point3::new(-0.7, 0.0, -2.0), CubeFace::Back),
+        (Point3::new(0.5, 1.0, 0.9), CubeFace::Up),
+        (Point3::new(-0.2, -1.3, 1.1), CubeFace::Down),
+    ] {
+        assert_eq!(CubeFace::from_point(p), face);
+    }
+}
+#[test]
+fn uv_mapping_of_the_cube_faces() {
+    for (p, face, uv) in [
+        (Point3::new(-0.5, 0.5, 1.0), CubeFace::Front, (0.25, 0.75)),
+        (Point3::new(0.5, -0.5, 1.0), CubeFace::Front, (0.75, 0.25)),
+        (Point3::new(0.5, 0.5, -1.0), CubeFace::Back, (0.25, 0.75)),
+        (Point3::new(-0.5, -0.5, -1.0), CubeFace::Back, (0.75, 0.25)),
+        (Point3::new(-1.0, 0.5, -0.5), CubeFace::Left, (0.25, 0.75)),
+        (Point3::new(-1.0, -0.5, 0.5), CubeFace::Left, (0.75, 0.25)),
+        (Point3::new(1.0, 0.5, 0.5), CubeFace::Right, (0.25, 0.75)),
+        (Point3::new(1.0, -0.5, -0.5), CubeFace::Right, (0.75, 0.25)),
+        (Point3::new(-0.5, 1.0, -0.5), CubeFace::Up, (0.25, 0.75)),
+        (Point3::new(0.5, 1.0, 0.5), CubeFace::Up, (0.75, 0.25)),
+        (Point3::new(-0.5, -1.0, 0.5), CubeFace::Down, (0.25, 0.75)),
+        (Point3::new(0.5, -1.0, -0.5), CubeFace::Down, (0.75, 0.25)),
+    ] {
+        let (f, u, v) = cube_map(p);
+        assert_eq!(f, face);
+        assert_uv((u, v), uv);
+    }
+}
+#[test]
+fn texture_map_with_cube_mapping_repeats_on_every_face() {
+    let pattern = TextureMap::new(UvChecker::new(2.0, 2.0, black(), white()), UvMapping::Cube);
+    // The bottom-left quarter of every face is `a`.
+    for p in [
+        Point3::new(-0.5, -0.5, 1.0),
+        Point3::new(0.5, -0.5, -1.0),
+        Point3::new(-0.5, -1.0, -0.5),
+    ] {
+        assert_eq!(pattern.pattern_at(p), black());
+    }
+}
+#[test]
+fn uv_image_maps_the_corners_to_the_pixels() {
+    let mut canvas: RawCanvas<3, 2, f64> = RawCanvas::default();
+    let red = Color::new(1.0, 0.0, 0.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+    canvas.write_pixel(0, 0, red).unwrap();
+    canvas.write_pixel(2, 1, blue).unwrap();
+    canvas.write_pixel(1, 1, white()).unwrap();
+    let image = UvImage::from_canvas(&canvas);
+    // The first row of the canvas is the top of the image.
+    assert_eq!(image.uv_pattern_at(0.0, 1.0), red);
+    assert_eq!(image.uv_pattern_at(1.0, 0.0), blue);
+    assert_eq!(image.uv_pattern_at(0.5, 0.0), white());
+    assert_eq!(image.uv_pattern_at(1.0, 1.0), black());
+}
+#[test]
+#[should_panic]
+fn uv_image_requires_all_pixels() {
+    UvImage::new(2, 2, vec![black(); 3]);
+}
+#[test]
+fn layout_of_the_align_check_pattern() {
+    let main = white();
+    let ul = Color::new(1.0, 0.0, 0.0);
+    let ur = Color::new(1.0, 1.0, 0.0);
+    let bl = Color::new(0.0, 1.0, 0.0);
+    let br = Color::new(0.0, 1.0, 1.0);
+    let pattern = UvAlignCheck::new(main, ul, ur, bl, br);
+    assert_eq!(pattern.uv_pattern_at(0.5, 0.5), main);
+    assert_eq!(pattern.uv_pattern_at(0.1, 0.9), ul);
+    assert_eq!(pattern.uv_pattern_at(0.9, 0.9), ur);
+    assert_eq!(pattern.uv_pattern_at(0.1, 0.1), bl);
+    assert_eq!(pattern.uv_pattern_at(0.9, 0.1), br);
+}
+#[test]
+fn finding_the_colors_on_a_mapped_cube() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let yellow = Color::new(1.0, 1.0, 0.0);
+    let brown = Color::new(1.0, 0.5, 0.0);
+    let green = Color::new(0.0, 1.0, 0.0);
+    let cyan = Color::new(0.0, 1.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+    let purple = Color::new(1.0, 0.0, 1.0);
+    let white = white();
+    let pattern = CubeMap::new(
+        UvAlignCheck::new(yellow, cyan, red, blue, brown),
+        UvAlignCheck::new(cyan, red, yellow, brown, green),
+        UvAlignCheck::new(red, yellow, purple, green, white),
+        UvAlignCheck::new(green, purple, cyan, white, blue),
+        UvAlignCheck::new(brown, cyan, purple, red, yellow),
+        UvAlignCheck::new(purple, brown, green, blue, white),
+    );
+    for (p, color) in [
+        // Left
+        (Point3::new(-1.0, 0.0, 0.0), yellow),
+        (Point3::new(-1.0, 0.9, -0.9), cyan),
+        (Point3::new(-1.0, 0.9, 0.9), red),
+        (Point3::new(-1.0, -0.9, -0.9), blue),
+        (Point3::new(-1.0, -0.9, 0.9), brown),
+        // Front
+        (Point3::new(0.0, 0.0, 1.0), cyan),
+        (Point3::new(-0.9, 0.9, 1.0), red),
+        (Point3::new(0.9, 0.9, 1.0), yellow),
+        (Point3::new(-0.9, -0.9, 1.0), brown),
+        (Point3::new(0.9, -0.9, 1.0), green),
+        // Right
+        (Point3::new(1.0, 0.0, 0.0), red),
+        (Point3::new(1.0, 0.9, 0.9), yellow),
+        (Point3::new(1.0, 0.9, -0.9), purple),
+        (Point3::new(1.0, -0.9, 0.9), green),
+        (Point3::new(1.0, -0.9, -0.9), white),
+        // Back
+        (Point3::new(0.0, 0.0, -1.0), green),
+        (Point3::new(0.9, 0.9, -1.0), purple),
+        (Point3::new(-0.9, 0.9, -1.0), cyan),
+        (Point3::new(0.9, -0.9, -1.0), white),
+        (Point3::new(-0.9, -0.9, -1.0), blue),
+        // Up
+        (Point3::new(0.0, 1.0, 0.0), brown),
+        (Point3::new(-0.9, 1.0, -0.9), cyan),
+        (Point3::new(0.9, 1.0, -0.9), purple),
+        (Point3::new(-0.9, 1.0, 0.9), red),
+        (Point3::new(0.9, 1.0, 0.9), yellow),
+        // Down
+        (Point3::new(0.0, -1.0, 0.0), purple),
+        (Point3::new(-0.9, -1.0, 0.9), brown),
+        (Point3::new(0.9, -1.0, 0.9), green),
+        (Point3::new(-0.9, -1.0, -0.9), blue),
+        (Point3::new(0.9, -1.0, -0.9), white),
+    ] {
+        assert_eq!(pattern.pattern_at(p), color);
+    }
+}
+#[test]
+fn cube_map_projects_the_points_off_the_cube() {
+    let pattern = CubeMap::new(
+        UvChecker::new(1.0, 1.0, black(), black()),
+        UvChecker::new(1.0, 1.0, white(), white()),
+        UvChecker::new(1.0, 1.0, black(), black()),
+        UvChecker::new(1.0, 1.0, black(), black()),
+        UvChecker::new(1.0, 1.0, black(), black()),
+        UvChecker::new(1.0, 1.0, black(), black()),
+    );
+    assert_eq!(pattern.pattern_at(Point3::new(0.1, -0.2, 0.5)), white());
+    assert_eq!(pattern.pattern_at(Point3::new(3.0, 2.0, 10.0)), white());
+}
+#[test]
+fn rays_missing_everything_see_the_skybox() {
+    let mut world = World::<f64>::new();
+    let sky = Color::new(0.2, 0.4, 0.8);
+    let ground = Color::new(0.3, 0.2, 0.1);
+    let down = UvChecker::new(1.0, 1.0, ground, ground);
+    let up = UvChecker::new(1.0, 1.0, sky, sky);
+    world.set_skybox(Some(Arc::new(CubeMap::new(up, up, up, up, up, down))));
+    let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 5.0));
+    assert_eq!(world.color_at(&ray, MAX_DEPTH), sky);
+    let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.1, -2.0, 0.0));
+    assert_eq!(world.color_at(&ray, MAX_DEPTH), ground);
+    world.set_skybox(None);
+    assert_eq!(world.color_at(&ray, MAX_DEPTH), black());
+}
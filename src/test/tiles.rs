@@ -0,0 +1,71 @@
+/// Tests of the tiles of a render.
+use std::f64::consts::PI;
+
+use crate::{
+    features::tiles::tiles, Camera, PixelBounds, Point3, RawCanvas, TileOrder, Transform, Vector3,
+    World,
+};
+
+const ORDERS: [TileOrder; 3] = [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert];
+
+#[test]
+fn tiles_cover_the_region_once() {
+    let region = PixelBounds::new(10, 5, 110, 75);
+    for order in ORDERS {
+        let tiles = tiles(&region, 32, order);
+        assert_eq!(tiles.len(), 12);
+        let mut pixels: Vec<(usize, usize)> = tiles.iter().flat_map(PixelBounds::pixels).collect();
+        pixels.sort();
+        let mut expected: Vec<(usize, usize)> = region.pixels().collect();
+        expected.sort();
+        assert_eq!(pixels, expected);
+    }
+}
+#[test]
+fn scanline_tiles_go_row_by_row() {
+    let tiles = tiles(&PixelBounds::new(0, 0, 100, 70), 32, TileOrder::Scanline);
+    assert_eq!(tiles[0], PixelBounds::new(0, 0, 32, 32));
+    assert_eq!(tiles[1], PixelBounds::new(32, 0, 64, 32));
+    assert_eq!(tiles[3], PixelBounds::new(96, 0, 100, 32));
+    assert_eq!(tiles[4], PixelBounds::new(0, 32, 32, 64));
+    assert_eq!(tiles[11], PixelBounds::new(96, 64, 100, 70));
+}
+#[test]
+fn spiral_tiles_start_from_the_center() {
+    let tiles = tiles(&PixelBounds::new(0, 0, 90, 90), 10, TileOrder::Spiral);
+    assert_eq!(tiles[0], PixelBounds::new(40, 40, 50, 50));
+    // Then the ring of the 8 tiles around it.
+    for tile in &tiles[1..9] {
+        assert!((30..=50).contains(&tile.min_x) && (30..=50).contains(&tile.min_y));
+    }
+    assert_eq!(tiles.len(), 81);
+}
+#[test]
+fn hilbert_tiles_follow_each_other() {
+    let tiles = tiles(&PixelBounds::new(0, 0, 64, 64), 8, TileOrder::Hilbert);
+    assert_eq!(tiles[0], PixelBounds::new(0, 0, 8, 8));
+    for pair in tiles.windows(2) {
+        let dx = pair[0].min_x.abs_diff(pair[1].min_x);
+        let dy = pair[0].min_y.abs_diff(pair[1].min_y);
+        assert_eq!(dx + dy, 8);
+    }
+}
+#[test]
+fn tile_order_does_not_change_the_image() {
+    let w = World::default();
+    let view = Transform::view_transform(
+        Point3::new(0.0, 0.0, -5.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::new(0.0, 1.0, 0.0),
+    );
+    let c = Camera::new(11, 11, PI / 2.0).with_transform(view);
+    assert_eq!(c.tile_size(), 32);
+    assert_eq!(c.tile_order(), TileOrder::Scanline);
+    let expected: RawCanvas<11, 11, f64> = c.render(&w);
+    for order in ORDERS {
+        let c = c.clone().with_tile_size(4).with_tile_order(order);
+        assert_eq!(c.tiles().len(), 9);
+        let image: RawCanvas<11, 11, f64> = c.render(&w);
+        assert_eq!(image.pixels(), expected.pixels());
+    }
+}
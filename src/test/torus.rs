@@ -0,0 +1,85 @@
+/// Tests of the torus and the polynomial solvers it relies on.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::math::roots::{solve_cubic, solve_quadratic, solve_quartic},
+    Point3, Ray, Shape, Torus, Transform, Vector3, EPSILON,
+};
+
+fn assert_roots(roots: Vec<f64>, expected: &[f64]) {
+    assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+    for (r, e) in roots.iter().zip(expected) {
+        assert_relative_eq!(r, e, epsilon = EPSILON);
+    }
+}
+#[test]
+fn quadratic_roots() {
+    assert_roots(solve_quadratic(1.0, -3.0, 2.0), &[1.0, 2.0]);
+    assert_roots(solve_quadratic(1.0, 2.0, 1.0), &[-1.0]);
+    assert_roots(solve_quadratic(1.0, 0.0, 1.0), &[]);
+    assert_roots(solve_quadratic(0.0, 2.0, -1.0), &[0.5]);
+}
+#[test]
+fn cubic_roots() {
+    // (x - 1)(x - 2)(x - 3)
+    assert_roots(solve_cubic(2.0, -12.0, 22.0, -12.0), &[1.0, 2.0, 3.0]);
+    // (x - 1)(x^2 + 1)
+    assert_roots(solve_cubic(1.0, -1.0, 1.0, -1.0), &[1.0]);
+}
+#[test]
+fn quartic_roots() {
+    // (x - 1)(x - 2)(x - 3)(x - 4)
+    assert_roots(
+        solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0),
+        &[1.0, 2.0, 3.0, 4.0],
+    );
+    // (x^2 - 2)(x^2 + 1)
+    assert_roots(
+        solve_quartic(1.0, 0.0, -1.0, 0.0, -2.0),
+        &[-(2_f64.sqrt()), 2_f64.sqrt()],
+    );
+    assert_roots(solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0), &[]);
+    // Roots far apart, as with rays starting far from a small torus:
+    // (x - 0.5)(x - 1)(x - 100)(x - 200)
+    assert_roots(
+        solve_quartic(1.0, -301.5, 20450.5, -30150.0, 10000.0),
+        &[0.5, 1.0, 100.0, 200.0],
+    );
+}
+#[test]
+fn ray_through_torus_hole_and_tube() {
+    let torus = Torus::default();
+    let r = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+    let ts: Vec<f64> = torus.local_intersect(&r).iter().map(|i| i.t()).collect();
+    assert_roots(ts, &[3.75, 4.25, 5.75, 6.25]);
+    let r = Ray::new(Point3::new(1.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    let ts: Vec<f64> = torus.local_intersect(&r).iter().map(|i| i.t()).collect();
+    assert_roots(ts, &[4.75, 5.25]);
+}
+#[test]
+fn ray_misses_torus() {
+    let torus = Torus::default();
+    // Through the hole along the y axis.
+    let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+    assert!(torus.local_intersect(&r).is_empty());
+    let r = Ray::new(Point3::new(-5.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+    assert!(torus.local_intersect(&r).is_empty());
+}
+#[test]
+fn torus_normals() {
+    let mut torus = Torus::new(2.0, 0.5);
+    for (point, normal) in [
+        (Point3::new(2.5, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+        (Point3::new(1.5, 0.0, 0.0), Vector3::new(-1.0, 0.0, 0.0)),
+        (Point3::new(0.0, 0.5, 2.0), Vector3::new(0.0, 1.0, 0.0)),
+        (Point3::new(0.0, -0.5, -2.0), Vector3::new(0.0, -1.0, 0.0)),
+    ] {
+        assert_relative_eq!(torus.local_normal_at(point), normal, epsilon = EPSILON);
+    }
+    torus.set_transform(Transform::rotation_x(std::f64::consts::FRAC_PI_2));
+    assert_relative_eq!(
+        torus.normal_at(Point3::new(0.0, 2.0, 0.5)),
+        Vector3::new(0.0, 0.0, 1.0),
+        epsilon = EPSILON
+    );
+}
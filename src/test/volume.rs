@@ -0,0 +1,80 @@
+/// Tests of the constant density volumes.
+use approx::assert_relative_eq;
+
+use crate::{
+    features::math::sampling::seed_thread_rng, Intersection, Point3, Ray, Shape, Sphere, Transform,
+    Vector3, Volume, EPSILON,
+};
+
+fn ray_through_center() -> Ray<f64> {
+    Ray::new(Point3::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0))
+}
+
+#[test]
+#[should_panic]
+fn volume_needs_positive_density() {
+    Volume::new(Sphere::<f64>::default(), 0.0);
+}
+#[test]
+fn dense_volume_scatters_at_its_boundary() {
+    seed_thread_rng(1);
+    let v = Volume::new(Sphere::default(), 1e6);
+    assert!(v.is_medium());
+    for _ in 0..100 {
+        let xs = v.intersect(&ray_through_center());
+        assert_eq!(xs.len(), 1);
+        assert_relative_eq!(xs[0].t(), 4.0, epsilon = EPSILON);
+        assert_eq!(xs[0].object(), &v as &dyn Shape<f64>);
+    }
+}
+#[test]
+fn thin_volume_scatters_with_exponential_probability() {
+    seed_thread_rng(2);
+    // Crossing 2 units at a density of 0.5: a ray scatters with the
+    // probability 1 - exp(-1).
+    let v = Volume::new(Sphere::default(), 0.5);
+    let n = 10000;
+    let hits: Vec<f64> = (0..n)
+        .filter_map(|_| v.intersect(&ray_through_center()).first().map(|x| x.t()))
+        .collect();
+    assert_relative_eq!(
+        hits.len() as f64 / n as f64,
+        1.0 - (-1.0_f64).exp(),
+        epsilon = 0.02
+    );
+    assert!(hits.iter().all(|&t| (4.0..=6.0).contains(&t)));
+}
+#[test]
+fn ray_missing_boundary_misses_volume() {
+    let v = Volume::new(Sphere::default(), 1e6);
+    let r = Ray::new(Point3::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+    assert!(v.intersect(&r).is_empty());
+}
+#[test]
+fn ray_starting_inside_scatters_ahead() {
+    seed_thread_rng(3);
+    let v = Volume::new(Sphere::default(), 1e6);
+    let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let xs = v.intersect(&r);
+    assert_eq!(xs.len(), 1);
+    assert_relative_eq!(xs[0].t(), 0.0, epsilon = EPSILON);
+}
+#[test]
+fn transformed_boundary() {
+    seed_thread_rng(4);
+    let mut s = Sphere::default();
+    s.set_transform(Transform::scaling(2.0, 2.0, 2.0));
+    let mut v = Volume::new(s, 1e6);
+    v.set_transform(Transform::translation(0.0, 0.0, 1.0));
+    let xs = v.intersect(&ray_through_center());
+    assert_relative_eq!(xs[0].t(), 4.0, epsilon = EPSILON);
+    assert_relative_eq!(v.bounds().min(), Point3::new(-2.0, -2.0, -2.0));
+}
+#[test]
+fn medium_faces_the_eye() {
+    let v = Volume::new(Sphere::default(), 1.0);
+    let r = ray_through_center();
+    let comps = Intersection::new(4.5, &v).prepare_computations(&r);
+    assert_eq!(comps.normalv, Vector3::new(0.0, 0.0, -1.0));
+    assert!(!comps.inside);
+}